@@ -0,0 +1,255 @@
+//! Protocols for proving transcript commitments to the Notary.
+//!
+//! The Notary never learns the plaintext of the transcript, so any commitment
+//! which is not derived from the encodings must be proven to the Notary in
+//! MPC. For plaintext hash commitments, the Prover proves in zero-knowledge
+//! that the hash was computed over the authenticated plaintext which is already
+//! in the VM memory.
+
+mod blake3;
+
+use std::{error::Error, sync::Arc};
+
+use mpz_circuits::{circuits::sha256_trace, types::Value, Circuit, CircuitBuilder, Tracer};
+use mpz_garble::{value::ValueRef, Execute, Memory, Prove, Verify};
+use tlsn_core::{
+    hash::{Blinder, HashAlgId},
+    transcript::{
+        get_value_ids,
+        hash::{preimage_prefix, PlaintextHash},
+        Direction, Idx,
+    },
+};
+
+/// Hash algorithms which plaintext hash commitments can be proven with.
+///
+/// Keccak-256 is not supported as there is no circuit for it, the Prover must
+/// use encoding commitments for transcripts committed with it.
+pub const SUPPORTED_PLAINTEXT_HASH_ALGS: &[HashAlgId] = &[HashAlgId::SHA256, HashAlgId::BLAKE3];
+
+static SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Proves a plaintext hash commitment to the verifier.
+///
+/// # Arguments
+///
+/// * `vm` - The VM which holds the transcript.
+/// * `id` - Unique identifier of the commitment.
+/// * `hash` - The plaintext hash commitment.
+/// * `blinder` - The blinder of the hash pre-image.
+pub async fn prove_plaintext_hash<T>(
+    vm: &mut T,
+    id: &str,
+    hash: &PlaintextHash,
+    blinder: &Blinder,
+) -> Result<(), CommitError>
+where
+    T: Memory + Execute + Prove + Send,
+{
+    let (circ, plaintext, output) = setup(vm, id, hash)?;
+
+    let blinder_ref = vm.new_private_input::<[u8; 16]>(&format!("{id}/blinder"))?;
+    vm.assign(&blinder_ref, *blinder.as_bytes())?;
+
+    vm.execute_prove(circ, &[plaintext, blinder_ref], &[output.clone()])
+        .await?;
+    vm.prove(&[output]).await?;
+
+    Ok(())
+}
+
+/// Verifies a plaintext hash commitment from the prover.
+///
+/// # Arguments
+///
+/// * `vm` - The VM which holds the transcript.
+/// * `id` - Unique identifier of the commitment.
+/// * `hash` - The plaintext hash commitment.
+pub async fn verify_plaintext_hash<T>(
+    vm: &mut T,
+    id: &str,
+    hash: &PlaintextHash,
+) -> Result<(), CommitError>
+where
+    T: Memory + Execute + Verify + Send,
+{
+    let (circ, plaintext, output) = setup(vm, id, hash)?;
+
+    let blinder_ref = vm.new_blind_input::<[u8; 16]>(&format!("{id}/blinder"))?;
+
+    vm.execute_verify(circ, &[plaintext, blinder_ref], &[output.clone()])
+        .await?;
+
+    let expected: Vec<u8> = hash.hash.value.into();
+    vm.verify(&[output], &[Value::from(expected)]).await?;
+
+    Ok(())
+}
+
+/// Sets up the plaintext and output values of a hash commitment, returning
+/// the circuit which computes the hash.
+fn setup<T: Memory>(
+    vm: &mut T,
+    id: &str,
+    hash: &PlaintextHash,
+) -> Result<(Arc<Circuit>, ValueRef, ValueRef), CommitError> {
+    if !SUPPORTED_PLAINTEXT_HASH_ALGS.contains(&hash.hash.alg) {
+        return Err(CommitError::new(format!(
+            "unsupported plaintext hash algorithm: {}",
+            hash.hash.alg
+        )));
+    }
+
+    // Both supported algorithms have 32-byte hashes.
+    if Vec::<u8>::from(hash.hash.value).len() != 32 {
+        return Err(CommitError::new(format!(
+            "invalid {} hash length",
+            hash.hash.alg
+        )));
+    }
+
+    let plaintext = transcript_refs(vm, hash.direction, &hash.idx)?;
+    let output = vm.new_output::<[u8; 32]>(&format!("{id}/hash"))?;
+
+    Ok((
+        build_plaintext_hash(hash.hash.alg, hash.idx.len()),
+        plaintext,
+        output,
+    ))
+}
+
+/// Returns a reference to the transcript data at the given index.
+pub(crate) fn transcript_refs<T: Memory>(
+    vm: &T,
+    direction: Direction,
+    idx: &Idx,
+) -> Result<ValueRef, CommitError> {
+    let refs = get_value_ids(direction, idx)
+        .map(|id| {
+            vm.get_value(id.as_str())
+                .ok_or_else(|| CommitError::new(format!("transcript value is missing: {id}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if refs.is_empty() {
+        return Err(CommitError::new("transcript index is empty"));
+    }
+
+    Ok(vm.array_from_values(&refs)?)
+}
+
+/// Builds a circuit which computes the hash of a plaintext hash commitment
+/// pre-image.
+///
+/// # Inputs
+///
+///   0. PLAINTEXT: `len`-byte plaintext
+///   1. BLINDER: 16-byte blinder
+///
+/// # Outputs
+///
+///   0. HASH: 32-byte hash
+///
+/// # Panics
+///
+/// Panics if the algorithm is not in [`SUPPORTED_PLAINTEXT_HASH_ALGS`].
+fn build_plaintext_hash(alg: HashAlgId, len: usize) -> Arc<Circuit> {
+    let builder = CircuitBuilder::new();
+    let plaintext = builder.add_vec_input::<u8>(len);
+    let blinder = builder.add_array_input::<u8, 16>();
+
+    let preimage = preimage_prefix(len)
+        .into_iter()
+        .map(|byte| Tracer::new(builder.state(), builder.get_constant(byte).to_inner()))
+        .chain(plaintext)
+        .chain(blinder)
+        .collect::<Vec<_>>();
+
+    match alg {
+        HashAlgId::SHA256 => {
+            let initial_state = SHA256_INITIAL_STATE
+                .map(|v| Tracer::new(builder.state(), builder.get_constant(v).to_inner()));
+
+            let hash = sha256_trace(builder.state(), initial_state, 0, &preimage);
+            builder.add_output(hash);
+        }
+        HashAlgId::BLAKE3 => {
+            let hash = blake3::blake3_trace(&builder, &preimage);
+            builder.add_output(hash);
+        }
+        alg => panic!("unsupported plaintext hash algorithm: {alg}"),
+    }
+
+    Arc::new(builder.build().expect("circuit is valid"))
+}
+
+/// Error for transcript commitment protocols.
+#[derive(Debug, thiserror::Error)]
+#[error("commit error: {0}")]
+pub struct CommitError(Box<dyn Error + Send + Sync + 'static>);
+
+impl CommitError {
+    pub(crate) fn new<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync + 'static>>,
+    {
+        Self(source.into())
+    }
+}
+
+impl From<mpz_garble::MemoryError> for CommitError {
+    fn from(e: mpz_garble::MemoryError) -> Self {
+        Self::new(e)
+    }
+}
+
+impl From<mpz_garble::ExecutionError> for CommitError {
+    fn from(e: mpz_garble::ExecutionError) -> Self {
+        Self::new(e)
+    }
+}
+
+impl From<mpz_garble::ProveError> for CommitError {
+    fn from(e: mpz_garble::ProveError) -> Self {
+        Self::new(e)
+    }
+}
+
+impl From<mpz_garble::VerifyError> for CommitError {
+    fn from(e: mpz_garble::VerifyError) -> Self {
+        Self::new(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+    use tlsn_core::hash::{Blake3, HashAlgorithm, Sha256};
+
+    #[rstest]
+    #[case::sha256(HashAlgId::SHA256, &Sha256::default())]
+    #[case::blake3(HashAlgId::BLAKE3, &Blake3::default())]
+    fn test_plaintext_hash_circuit(#[case] alg: HashAlgId, #[case] hasher: &dyn HashAlgorithm) {
+        let plaintext = b"hello world".to_vec();
+        let blinder = [42u8; 16];
+
+        let circ = build_plaintext_hash(alg, plaintext.len());
+
+        let output = circ
+            .evaluate(&[Value::from(plaintext.clone()), Value::from(blinder)])
+            .unwrap();
+        let hash: [u8; 32] = output[0].clone().try_into().unwrap();
+
+        let mut preimage = preimage_prefix(plaintext.len());
+        preimage.extend_from_slice(&plaintext);
+        preimage.extend_from_slice(&blinder);
+
+        let expected: Vec<u8> = hasher.hash(&preimage).into();
+
+        assert_eq!(hash.to_vec(), expected);
+    }
+}
@@ -0,0 +1,276 @@
+//! BLAKE3 circuit.
+//!
+//! Traces the default hash mode of BLAKE3 with a 32-byte output. The length of
+//! the input is known when the circuit is built, so the chunks and the tree
+//! which is built from them, as well as the counters, block lengths and flags of
+//! every compression, are constants of the circuit.
+
+use mpz_circuits::{ops::WrappingAdd, types::U8, CircuitBuilder, Tracer};
+
+/// Length of a chunk in bytes.
+const CHUNK_LEN: usize = 1024;
+/// Length of a block in bytes.
+const BLOCK_LEN: usize = 64;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+
+/// The BLAKE3 IV, which is the initial state of SHA-256.
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Permutation of the message words which is applied after each round.
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// A word of 4 little-endian bytes.
+type Word<'a> = Vec<Tracer<'a, U8>>;
+
+/// Traces the BLAKE3 hash of the input, returning the 32-byte hash.
+///
+/// # Arguments
+///
+/// * `builder` - The circuit builder.
+/// * `input` - The input to hash.
+pub(crate) fn blake3_trace<'a>(
+    builder: &'a CircuitBuilder,
+    input: &[Tracer<'a, U8>],
+) -> Vec<Tracer<'a, U8>> {
+    let mut chunks = input.chunks(CHUNK_LEN).collect::<Vec<_>>();
+    // An empty input is hashed as a single empty chunk.
+    if chunks.is_empty() {
+        chunks.push(input);
+    }
+
+    subtree_cv(builder, &chunks, 0, true)
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Returns the chaining value of the subtree over the given chunks.
+///
+/// The left subtree holds the largest power of two number of chunks which
+/// leaves at least one chunk for the right subtree.
+fn subtree_cv<'a>(
+    builder: &'a CircuitBuilder,
+    chunks: &[&[Tracer<'a, U8>]],
+    counter: u64,
+    root: bool,
+) -> Vec<Word<'a>> {
+    if chunks.len() == 1 {
+        return chunk_cv(builder, chunks[0], counter, root);
+    }
+
+    let left_len = 1 << (usize::BITS - 1 - (chunks.len() - 1).leading_zeros());
+    let left = subtree_cv(builder, &chunks[..left_len], counter, false);
+    let right = subtree_cv(
+        builder,
+        &chunks[left_len..],
+        counter + left_len as u64,
+        false,
+    );
+
+    let block = left.into_iter().chain(right).collect::<Vec<_>>();
+    let flags = if root { PARENT | ROOT } else { PARENT };
+
+    compress(builder, &iv(builder), &block, 0, BLOCK_LEN as u32, flags)
+}
+
+/// Returns the chaining value of a chunk.
+fn chunk_cv<'a>(
+    builder: &'a CircuitBuilder,
+    chunk: &[Tracer<'a, U8>],
+    counter: u64,
+    root: bool,
+) -> Vec<Word<'a>> {
+    let zero = constant(builder, 0);
+
+    let mut blocks = chunk.chunks(BLOCK_LEN).collect::<Vec<_>>();
+    if blocks.is_empty() {
+        blocks.push(chunk);
+    }
+
+    let last = blocks.len() - 1;
+    let mut cv = iv(builder);
+    for (i, block) in blocks.into_iter().enumerate() {
+        let mut flags = 0;
+        if i == 0 {
+            flags |= CHUNK_START;
+        }
+        if i == last {
+            flags |= CHUNK_END;
+            if root {
+                flags |= ROOT;
+            }
+        }
+
+        // The last block is padded with zeros.
+        let words = block
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(zero))
+            .take(BLOCK_LEN)
+            .collect::<Vec<_>>()
+            .chunks(4)
+            .map(|word| word.to_vec())
+            .collect::<Vec<_>>();
+
+        cv = compress(builder, &cv, &words, counter, block.len() as u32, flags);
+    }
+
+    cv
+}
+
+/// The BLAKE3 compression function, returning the first 8 words of the output
+/// which are the chaining value, or the hash for the root node.
+fn compress<'a>(
+    builder: &'a CircuitBuilder,
+    cv: &[Word<'a>],
+    block: &[Word<'a>],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> Vec<Word<'a>> {
+    let zero = constant(builder, 0);
+
+    let mut state = cv.to_vec();
+    state.extend(iv(builder).into_iter().take(4));
+    state.push(constant_word(builder, counter as u32));
+    state.push(constant_word(builder, (counter >> 32) as u32));
+    state.push(constant_word(builder, block_len));
+    state.push(constant_word(builder, flags));
+
+    let mut msg = block.to_vec();
+    for round in 0..7 {
+        // Column rounds.
+        g(&mut state, [0, 4, 8, 12], &msg[0], &msg[1], zero);
+        g(&mut state, [1, 5, 9, 13], &msg[2], &msg[3], zero);
+        g(&mut state, [2, 6, 10, 14], &msg[4], &msg[5], zero);
+        g(&mut state, [3, 7, 11, 15], &msg[6], &msg[7], zero);
+        // Diagonal rounds.
+        g(&mut state, [0, 5, 10, 15], &msg[8], &msg[9], zero);
+        g(&mut state, [1, 6, 11, 12], &msg[10], &msg[11], zero);
+        g(&mut state, [2, 7, 8, 13], &msg[12], &msg[13], zero);
+        g(&mut state, [3, 4, 9, 14], &msg[14], &msg[15], zero);
+
+        if round < 6 {
+            msg = MSG_PERMUTATION.iter().map(|i| msg[*i].clone()).collect();
+        }
+    }
+
+    (0..8)
+        .map(|i| xor_words(&state[i], &state[i + 8]))
+        .collect()
+}
+
+/// The BLAKE3 mixing function on the words at the given indices.
+fn g<'a>(
+    state: &mut [Word<'a>],
+    [a, b, c, d]: [usize; 4],
+    mx: &[Tracer<'a, U8>],
+    my: &[Tracer<'a, U8>],
+    zero: Tracer<'a, U8>,
+) {
+    state[a] = add_words(&add_words(&state[a], &state[b], zero), mx, zero);
+    state[d] = rotate_right(&xor_words(&state[d], &state[a]), 16);
+    state[c] = add_words(&state[c], &state[d], zero);
+    state[b] = rotate_right(&xor_words(&state[b], &state[c]), 12);
+    state[a] = add_words(&add_words(&state[a], &state[b], zero), my, zero);
+    state[d] = rotate_right(&xor_words(&state[d], &state[a]), 8);
+    state[c] = add_words(&state[c], &state[d], zero);
+    state[b] = rotate_right(&xor_words(&state[b], &state[c]), 7);
+}
+
+fn constant(builder: &CircuitBuilder, value: u8) -> Tracer<'_, U8> {
+    Tracer::new(builder.state(), builder.get_constant(value).to_inner())
+}
+
+fn constant_word(builder: &CircuitBuilder, value: u32) -> Word<'_> {
+    value
+        .to_le_bytes()
+        .into_iter()
+        .map(|byte| constant(builder, byte))
+        .collect()
+}
+
+fn iv(builder: &CircuitBuilder) -> Vec<Word<'_>> {
+    IV.iter()
+        .map(|word| constant_word(builder, *word))
+        .collect()
+}
+
+/// Adds two little-endian words, ignoring the final carry.
+fn add_words<'a>(
+    a: &[Tracer<'a, U8>],
+    b: &[Tracer<'a, U8>],
+    zero: Tracer<'a, U8>,
+) -> Vec<Tracer<'a, U8>> {
+    let mut carry = zero;
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let sum = a.wrapping_add(*b);
+            let out = sum.wrapping_add(carry);
+            // The carries out of `a + b` and `sum + carry` are never both set, so they
+            // can be combined with XOR.
+            carry = (((*a & *b) ^ ((*a ^ *b) & !sum)) ^ (sum & !out)) >> 7;
+            out
+        })
+        .collect()
+}
+
+/// XORs two words.
+fn xor_words<'a>(a: &[Tracer<'a, U8>], b: &[Tracer<'a, U8>]) -> Vec<Tracer<'a, U8>> {
+    a.iter().zip(b).map(|(a, b)| *a ^ *b).collect()
+}
+
+/// Rotates a little-endian word right by `n` bits.
+fn rotate_right<'a>(word: &[Tracer<'a, U8>], n: usize) -> Vec<Tracer<'a, U8>> {
+    // Rotating right by `n` bits is rotating left by `32 - n` bits.
+    let n = 32 - n;
+    let (bytes, bits) = (n / 8, n % 8);
+    let word = (0..4)
+        .map(|i| word[(i + 4 - bytes) % 4])
+        .collect::<Vec<_>>();
+
+    if bits == 0 {
+        return word;
+    }
+
+    (0..4)
+        .map(|i| (word[i] << bits) ^ (word[(i + 3) % 4] >> (8 - bits)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mpz_circuits::types::Value;
+    use tlsn_core::hash::{Blake3, HashAlgorithm};
+
+    #[test]
+    fn test_blake3_trace() {
+        // Single block, block boundary, chunk boundary and multiple chunks which
+        // form unbalanced trees.
+        for len in [1, 63, 64, 65, 1023, 1024, 1025, 2048, 2049, 3073, 4097] {
+            let input = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+            let builder = CircuitBuilder::new();
+            let input_refs = builder.add_vec_input::<u8>(len);
+            let hash = blake3_trace(&builder, &input_refs);
+            builder.add_output(hash);
+            let circ = builder.build().unwrap();
+
+            let output = circ.evaluate(&[Value::from(input.clone())]).unwrap();
+            let hash: Vec<u8> = output[0].clone().try_into().unwrap();
+
+            let expected: Vec<u8> = Blake3::default().hash(&input).into();
+
+            assert_eq!(hash, expected, "input length: {len}");
+        }
+    }
+}
@@ -16,6 +16,14 @@ const OTS_PER_BYTE_SENT: usize = 8;
 const OTS_PER_BYTE_RECV_ONLINE: usize = 16;
 const OTS_PER_BYTE_RECV_DEFER: usize = 8;
 
+// Poly1305 share conversions are performed over GF(2^130 - 5), with one OT
+// per bit for every 16-byte block and the prover as the sender.
+const OTS_PER_BYTE_POLY1305: usize = 9;
+
+// X25519 share conversions are performed over GF(2^255 - 19), with one OT per
+// bit for each of the 3 conversions in either direction.
+const OTS_X25519: usize = 3 * 255;
+
 // Current version that is running.
 static VERSION: Lazy<Version> = Lazy::new(|| {
     Version::parse(env!("CARGO_PKG_VERSION"))
@@ -35,11 +43,43 @@ pub struct ProtocolConfig {
     max_recv_data_online: usize,
     /// Maximum number of bytes that can be received.
     max_recv_data: usize,
+    /// The AEAD used to protect TLS records.
+    #[builder(default)]
+    aead_algorithm: AeadAlgorithm,
+    /// The groups offered for the key exchange, in order of preference.
+    ///
+    /// The key exchange is preprocessed for each group, so only P-256 is
+    /// offered by default.
+    #[builder(default = "vec![KeyExchangeGroup::P256]")]
+    key_exchange_groups: Vec<KeyExchangeGroup>,
     /// Version that is being run by prover/verifier.
     #[builder(setter(skip), default = "VERSION.clone()")]
     version: Version,
 }
 
+/// The AEAD used to protect TLS records.
+///
+/// Only cipher suites using this AEAD are offered to the server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AeadAlgorithm {
+    /// AES-128-GCM.
+    #[default]
+    Aes128Gcm,
+    /// ChaCha20-Poly1305.
+    ChaCha20Poly1305,
+}
+
+/// A group for the key exchange with the server.
+///
+/// The key exchange is preprocessed for each offered group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum KeyExchangeGroup {
+    /// X25519.
+    X25519,
+    /// NIST P-256.
+    P256,
+}
+
 impl ProtocolConfigBuilder {
     fn validate(&self) -> Result<(), String> {
         if self.max_recv_data_online > self.max_recv_data {
@@ -47,6 +87,11 @@ impl ProtocolConfigBuilder {
                 "max_recv_data_online must be smaller or equal to max_recv_data".to_string(),
             );
         }
+        if let Some(groups) = &self.key_exchange_groups {
+            if groups.is_empty() {
+                return Err("at least one key exchange group must be offered".to_string());
+            }
+        }
         Ok(())
     }
 }
@@ -72,24 +117,66 @@ impl ProtocolConfig {
         self.max_recv_data
     }
 
+    /// Returns the AEAD used to protect TLS records.
+    pub fn aead_algorithm(&self) -> AeadAlgorithm {
+        self.aead_algorithm
+    }
+
+    /// Returns the groups offered for the key exchange.
+    pub fn key_exchange_groups(&self) -> &[KeyExchangeGroup] {
+        &self.key_exchange_groups
+    }
+
     /// Returns OT sender setup count.
     pub fn ot_sender_setup_count(&self, role: Role) -> usize {
+        let poly1305_ots = match role {
+            Role::Prover => self.poly1305_ot_estimate(),
+            Role::Verifier => 0,
+        };
+
         ot_send_estimate(
             role,
             self.max_sent_data,
             self.max_recv_data_online,
             self.max_recv_data,
-        )
+        ) + poly1305_ots
+            + self.x25519_ot_estimate()
     }
 
     /// Returns OT receiver setup count.
     pub fn ot_receiver_setup_count(&self, role: Role) -> usize {
+        let poly1305_ots = match role {
+            Role::Prover => 0,
+            Role::Verifier => self.poly1305_ot_estimate(),
+        };
+
         ot_recv_estimate(
             role,
             self.max_sent_data,
             self.max_recv_data_online,
             self.max_recv_data,
-        )
+        ) + poly1305_ots
+            + self.x25519_ot_estimate()
+    }
+
+    // Returns the number of OTs used for Poly1305 share conversions.
+    fn poly1305_ot_estimate(&self) -> usize {
+        match self.aead_algorithm {
+            AeadAlgorithm::Aes128Gcm => 0,
+            AeadAlgorithm::ChaCha20Poly1305 => {
+                (self.max_sent_data + self.max_recv_data) * OTS_PER_BYTE_POLY1305
+            }
+        }
+    }
+
+    // Returns the number of OTs used for X25519 share conversions, which each
+    // party both sends and receives.
+    fn x25519_ot_estimate(&self) -> usize {
+        if self.key_exchange_groups.contains(&KeyExchangeGroup::X25519) {
+            OTS_X25519
+        } else {
+            0
+        }
     }
 }
 
@@ -312,4 +399,40 @@ mod test {
 
         assert!(config_validator.validate(&peer_config).is_err())
     }
+
+    #[test]
+    fn test_x25519_ot_count() {
+        let p256 = ProtocolConfig::builder()
+            .max_sent_data(TEST_MAX_SENT_LIMIT)
+            .max_recv_data(TEST_MAX_RECV_LIMIT)
+            .build()
+            .unwrap();
+        let x25519 = ProtocolConfig::builder()
+            .max_sent_data(TEST_MAX_SENT_LIMIT)
+            .max_recv_data(TEST_MAX_RECV_LIMIT)
+            .key_exchange_groups(vec![KeyExchangeGroup::X25519, KeyExchangeGroup::P256])
+            .build()
+            .unwrap();
+
+        for (x25519_count, p256_count) in [
+            (
+                x25519.ot_sender_setup_count(Role::Prover),
+                p256.ot_sender_setup_count(Role::Prover),
+            ),
+            (
+                x25519.ot_receiver_setup_count(Role::Prover),
+                p256.ot_receiver_setup_count(Role::Prover),
+            ),
+            (
+                x25519.ot_sender_setup_count(Role::Verifier),
+                p256.ot_sender_setup_count(Role::Verifier),
+            ),
+            (
+                x25519.ot_receiver_setup_count(Role::Verifier),
+                p256.ot_receiver_setup_count(Role::Verifier),
+            ),
+        ] {
+            assert_eq!(x25519_count, p256_count + OTS_X25519);
+        }
+    }
 }
@@ -4,9 +4,11 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
+pub mod commit;
 pub mod config;
 pub mod msg;
 pub mod mux;
+pub mod predicate;
 
 use serio::codec::Codec;
 
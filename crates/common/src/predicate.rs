@@ -0,0 +1,370 @@
+//! Protocol for proving predicates over the transcript to the Notary.
+//!
+//! The Prover proves in zero-knowledge that a predicate holds for the
+//! authenticated plaintext which is already in the VM memory.
+//!
+//! The predicate circuits only operate on bytes. Boolean results are carried in
+//! the most significant bit of a byte, ie. `0x80` is true and `0x00` is false.
+
+use std::{collections::HashMap, sync::Arc};
+
+use mpz_circuits::{ops::WrappingSub, types::U8, Circuit, CircuitBuilder, Tracer};
+use mpz_garble::{value::ValueRef, Execute, Memory, Prove, Verify};
+use regex_automata::{
+    dfa::{dense, Automaton},
+    util::start,
+    Anchored,
+};
+use tlsn_core::transcript::{regex_dfa, Predicate, PredicateStatement};
+
+use crate::commit::{transcript_refs, CommitError};
+
+/// Value of the circuit output if the predicate holds.
+const TRUE: u8 = 0x80;
+
+/// Proves a predicate statement to the verifier.
+///
+/// # Arguments
+///
+/// * `vm` - The VM which holds the transcript.
+/// * `id` - Unique identifier of the statement.
+/// * `statement` - The predicate statement.
+pub async fn prove_predicate<T>(
+    vm: &mut T,
+    id: &str,
+    statement: &PredicateStatement,
+) -> Result<(), CommitError>
+where
+    T: Memory + Execute + Prove + Send,
+{
+    let (circ, plaintext, output) = setup(vm, id, statement)?;
+
+    vm.execute_prove(circ, &[plaintext], &[output.clone()])
+        .await?;
+    vm.prove(&[output]).await?;
+
+    Ok(())
+}
+
+/// Verifies a predicate statement from the prover.
+///
+/// # Arguments
+///
+/// * `vm` - The VM which holds the transcript.
+/// * `id` - Unique identifier of the statement.
+/// * `statement` - The predicate statement.
+pub async fn verify_predicate<T>(
+    vm: &mut T,
+    id: &str,
+    statement: &PredicateStatement,
+) -> Result<(), CommitError>
+where
+    T: Memory + Execute + Verify + Send,
+{
+    let (circ, plaintext, output) = setup(vm, id, statement)?;
+
+    vm.execute_verify(circ, &[plaintext], &[output.clone()])
+        .await?;
+    vm.verify(&[output], &[TRUE.into()]).await?;
+
+    Ok(())
+}
+
+fn setup<T: Memory>(
+    vm: &mut T,
+    id: &str,
+    statement: &PredicateStatement,
+) -> Result<(Arc<Circuit>, ValueRef, ValueRef), CommitError> {
+    let len = statement.idx.len();
+    if !statement.predicate.is_valid(len) {
+        return Err(CommitError::new(format!(
+            "predicate is not valid for data of length {len}: {}",
+            statement.predicate
+        )));
+    }
+
+    let circ = build_predicate(&statement.predicate, len).ok_or_else(|| {
+        CommitError::new(format!("unsupported predicate: {}", statement.predicate))
+    })?;
+
+    let plaintext = transcript_refs(vm, statement.direction, &statement.idx)?;
+    let output = vm.new_output::<u8>(&format!("{id}/result"))?;
+
+    Ok((circ, plaintext, output))
+}
+
+/// Builds a circuit which evaluates a predicate, returning `None` if the
+/// predicate is not supported.
+///
+/// # Inputs
+///
+///   0. DATA: `len`-byte data
+///
+/// # Outputs
+///
+///   0. RESULT: `0x80` if the predicate holds, otherwise `0x00`
+fn build_predicate(predicate: &Predicate, len: usize) -> Option<Arc<Circuit>> {
+    let builder = CircuitBuilder::new();
+    let data = builder.add_vec_input::<u8>(len);
+    let constant = |value: u8| Tracer::new(builder.state(), builder.get_constant(value).to_inner());
+
+    let result = match predicate {
+        Predicate::Eq(value) => eq_bytes(
+            &data,
+            &value.iter().copied().map(constant).collect::<Vec<_>>(),
+        ),
+        Predicate::Contains(value) => {
+            let value = value.iter().copied().map(constant).collect::<Vec<_>>();
+            data.windows(value.len())
+                .map(|window| eq_bytes(window, &value))
+                .reduce(or)
+                .expect("value is not longer than the data")
+        }
+        Predicate::Lt(value)
+        | Predicate::Lte(value)
+        | Predicate::Gt(value)
+        | Predicate::Gte(value) => {
+            let digits = data
+                .iter()
+                .map(|byte| !lt(*byte, constant(b'0')) & !lt(constant(b'9'), *byte))
+                .reduce(|a, b| a & b)
+                .expect("data is not empty");
+
+            let value = value.to_string();
+            let (lt_value, eq_value) = if value.len() > len {
+                // The value has more digits than the data.
+                (constant(TRUE), constant(0))
+            } else {
+                let value = format!("{value:0>len$}")
+                    .bytes()
+                    .map(constant)
+                    .collect::<Vec<_>>();
+                cmp_bytes(&data, &value)
+            };
+
+            let holds = match predicate {
+                Predicate::Lt(_) => lt_value,
+                Predicate::Lte(_) => or(lt_value, eq_value),
+                Predicate::Gt(_) => !or(lt_value, eq_value),
+                Predicate::Gte(_) => !lt_value,
+                _ => unreachable!(),
+            };
+
+            digits & holds
+        }
+        Predicate::Regex(pattern) => dfa_matches(&regex_dfa(pattern)?, &data, constant),
+        _ => return None,
+    };
+
+    builder.add_output(result & constant(TRUE));
+
+    Some(Arc::new(builder.build().expect("circuit is valid")))
+}
+
+/// Returns whether the DFA matches all of the data in the most significant
+/// bit.
+///
+/// The circuit tracks which state the DFA is in with one bit for each state
+/// which is reachable from the start state. After each byte, a state is
+/// active if an active state has a transition to it on the byte.
+fn dfa_matches<'a>(
+    dfa: &dense::DFA<Vec<u32>>,
+    data: &[Tracer<'a, U8>],
+    constant: impl Fn(u8) -> Tracer<'a, U8>,
+) -> Tracer<'a, U8> {
+    let start = dfa
+        .start_state(&start::Config::new().anchored(Anchored::Yes))
+        .expect("DFA has an anchored start state");
+
+    // The bytes of each equivalence class, which the DFA does not distinguish.
+    let classes = dfa.byte_classes();
+    let mut class_bytes = vec![Vec::new(); classes.alphabet_len()];
+    for byte in 0..=u8::MAX {
+        class_bytes[classes.get(byte) as usize].push(byte);
+    }
+    class_bytes.retain(|bytes| !bytes.is_empty());
+
+    // Number the reachable states, and collect their transitions to states
+    // other than the dead state as pairs of byte class and target state.
+    let mut ids = HashMap::from([(start, 0)]);
+    let mut states = vec![start];
+    let mut transitions = Vec::new();
+    while let Some(state) = states.get(transitions.len()).copied() {
+        let mut targets = Vec::new();
+        for (class, bytes) in class_bytes.iter().enumerate() {
+            let next = dfa.next_state(state, bytes[0]);
+            if dfa.is_dead_state(next) {
+                continue;
+            }
+
+            let id = *ids.entry(next).or_insert_with(|| {
+                states.push(next);
+                states.len() - 1
+            });
+            targets.push((class, id));
+        }
+        transitions.push(targets);
+    }
+
+    // Inactive states are `None`, so that no gates are spent on them.
+    let mut active = vec![None; states.len()];
+    active[0] = Some(constant(TRUE));
+    for byte in data {
+        let in_class = class_bytes
+            .iter()
+            .map(|bytes| in_bytes(*byte, bytes, &constant))
+            .collect::<Vec<_>>();
+
+        let mut next = vec![None; states.len()];
+        for (state, targets) in active.iter().zip(&transitions) {
+            let Some(state) = state else {
+                continue;
+            };
+            for (class, target) in targets {
+                let bit = *state & in_class[*class];
+                next[*target] = Some(match next[*target] {
+                    Some(acc) => or(acc, bit),
+                    None => bit,
+                });
+            }
+        }
+        active = next;
+    }
+
+    // The data is matched if the DFA is in a state which matches at the end of
+    // the input.
+    active
+        .into_iter()
+        .zip(&states)
+        .filter(|(_, state)| dfa.is_match_state(dfa.next_eoi_state(**state)))
+        .filter_map(|(bit, _)| bit)
+        .reduce(or)
+        .unwrap_or_else(|| constant(0))
+}
+
+/// Returns whether the byte is one of the sorted bytes in the most significant
+/// bit.
+fn in_bytes<'a>(
+    byte: Tracer<'a, U8>,
+    bytes: &[u8],
+    constant: impl Fn(u8) -> Tracer<'a, U8>,
+) -> Tracer<'a, U8> {
+    // Split the bytes into ranges of consecutive bytes.
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+    for value in bytes {
+        match ranges.last_mut() {
+            Some((_, end)) if end.checked_add(1) == Some(*value) => *end = *value,
+            _ => ranges.push((*value, *value)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| match (start, end) {
+            (0, u8::MAX) => constant(TRUE),
+            (start, end) if start == end => eq(byte, constant(start)),
+            (0, end) => !lt(constant(end), byte),
+            (start, u8::MAX) => !lt(byte, constant(start)),
+            (start, end) => !lt(byte, constant(start)) & !lt(constant(end), byte),
+        })
+        .reduce(or)
+        .expect("byte class is not empty")
+}
+
+/// Returns `a | b`.
+fn or<'a>(a: Tracer<'a, U8>, b: Tracer<'a, U8>) -> Tracer<'a, U8> {
+    !(!a & !b)
+}
+
+/// Returns whether `a < b` in the most significant bit.
+///
+/// This is the borrow out of `a - b`.
+fn lt<'a>(a: Tracer<'a, U8>, b: Tracer<'a, U8>) -> Tracer<'a, U8> {
+    or(!a & b, !(a ^ b) & a.wrapping_sub(b))
+}
+
+/// Returns whether `a == b` in the most significant bit.
+fn eq<'a>(a: Tracer<'a, U8>, b: Tracer<'a, U8>) -> Tracer<'a, U8> {
+    !or(lt(a, b), lt(b, a))
+}
+
+/// Returns whether the byte strings are equal in the most significant bit.
+fn eq_bytes<'a>(a: &[Tracer<'a, U8>], b: &[Tracer<'a, U8>]) -> Tracer<'a, U8> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| eq(*a, *b))
+        .reduce(|a, b| a & b)
+        .expect("byte strings are not empty")
+}
+
+/// Compares byte strings of the same length lexicographically, returning
+/// whether `a < b` and `a == b` in the most significant bit.
+fn cmp_bytes<'a>(a: &[Tracer<'a, U8>], b: &[Tracer<'a, U8>]) -> (Tracer<'a, U8>, Tracer<'a, U8>) {
+    let mut pairs = a.iter().zip(b).rev();
+    let (a_last, b_last) = pairs.next().expect("byte strings are not empty");
+
+    let mut lt_acc = lt(*a_last, *b_last);
+    let mut eq_acc = eq(*a_last, *b_last);
+    for (a, b) in pairs {
+        let eq_byte = eq(*a, *b);
+        lt_acc = or(lt(*a, *b), eq_byte & lt_acc);
+        eq_acc = eq_byte & eq_acc;
+    }
+
+    (lt_acc, eq_acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mpz_circuits::types::Value;
+
+    fn eval(predicate: Predicate, data: &[u8]) -> bool {
+        let circ = build_predicate(&predicate, data.len()).unwrap();
+        let output = circ.evaluate(&[Value::from(data.to_vec())]).unwrap();
+        let result: u8 = output[0].clone().try_into().unwrap();
+
+        let expected = predicate.eval(data);
+        assert_eq!(result == TRUE, expected);
+
+        expected
+    }
+
+    #[test]
+    fn test_predicate_circuits() {
+        assert!(eval(Predicate::Eq(b"abc".to_vec()), b"abc"));
+        assert!(!eval(Predicate::Eq(b"abc".to_vec()), b"abd"));
+        assert!(eval(Predicate::Contains(b"bc".to_vec()), b"abcd"));
+        assert!(!eval(Predicate::Contains(b"ce".to_vec()), b"abcd"));
+        assert!(eval(Predicate::Gt(1000), b"1001"));
+        assert!(!eval(Predicate::Gt(1000), b"1000"));
+        assert!(eval(Predicate::Gte(1000), b"1000"));
+        assert!(eval(Predicate::Lte(1000), b"0999"));
+        assert!(eval(Predicate::Lt(20060101), b"20051231"));
+        assert!(!eval(Predicate::Lt(20060101), b"20060101"));
+        assert!(eval(Predicate::Lt(100000), b"9999"));
+        assert!(!eval(Predicate::Gt(5), b"1a"));
+    }
+
+    #[test]
+    fn test_regex_circuit() {
+        let date = || Predicate::Regex(r"[0-9]{4}-[0-9]{2}-[0-9]{2}".to_string());
+        assert!(eval(date(), b"2005-12-31"));
+        assert!(!eval(date(), b"2005-12-3"));
+        assert!(!eval(date(), b"2005-12-311"));
+        assert!(!eval(date(), b"x2005-12-31"));
+
+        let alternation = || Predicate::Regex("abc|abcd".to_string());
+        assert!(eval(alternation(), b"abc"));
+        assert!(eval(alternation(), b"abcd"));
+        assert!(!eval(alternation(), b"abcde"));
+
+        let email = || Predicate::Regex(r"\w+@example\.com".to_string());
+        assert!(eval(email(), b"bob@example.com"));
+        assert!(!eval(email(), b"bob@example.comx"));
+
+        assert!(eval(Predicate::Regex(r"\xFF.*".to_string()), b"\xff\x00a"));
+        assert!(!eval(Predicate::Regex(r"(a|b)*c".to_string()), b"ababcc"));
+    }
+}
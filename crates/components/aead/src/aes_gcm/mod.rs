@@ -1,13 +1,11 @@
 //! This module provides an implementation of 2PC AES-GCM.
 
 mod config;
-mod error;
 #[cfg(feature = "mock")]
 pub mod mock;
 mod tag;
 
-pub use config::{AesGcmConfig, AesGcmConfigBuilder, AesGcmConfigBuilderError, Role};
-pub use error::AesGcmError;
+pub use config::{AesGcmConfig, AesGcmConfigBuilder, AesGcmConfigBuilderError};
 
 use async_trait::async_trait;
 use block_cipher::{Aes128, BlockCipher};
@@ -20,7 +18,7 @@ use tracing::instrument;
 
 use crate::{
     aes_gcm::tag::{compute_tag, verify_tag, TAG_LEN},
-    Aead,
+    Aead, AeadError,
 };
 
 /// MPC AES-GCM.
@@ -61,10 +59,10 @@ impl<Ctx: Context> MpcAesGcm<Ctx> {
 
 #[async_trait]
 impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
-    type Error = AesGcmError;
+    type Error = AeadError;
 
     #[instrument(level = "info", skip_all, err)]
-    async fn set_key(&mut self, key: ValueRef, iv: ValueRef) -> Result<(), AesGcmError> {
+    async fn set_key(&mut self, key: ValueRef, iv: ValueRef) -> Result<(), AeadError> {
         self.aes_block.set_key(key.clone());
         self.aes_ctr.set_key(key, iv);
 
@@ -72,19 +70,19 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
     }
 
     #[instrument(level = "info", skip_all, err)]
-    async fn decode_key_private(&mut self) -> Result<(), AesGcmError> {
+    async fn decode_key_private(&mut self) -> Result<(), AeadError> {
         self.aes_ctr
             .decode_key_private()
             .await
-            .map_err(AesGcmError::from)
+            .map_err(AeadError::from)
     }
 
     #[instrument(level = "info", skip_all, err)]
-    async fn decode_key_blind(&mut self) -> Result<(), AesGcmError> {
+    async fn decode_key_blind(&mut self) -> Result<(), AeadError> {
         self.aes_ctr
             .decode_key_blind()
             .await
-            .map_err(AesGcmError::from)
+            .map_err(AeadError::from)
     }
 
     fn set_transcript_id(&mut self, id: &str) {
@@ -92,28 +90,28 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
     }
 
     #[instrument(level = "debug", skip(self), err)]
-    async fn setup(&mut self) -> Result<(), AesGcmError> {
+    async fn setup(&mut self) -> Result<(), AeadError> {
         self.ghash.setup().await?;
 
         Ok(())
     }
 
     #[instrument(level = "debug", skip(self), err)]
-    async fn preprocess(&mut self, len: usize) -> Result<(), AesGcmError> {
+    async fn preprocess(&mut self, len: usize) -> Result<(), AeadError> {
         futures::try_join!(
             // Preprocess the GHASH key block.
             self.aes_block
                 .preprocess(block_cipher::Visibility::Public, 1)
-                .map_err(AesGcmError::from),
-            self.aes_ctr.preprocess(len).map_err(AesGcmError::from),
-            self.ghash.preprocess().map_err(AesGcmError::from),
+                .map_err(AeadError::from),
+            self.aes_ctr.preprocess(len).map_err(AeadError::from),
+            self.ghash.preprocess().map_err(AeadError::from),
         )?;
 
         Ok(())
     }
 
     #[instrument(level = "debug", skip_all, err)]
-    async fn start(&mut self) -> Result<(), AesGcmError> {
+    async fn start(&mut self) -> Result<(), AeadError> {
         let h_share = self.aes_block.encrypt_share(vec![0u8; 16]).await?;
         self.ghash.set_key(h_share).await?;
 
@@ -126,7 +124,7 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         plaintext: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<Vec<u8>, AesGcmError> {
+    ) -> Result<Vec<u8>, AeadError> {
         let ciphertext = self
             .aes_ctr
             .encrypt_public(explicit_nonce.clone(), plaintext)
@@ -154,7 +152,7 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         plaintext: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<Vec<u8>, AesGcmError> {
+    ) -> Result<Vec<u8>, AeadError> {
         let ciphertext = self
             .aes_ctr
             .encrypt_private(explicit_nonce.clone(), plaintext)
@@ -182,7 +180,7 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         plaintext_len: usize,
         aad: Vec<u8>,
-    ) -> Result<Vec<u8>, AesGcmError> {
+    ) -> Result<Vec<u8>, AeadError> {
         let ciphertext = self
             .aes_ctr
             .encrypt_blind(explicit_nonce.clone(), plaintext_len)
@@ -210,11 +208,11 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         mut payload: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<Vec<u8>, AesGcmError> {
+    ) -> Result<Vec<u8>, AeadError> {
         let purported_tag: [u8; TAG_LEN] = payload
             .split_off(payload.len() - TAG_LEN)
             .try_into()
-            .map_err(|_| AesGcmError::payload("payload is not long enough to contain tag"))?;
+            .map_err(|_| AeadError::payload("payload is not long enough to contain tag"))?;
         let ciphertext = payload;
 
         verify_tag(
@@ -243,11 +241,11 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         mut payload: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<Vec<u8>, AesGcmError> {
+    ) -> Result<Vec<u8>, AeadError> {
         let purported_tag: [u8; TAG_LEN] = payload
             .split_off(payload.len() - TAG_LEN)
             .try_into()
-            .map_err(|_| AesGcmError::payload("payload is not long enough to contain tag"))?;
+            .map_err(|_| AeadError::payload("payload is not long enough to contain tag"))?;
         let ciphertext = payload;
 
         verify_tag(
@@ -276,11 +274,11 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         mut payload: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<(), AesGcmError> {
+    ) -> Result<(), AeadError> {
         let purported_tag: [u8; TAG_LEN] = payload
             .split_off(payload.len() - TAG_LEN)
             .try_into()
-            .map_err(|_| AesGcmError::payload("payload is not long enough to contain tag"))?;
+            .map_err(|_| AeadError::payload("payload is not long enough to contain tag"))?;
         let ciphertext = payload;
 
         verify_tag(
@@ -308,11 +306,11 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         mut payload: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<(), AesGcmError> {
+    ) -> Result<(), AeadError> {
         let purported_tag: [u8; TAG_LEN] = payload
             .split_off(payload.len() - TAG_LEN)
             .try_into()
-            .map_err(|_| AesGcmError::payload("payload is not long enough to contain tag"))?;
+            .map_err(|_| AeadError::payload("payload is not long enough to contain tag"))?;
         let ciphertext = payload;
 
         verify_tag(
@@ -336,11 +334,11 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         mut payload: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<Vec<u8>, AesGcmError> {
+    ) -> Result<Vec<u8>, AeadError> {
         let purported_tag: [u8; TAG_LEN] = payload
             .split_off(payload.len() - TAG_LEN)
             .try_into()
-            .map_err(|_| AesGcmError::payload("payload is not long enough to contain tag"))?;
+            .map_err(|_| AeadError::payload("payload is not long enough to contain tag"))?;
         let ciphertext = payload;
 
         verify_tag(
@@ -368,10 +366,10 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         &mut self,
         explicit_nonce: Vec<u8>,
         ciphertext: Vec<u8>,
-    ) -> Result<Vec<u8>, AesGcmError> {
+    ) -> Result<Vec<u8>, AeadError> {
         self.aes_ctr
             .prove_plaintext(explicit_nonce, ciphertext)
-            .map_err(AesGcmError::from)
+            .map_err(AeadError::from)
             .await
     }
 
@@ -381,11 +379,11 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         explicit_nonce: Vec<u8>,
         mut payload: Vec<u8>,
         aad: Vec<u8>,
-    ) -> Result<(), AesGcmError> {
+    ) -> Result<(), AeadError> {
         let purported_tag: [u8; TAG_LEN] = payload
             .split_off(payload.len() - TAG_LEN)
             .try_into()
-            .map_err(|_| AesGcmError::payload("payload is not long enough to contain tag"))?;
+            .map_err(|_| AeadError::payload("payload is not long enough to contain tag"))?;
         let ciphertext = payload;
 
         verify_tag(
@@ -412,10 +410,10 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
         &mut self,
         explicit_nonce: Vec<u8>,
         ciphertext: Vec<u8>,
-    ) -> Result<(), AesGcmError> {
+    ) -> Result<(), AeadError> {
         self.aes_ctr
             .verify_plaintext(explicit_nonce, ciphertext)
-            .map_err(AesGcmError::from)
+            .map_err(AeadError::from)
             .await
     }
 }
@@ -424,11 +422,11 @@ impl<Ctx: Context> Aead for MpcAesGcm<Ctx> {
 mod tests {
     use super::*;
     use crate::{
-        aes_gcm::{mock::create_mock_aes_gcm_pair, AesGcmConfigBuilder, Role},
-        Aead,
+        aes_gcm::{mock::create_mock_aes_gcm_pair, AesGcmConfigBuilder},
+        error::ErrorKind,
+        Aead, Role,
     };
     use ::aes_gcm::{aead::AeadInPlace, Aes128Gcm, NewAead, Nonce};
-    use error::ErrorKind;
     use mpz_common::executor::STExecutor;
     use mpz_garble::{protocol::deap::mock::create_mock_deap_vm, Memory};
     use serio::channel::MemoryDuplex;
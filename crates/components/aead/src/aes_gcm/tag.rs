@@ -11,7 +11,7 @@ use tlsn_stream_cipher::{Aes128Ctr, StreamCipher};
 use tlsn_universal_hash::UniversalHash;
 use tracing::instrument;
 
-use crate::aes_gcm::{AesGcmError, Role};
+use crate::{AeadError, Role};
 
 pub(crate) const TAG_LEN: usize = 16;
 
@@ -39,14 +39,14 @@ async fn compute_tag_share<C: StreamCipher<Aes128Ctr> + ?Sized, H: UniversalHash
     explicit_nonce: Vec<u8>,
     ciphertext: Vec<u8>,
     aad: Vec<u8>,
-) -> Result<TagShare, AesGcmError> {
+) -> Result<TagShare, AeadError> {
     let (j0, hash) = futures::try_join!(
         aes_ctr
             .share_keystream_block(explicit_nonce, 1)
-            .map_err(AesGcmError::from),
+            .map_err(AeadError::from),
         hasher
             .finalize(build_ghash_data(aad, ciphertext))
-            .map_err(AesGcmError::from)
+            .map_err(AeadError::from)
     )?;
 
     debug_assert!(j0.len() == TAG_LEN);
@@ -73,7 +73,7 @@ pub(crate) async fn compute_tag<
     explicit_nonce: Vec<u8>,
     ciphertext: Vec<u8>,
     aad: Vec<u8>,
-) -> Result<[u8; TAG_LEN], AesGcmError> {
+) -> Result<[u8; TAG_LEN], AeadError> {
     let tag_share = compute_tag_share(aes_ctr, hasher, explicit_nonce, ciphertext, aad).await?;
 
     // TODO: The follower doesn't really need to learn the tag,
@@ -108,7 +108,7 @@ pub(crate) async fn verify_tag<
     ciphertext: Vec<u8>,
     aad: Vec<u8>,
     purported_tag: [u8; TAG_LEN],
-) -> Result<(), AesGcmError> {
+) -> Result<(), AeadError> {
     let tag_share = compute_tag_share(aes_ctr, hasher, explicit_nonce, ciphertext, aad).await?;
 
     let io = ctx.io_mut();
@@ -137,9 +137,9 @@ pub(crate) async fn verify_tag<
             let decommitment: Decommitment<TagShare> = io.expect_next().await?;
 
             // Verify decommitment.
-            decommitment.verify(&commitment).map_err(|_| {
-                AesGcmError::peer("leader tag share commitment verification failed")
-            })?;
+            decommitment
+                .verify(&commitment)
+                .map_err(|_| AeadError::peer("leader tag share commitment verification failed"))?;
 
             let leader_tag_share = decommitment.into_inner();
 
@@ -149,7 +149,7 @@ pub(crate) async fn verify_tag<
 
     // Reject if tag is incorrect.
     if tag != purported_tag {
-        return Err(AesGcmError::invalid_tag());
+        return Err(AeadError::invalid_tag());
     }
 
     Ok(())
@@ -0,0 +1,180 @@
+//! This module provides the circuits used for computing Poly1305 tags.
+
+use std::sync::Arc;
+
+use mpz_circuits::{
+    circuits::big_num::nbyte_add_mod_trace, once_cell::sync::Lazy, Circuit, CircuitBuilder, Tracer,
+};
+use tlsn_stream_cipher::chacha20_block;
+
+/// The Poly1305 prime `2^130 - 5`, big-endian.
+static P1305: [u8; 17] = [
+    0x03, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xfb,
+];
+
+/// `2^128`, big-endian.
+static TWO_POW_128: [u8; 17] = [
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00,
+];
+
+/// The clamping mask for `r`, little-endian.
+static R_CLAMP: [u8; 16] = [
+    0xff, 0xff, 0xff, 0x0f, 0xfc, 0xff, 0xff, 0x0f, 0xfc, 0xff, 0xff, 0x0f, 0xfc, 0xff, 0xff, 0x0f,
+];
+
+/// Derives the one-time Poly1305 key from the first ChaCha20 keystream block,
+/// masking `r` so it can be revealed to one party as an additive share.
+///
+/// # Inputs
+///
+///   0. KEY: 32-byte encryption key
+///   1. IV: 12-byte IV
+///   2. EXPLICIT_NONCE: 8-byte sequence number
+///   3. MASK: 17-byte big-endian field element
+///
+/// # Outputs
+///
+///   0. R_MASKED: 17-byte big-endian `r + MASK mod 2^130 - 5`
+///   1. S: 16-byte little-endian `s`
+pub(crate) static POLY1305_KEY: Lazy<Arc<Circuit>> = Lazy::new(|| {
+    let builder = CircuitBuilder::new();
+    let key = builder.add_array_input::<u8, 32>();
+    let iv = builder.add_array_input::<u8, 12>();
+    let explicit_nonce = builder.add_array_input::<u8, 8>();
+    let mask = builder.add_array_input::<u8, 17>();
+
+    let constant = |value: u8| Tracer::new(builder.state(), builder.get_constant(value).to_inner());
+
+    // The nonce is the IV XORed with the left-padded sequence number.
+    let nonce = iv[..4]
+        .iter()
+        .copied()
+        .chain(
+            iv[4..]
+                .iter()
+                .zip(explicit_nonce)
+                .map(|(iv, seq)| *iv ^ seq),
+        )
+        .collect::<Vec<_>>();
+    let ctr = [constant(0); 4];
+
+    let block = chacha20_block(&builder, &key, &nonce, &ctr);
+
+    // Clamp r and convert it to a big-endian field element.
+    let r = block[..16]
+        .iter()
+        .zip(R_CLAMP)
+        .map(|(byte, clamp)| *byte & constant(clamp))
+        .collect::<Vec<_>>();
+    let r: [_; 17] = std::array::from_fn(|i| if i == 0 { constant(0) } else { r[16 - i] });
+
+    let r_masked = nbyte_add_mod_trace(builder.state(), r, mask, P1305);
+    let s: [_; 16] = std::array::from_fn(|i| block[16 + i]);
+
+    builder.add_output(r_masked);
+    builder.add_output(s);
+
+    Arc::new(builder.build().expect("poly1305 key circuit is valid"))
+});
+
+/// Combines the shares of the Poly1305 polynomial evaluation and adds `s`.
+///
+/// # Inputs
+///
+///   0. H_LEADER: 17-byte big-endian field element
+///   1. H_FOLLOWER: 17-byte big-endian field element
+///   2. S: 16-byte little-endian `s`
+///
+/// # Outputs
+///
+///   0. TAG: 16-byte `(H_LEADER + H_FOLLOWER mod 2^130 - 5) + S mod 2^128`
+pub(crate) static POLY1305_TAG: Lazy<Arc<Circuit>> = Lazy::new(|| {
+    let builder = CircuitBuilder::new();
+    let h_leader = builder.add_array_input::<u8, 17>();
+    let h_follower = builder.add_array_input::<u8, 17>();
+    let s = builder.add_array_input::<u8, 16>();
+
+    let constant = |value: u8| Tracer::new(builder.state(), builder.get_constant(value).to_inner());
+
+    let h = nbyte_add_mod_trace(builder.state(), h_leader, h_follower, P1305);
+
+    // Both summands are reduced below 2^128 and converted to big-endian.
+    let h: [_; 17] = std::array::from_fn(|i| if i == 0 { constant(0) } else { h[i] });
+    let s: [_; 17] = std::array::from_fn(|i| if i == 0 { constant(0) } else { s[16 - i] });
+
+    let tag = nbyte_add_mod_trace(builder.state(), h, s, TWO_POW_128);
+    let tag: [_; 16] = std::array::from_fn(|i| tag[16 - i]);
+
+    builder.add_output(tag);
+
+    Arc::new(builder.build().expect("poly1305 tag circuit is valid"))
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mpz_circuits::types::Value;
+    use mpz_fields::Field;
+    use poly1305::{universal_hash::KeyInit, Poly1305};
+    use tlsn_stream_cipher::{ChaCha20, CtrCircuit};
+    use tlsn_universal_hash::poly1305::P1305 as Field1305;
+
+    #[test]
+    fn test_poly1305_circuits() {
+        let key = [1u8; 32];
+        let iv = [2u8; 12];
+        let explicit_nonce = 3u64.to_be_bytes();
+        let mask = Field1305::new(u128::MAX) + Field1305::new(42);
+
+        let output = POLY1305_KEY
+            .evaluate(&[
+                Value::from(key.to_vec()),
+                Value::from(iv.to_vec()),
+                Value::from(explicit_nonce.to_vec()),
+                Value::from(mask.to_be_bytes()),
+            ])
+            .unwrap();
+        let r_masked: Vec<u8> = output[0].clone().try_into().unwrap();
+        let s: Vec<u8> = output[1].clone().try_into().unwrap();
+
+        let poly_key: [u8; 32] =
+            ChaCha20::apply_keystream(&key, &iv, 0, &explicit_nonce, &[0u8; 32])
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        let mut r = u128::from_le_bytes(poly_key[..16].try_into().unwrap());
+        r &= 0x0ffffffc_0ffffffc_0ffffffc_0fffffff;
+        let r_share = Field1305::from_be_bytes_mod_order(&r_masked) - mask;
+
+        assert_eq!(r_share, Field1305::new(r));
+        assert_eq!(s, poly_key[16..].to_vec());
+
+        // Evaluate a message with the reference implementation, then split the
+        // polynomial evaluation into shares.
+        let message = [5u8; 48];
+        let expected = Poly1305::new(&poly_key.into()).compute_unpadded(&message);
+
+        let h = message.chunks(16).fold(Field1305::zero(), |acc, chunk| {
+            let mut block = chunk.to_vec();
+            block.push(1);
+            (acc + Field1305::from_le_bytes_mod_order(&block)) * Field1305::new(r)
+        });
+        let h_leader = Field1305::new(7);
+        let h_follower = h - h_leader;
+
+        let output = POLY1305_TAG
+            .evaluate(&[
+                Value::from(h_leader.to_be_bytes()),
+                Value::from(h_follower.to_be_bytes()),
+                Value::from(s),
+            ])
+            .unwrap();
+        let tag: Vec<u8> = output[0].clone().try_into().unwrap();
+
+        assert_eq!(tag, expected.into_bytes().to_vec());
+    }
+}
@@ -0,0 +1,30 @@
+use derive_builder::Builder;
+
+use crate::Role;
+
+/// Configuration for ChaCha20-Poly1305.
+#[derive(Debug, Clone, Builder)]
+pub struct ChaCha20Poly1305Config {
+    /// The id of this instance.
+    #[builder(setter(into))]
+    id: String,
+    /// The protocol role.
+    role: Role,
+}
+
+impl ChaCha20Poly1305Config {
+    /// Creates a new builder for the ChaCha20-Poly1305 configuration.
+    pub fn builder() -> ChaCha20Poly1305ConfigBuilder {
+        ChaCha20Poly1305ConfigBuilder::default()
+    }
+
+    /// Returns the id of this instance.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the protocol role.
+    pub fn role(&self) -> &Role {
+        &self.role
+    }
+}
@@ -0,0 +1,78 @@
+//! Mock implementation of ChaCha20-Poly1305 for testing purposes.
+
+use mpz_common::executor::test_st_executor;
+use mpz_garble::protocol::deap::mock::{MockFollower, MockLeader};
+use mpz_ot::ideal::ot::ideal_ot;
+use tlsn_stream_cipher::{MpcStreamCipher, StreamCipherConfig};
+use tlsn_universal_hash::poly1305::ideal_poly1305;
+
+use super::*;
+
+/// Creates a mock ChaCha20-Poly1305 pair.
+///
+/// # Arguments
+///
+/// * `id` - The id of the ChaCha20-Poly1305 instances.
+/// * `(leader, follower)` - The leader and follower vms.
+/// * `leader_config` - The configuration of the leader.
+/// * `follower_config` - The configuration of the follower.
+pub async fn create_mock_chacha20_poly1305_pair(
+    id: &str,
+    (leader, follower): (MockLeader, MockFollower),
+    leader_config: ChaCha20Poly1305Config,
+    follower_config: ChaCha20Poly1305Config,
+) -> (
+    MpcChaCha20Poly1305<MockLeader>,
+    MpcChaCha20Poly1305<MockFollower>,
+) {
+    let (ctx_leader, ctx_follower) = test_st_executor(128);
+
+    let (leader_ot_send, follower_ot_recv) = ideal_ot();
+    let (follower_ot_send, leader_ot_recv) = ideal_ot();
+
+    let tag_leader = leader
+        .new_thread(ctx_leader, leader_ot_send, leader_ot_recv)
+        .unwrap();
+
+    let tag_follower = follower
+        .new_thread(ctx_follower, follower_ot_send, follower_ot_recv)
+        .unwrap();
+
+    // The first keystream block is used for the Poly1305 key.
+    let stream_cipher_id = format!("{}/stream_cipher", id);
+    let leader_stream_cipher = MpcStreamCipher::<ChaCha20, _>::new(
+        StreamCipherConfig::builder()
+            .id(stream_cipher_id.clone())
+            .start_ctr(1)
+            .build()
+            .unwrap(),
+        leader,
+    );
+    let follower_stream_cipher = MpcStreamCipher::<ChaCha20, _>::new(
+        StreamCipherConfig::builder()
+            .id(stream_cipher_id.clone())
+            .start_ctr(1)
+            .build()
+            .unwrap(),
+        follower,
+    );
+
+    let (ctx_a, ctx_b) = test_st_executor(128);
+    let (leader_poly1305, follower_poly1305) = ideal_poly1305(ctx_a, ctx_b);
+
+    let leader = MpcChaCha20Poly1305::new(
+        leader_config,
+        tag_leader,
+        Box::new(leader_stream_cipher),
+        Box::new(leader_poly1305),
+    );
+
+    let follower = MpcChaCha20Poly1305::new(
+        follower_config,
+        tag_follower,
+        Box::new(follower_stream_cipher),
+        Box::new(follower_poly1305),
+    );
+
+    (leader, follower)
+}
@@ -0,0 +1,672 @@
+//! This module provides an implementation of 2PC ChaCha20-Poly1305, using the
+//! TLS 1.2 nonce construction of RFC 7905.
+//!
+//! The one-time Poly1305 key of each message is derived from the first
+//! ChaCha20 keystream block inside the garbled circuit. Its `r` half is
+//! revealed to the leader masked with a random field element chosen by the
+//! follower, which gives both parties additive shares of `r` for computing
+//! the polynomial evaluation with [`UniversalHash`]. The `s` half never leaves
+//! the circuit, and the shares of the evaluation are combined with `s` in a
+//! second circuit which reveals the tag to both parties.
+
+mod circuit;
+mod config;
+#[cfg(feature = "mock")]
+pub mod mock;
+
+pub use config::{
+    ChaCha20Poly1305Config, ChaCha20Poly1305ConfigBuilder, ChaCha20Poly1305ConfigBuilderError,
+};
+
+use async_trait::async_trait;
+use futures::TryFutureExt;
+use mpz_fields::Field;
+use mpz_garble::{value::ValueRef, Decode, DecodePrivate, Execute, Memory, Thread};
+use rand::Rng;
+use tlsn_stream_cipher::{ChaCha20, StreamCipher};
+use tlsn_universal_hash::{poly1305::P1305, UniversalHash};
+use tracing::instrument;
+
+use crate::{
+    chacha20_poly1305::circuit::{POLY1305_KEY, POLY1305_TAG},
+    Aead, AeadError, Role,
+};
+
+/// The length of the Poly1305 tag.
+const TAG_LEN: usize = 16;
+
+/// MPC ChaCha20-Poly1305.
+pub struct MpcChaCha20Poly1305<T> {
+    config: ChaCha20Poly1305Config,
+    /// Thread used for deriving the Poly1305 key and computing the tag.
+    thread: T,
+    key_iv: Option<(ValueRef, ValueRef)>,
+    chacha20: Box<dyn StreamCipher<ChaCha20>>,
+    poly1305: Box<dyn UniversalHash>,
+    /// Number of tags computed.
+    counter: usize,
+}
+
+impl<T> std::fmt::Debug for MpcChaCha20Poly1305<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MpcChaCha20Poly1305")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl<T> MpcChaCha20Poly1305<T>
+where
+    T: Thread + Memory + Execute + Decode + DecodePrivate + Send + Sync + 'static,
+{
+    /// Creates a new instance of [`MpcChaCha20Poly1305`].
+    ///
+    /// The stream cipher must be configured to start at counter 1, as the
+    /// first keystream block is used for the Poly1305 key.
+    pub fn new(
+        config: ChaCha20Poly1305Config,
+        thread: T,
+        chacha20: Box<dyn StreamCipher<ChaCha20>>,
+        poly1305: Box<dyn UniversalHash>,
+    ) -> Self {
+        Self {
+            config,
+            thread,
+            key_iv: None,
+            chacha20,
+            poly1305,
+            counter: 0,
+        }
+    }
+
+    /// Derives the Poly1305 key for the given explicit nonce, setting the
+    /// share of `r` and returning a reference to `s`.
+    async fn derive_poly1305_key(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+    ) -> Result<ValueRef, AeadError> {
+        let (key, iv) = self
+            .key_iv
+            .clone()
+            .ok_or_else(|| AeadError::payload("key not set"))?;
+        let explicit_nonce: [u8; 8] = explicit_nonce
+            .try_into()
+            .map_err(|_| AeadError::payload("explicit nonce must be 8 bytes"))?;
+
+        let id = format!("{}/poly1305/{}", self.config.id(), self.counter);
+        self.counter += 1;
+
+        let nonce_ref = self
+            .thread
+            .new_public_input::<[u8; 8]>(&format!("{id}/nonce"))?;
+        self.thread.assign(&nonce_ref, explicit_nonce)?;
+
+        let mask_id = format!("{id}/mask");
+        let (mask_ref, mask) = match self.config.role() {
+            Role::Leader => (self.thread.new_blind_input::<[u8; 17]>(&mask_id)?, None),
+            Role::Follower => {
+                let mask: P1305 = rand::thread_rng().gen();
+                let mask_ref = self.thread.new_private_input::<[u8; 17]>(&mask_id)?;
+                self.thread.assign(
+                    &mask_ref,
+                    <[u8; 17]>::try_from(mask.to_be_bytes()).expect("field element is 17 bytes"),
+                )?;
+                (mask_ref, Some(mask))
+            }
+        };
+
+        let r_masked_ref = self
+            .thread
+            .new_output::<[u8; 17]>(&format!("{id}/r_masked"))?;
+        let s_ref = self.thread.new_output::<[u8; 16]>(&format!("{id}/s"))?;
+
+        self.thread
+            .execute(
+                POLY1305_KEY.clone(),
+                &[key, iv, nonce_ref, mask_ref],
+                &[r_masked_ref.clone(), s_ref.clone()],
+            )
+            .await?;
+
+        let r_share = match mask {
+            None => {
+                let r_masked: [u8; 17] = self
+                    .thread
+                    .decode_private(&[r_masked_ref])
+                    .await?
+                    .pop()
+                    .expect("decoded 1 value")
+                    .try_into()
+                    .expect("r is 17 bytes");
+                P1305::from_be_bytes_mod_order(&r_masked)
+            }
+            Some(mask) => {
+                self.thread.decode_blind(&[r_masked_ref]).await?;
+                -mask
+            }
+        };
+
+        self.poly1305.set_key(r_share.to_be_bytes()).await?;
+
+        Ok(s_ref)
+    }
+
+    /// Computes the tag for a ciphertext and additional data.
+    ///
+    /// Both parties learn the tag. This does not require a commit-reveal step
+    /// when verifying a tag, because both shares of the evaluation are inputs
+    /// to the circuit before either party learns the tag.
+    #[instrument(level = "debug", skip_all, err)]
+    async fn compute_tag(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        ciphertext: &[u8],
+        aad: &[u8],
+    ) -> Result<[u8; TAG_LEN], AeadError> {
+        let s_ref = self.derive_poly1305_key(explicit_nonce).await?;
+
+        let h_share: [u8; 17] = self
+            .poly1305
+            .finalize(build_poly1305_data(aad, ciphertext))
+            .await?
+            .try_into()
+            .map_err(|_| AeadError::payload("poly1305 output is not 17 bytes"))?;
+
+        let id = format!("{}/poly1305/{}", self.config.id(), self.counter - 1);
+        let (h_leader_id, h_follower_id) = (format!("{id}/h_leader"), format!("{id}/h_follower"));
+        let (h_leader_ref, h_follower_ref) = match self.config.role() {
+            Role::Leader => {
+                let h_leader_ref = self.thread.new_private_input::<[u8; 17]>(&h_leader_id)?;
+                self.thread.assign(&h_leader_ref, h_share)?;
+                let h_follower_ref = self.thread.new_blind_input::<[u8; 17]>(&h_follower_id)?;
+                (h_leader_ref, h_follower_ref)
+            }
+            Role::Follower => {
+                let h_leader_ref = self.thread.new_blind_input::<[u8; 17]>(&h_leader_id)?;
+                let h_follower_ref = self.thread.new_private_input::<[u8; 17]>(&h_follower_id)?;
+                self.thread.assign(&h_follower_ref, h_share)?;
+                (h_leader_ref, h_follower_ref)
+            }
+        };
+
+        let tag_ref = self
+            .thread
+            .new_output::<[u8; TAG_LEN]>(&format!("{id}/tag"))?;
+
+        self.thread
+            .execute(
+                POLY1305_TAG.clone(),
+                &[h_leader_ref, h_follower_ref, s_ref],
+                &[tag_ref.clone()],
+            )
+            .await?;
+
+        let tag: [u8; TAG_LEN] = self
+            .thread
+            .decode(&[tag_ref])
+            .await?
+            .pop()
+            .expect("decoded 1 value")
+            .try_into()
+            .expect("tag is 16 bytes");
+
+        Ok(tag)
+    }
+
+    /// Splits the tag off the payload and verifies it.
+    async fn verify_payload(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        mut payload: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        let purported_tag: [u8; TAG_LEN] =
+            payload
+                .split_off(payload.len().checked_sub(TAG_LEN).ok_or_else(|| {
+                    AeadError::payload("payload is not long enough to contain tag")
+                })?)
+                .try_into()
+                .expect("tag is 16 bytes");
+        let ciphertext = payload;
+
+        let tag = self.compute_tag(explicit_nonce, &ciphertext, &aad).await?;
+
+        // Reject if tag is incorrect.
+        if tag != purported_tag {
+            return Err(AeadError::invalid_tag());
+        }
+
+        Ok(ciphertext)
+    }
+}
+
+#[async_trait]
+impl<T> Aead for MpcChaCha20Poly1305<T>
+where
+    T: Thread + Memory + Execute + Decode + DecodePrivate + Send + Sync + 'static,
+{
+    type Error = AeadError;
+
+    #[instrument(level = "info", skip_all, err)]
+    async fn set_key(&mut self, key: ValueRef, iv: ValueRef) -> Result<(), AeadError> {
+        self.chacha20.set_key(key.clone(), iv.clone());
+        self.key_iv = Some((key, iv));
+
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip_all, err)]
+    async fn decode_key_private(&mut self) -> Result<(), AeadError> {
+        self.chacha20
+            .decode_key_private()
+            .await
+            .map_err(AeadError::from)
+    }
+
+    #[instrument(level = "info", skip_all, err)]
+    async fn decode_key_blind(&mut self) -> Result<(), AeadError> {
+        self.chacha20
+            .decode_key_blind()
+            .await
+            .map_err(AeadError::from)
+    }
+
+    fn set_transcript_id(&mut self, id: &str) {
+        self.chacha20.set_transcript_id(id)
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn setup(&mut self) -> Result<(), AeadError> {
+        self.poly1305.setup().await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn preprocess(&mut self, len: usize) -> Result<(), AeadError> {
+        futures::try_join!(
+            self.chacha20.preprocess(len).map_err(AeadError::from),
+            self.poly1305.preprocess().map_err(AeadError::from),
+        )?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn start(&mut self) -> Result<(), AeadError> {
+        // Poly1305 keys are derived per message.
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn encrypt_public(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        plaintext: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        let ciphertext = self
+            .chacha20
+            .encrypt_public(explicit_nonce.clone(), plaintext)
+            .await?;
+
+        let tag = self.compute_tag(explicit_nonce, &ciphertext, &aad).await?;
+
+        let mut payload = ciphertext;
+        payload.extend(tag);
+
+        Ok(payload)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn encrypt_private(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        plaintext: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        let ciphertext = self
+            .chacha20
+            .encrypt_private(explicit_nonce.clone(), plaintext)
+            .await?;
+
+        let tag = self.compute_tag(explicit_nonce, &ciphertext, &aad).await?;
+
+        let mut payload = ciphertext;
+        payload.extend(tag);
+
+        Ok(payload)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn encrypt_blind(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        plaintext_len: usize,
+        aad: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        let ciphertext = self
+            .chacha20
+            .encrypt_blind(explicit_nonce.clone(), plaintext_len)
+            .await?;
+
+        let tag = self.compute_tag(explicit_nonce, &ciphertext, &aad).await?;
+
+        let mut payload = ciphertext;
+        payload.extend(tag);
+
+        Ok(payload)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn decrypt_public(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        payload: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        let ciphertext = self
+            .verify_payload(explicit_nonce.clone(), payload, aad)
+            .await?;
+
+        let plaintext = self
+            .chacha20
+            .decrypt_public(explicit_nonce, ciphertext)
+            .await?;
+
+        Ok(plaintext)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn decrypt_private(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        payload: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        let ciphertext = self
+            .verify_payload(explicit_nonce.clone(), payload, aad)
+            .await?;
+
+        let plaintext = self
+            .chacha20
+            .decrypt_private(explicit_nonce, ciphertext)
+            .await?;
+
+        Ok(plaintext)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn decrypt_blind(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        payload: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<(), AeadError> {
+        let ciphertext = self
+            .verify_payload(explicit_nonce.clone(), payload, aad)
+            .await?;
+
+        self.chacha20
+            .decrypt_blind(explicit_nonce, ciphertext)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn verify_tag(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        payload: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<(), AeadError> {
+        self.verify_payload(explicit_nonce, payload, aad).await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn prove_plaintext(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        payload: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        let ciphertext = self
+            .verify_payload(explicit_nonce.clone(), payload, aad)
+            .await?;
+
+        let plaintext = self
+            .chacha20
+            .prove_plaintext(explicit_nonce, ciphertext)
+            .await?;
+
+        Ok(plaintext)
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn prove_plaintext_no_tag(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        ciphertext: Vec<u8>,
+    ) -> Result<Vec<u8>, AeadError> {
+        self.chacha20
+            .prove_plaintext(explicit_nonce, ciphertext)
+            .map_err(AeadError::from)
+            .await
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn verify_plaintext(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        payload: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> Result<(), AeadError> {
+        let ciphertext = self
+            .verify_payload(explicit_nonce.clone(), payload, aad)
+            .await?;
+
+        self.chacha20
+            .verify_plaintext(explicit_nonce, ciphertext)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn verify_plaintext_no_tag(
+        &mut self,
+        explicit_nonce: Vec<u8>,
+        ciphertext: Vec<u8>,
+    ) -> Result<(), AeadError> {
+        self.chacha20
+            .verify_plaintext(explicit_nonce, ciphertext)
+            .map_err(AeadError::from)
+            .await
+    }
+}
+
+/// Builds the padded Poly1305 input of RFC 8439, section 2.8.
+fn build_poly1305_data(aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let padded_len = |len: usize| len.div_ceil(16) * 16;
+
+    let mut data = Vec::with_capacity(padded_len(aad.len()) + padded_len(ciphertext.len()) + 16);
+    data.extend_from_slice(aad);
+    data.resize(padded_len(aad.len()), 0);
+    data.extend_from_slice(ciphertext);
+    data.resize(padded_len(aad.len()) + padded_len(ciphertext.len()), 0);
+    data.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+    data.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chacha20_poly1305::mock::create_mock_chacha20_poly1305_pair, error::ErrorKind};
+    use ::chacha20poly1305::{aead::AeadInPlace, ChaCha20Poly1305, Key, NewAead, Nonce};
+    use mpz_garble::protocol::deap::mock::{create_mock_deap_vm, MockFollower, MockLeader};
+
+    fn reference_impl(
+        key: &[u8],
+        iv: &[u8],
+        explicit_nonce: &[u8],
+        plaintext: &[u8],
+        aad: &[u8],
+    ) -> Vec<u8> {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        let mut nonce = iv.to_vec();
+        nonce[4..]
+            .iter_mut()
+            .zip(explicit_nonce)
+            .for_each(|(nonce, seq)| *nonce ^= seq);
+
+        let mut ciphertext = plaintext.to_vec();
+        cipher
+            .encrypt_in_place(Nonce::from_slice(&nonce), aad, &mut ciphertext)
+            .unwrap();
+
+        ciphertext
+    }
+
+    async fn setup_pair(
+        key: Vec<u8>,
+        iv: Vec<u8>,
+    ) -> (
+        MpcChaCha20Poly1305<MockLeader>,
+        MpcChaCha20Poly1305<MockFollower>,
+    ) {
+        let (leader_vm, follower_vm) = create_mock_deap_vm();
+
+        let leader_key = leader_vm
+            .new_public_array_input::<u8>("key", key.len())
+            .unwrap();
+        let leader_iv = leader_vm
+            .new_public_array_input::<u8>("iv", iv.len())
+            .unwrap();
+
+        leader_vm.assign(&leader_key, key.clone()).unwrap();
+        leader_vm.assign(&leader_iv, iv.clone()).unwrap();
+
+        let follower_key = follower_vm
+            .new_public_array_input::<u8>("key", key.len())
+            .unwrap();
+        let follower_iv = follower_vm
+            .new_public_array_input::<u8>("iv", iv.len())
+            .unwrap();
+
+        follower_vm.assign(&follower_key, key.clone()).unwrap();
+        follower_vm.assign(&follower_iv, iv.clone()).unwrap();
+
+        let leader_config = ChaCha20Poly1305Config::builder()
+            .id("test")
+            .role(Role::Leader)
+            .build()
+            .unwrap();
+        let follower_config = ChaCha20Poly1305Config::builder()
+            .id("test")
+            .role(Role::Follower)
+            .build()
+            .unwrap();
+
+        let (mut leader, mut follower) = create_mock_chacha20_poly1305_pair(
+            "test",
+            (leader_vm, follower_vm),
+            leader_config,
+            follower_config,
+        )
+        .await;
+
+        futures::try_join!(
+            leader.set_key(leader_key, leader_iv),
+            follower.set_key(follower_key, follower_iv)
+        )
+        .unwrap();
+
+        futures::try_join!(leader.setup(), follower.setup()).unwrap();
+        futures::try_join!(leader.start(), follower.start()).unwrap();
+
+        (leader, follower)
+    }
+
+    #[test]
+    fn test_build_poly1305_data() {
+        let data = build_poly1305_data(&[1; 13], &[2; 17]);
+
+        assert_eq!(data.len(), 16 + 32 + 16);
+        assert_eq!(&data[..13], &[1; 13]);
+        assert_eq!(&data[16..33], &[2; 17]);
+        assert_eq!(&data[48..56], &13u64.to_le_bytes());
+        assert_eq!(&data[56..], &17u64.to_le_bytes());
+    }
+
+    #[tokio::test]
+    #[ignore = "expensive"]
+    async fn test_chacha20_poly1305_encrypt_private() {
+        let key = vec![1u8; 32];
+        let iv = vec![2u8; 12];
+        let explicit_nonce = 3u64.to_be_bytes().to_vec();
+        let plaintext = vec![4u8; 100];
+        let aad = vec![5u8; 13];
+
+        let (mut leader, mut follower) = setup_pair(key.clone(), iv.clone()).await;
+
+        let (leader_payload, follower_payload) = futures::try_join!(
+            leader.encrypt_private(explicit_nonce.clone(), plaintext.clone(), aad.clone()),
+            follower.encrypt_blind(explicit_nonce.clone(), plaintext.len(), aad.clone())
+        )
+        .unwrap();
+
+        assert_eq!(leader_payload, follower_payload);
+        assert_eq!(
+            leader_payload,
+            reference_impl(&key, &iv, &explicit_nonce, &plaintext, &aad)
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "expensive"]
+    async fn test_chacha20_poly1305_decrypt_private() {
+        let key = vec![1u8; 32];
+        let iv = vec![2u8; 12];
+        let explicit_nonce = 3u64.to_be_bytes().to_vec();
+        let plaintext = vec![4u8; 100];
+        let aad = vec![5u8; 13];
+        let payload = reference_impl(&key, &iv, &explicit_nonce, &plaintext, &aad);
+
+        let (mut leader, mut follower) = setup_pair(key.clone(), iv.clone()).await;
+
+        let (leader_plaintext, _) = futures::try_join!(
+            leader.decrypt_private(explicit_nonce.clone(), payload.clone(), aad.clone()),
+            follower.decrypt_blind(explicit_nonce.clone(), payload.clone(), aad.clone())
+        )
+        .unwrap();
+
+        assert_eq!(leader_plaintext, plaintext);
+    }
+
+    #[tokio::test]
+    #[ignore = "expensive"]
+    async fn test_chacha20_poly1305_decrypt_bad_tag() {
+        let key = vec![1u8; 32];
+        let iv = vec![2u8; 12];
+        let explicit_nonce = 3u64.to_be_bytes().to_vec();
+        let plaintext = vec![4u8; 100];
+        let aad = vec![5u8; 13];
+        let mut payload = reference_impl(&key, &iv, &explicit_nonce, &plaintext, &aad);
+        *payload.last_mut().unwrap() ^= 1;
+
+        let (mut leader, mut follower) = setup_pair(key.clone(), iv.clone()).await;
+
+        let (leader_res, follower_res) = futures::join!(
+            leader.decrypt_private(explicit_nonce.clone(), payload.clone(), aad.clone()),
+            follower.decrypt_blind(explicit_nonce.clone(), payload.clone(), aad.clone())
+        );
+
+        assert_eq!(leader_res.unwrap_err().kind(), ErrorKind::Tag);
+        assert_eq!(follower_res.unwrap_err().kind(), ErrorKind::Tag);
+    }
+}
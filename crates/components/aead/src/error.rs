@@ -1,14 +1,14 @@
 use std::fmt::Display;
 
-/// AES-GCM error.
+/// AEAD error.
 #[derive(Debug, thiserror::Error)]
-pub struct AesGcmError {
+pub struct AeadError {
     kind: ErrorKind,
     #[source]
     source: Option<Box<dyn std::error::Error + Send + Sync>>,
 }
 
-impl AesGcmError {
+impl AeadError {
     pub(crate) fn new<E>(kind: ErrorKind, source: E) -> Self
     where
         E: Into<Box<dyn std::error::Error + Send + Sync>>,
@@ -51,19 +51,21 @@ pub(crate) enum ErrorKind {
     Io,
     BlockCipher,
     StreamCipher,
-    Ghash,
+    UniversalHash,
+    Vm,
     Tag,
     PeerMisbehaved,
     Payload,
 }
 
-impl Display for AesGcmError {
+impl Display for AeadError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.kind {
             ErrorKind::Io => write!(f, "io error")?,
             ErrorKind::BlockCipher => write!(f, "block cipher error")?,
             ErrorKind::StreamCipher => write!(f, "stream cipher error")?,
-            ErrorKind::Ghash => write!(f, "ghash error")?,
+            ErrorKind::UniversalHash => write!(f, "universal hash error")?,
+            ErrorKind::Vm => write!(f, "vm error")?,
             ErrorKind::Tag => write!(f, "payload has corrupted tag")?,
             ErrorKind::PeerMisbehaved => write!(f, "peer misbehaved")?,
             ErrorKind::Payload => write!(f, "payload error")?,
@@ -77,26 +79,50 @@ impl Display for AesGcmError {
     }
 }
 
-impl From<std::io::Error> for AesGcmError {
+impl From<std::io::Error> for AeadError {
     fn from(err: std::io::Error) -> Self {
         Self::new(ErrorKind::Io, err)
     }
 }
 
-impl From<block_cipher::BlockCipherError> for AesGcmError {
+impl From<block_cipher::BlockCipherError> for AeadError {
     fn from(err: block_cipher::BlockCipherError) -> Self {
         Self::new(ErrorKind::BlockCipher, err)
     }
 }
 
-impl From<tlsn_stream_cipher::StreamCipherError> for AesGcmError {
+impl From<tlsn_stream_cipher::StreamCipherError> for AeadError {
     fn from(err: tlsn_stream_cipher::StreamCipherError) -> Self {
         Self::new(ErrorKind::StreamCipher, err)
     }
 }
 
-impl From<tlsn_universal_hash::UniversalHashError> for AesGcmError {
+impl From<tlsn_universal_hash::UniversalHashError> for AeadError {
     fn from(err: tlsn_universal_hash::UniversalHashError) -> Self {
-        Self::new(ErrorKind::Ghash, err)
+        Self::new(ErrorKind::UniversalHash, err)
+    }
+}
+
+impl From<mpz_garble::VmError> for AeadError {
+    fn from(err: mpz_garble::VmError) -> Self {
+        Self::new(ErrorKind::Vm, err)
+    }
+}
+
+impl From<mpz_garble::MemoryError> for AeadError {
+    fn from(err: mpz_garble::MemoryError) -> Self {
+        Self::new(ErrorKind::Vm, err)
+    }
+}
+
+impl From<mpz_garble::ExecutionError> for AeadError {
+    fn from(err: mpz_garble::ExecutionError) -> Self {
+        Self::new(ErrorKind::Vm, err)
+    }
+}
+
+impl From<mpz_garble::DecodeError> for AeadError {
+    fn from(err: mpz_garble::DecodeError) -> Self {
+        Self::new(ErrorKind::Vm, err)
     }
 }
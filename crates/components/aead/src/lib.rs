@@ -15,10 +15,22 @@
 #![forbid(unsafe_code)]
 
 pub mod aes_gcm;
+pub mod chacha20_poly1305;
+mod error;
+
+pub use error::AeadError;
 
 use async_trait::async_trait;
 use mpz_garble::value::ValueRef;
 
+/// Protocol role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Role {
+    Leader,
+    Follower,
+}
+
 /// This trait defines the interface for AEADs.
 #[async_trait]
 pub trait Aead: Send {
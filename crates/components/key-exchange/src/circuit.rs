@@ -10,14 +10,30 @@ static P: [u8; 32] = [
     0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
 ];
 
+/// Curve25519 prime `2^255 - 19` big-endian.
+static P25519: [u8; 32] = [
+    0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xED,
+];
+
+/// Value of the GROUP input selecting P-256.
+pub(crate) const GROUP_P256: u8 = 0x00;
+/// Value of the GROUP input selecting X25519.
+pub(crate) const GROUP_X25519: u8 = 0xFF;
+
 /// Circuit for combining additive shares of the PMS, twice
 ///
+/// The shares are big-endian field elements of the selected group. For P-256
+/// the PMS is the big-endian x coordinate, for X25519 it is the little-endian
+/// u coordinate.
+///
 /// # Inputs
 ///
 /// 0. PMS_SHARE_A0: 32 bytes PMS Additive Share
 /// 1. PMS_SHARE_B0: 32 bytes PMS Additive Share
 /// 2. PMS_SHARE_A1: 32 bytes PMS Additive Share
 /// 3. PMS_SHARE_B1: 32 bytes PMS Additive Share
+/// 4. GROUP: 1 byte, [`GROUP_P256`] or [`GROUP_X25519`]
 ///
 /// # Outputs
 /// 0. PMS_0: Pre-master Secret = PMS_SHARE_A0 + PMS_SHARE_B0
@@ -29,9 +45,24 @@ pub(crate) fn build_pms_circuit() -> Arc<Circuit> {
     let share_b0 = builder.add_array_input::<u8, 32>();
     let share_a1 = builder.add_array_input::<u8, 32>();
     let share_b1 = builder.add_array_input::<u8, 32>();
+    let group = builder.add_input::<u8>();
+
+    let mut pms = [share_a0, share_a1]
+        .into_iter()
+        .zip([share_b0, share_b1])
+        .map(|(share_a, share_b)| {
+            let p256 = nbyte_add_mod_trace(builder.state(), share_a, share_b, P);
+            let x25519 = nbyte_add_mod_trace(builder.state(), share_a, share_b, P25519);
 
-    let pms_0 = nbyte_add_mod_trace(builder.state(), share_a0, share_b0, P);
-    let pms_1 = nbyte_add_mod_trace(builder.state(), share_a1, share_b1, P);
+            // Select the sum of the chosen group, reversing the byte order for
+            // X25519.
+            let pms: [_; 32] =
+                std::array::from_fn(|i| p256[i] ^ ((p256[i] ^ x25519[31 - i]) & group));
+            pms
+        });
+
+    let pms_0 = pms.next().expect("pms_0 is computed");
+    let pms_1 = pms.next().expect("pms_1 is computed");
 
     let eq: [_; 32] = std::array::from_fn(|i| pms_0[i] ^ pms_1[i]);
 
@@ -41,3 +72,49 @@ pub(crate) fn build_pms_circuit() -> Arc<Circuit> {
 
     Arc::new(builder.build().expect("pms circuit is valid"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mpz_circuits::types::Value;
+    use mpz_fields::{p256::P256, Field};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+
+    use crate::x25519::F25519;
+
+    fn evaluate_pms<F: Field>(group: u8, a: F, b: F) -> (Vec<u8>, Vec<u8>) {
+        let output = build_pms_circuit()
+            .evaluate(&[
+                Value::from(a.to_be_bytes()),
+                Value::from(b.to_be_bytes()),
+                Value::from(a.to_be_bytes()),
+                Value::from(b.to_be_bytes()),
+                Value::from(group),
+            ])
+            .unwrap();
+
+        let pms: Vec<u8> = output[0].clone().try_into().unwrap();
+        let eq: Vec<u8> = output[2].clone().try_into().unwrap();
+
+        (pms, eq)
+    }
+
+    #[test]
+    fn test_pms_circuit() {
+        let mut rng = ChaCha12Rng::from_seed([0u8; 32]);
+
+        let (a, b): (P256, P256) = (rng.gen(), rng.gen());
+        let (pms, eq) = evaluate_pms(GROUP_P256, a, b);
+
+        assert_eq!(pms, (a + b).to_be_bytes());
+        assert_eq!(eq, vec![0u8; 32]);
+
+        let (a, b): (F25519, F25519) = (rng.gen(), rng.gen());
+        let (pms, eq) = evaluate_pms(GROUP_X25519, a, b);
+
+        assert_eq!(pms, (a + b).to_le_bytes());
+        assert_eq!(eq, vec![0u8; 32]);
+    }
+}
@@ -1,4 +1,5 @@
 use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
 
 /// Role in the key exchange protocol.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -9,11 +10,24 @@ pub enum Role {
     Follower,
 }
 
+/// A group supported by the key exchange protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Group {
+    /// NIST P-256.
+    P256,
+    /// Curve25519, ie. X25519.
+    X25519,
+}
+
 /// A config used for [MpcKeyExchange](super::MpcKeyExchange).
 #[derive(Debug, Clone, Builder)]
 pub struct KeyExchangeConfig {
     /// Protocol role.
     role: Role,
+    /// The groups which are preprocessed, one of which can be chosen by the
+    /// server.
+    #[builder(default = "vec![Group::P256]")]
+    groups: Vec<Group>,
 }
 
 impl KeyExchangeConfig {
@@ -26,4 +40,14 @@ impl KeyExchangeConfig {
     pub fn role(&self) -> &Role {
         &self.role
     }
+
+    /// Returns the groups which are preprocessed.
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    /// Returns whether the given group is enabled.
+    pub fn supports(&self, group: Group) -> bool {
+        self.groups.contains(&group)
+    }
 }
@@ -4,21 +4,37 @@ use async_trait::async_trait;
 use mpz_common::{scoped_futures::ScopedFutureExt, Allocate, Context, Preprocess};
 use mpz_garble::{value::ValueRef, Decode, Execute, Load, Memory};
 
+use curve25519_dalek::{
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    montgomery::MontgomeryPoint,
+    scalar::Scalar,
+};
 use mpz_fields::{p256::P256, Field};
 use mpz_share_conversion::{ShareConversionError, ShareConvert};
-use p256::{EncodedPoint, PublicKey, SecretKey};
+use p256::{EncodedPoint, SecretKey};
+use serde::{Deserialize, Serialize};
 use serio::{stream::IoStreamExt, SinkExt};
 use std::fmt::Debug;
 use tracing::{debug, instrument};
 
 use crate::{
-    circuit::build_pms_circuit,
-    config::{KeyExchangeConfig, Role},
+    circuit::{build_pms_circuit, GROUP_P256, GROUP_X25519},
+    config::{Group, KeyExchangeConfig, Role},
     error::ErrorKind,
-    point_addition::derive_x_coord_share,
-    KeyExchange, KeyExchangeError, Pms,
+    point_addition::{decompose_point, derive_x_coord_share},
+    x25519::{self, F25519, MONTGOMERY_A},
+    KeyExchange, KeyExchangeError, Pms, PublicKey,
 };
 
+/// The public key shares of the follower, one for each enabled group.
+#[derive(Debug, Serialize, Deserialize)]
+struct FollowerKeyShares {
+    p256: Option<p256::PublicKey>,
+    /// The compressed Edwards point, which unlike the X25519 encoding can be
+    /// added to the leader's share.
+    x25519: Option<[u8; 32]>,
+}
+
 #[derive(Debug)]
 enum State {
     Initialized,
@@ -27,6 +43,7 @@ enum State {
         share_b0: ValueRef,
         share_a1: ValueRef,
         share_b1: ValueRef,
+        group: ValueRef,
         pms_0: ValueRef,
         pms_1: ValueRef,
         eq: ValueRef,
@@ -36,6 +53,7 @@ enum State {
         share_b0: ValueRef,
         share_a1: ValueRef,
         share_b1: ValueRef,
+        group: ValueRef,
         pms_0: ValueRef,
         pms_1: ValueRef,
         eq: ValueRef,
@@ -59,17 +77,23 @@ impl State {
 /// Can be either a leader or a follower depending on the `role` field in
 /// [`KeyExchangeConfig`].
 #[derive(Debug)]
-pub struct MpcKeyExchange<Ctx, C0, C1, E> {
+pub struct MpcKeyExchange<Ctx, C0, C1, X0, X1, E> {
     ctx: Ctx,
     /// Share conversion protocol 0.
     converter_0: C0,
     /// Share conversion protocol 1.
     converter_1: C1,
+    /// X25519 share conversion protocol 0.
+    x25519_converter_0: X0,
+    /// X25519 share conversion protocol 1.
+    x25519_converter_1: X1,
     /// MPC executor.
     executor: E,
     /// The private key of the party behind this instance, either follower or
     /// leader.
     private_key: Option<SecretKey>,
+    /// The X25519 private key of the party behind this instance.
+    x25519_private_key: Option<Scalar>,
     /// The public key of the server.
     server_key: Option<PublicKey>,
     /// The config used for the key exchange protocol.
@@ -78,29 +102,36 @@ pub struct MpcKeyExchange<Ctx, C0, C1, E> {
     state: State,
 }
 
-impl<Ctx, C0, C1, E> MpcKeyExchange<Ctx, C0, C1, E> {
+impl<Ctx, C0, C1, X0, X1, E> MpcKeyExchange<Ctx, C0, C1, X0, X1, E> {
     /// Creates a new [`MpcKeyExchange`].
     ///
     /// # Arguments
     ///
     /// * `config` - Key exchange configuration.
     /// * `ctx` - Thread context.
-    /// * `converter_0` - Share conversion protocol instance 0.
-    /// * `converter_1` - Share conversion protocol instance 1.
+    /// * `converter_0` - P-256 share conversion protocol instance 0.
+    /// * `converter_1` - P-256 share conversion protocol instance 1.
+    /// * `x25519_converter_0` - X25519 share conversion protocol instance 0.
+    /// * `x25519_converter_1` - X25519 share conversion protocol instance 1.
     /// * `executor` - MPC executor.
     pub fn new(
         config: KeyExchangeConfig,
         ctx: Ctx,
         converter_0: C0,
         converter_1: C1,
+        x25519_converter_0: X0,
+        x25519_converter_1: X1,
         executor: E,
     ) -> Self {
         Self {
             ctx,
             converter_0,
             converter_1,
+            x25519_converter_0,
+            x25519_converter_1,
             executor,
             private_key: None,
+            x25519_private_key: None,
             server_key: None,
             config,
             state: State::Initialized,
@@ -108,41 +139,87 @@ impl<Ctx, C0, C1, E> MpcKeyExchange<Ctx, C0, C1, E> {
     }
 }
 
-impl<Ctx, C0, C1, E> MpcKeyExchange<Ctx, C0, C1, E>
+impl<Ctx, C0, C1, X0, X1, E> MpcKeyExchange<Ctx, C0, C1, X0, X1, E>
 where
     Ctx: Context,
     E: Execute + Load + Memory + Decode + Send,
     C0: ShareConvert<Ctx, P256> + Send,
     C1: ShareConvert<Ctx, P256> + Send,
+    X0: ShareConvert<Ctx, F25519> + Send,
+    X1: ShareConvert<Ctx, F25519> + Send,
 {
+    /// Computes two pairs of PMS shares in the group of the server key,
+    /// encoded as big-endian field elements.
     async fn compute_pms_shares(
         &mut self,
         server_key: PublicKey,
-        private_key: SecretKey,
-    ) -> Result<(P256, P256), KeyExchangeError> {
-        compute_pms_shares(
-            &mut self.ctx,
-            *self.config.role(),
-            &mut self.converter_0,
-            &mut self.converter_1,
-            server_key,
-            private_key,
-        )
-        .await
+    ) -> Result<([u8; 32], [u8; 32]), KeyExchangeError> {
+        let role = *self.config.role();
+
+        let (share_0, share_1) = match server_key {
+            PublicKey::P256(server_key) => {
+                let private_key = self
+                    .private_key
+                    .take()
+                    .ok_or(KeyExchangeError::state("private key not set"))?;
+
+                let (share_0, share_1) = compute_pms_shares(
+                    &mut self.ctx,
+                    role,
+                    &mut self.converter_0,
+                    &mut self.converter_1,
+                    p256_shared_point(server_key, private_key)?,
+                )
+                .await?;
+
+                (share_0.to_be_bytes(), share_1.to_be_bytes())
+            }
+            PublicKey::X25519(server_key) => {
+                let private_key = self
+                    .x25519_private_key
+                    .take()
+                    .ok_or(KeyExchangeError::state("private key not set"))?;
+
+                let (mut share_0, mut share_1) = compute_pms_shares(
+                    &mut self.ctx,
+                    role,
+                    &mut self.x25519_converter_0,
+                    &mut self.x25519_converter_1,
+                    x25519_shared_point(server_key, private_key)?,
+                )
+                .await?;
+
+                // The Montgomery chord formula subtracts A, which the leader
+                // accounts for.
+                if let Role::Leader = role {
+                    share_0 = share_0 - F25519::new(MONTGOMERY_A);
+                    share_1 = share_1 - F25519::new(MONTGOMERY_A);
+                }
+
+                (share_0.to_be_bytes(), share_1.to_be_bytes())
+            }
+        };
+
+        Ok((
+            share_0.try_into().expect("pms share is 32 bytes"),
+            share_1.try_into().expect("pms share is 32 bytes"),
+        ))
     }
 
     // Computes the PMS using both parties' shares, performing an equality check
     // to ensure the shares are equal.
     async fn compute_pms_with(
         &mut self,
-        share_0: P256,
-        share_1: P256,
+        group: Group,
+        share_0_bytes: [u8; 32],
+        share_1_bytes: [u8; 32],
     ) -> Result<Pms, KeyExchangeError> {
         let State::Preprocessed {
             share_a0,
             share_b0,
             share_a1,
             share_b1,
+            group: group_ref,
             pms_0,
             pms_1,
             eq,
@@ -151,15 +228,6 @@ where
             return Err(KeyExchangeError::state("not in preprocessed state"));
         };
 
-        let share_0_bytes: [u8; 32] = share_0
-            .to_be_bytes()
-            .try_into()
-            .expect("pms share is 32 bytes");
-        let share_1_bytes: [u8; 32] = share_1
-            .to_be_bytes()
-            .try_into()
-            .expect("pms share is 32 bytes");
-
         match self.config.role() {
             Role::Leader => {
                 self.executor.assign(&share_a0, share_0_bytes)?;
@@ -171,10 +239,18 @@ where
             }
         }
 
+        self.executor.assign(
+            &group_ref,
+            match group {
+                Group::P256 => GROUP_P256,
+                Group::X25519 => GROUP_X25519,
+            },
+        )?;
+
         self.executor
             .execute(
                 build_pms_circuit(),
-                &[share_a0, share_b0, share_a1, share_b1],
+                &[share_a0, share_b0, share_a1, share_b1, group_ref],
                 &[pms_0.clone(), pms_1, eq.clone()],
             )
             .await?;
@@ -202,12 +278,14 @@ where
 }
 
 #[async_trait]
-impl<Ctx, C0, C1, E> KeyExchange for MpcKeyExchange<Ctx, C0, C1, E>
+impl<Ctx, C0, C1, X0, X1, E> KeyExchange for MpcKeyExchange<Ctx, C0, C1, X0, X1, E>
 where
     Ctx: Context,
     E: Execute + Load + Memory + Decode + Send,
     C0: Allocate + Preprocess<Ctx, Error = ShareConversionError> + ShareConvert<Ctx, P256> + Send,
     C1: Allocate + Preprocess<Ctx, Error = ShareConversionError> + ShareConvert<Ctx, P256> + Send,
+    X0: Allocate + Preprocess<Ctx, Error = ShareConversionError> + ShareConvert<Ctx, F25519> + Send,
+    X1: Allocate + Preprocess<Ctx, Error = ShareConversionError> + ShareConvert<Ctx, F25519> + Send,
 {
     fn server_key(&self) -> Option<PublicKey> {
         self.server_key
@@ -218,6 +296,13 @@ where
             return Err(KeyExchangeError::role("follower cannot set server key"));
         };
 
+        if !self.config.supports(server_key.group()) {
+            return Err(KeyExchangeError::new(
+                ErrorKind::Key,
+                format!("group is not enabled: {:?}", server_key.group()),
+            ));
+        }
+
         // Send server public key to follower.
         self.ctx.io_mut().send(server_key).await?;
 
@@ -232,9 +317,15 @@ where
             return Err(KeyExchangeError::state("not in initialized state"));
         };
 
-        // 2 A2M, 1 M2A.
-        self.converter_0.alloc(3);
-        self.converter_1.alloc(3);
+        // 2 A2M, 1 M2A for each enabled group.
+        if self.config.supports(Group::P256) {
+            self.converter_0.alloc(3);
+            self.converter_1.alloc(3);
+        }
+        if self.config.supports(Group::X25519) {
+            self.x25519_converter_0.alloc(3);
+            self.x25519_converter_1.alloc(3);
+        }
 
         let (share_a0, share_b0, share_a1, share_b1) = match self.config.role() {
             Role::Leader => {
@@ -263,6 +354,9 @@ where
             }
         };
 
+        // The group is only known once the server has sent its key.
+        let group = self.executor.new_public_input::<u8>("pms/group")?;
+
         let pms_0 = self.executor.new_output::<[u8; 32]>("pms_0")?;
         let pms_1 = self.executor.new_output::<[u8; 32]>("pms_1")?;
         let eq = self.executor.new_output::<[u8; 32]>("eq")?;
@@ -272,6 +366,7 @@ where
             share_b0,
             share_a1,
             share_b1,
+            group,
             pms_0: pms_0.clone(),
             pms_1,
             eq,
@@ -287,6 +382,7 @@ where
             share_b0,
             share_a1,
             share_b1,
+            group,
             pms_0,
             pms_1,
             eq,
@@ -295,15 +391,29 @@ where
             return Err(KeyExchangeError::state("not in setup state"));
         };
 
+        let p256_enabled = self.config.supports(Group::P256);
+        let x25519_enabled = self.config.supports(Group::X25519);
+
         // Preprocess share conversion and garbled circuits concurrently.
         futures::try_join!(
             async {
-                self.ctx
-                    .try_join(
-                        |ctx| self.converter_0.preprocess(ctx).scope_boxed(),
-                        |ctx| self.converter_1.preprocess(ctx).scope_boxed(),
-                    )
-                    .await??;
+                if p256_enabled {
+                    self.ctx
+                        .try_join(
+                            |ctx| self.converter_0.preprocess(ctx).scope_boxed(),
+                            |ctx| self.converter_1.preprocess(ctx).scope_boxed(),
+                        )
+                        .await??;
+                }
+
+                if x25519_enabled {
+                    self.ctx
+                        .try_join(
+                            |ctx| self.x25519_converter_0.preprocess(ctx).scope_boxed(),
+                            |ctx| self.x25519_converter_1.preprocess(ctx).scope_boxed(),
+                        )
+                        .await??;
+                }
 
                 Ok::<_, KeyExchangeError>(())
             },
@@ -316,6 +426,7 @@ where
                             share_b0.clone(),
                             share_a1.clone(),
                             share_b1.clone(),
+                            group.clone(),
                         ],
                         &[pms_0.clone(), pms_1.clone(), eq.clone()],
                     )
@@ -325,15 +436,27 @@ where
             }
         )?;
 
-        // Follower can forward their key share immediately.
+        // Follower can forward their key shares immediately.
         if let Role::Follower = self.config.role() {
-            let private_key = self
-                .private_key
-                .get_or_insert_with(|| SecretKey::random(&mut rand::rngs::OsRng));
-
-            self.ctx.io_mut().send(private_key.public_key()).await?;
-
-            debug!("sent public key share to leader");
+            let p256 = p256_enabled.then(|| {
+                self.private_key
+                    .get_or_insert_with(|| SecretKey::random(&mut rand::rngs::OsRng))
+                    .public_key()
+            });
+            let x25519 = x25519_enabled.then(|| {
+                let private_key = self
+                    .x25519_private_key
+                    .get_or_insert_with(|| Scalar::random(&mut rand::rngs::OsRng));
+
+                EdwardsPoint::mul_base(private_key).compress().to_bytes()
+            });
+
+            self.ctx
+                .io_mut()
+                .send(FollowerKeyShares { p256, x25519 })
+                .await?;
+
+            debug!("sent public key shares to leader");
         }
 
         self.state = State::Preprocessed {
@@ -341,6 +464,7 @@ where
             share_b0,
             share_a1,
             share_b1,
+            group,
             pms_0,
             pms_1,
             eq,
@@ -351,26 +475,58 @@ where
 
     #[instrument(level = "debug", skip_all, err)]
     async fn client_key(&mut self) -> Result<PublicKey, KeyExchangeError> {
-        if let Role::Leader = self.config.role() {
-            let private_key = self
-                .private_key
-                .get_or_insert_with(|| SecretKey::random(&mut rand::rngs::OsRng));
-            let public_key = private_key.public_key();
-
-            // Receive public key share from follower.
-            let follower_public_key: PublicKey = self.ctx.io_mut().expect_next().await?;
-
-            debug!("received public key share from follower");
+        let Role::Leader = self.config.role() else {
+            return Err(KeyExchangeError::role("follower does not learn client key"));
+        };
 
-            // Combine public keys.
-            let client_public_key = PublicKey::from_affine(
-                (public_key.to_projective() + follower_public_key.to_projective()).to_affine(),
-            )?;
+        let group = self
+            .server_key
+            .ok_or_else(|| KeyExchangeError::state("server public key not set"))?
+            .group();
+
+        // Receive public key shares from follower.
+        let follower_shares: FollowerKeyShares = self.ctx.io_mut().expect_next().await?;
+
+        debug!("received public key shares from follower");
+
+        // Combine public keys.
+        let client_public_key = match group {
+            Group::P256 => {
+                let follower_public_key = follower_shares.p256.ok_or_else(|| {
+                    KeyExchangeError::new(ErrorKind::Key, "follower did not send a P-256 share")
+                })?;
+                let public_key = self
+                    .private_key
+                    .get_or_insert_with(|| SecretKey::random(&mut rand::rngs::OsRng))
+                    .public_key();
+
+                PublicKey::P256(p256::PublicKey::from_affine(
+                    (public_key.to_projective() + follower_public_key.to_projective()).to_affine(),
+                )?)
+            }
+            Group::X25519 => {
+                let follower_public_key = follower_shares
+                    .x25519
+                    .and_then(|share| CompressedEdwardsY(share).decompress())
+                    .ok_or_else(|| {
+                        KeyExchangeError::new(
+                            ErrorKind::Key,
+                            "follower did not send a valid X25519 share",
+                        )
+                    })?;
+                let private_key = self
+                    .x25519_private_key
+                    .get_or_insert_with(|| Scalar::random(&mut rand::rngs::OsRng));
+
+                PublicKey::X25519(
+                    (EdwardsPoint::mul_base(private_key) + follower_public_key)
+                        .to_montgomery()
+                        .to_bytes(),
+                )
+            }
+        };
 
-            Ok(client_public_key)
-        } else {
-            Err(KeyExchangeError::role("follower does not learn client key"))
-        }
+        Ok(client_public_key)
     }
 
     #[instrument(level = "debug", skip_all, err)]
@@ -385,7 +541,14 @@ where
                 .ok_or_else(|| KeyExchangeError::state("server public key not set"))?,
             Role::Follower => {
                 // Receive server public key from leader.
-                let server_key = self.ctx.io_mut().expect_next().await?;
+                let server_key: PublicKey = self.ctx.io_mut().expect_next().await?;
+
+                if !self.config.supports(server_key.group()) {
+                    return Err(KeyExchangeError::new(
+                        ErrorKind::Key,
+                        format!("group is not enabled: {:?}", server_key.group()),
+                    ));
+                }
 
                 self.server_key = Some(server_key);
 
@@ -393,13 +556,10 @@ where
             }
         };
 
-        let private_key = self
-            .private_key
-            .take()
-            .ok_or(KeyExchangeError::state("private key not set"))?;
-
-        let (pms_share_0, pms_share_1) = self.compute_pms_shares(server_key, private_key).await?;
-        let pms = self.compute_pms_with(pms_share_0, pms_share_1).await?;
+        let (pms_share_0, pms_share_1) = self.compute_pms_shares(server_key).await?;
+        let pms = self
+            .compute_pms_with(server_key.group(), pms_share_0, pms_share_1)
+            .await?;
 
         self.state = State::Complete;
 
@@ -407,20 +567,11 @@ where
     }
 }
 
-async fn compute_pms_shares<
-    Ctx: Context,
-    C0: ShareConvert<Ctx, P256> + Send,
-    C1: ShareConvert<Ctx, P256> + Send,
->(
-    ctx: &mut Ctx,
-    role: Role,
-    converter_0: &mut C0,
-    converter_1: &mut C1,
-    server_key: PublicKey,
+/// Computes this party's summand of the P-256 shared secret point.
+fn p256_shared_point(
+    server_key: p256::PublicKey,
     private_key: SecretKey,
-) -> Result<(P256, P256), KeyExchangeError> {
-    // Compute the leader's/follower's share of the pre-master secret.
-    //
+) -> Result<[P256; 2], KeyExchangeError> {
     // We need to mimic the [diffie-hellman](p256::ecdh::diffie_hellman) function
     // without the [SharedSecret](p256::ecdh::SharedSecret) wrapper, because
     // this makes it harder to get the result as an EC curve point.
@@ -429,18 +580,51 @@ async fn compute_pms_shares<
         (public_projective * private_key.to_nonzero_scalar().as_ref()).to_affine()
     };
 
-    let encoded_point = EncodedPoint::from(PublicKey::from_affine(shared_secret)?);
+    decompose_point(EncodedPoint::from(p256::PublicKey::from_affine(
+        shared_secret,
+    )?))
+}
+
+/// Computes this party's summand of the X25519 shared secret point.
+fn x25519_shared_point(
+    server_key: [u8; 32],
+    private_key: Scalar,
+) -> Result<[F25519; 2], KeyExchangeError> {
+    // The server key only encodes u, so both parties lift it to the same
+    // Edwards point. The sign does not matter, as negation does not change
+    // the u coordinate of the sum.
+    let server_point = MontgomeryPoint(server_key)
+        .to_edwards(0)
+        .ok_or_else(|| KeyExchangeError::new(ErrorKind::Key, "server key is not on the curve"))?;
+
+    if server_point.is_small_order() {
+        return Err(KeyExchangeError::new(
+            ErrorKind::Key,
+            "server key is a point of small order",
+        ));
+    }
 
+    x25519::decompose_point(&(server_point * private_key))
+}
+
+/// Computes two additive sharings of the x coordinate of the sum of both
+/// parties' points.
+async fn compute_pms_shares<
+    Ctx: Context,
+    F: Field,
+    C0: ShareConvert<Ctx, F> + Send,
+    C1: ShareConvert<Ctx, F> + Send,
+>(
+    ctx: &mut Ctx,
+    role: Role,
+    converter_0: &mut C0,
+    converter_1: &mut C1,
+    point: [F; 2],
+) -> Result<(F, F), KeyExchangeError> {
     let (pms_share_0, pms_share_1) = ctx
         .try_join(
-            |ctx| {
-                async { derive_x_coord_share(role, ctx, converter_0, encoded_point).await }
-                    .scope_boxed()
-            },
-            |ctx| {
-                async { derive_x_coord_share(role, ctx, converter_1, encoded_point).await }
-                    .scope_boxed()
-            },
+            |ctx| async { derive_x_coord_share(role, ctx, converter_0, point).await }.scope_boxed(),
+            |ctx| async { derive_x_coord_share(role, ctx, converter_1, point).await }.scope_boxed(),
         )
         .await??;
 
@@ -454,50 +638,65 @@ mod tests {
     use mpz_common::executor::{test_st_executor, STExecutor};
     use mpz_garble::protocol::deap::mock::{create_mock_deap_vm, MockFollower, MockLeader};
     use mpz_share_conversion::ideal::{ideal_share_converter, IdealShareConverter};
-    use p256::{NonZeroScalar, PublicKey, SecretKey};
+    use p256::{NonZeroScalar, SecretKey};
+    use rand::Rng;
     use rand_chacha::ChaCha12Rng;
     use rand_core::SeedableRng;
     use serio::channel::MemoryDuplex;
 
     #[allow(clippy::type_complexity)]
-    fn create_pair() -> (
+    fn create_pair(
+        groups: &[Group],
+    ) -> (
         MpcKeyExchange<
             STExecutor<MemoryDuplex>,
             IdealShareConverter,
             IdealShareConverter,
+            IdealShareConverter,
+            IdealShareConverter,
             MockLeader,
         >,
         MpcKeyExchange<
             STExecutor<MemoryDuplex>,
             IdealShareConverter,
             IdealShareConverter,
+            IdealShareConverter,
+            IdealShareConverter,
             MockFollower,
         >,
     ) {
         let (leader_ctx, follower_ctx) = test_st_executor(8);
         let (leader_converter_0, follower_converter_0) = ideal_share_converter();
         let (follower_converter_1, leader_converter_1) = ideal_share_converter();
+        let (leader_x25519_converter_0, follower_x25519_converter_0) = ideal_share_converter();
+        let (follower_x25519_converter_1, leader_x25519_converter_1) = ideal_share_converter();
         let (leader_vm, follower_vm) = create_mock_deap_vm();
 
         let leader = MpcKeyExchange::new(
             KeyExchangeConfig::builder()
                 .role(Role::Leader)
+                .groups(groups.to_vec())
                 .build()
                 .unwrap(),
             leader_ctx,
             leader_converter_0,
             leader_converter_1,
+            leader_x25519_converter_0,
+            leader_x25519_converter_1,
             leader_vm,
         );
 
         let follower = MpcKeyExchange::new(
             KeyExchangeConfig::builder()
                 .role(Role::Follower)
+                .groups(groups.to_vec())
                 .build()
                 .unwrap(),
             follower_ctx,
             follower_converter_0,
             follower_converter_1,
+            follower_x25519_converter_0,
+            follower_x25519_converter_1,
             follower_vm,
         );
 
@@ -510,9 +709,10 @@ mod tests {
 
         let leader_private_key = SecretKey::random(&mut rng);
         let follower_private_key = SecretKey::random(&mut rng);
-        let server_public_key = PublicKey::from_secret_scalar(&NonZeroScalar::random(&mut rng));
+        let server_public_key =
+            p256::PublicKey::from_secret_scalar(&NonZeroScalar::random(&mut rng));
 
-        let (mut leader, mut follower) = create_pair();
+        let (mut leader, mut follower) = create_pair(&[Group::X25519, Group::P256]);
 
         leader.private_key = Some(leader_private_key.clone());
         follower.private_key = Some(follower_private_key.clone());
@@ -520,17 +720,56 @@ mod tests {
         tokio::try_join!(leader.setup(), follower.setup()).unwrap();
         tokio::try_join!(leader.preprocess(), follower.preprocess()).unwrap();
 
+        leader
+            .set_server_key(PublicKey::P256(server_public_key))
+            .await
+            .unwrap();
         let client_public_key = leader.client_key().await.unwrap();
-        leader.set_server_key(server_public_key).await.unwrap();
 
-        let expected_client_public_key = PublicKey::from_affine(
+        let expected_client_public_key = p256::PublicKey::from_affine(
             (leader_private_key.public_key().to_projective()
                 + follower_private_key.public_key().to_projective())
             .to_affine(),
         )
         .unwrap();
 
-        assert_eq!(client_public_key, expected_client_public_key);
+        assert_eq!(
+            client_public_key,
+            PublicKey::P256(expected_client_public_key)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_key_exchange_x25519() {
+        let mut rng = ChaCha12Rng::from_seed([0_u8; 32]);
+
+        let leader_private_key = Scalar::random(&mut rng);
+        let follower_private_key = Scalar::random(&mut rng);
+        let server_public_key = MontgomeryPoint::mul_base_clamped(rng.gen());
+
+        let (mut leader, mut follower) = create_pair(&[Group::X25519, Group::P256]);
+
+        leader.x25519_private_key = Some(leader_private_key);
+        follower.x25519_private_key = Some(follower_private_key);
+
+        tokio::try_join!(leader.setup(), follower.setup()).unwrap();
+        tokio::try_join!(leader.preprocess(), follower.preprocess()).unwrap();
+
+        leader
+            .set_server_key(PublicKey::X25519(server_public_key.to_bytes()))
+            .await
+            .unwrap();
+        let client_public_key = leader.client_key().await.unwrap();
+
+        let expected_client_public_key =
+            EdwardsPoint::mul_base(&(leader_private_key + follower_private_key))
+                .to_montgomery()
+                .to_bytes();
+
+        assert_eq!(
+            client_public_key,
+            PublicKey::X25519(expected_client_public_key)
+        );
     }
 
     #[tokio::test]
@@ -540,9 +779,10 @@ mod tests {
         let leader_private_key = SecretKey::random(&mut rng);
         let follower_private_key = SecretKey::random(&mut rng);
         let server_private_key = NonZeroScalar::random(&mut rng);
-        let server_public_key = PublicKey::from_secret_scalar(&server_private_key);
+        let server_public_key =
+            PublicKey::P256(p256::PublicKey::from_secret_scalar(&server_private_key));
 
-        let (mut leader, mut follower) = create_pair();
+        let (mut leader, mut follower) = create_pair(&[Group::P256]);
 
         leader.private_key = Some(leader_private_key);
         follower.private_key = Some(follower_private_key);
@@ -559,6 +799,62 @@ mod tests {
         assert_eq!(follower.server_key.unwrap(), server_public_key);
     }
 
+    #[tokio::test]
+    async fn test_compute_pms_x25519() {
+        let mut rng = ChaCha12Rng::from_seed([0_u8; 32]);
+
+        let server_private_key: [u8; 32] = rng.gen();
+        let server_public_key =
+            PublicKey::X25519(MontgomeryPoint::mul_base_clamped(server_private_key).to_bytes());
+
+        let (mut leader, mut follower) = create_pair(&[Group::X25519, Group::P256]);
+
+        tokio::try_join!(leader.setup(), follower.setup()).unwrap();
+        tokio::try_join!(leader.preprocess(), follower.preprocess()).unwrap();
+
+        leader.set_server_key(server_public_key).await.unwrap();
+        let PublicKey::X25519(client_public_key) = leader.client_key().await.unwrap() else {
+            panic!("client key is not an X25519 key");
+        };
+
+        let (leader_pms, follower_pms) =
+            tokio::try_join!(leader.compute_pms(), follower.compute_pms()).unwrap();
+
+        let (leader_pms, follower_pms) = tokio::try_join!(
+            leader.executor.decode(&[leader_pms.into_value()]),
+            follower.executor.decode(&[follower_pms.into_value()])
+        )
+        .unwrap();
+
+        let leader_pms: [u8; 32] = leader_pms[0].clone().try_into().unwrap();
+        let follower_pms: [u8; 32] = follower_pms[0].clone().try_into().unwrap();
+
+        // The PMS is the output of X25519 as computed by the server.
+        let expected_pms = MontgomeryPoint(client_public_key)
+            .mul_clamped(server_private_key)
+            .to_bytes();
+
+        assert_eq!(leader_pms, expected_pms);
+        assert_eq!(follower_pms, expected_pms);
+    }
+
+    #[tokio::test]
+    async fn test_group_not_enabled() {
+        let mut rng = ChaCha12Rng::from_seed([0_u8; 32]);
+
+        let server_public_key =
+            PublicKey::X25519(MontgomeryPoint::mul_base_clamped(rng.gen()).to_bytes());
+
+        let (mut leader, mut follower) = create_pair(&[Group::P256]);
+
+        tokio::try_join!(leader.setup(), follower.setup()).unwrap();
+        tokio::try_join!(leader.preprocess(), follower.preprocess()).unwrap();
+
+        let err = leader.set_server_key(server_public_key).await.unwrap_err();
+
+        assert!(matches!(err.kind(), ErrorKind::Key));
+    }
+
     #[tokio::test]
     async fn test_compute_pms_shares() {
         let mut rng = ChaCha12Rng::from_seed([0_u8; 32]);
@@ -569,9 +865,9 @@ mod tests {
         let leader_private_key = SecretKey::random(&mut rng);
         let follower_private_key = SecretKey::random(&mut rng);
         let server_private_key = NonZeroScalar::random(&mut rng);
-        let server_public_key = PublicKey::from_secret_scalar(&server_private_key);
+        let server_public_key = p256::PublicKey::from_secret_scalar(&server_private_key);
 
-        let client_public_key = PublicKey::from_affine(
+        let client_public_key = p256::PublicKey::from_affine(
             (leader_private_key.public_key().to_projective()
                 + follower_private_key.public_key().to_projective())
             .to_affine(),
@@ -585,16 +881,14 @@ mod tests {
                     Role::Leader,
                     &mut leader_converter_0,
                     &mut leader_converter_1,
-                    server_public_key,
-                    leader_private_key
+                    p256_shared_point(server_public_key, leader_private_key).unwrap()
                 ),
                 compute_pms_shares(
                     &mut ctx_follower,
                     Role::Follower,
                     &mut follower_converter_0,
                     &mut follower_converter_1,
-                    server_public_key,
-                    follower_private_key
+                    p256_shared_point(server_public_key, follower_private_key).unwrap()
                 )
             )
             .unwrap();
@@ -622,9 +916,10 @@ mod tests {
         let leader_private_key = SecretKey::random(&mut rng);
         let follower_private_key = SecretKey::random(&mut rng);
         let server_private_key = NonZeroScalar::random(&mut rng);
-        let server_public_key = PublicKey::from_secret_scalar(&server_private_key);
+        let server_public_key =
+            PublicKey::P256(p256::PublicKey::from_secret_scalar(&server_private_key));
 
-        let (mut leader, mut follower) = create_pair();
+        let (mut leader, mut follower) = create_pair(&[Group::P256]);
 
         leader.private_key = Some(leader_private_key.clone());
         follower.private_key = Some(follower_private_key.clone());
@@ -635,16 +930,17 @@ mod tests {
         leader.set_server_key(server_public_key).await.unwrap();
 
         let ((mut share_a0, share_a1), (share_b0, share_b1)) = tokio::try_join!(
-            leader.compute_pms_shares(server_public_key, leader_private_key),
-            follower.compute_pms_shares(server_public_key, follower_private_key)
+            leader.compute_pms_shares(server_public_key),
+            follower.compute_pms_shares(server_public_key)
         )
         .unwrap();
 
-        share_a0 = share_a0 + P256::one();
+        // Corrupt one of the leader's shares.
+        share_a0[31] ^= 1;
 
         let (leader_res, follower_res) = tokio::join!(
-            leader.compute_pms_with(share_a0, share_a1),
-            follower.compute_pms_with(share_b0, share_b1)
+            leader.compute_pms_with(Group::P256, share_a0, share_a1),
+            follower.compute_pms_with(Group::P256, share_b0, share_b1)
         );
 
         let leader_err = leader_res.unwrap_err();
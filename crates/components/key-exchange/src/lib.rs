@@ -7,6 +7,10 @@
 //! the shared secret. The leader will do all the necessary communication
 //! with the server alone and forward all messages from and to the follower.
 //!
+//! Both NIST P-256 and X25519 are supported. The groups enabled in the
+//! [`KeyExchangeConfig`] are preprocessed, and the group of the server's key
+//! determines which one is used.
+//!
 //! A detailed description of this protocol can be found in our documentation
 //! <https://docs.tlsnotary.org/protocol/notarization/key_exchange.html>.
 
@@ -21,16 +25,19 @@ mod exchange;
 #[cfg(feature = "mock")]
 pub mod mock;
 pub(crate) mod point_addition;
+pub mod x25519;
 
 pub use config::{
-    KeyExchangeConfig, KeyExchangeConfigBuilder, KeyExchangeConfigBuilderError, Role,
+    Group, KeyExchangeConfig, KeyExchangeConfigBuilder, KeyExchangeConfigBuilderError, Role,
 };
 pub use error::KeyExchangeError;
 pub use exchange::MpcKeyExchange;
 
 use async_trait::async_trait;
+use error::ErrorKind;
 use mpz_garble::value::ValueRef;
-use p256::PublicKey;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use serde::{Deserialize, Serialize};
 
 /// Pre-master secret.
 #[derive(Debug, Clone)]
@@ -48,20 +55,61 @@ impl Pms {
     }
 }
 
+/// A public key in one of the supported groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PublicKey {
+    /// A NIST P-256 public key.
+    P256(p256::PublicKey),
+    /// An X25519 public key, ie. the little-endian `u` coordinate.
+    X25519([u8; 32]),
+}
+
+impl PublicKey {
+    /// Parses a public key, which is SEC1 encoded for P-256.
+    pub fn from_bytes(group: Group, bytes: &[u8]) -> Result<Self, KeyExchangeError> {
+        match group {
+            Group::P256 => p256::PublicKey::from_sec1_bytes(bytes)
+                .map(Self::P256)
+                .map_err(|err| KeyExchangeError::new(ErrorKind::Key, err)),
+            Group::X25519 => bytes
+                .try_into()
+                .map(Self::X25519)
+                .map_err(|_| KeyExchangeError::new(ErrorKind::Key, "x25519 key is not 32 bytes")),
+        }
+    }
+
+    /// Returns the group of the key.
+    pub fn group(&self) -> Group {
+        match self {
+            Self::P256(_) => Group::P256,
+            Self::X25519(_) => Group::X25519,
+        }
+    }
+
+    /// Returns the encoded key, which is uncompressed SEC1 for P-256.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::P256(key) => key.to_encoded_point(false).as_bytes().to_vec(),
+            Self::X25519(key) => key.to_vec(),
+        }
+    }
+}
+
 /// A trait for the 3-party key exchange protocol.
 #[async_trait]
 pub trait KeyExchange {
     /// Gets the server's public key.
     fn server_key(&self) -> Option<PublicKey>;
 
-    /// Sets the server's public key.
+    /// Sets the server's public key, which also selects the group.
     async fn set_server_key(&mut self, server_key: PublicKey) -> Result<(), KeyExchangeError>;
 
     /// Computes the client's public key.
     ///
     /// The client's public key in this context is the combined public key (EC
     /// point addition) of the leader's public key and the follower's public
-    /// key.
+    /// key. It is in the group of the server's key, so that has to be set
+    /// first.
     async fn client_key(&mut self) -> Result<PublicKey, KeyExchangeError>;
 
     /// Performs any necessary one-time setup, returning a reference to the PMS.
@@ -9,8 +9,14 @@ use mpz_share_conversion::ideal::{ideal_share_converter, IdealShareConverter};
 use serio::channel::MemoryDuplex;
 
 /// A mock key exchange instance.
-pub type MockKeyExchange<E> =
-    MpcKeyExchange<STExecutor<MemoryDuplex>, IdealShareConverter, IdealShareConverter, E>;
+pub type MockKeyExchange<E> = MpcKeyExchange<
+    STExecutor<MemoryDuplex>,
+    IdealShareConverter,
+    IdealShareConverter,
+    IdealShareConverter,
+    IdealShareConverter,
+    E,
+>;
 
 /// Creates a mock pair of key exchange leader and follower.
 pub fn create_mock_key_exchange_pair<E: Memory + Execute + Decode + Send>(
@@ -20,6 +26,8 @@ pub fn create_mock_key_exchange_pair<E: Memory + Execute + Decode + Send>(
     let (leader_ctx, follower_ctx) = test_st_executor(8);
     let (leader_converter_0, follower_converter_0) = ideal_share_converter();
     let (leader_converter_1, follower_converter_1) = ideal_share_converter();
+    let (leader_x25519_converter_0, follower_x25519_converter_0) = ideal_share_converter();
+    let (leader_x25519_converter_1, follower_x25519_converter_1) = ideal_share_converter();
 
     let key_exchange_config_leader = KeyExchangeConfig::builder()
         .role(Role::Leader)
@@ -36,6 +44,8 @@ pub fn create_mock_key_exchange_pair<E: Memory + Execute + Decode + Send>(
         leader_ctx,
         leader_converter_0,
         leader_converter_1,
+        leader_x25519_converter_0,
+        leader_x25519_converter_1,
         leader_executor,
     );
 
@@ -44,6 +54,8 @@ pub fn create_mock_key_exchange_pair<E: Memory + Execute + Decode + Send>(
         follower_ctx,
         follower_converter_0,
         follower_converter_1,
+        follower_x25519_converter_0,
+        follower_x25519_converter_1,
         follower_executor,
     );
 
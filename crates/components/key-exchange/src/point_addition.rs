@@ -3,6 +3,10 @@
 //! shares are field elements of the field underlying the elliptic curve).
 //! This protocol has semi-honest security.
 //!
+//! The shares are derived for `λ^2 - x_1 - x_2`, which is the x coordinate
+//! on a short Weierstrass curve. On a Montgomery curve the constant `A` has to
+//! be subtracted from one of the shares as well.
+//!
 //! The protocol is described in <https://docs.tlsnotary.org/protocol/notarization/key_exchange.html>
 
 use mpz_common::Context;
@@ -12,19 +16,19 @@ use p256::EncodedPoint;
 
 use crate::{config::Role, error::ErrorKind, KeyExchangeError};
 
-/// Derives the x-coordinate share of an elliptic curve point.
-pub(crate) async fn derive_x_coord_share<Ctx, C>(
+/// Derives the x-coordinate share of an elliptic curve point, given the
+/// affine coordinates `[x, y]` of this party's summand.
+pub(crate) async fn derive_x_coord_share<Ctx, C, F>(
     role: Role,
     ctx: &mut Ctx,
     converter: &mut C,
-    share: EncodedPoint,
-) -> Result<P256, KeyExchangeError>
+    [x, y]: [F; 2],
+) -> Result<F, KeyExchangeError>
 where
     Ctx: Context,
-    C: AdditiveToMultiplicative<Ctx, P256> + MultiplicativeToAdditive<Ctx, P256>,
+    C: AdditiveToMultiplicative<Ctx, F> + MultiplicativeToAdditive<Ctx, F>,
+    F: Field,
 {
-    let [x, y] = decompose_point(share)?;
-
     // Follower negates their share coordinates.
     let inputs = match role {
         Role::Leader => vec![y, x],
@@ -48,7 +52,7 @@ where
 }
 
 /// Decomposes the x and y coordinates of a SEC1 encoded point.
-fn decompose_point(point: EncodedPoint) -> Result<[P256; 2], KeyExchangeError> {
+pub(crate) fn decompose_point(point: EncodedPoint) -> Result<[P256; 2], KeyExchangeError> {
     // Coordinates are stored as big-endian bytes.
     let mut x: [u8; 32] = (*point.x().ok_or(KeyExchangeError::new(
         ErrorKind::Key,
@@ -75,6 +79,9 @@ fn decompose_point(point: EncodedPoint) -> Result<[P256; 2], KeyExchangeError> {
 mod tests {
     use super::*;
 
+    use crate::x25519::{self, F25519, MONTGOMERY_A};
+
+    use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar};
     use mpz_common::executor::test_st_executor;
     use mpz_fields::{p256::P256, Field};
     use mpz_share_conversion::ideal::ideal_share_converter;
@@ -101,8 +108,18 @@ mod tests {
         let (mut c_a, mut c_b) = ideal_share_converter();
 
         let (a, b) = tokio::try_join!(
-            derive_x_coord_share(Role::Leader, &mut ctx_a, &mut c_a, p1),
-            derive_x_coord_share(Role::Follower, &mut ctx_b, &mut c_b, p2)
+            derive_x_coord_share(
+                Role::Leader,
+                &mut ctx_a,
+                &mut c_a,
+                decompose_point(p1).unwrap()
+            ),
+            derive_x_coord_share(
+                Role::Follower,
+                &mut ctx_b,
+                &mut c_b,
+                decompose_point(p2).unwrap()
+            )
         )
         .unwrap();
 
@@ -111,6 +128,40 @@ mod tests {
         assert_eq!(expected_x, a + b);
     }
 
+    #[tokio::test]
+    async fn test_point_addition_x25519() {
+        let (mut ctx_a, mut ctx_b) = test_st_executor(8);
+        let mut rng = ChaCha12Rng::from_seed([0u8; 32]);
+
+        let p1 = EdwardsPoint::mul_base(&Scalar::random(&mut rng));
+        let p2 = EdwardsPoint::mul_base(&Scalar::random(&mut rng));
+
+        let (mut c_a, mut c_b) = ideal_share_converter();
+
+        let (a, b) = tokio::try_join!(
+            derive_x_coord_share(
+                Role::Leader,
+                &mut ctx_a,
+                &mut c_a,
+                x25519::decompose_point(&p1).unwrap()
+            ),
+            derive_x_coord_share(
+                Role::Follower,
+                &mut ctx_b,
+                &mut c_b,
+                x25519::decompose_point(&p2).unwrap()
+            )
+        )
+        .unwrap();
+
+        let u = a + b - F25519::new(MONTGOMERY_A);
+
+        assert_eq!(
+            u.to_le_bytes(),
+            (p1 + p2).to_montgomery().to_bytes().to_vec()
+        );
+    }
+
     #[test]
     fn test_decompose_point() {
         let mut rng = ChaCha12Rng::from_seed([0_u8; 32]);
@@ -0,0 +1,337 @@
+//! The prime field `GF(2^255 - 19)` underlying Curve25519.
+
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use itybity::{BitLength, FromBitIterator, GetBit, Lsb0, Msb0};
+use mpz_fields::Field;
+use rand::{distributions::Standard, prelude::Distribution, Rng};
+use serde::{Deserialize, Serialize};
+
+/// The prime `2^255 - 19` as little-endian 64-bit limbs.
+const MODULUS: [u64; 4] = [
+    0xffff_ffff_ffff_ffed,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0x7fff_ffff_ffff_ffff,
+];
+
+/// `p - 2`, the exponent used for inversion.
+const MODULUS_MINUS_TWO: [u64; 4] = [
+    0xffff_ffff_ffff_ffeb,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0x7fff_ffff_ffff_ffff,
+];
+
+/// `(p + 3) / 8`, the exponent used for computing square roots.
+const SQRT_EXPONENT: [u64; 4] = [
+    0xffff_ffff_ffff_fffe,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0x0fff_ffff_ffff_ffff,
+];
+
+/// A square root of `-1`.
+const SQRT_M1: F25519 = F25519([
+    0xc4ee_1b27_4a0e_a0b0,
+    0x2f43_1806_ad2f_e478,
+    0x2b4d_0099_3dfb_d7a7,
+    0x2b83_2480_4fc1_df0b,
+]);
+
+/// An element of the prime field with modulus `2^255 - 19`.
+///
+/// Elements are always fully reduced and stored as little-endian 64-bit limbs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct F25519(pub(crate) [u64; 4]);
+
+impl F25519 {
+    /// The length of an encoded field element in bytes.
+    pub const BYTE_LEN: usize = 32;
+
+    /// Creates a new field element from a `u64`.
+    pub fn new(value: u64) -> Self {
+        Self([value, 0, 0, 0])
+    }
+
+    /// Creates a field element from little-endian bytes, reducing modulo `p`.
+    pub fn from_le_bytes_mod_order(bytes: &[u8]) -> Self {
+        Self::from_be_bytes_mod_order(&bytes.iter().rev().copied().collect::<Vec<_>>())
+    }
+
+    /// Creates a field element from big-endian bytes, reducing modulo `p`.
+    pub fn from_be_bytes_mod_order(bytes: &[u8]) -> Self {
+        bytes.iter().fold(Self::zero(), |acc, byte| {
+            acc * Self::new(256) + Self::new(*byte as u64)
+        })
+    }
+
+    /// Returns whether the canonical encoding of this element is odd.
+    pub(crate) fn is_odd(&self) -> bool {
+        self.0[0] & 1 == 1
+    }
+
+    /// Returns a square root of this element, if it exists.
+    pub(crate) fn sqrt(self) -> Option<Self> {
+        // As p = 5 mod 8, a^((p + 3) / 8) is a square root of either a or -a.
+        let root = self.pow(&SQRT_EXPONENT);
+        if root * root == self {
+            Some(root)
+        } else if root * root == -self {
+            Some(root * SQRT_M1)
+        } else {
+            None
+        }
+    }
+
+    /// Raises this element to the power of a little-endian 255-bit exponent.
+    fn pow(self, exponent: &[u64; 4]) -> Self {
+        let mut acc = Self::one();
+        for bit in (0..255).rev() {
+            acc = acc * acc;
+            if (exponent[bit / 64] >> (bit % 64)) & 1 == 1 {
+                acc = acc * self;
+            }
+        }
+        acc
+    }
+
+    /// Reduces a value which is at most a few multiples of `p`.
+    fn reduce_small(mut limbs: [u64; 4]) -> Self {
+        while cmp_limbs(&limbs, &MODULUS) != Ordering::Less {
+            limbs = sub_limbs(&limbs, &MODULUS);
+        }
+        Self(limbs)
+    }
+}
+
+/// Compares two little-endian limb arrays.
+fn cmp_limbs(a: &[u64; 4], b: &[u64; 4]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+/// Returns `a - b`, assuming `a >= b`.
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut out = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (diff, borrow_0) = a[i].overflowing_sub(b[i]);
+        let (diff, borrow_1) = diff.overflowing_sub(borrow as u64);
+        out[i] = diff;
+        borrow = borrow_0 || borrow_1;
+    }
+    out
+}
+
+/// Returns `low + high * 38`, which is congruent to `low + high * 2^256`.
+fn fold_high(low: &[u64], high: &[u64]) -> [u64; 5] {
+    let mut out = [0u64; 5];
+    let mut carry = 0u128;
+    for i in 0..4 {
+        let t = low[i] as u128 + high.get(i).copied().unwrap_or(0) as u128 * 38 + carry;
+        out[i] = t as u64;
+        carry = t >> 64;
+    }
+    out[4] = carry as u64;
+    out
+}
+
+impl Field for F25519 {
+    const BIT_SIZE: u32 = 255;
+
+    fn zero() -> Self {
+        Self([0; 4])
+    }
+
+    fn one() -> Self {
+        Self([1, 0, 0, 0])
+    }
+
+    fn two_pow(rhs: u32) -> Self {
+        (0..rhs).fold(Self::one(), |acc, _| acc + acc)
+    }
+
+    fn inverse(self) -> Self {
+        // Fermat's little theorem, a^(p - 2) = a^-1.
+        self.pow(&MODULUS_MINUS_TWO)
+    }
+
+    fn to_le_bytes(&self) -> Vec<u8> {
+        self.0.iter().flat_map(|limb| limb.to_le_bytes()).collect()
+    }
+
+    fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.to_le_bytes();
+        bytes.reverse();
+        bytes
+    }
+}
+
+impl Add for F25519 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // Both operands are below 2^255, so the sum can not overflow.
+        let mut out = [0u64; 4];
+        let mut carry = false;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (sum, carry_0) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, carry_1) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = carry_0 || carry_1;
+        }
+        Self::reduce_small(out)
+    }
+}
+
+impl Neg for F25519 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if self == Self::zero() {
+            self
+        } else {
+            Self(sub_limbs(&MODULUS, &self.0))
+        }
+    }
+}
+
+impl Sub for F25519 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for F25519 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut wide = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = wide[i + j] as u128 + self.0[i] as u128 * rhs.0[j] as u128 + carry;
+                wide[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            wide[i + 4] = carry as u64;
+        }
+
+        // Fold everything above 2^256 back in, using 2^256 = 38 mod p. The carry
+        // limb shrinks with every fold, so this terminates after a few rounds.
+        let mut folded = fold_high(&wide[..4], &wide[4..]);
+        while folded[4] != 0 {
+            folded = fold_high(&folded[..4], &folded[4..]);
+        }
+
+        Self::reduce_small([folded[0], folded[1], folded[2], folded[3]])
+    }
+}
+
+impl PartialOrd for F25519 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for F25519 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.0, &other.0)
+    }
+}
+
+impl Distribution<F25519> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> F25519 {
+        // Rejection sampling of 255-bit values.
+        loop {
+            let limbs = [
+                rng.gen(),
+                rng.gen(),
+                rng.gen(),
+                rng.gen::<u64>() & 0x7fff_ffff_ffff_ffff,
+            ];
+            if cmp_limbs(&limbs, &MODULUS) == Ordering::Less {
+                return F25519(limbs);
+            }
+        }
+    }
+}
+
+impl BitLength for F25519 {
+    const BITS: usize = 255;
+}
+
+impl GetBit<Lsb0> for F25519 {
+    fn get_bit(&self, index: usize) -> bool {
+        assert!(index < Self::BITS, "index out of bounds");
+        (self.0[index / 64] >> (index % 64)) & 1 == 1
+    }
+}
+
+impl GetBit<Msb0> for F25519 {
+    fn get_bit(&self, index: usize) -> bool {
+        assert!(index < Self::BITS, "index out of bounds");
+        GetBit::<Lsb0>::get_bit(self, Self::BITS - 1 - index)
+    }
+}
+
+impl FromBitIterator for F25519 {
+    fn from_lsb0_iter(iter: impl IntoIterator<Item = bool>) -> Self {
+        let mut limbs = [0u64; 4];
+        for (i, bit) in iter.into_iter().take(Self::BITS).enumerate() {
+            limbs[i / 64] |= (bit as u64) << (i % 64);
+        }
+        Self::reduce_small(limbs)
+    }
+
+    fn from_msb0_iter(iter: impl IntoIterator<Item = bool>) -> Self {
+        let mut bits = iter.into_iter().take(Self::BITS).collect::<Vec<_>>();
+        bits.reverse();
+        Self::from_lsb0_iter(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_f25519_arithmetic() {
+        let mut rng = ChaCha12Rng::from_seed([0; 32]);
+
+        for _ in 0..100 {
+            let a: u32 = rng.gen();
+            let b: u32 = rng.gen();
+            assert_eq!(
+                F25519::new(a as u64) * F25519::new(b as u64),
+                F25519::new(a as u64 * b as u64)
+            );
+
+            let x: F25519 = rng.gen();
+            let y: F25519 = rng.gen();
+            assert_eq!(x * x.inverse(), F25519::one());
+            assert_eq!(x + -x, F25519::zero());
+            assert_eq!((x - y) + y, x);
+            assert_eq!((x * x).sqrt().map(|root| root * root), Some(x * x));
+            assert_eq!(F25519::from_le_bytes_mod_order(&x.to_le_bytes()), x);
+            assert_eq!(F25519::from_be_bytes_mod_order(&x.to_be_bytes()), x);
+            assert_eq!(
+                F25519::from_lsb0_iter((0..255).map(|i| GetBit::<Lsb0>::get_bit(&x, i))),
+                x
+            );
+        }
+
+        assert_eq!(F25519::two_pow(255), F25519::new(19));
+        assert_eq!(-F25519::one() + F25519::new(20), F25519::two_pow(255));
+        assert_eq!(SQRT_M1 * SQRT_M1, -F25519::one());
+    }
+}
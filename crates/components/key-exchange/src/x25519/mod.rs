@@ -0,0 +1,109 @@
+//! Curve25519 support for the X25519 key exchange.
+//!
+//! Scalar multiplication is done on the birationally equivalent Edwards curve,
+//! but point addition in 2PC uses the Montgomery form `v^2 = u^3 + A u^2 + u`,
+//! whose chord formula `u_r = λ^2 - A - u_1 - u_2` only differs from the
+//! short Weierstrass one by the constant `A`. The X25519 shared secret is the
+//! `u` coordinate of the resulting point.
+
+mod field;
+
+pub use field::F25519;
+
+use curve25519_dalek::{edwards::EdwardsPoint, traits::IsIdentity};
+use mpz_fields::Field;
+
+use crate::{error::ErrorKind, KeyExchangeError};
+
+/// The Montgomery curve coefficient `A`.
+pub(crate) const MONTGOMERY_A: u64 = 486662;
+
+/// The Edwards curve coefficient `d = -121665 / 121666`.
+const EDWARDS_D: F25519 = F25519([
+    0x75eb_4dca_1359_78a3,
+    0x0070_0a4d_4141_d8ab,
+    0x8cc7_4079_7779_e898,
+    0x5203_6cee_2b6f_fe73,
+]);
+
+/// The even square root of `-(A + 2)`, used to map Edwards points to the
+/// Montgomery curve.
+const SQRT_MINUS_A_PLUS_TWO: F25519 = F25519([
+    0xcc6e_04aa_ff45_7e06,
+    0xc5a1_d3d1_4b7d_1a82,
+    0xd27b_08dc_03fc_4f7e,
+    0x0f26_edf4_60a0_06bb,
+]);
+
+/// Decomposes the Montgomery `u` and `v` coordinates of an Edwards point.
+pub(crate) fn decompose_point(point: &EdwardsPoint) -> Result<[F25519; 2], KeyExchangeError> {
+    if point.is_identity() || point.is_small_order() {
+        return Err(KeyExchangeError::new(
+            ErrorKind::Key,
+            "key share is a point of small order",
+        ));
+    }
+
+    // The compressed encoding is the little-endian y coordinate with the sign
+    // of x in the most significant bit.
+    let mut y = point.compress().to_bytes();
+    let x_is_odd = y[31] >> 7 == 1;
+    y[31] &= 0x7f;
+    let y = F25519::from_le_bytes_mod_order(&y);
+
+    // x^2 = (y^2 - 1) / (d y^2 + 1)
+    let y2 = y * y;
+    let x = ((y2 - F25519::one()) * (EDWARDS_D * y2 + F25519::one()).inverse())
+        .sqrt()
+        .expect("point is on the curve");
+    let x = if x.is_odd() == x_is_odd { x } else { -x };
+
+    // (u, v) = ((1 + y) / (1 - y), sqrt(-(A + 2)) u / x)
+    let u = (F25519::one() + y) * (F25519::one() - y).inverse();
+    let v = SQRT_MINUS_A_PLUS_TWO * u * x.inverse();
+
+    Ok([u, v])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use curve25519_dalek::scalar::Scalar;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(EDWARDS_D * F25519::new(121666), -F25519::new(121665));
+        assert_eq!(
+            SQRT_MINUS_A_PLUS_TWO * SQRT_MINUS_A_PLUS_TWO,
+            -F25519::new(MONTGOMERY_A + 2)
+        );
+    }
+
+    #[test]
+    fn test_decompose_point() {
+        let mut rng = ChaCha12Rng::from_seed([0u8; 32]);
+        let a = F25519::new(MONTGOMERY_A);
+
+        let p = EdwardsPoint::mul_base(&Scalar::random(&mut rng));
+        let q = EdwardsPoint::mul_base(&Scalar::random(&mut rng));
+
+        let [u_p, v_p] = decompose_point(&p).unwrap();
+        let [u_q, v_q] = decompose_point(&q).unwrap();
+
+        // The coordinates are on the Montgomery curve and u matches X25519.
+        assert_eq!(v_p * v_p, u_p * u_p * u_p + a * u_p * u_p + u_p);
+        assert_eq!(u_p.to_le_bytes(), p.to_montgomery().to_bytes().to_vec());
+
+        // The chord formula agrees with Edwards addition.
+        let lambda = (v_q - v_p) * (u_q - u_p).inverse();
+        let u_r = lambda * lambda - a - u_p - u_q;
+
+        assert_eq!(
+            u_r.to_le_bytes(),
+            (p + q).to_montgomery().to_bytes().to_vec()
+        );
+    }
+}
@@ -5,7 +5,10 @@ use mpz_circuits::{
     Circuit,
 };
 
-use crate::{circuit::AES_CTR, StreamCipherError};
+use crate::{
+    circuit::{AES_CTR, CHACHA20_CTR},
+    StreamCipherError,
+};
 
 /// A counter-mode block cipher circuit.
 pub trait CtrCircuit: Default + Clone + Send + Sync + 'static {
@@ -116,3 +119,62 @@ impl CtrCircuit for Aes128Ctr {
         Ok(buf)
     }
 }
+
+/// A circuit for ChaCha20, using the TLS 1.2 nonce construction of RFC 7905.
+///
+/// The explicit nonce is the 8-byte record sequence number, which is XORed
+/// into the 12-byte IV. It is not sent on the wire.
+#[derive(Default, Debug, Clone)]
+pub struct ChaCha20;
+
+impl CtrCircuit for ChaCha20 {
+    type KEY = [u8; 32];
+    type BLOCK = [u8; 64];
+    type IV = [u8; 12];
+    type NONCE = [u8; 8];
+
+    const KEY_LEN: usize = 32;
+    const BLOCK_LEN: usize = 64;
+    const IV_LEN: usize = 12;
+    const NONCE_LEN: usize = 8;
+
+    fn circuit() -> Arc<Circuit> {
+        CHACHA20_CTR.clone()
+    }
+
+    fn apply_keystream(
+        key: &[u8],
+        iv: &[u8],
+        start_ctr: usize,
+        explicit_nonce: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, StreamCipherError> {
+        use ::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+
+        let key: &[u8; 32] = key
+            .try_into()
+            .map_err(|_| StreamCipherError::key_len::<Self>(key.len()))?;
+        let iv: &[u8; 12] = iv
+            .try_into()
+            .map_err(|_| StreamCipherError::iv_len::<Self>(iv.len()))?;
+        let explicit_nonce: &[u8; 8] = explicit_nonce
+            .try_into()
+            .map_err(|_| StreamCipherError::explicit_nonce_len::<Self>(explicit_nonce.len()))?;
+
+        let mut nonce = *iv;
+        nonce[4..]
+            .iter_mut()
+            .zip(explicit_nonce)
+            .for_each(|(nonce, seq)| *nonce ^= seq);
+
+        let mut cipher = chacha20::ChaCha20::new(key.into(), &nonce.into());
+        let mut buf = msg.to_vec();
+
+        cipher
+            .try_seek(start_ctr * Self::BLOCK_LEN)
+            .expect("start counter is less than keystream length");
+        cipher.apply_keystream(&mut buf);
+
+        Ok(buf)
+    }
+}
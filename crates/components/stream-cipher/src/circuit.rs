@@ -1,4 +1,7 @@
-use mpz_circuits::{circuits::aes128_trace, once_cell::sync::Lazy, trace, Circuit, CircuitBuilder};
+use mpz_circuits::{
+    circuits::aes128_trace, once_cell::sync::Lazy, ops::WrappingAdd, trace, types::U8, Circuit,
+    CircuitBuilder, Tracer,
+};
 use std::sync::Arc;
 
 /// AES encrypts a counter block.
@@ -46,6 +49,165 @@ fn aes_128(key: [u8; 16], msg: [u8; 16]) -> [u8; 16] {
     ciphertext.into()
 }
 
+/// ChaCha20 computes a keystream block, using the TLS 1.2 nonce construction
+/// of RFC 7905.
+///
+/// # Inputs
+///
+///   0. KEY: 32-byte encryption key
+///   1. IV: 12-byte IV
+///   2. EXPLICIT_NONCE: 8-byte sequence number
+///   3. CTR: 4-byte big-endian counter
+///
+/// # Outputs
+///
+///   0. BLOCK: 64-byte keystream block
+pub(crate) static CHACHA20_CTR: Lazy<Arc<Circuit>> = Lazy::new(|| {
+    let builder = CircuitBuilder::new();
+    let key = builder.add_array_input::<u8, 32>();
+    let iv = builder.add_array_input::<u8, 12>();
+    let explicit_nonce = builder.add_array_input::<u8, 8>();
+    let ctr = builder.add_array_input::<u8, 4>();
+
+    // The nonce is the IV XORed with the left-padded sequence number.
+    let nonce = iv[..4]
+        .iter()
+        .copied()
+        .chain(
+            iv[4..]
+                .iter()
+                .zip(explicit_nonce)
+                .map(|(iv, seq)| *iv ^ seq),
+        )
+        .collect::<Vec<_>>();
+    // ChaCha20 words are little-endian.
+    let ctr = ctr.into_iter().rev().collect::<Vec<_>>();
+
+    let block = chacha20_block(&builder, &key, &nonce, &ctr);
+    builder.add_output(block);
+
+    Arc::new(builder.build().unwrap())
+});
+
+/// The ChaCha20 constant "expand 32-byte k".
+const CHACHA20_SIGMA: &[u8; 16] = b"expand 32-byte k";
+
+/// Traces the ChaCha20 block function of RFC 8439, returning the 64-byte
+/// keystream block.
+///
+/// # Arguments
+///
+/// * `builder` - The circuit builder.
+/// * `key` - The 32-byte key.
+/// * `nonce` - The 12-byte nonce.
+/// * `ctr` - The 4-byte little-endian block counter.
+///
+/// # Panics
+///
+/// Panics if any of the inputs has the wrong length.
+pub fn chacha20_block<'a>(
+    builder: &'a CircuitBuilder,
+    key: &[Tracer<'a, U8>],
+    nonce: &[Tracer<'a, U8>],
+    ctr: &[Tracer<'a, U8>],
+) -> Vec<Tracer<'a, U8>> {
+    assert_eq!(key.len(), 32, "key must be 32 bytes");
+    assert_eq!(nonce.len(), 12, "nonce must be 12 bytes");
+    assert_eq!(ctr.len(), 4, "counter must be 4 bytes");
+
+    let constant = |value: u8| Tracer::new(builder.state(), builder.get_constant(value).to_inner());
+    let zero = constant(0);
+
+    // The state is 16 words of 4 little-endian bytes each.
+    let init = CHACHA20_SIGMA
+        .iter()
+        .map(|byte| constant(*byte))
+        .chain(key.iter().copied())
+        .chain(ctr.iter().copied())
+        .chain(nonce.iter().copied())
+        .collect::<Vec<_>>()
+        .chunks(4)
+        .map(|word| word.to_vec())
+        .collect::<Vec<_>>();
+
+    let mut state = init.clone();
+    for _ in 0..10 {
+        // Column rounds.
+        quarter_round(&mut state, [0, 4, 8, 12], zero);
+        quarter_round(&mut state, [1, 5, 9, 13], zero);
+        quarter_round(&mut state, [2, 6, 10, 14], zero);
+        quarter_round(&mut state, [3, 7, 11, 15], zero);
+        // Diagonal rounds.
+        quarter_round(&mut state, [0, 5, 10, 15], zero);
+        quarter_round(&mut state, [1, 6, 11, 12], zero);
+        quarter_round(&mut state, [2, 7, 8, 13], zero);
+        quarter_round(&mut state, [3, 4, 9, 14], zero);
+    }
+
+    state
+        .iter()
+        .zip(&init)
+        .flat_map(|(word, init)| add_words(word, init, zero))
+        .collect()
+}
+
+/// The ChaCha20 quarter round on the words at the given indices.
+fn quarter_round<'a>(
+    state: &mut [Vec<Tracer<'a, U8>>],
+    [a, b, c, d]: [usize; 4],
+    zero: Tracer<'a, U8>,
+) {
+    state[a] = add_words(&state[a], &state[b], zero);
+    state[d] = rotate_left(&xor_words(&state[d], &state[a]), 16);
+    state[c] = add_words(&state[c], &state[d], zero);
+    state[b] = rotate_left(&xor_words(&state[b], &state[c]), 12);
+    state[a] = add_words(&state[a], &state[b], zero);
+    state[d] = rotate_left(&xor_words(&state[d], &state[a]), 8);
+    state[c] = add_words(&state[c], &state[d], zero);
+    state[b] = rotate_left(&xor_words(&state[b], &state[c]), 7);
+}
+
+/// Adds two little-endian words, ignoring the final carry.
+fn add_words<'a>(
+    a: &[Tracer<'a, U8>],
+    b: &[Tracer<'a, U8>],
+    zero: Tracer<'a, U8>,
+) -> Vec<Tracer<'a, U8>> {
+    let mut carry = zero;
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let sum = a.wrapping_add(*b);
+            let out = sum.wrapping_add(carry);
+            // The carries out of `a + b` and `sum + carry` are never both set, so they
+            // can be combined with XOR.
+            carry = (((*a & *b) ^ ((*a ^ *b) & !sum)) ^ (sum & !out)) >> 7;
+            out
+        })
+        .collect()
+}
+
+/// XORs two words.
+fn xor_words<'a>(a: &[Tracer<'a, U8>], b: &[Tracer<'a, U8>]) -> Vec<Tracer<'a, U8>> {
+    a.iter().zip(b).map(|(a, b)| *a ^ *b).collect()
+}
+
+/// Rotates a little-endian word left by `n` bits.
+fn rotate_left<'a>(word: &[Tracer<'a, U8>], n: usize) -> Vec<Tracer<'a, U8>> {
+    let (bytes, bits) = (n / 8, n % 8);
+    let word = (0..4)
+        .map(|i| word[(i + 4 - bytes) % 4])
+        .collect::<Vec<_>>();
+
+    if bits == 0 {
+        return word;
+    }
+
+    (0..4)
+        .map(|i| (word[i] << bits) ^ (word[(i + 3) % 4] >> (8 - bits)))
+        .collect()
+}
+
 /// Builds a circuit for computing the XOR of two arrays.
 pub(crate) fn build_array_xor(len: usize) -> Arc<Circuit> {
     let builder = CircuitBuilder::new();
@@ -55,3 +217,37 @@ pub(crate) fn build_array_xor(len: usize) -> Arc<Circuit> {
     builder.add_output(c);
     Arc::new(builder.build().expect("circuit is valid"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mpz_circuits::types::Value;
+
+    use crate::{ChaCha20, CtrCircuit};
+
+    #[test]
+    fn test_chacha20_ctr_circuit() {
+        let key = [42u8; 32];
+        let iv = [7u8; 12];
+        let explicit_nonce = 1u64.to_be_bytes();
+
+        for ctr in [0u32, 1, 2] {
+            let output = CHACHA20_CTR
+                .evaluate(&[
+                    Value::from(key.to_vec()),
+                    Value::from(iv.to_vec()),
+                    Value::from(explicit_nonce.to_vec()),
+                    Value::from(ctr.to_be_bytes().to_vec()),
+                ])
+                .unwrap();
+            let block: Vec<u8> = output[0].clone().try_into().unwrap();
+
+            let expected =
+                ChaCha20::apply_keystream(&key, &iv, ctr as usize, &explicit_nonce, &[0u8; 64])
+                    .unwrap();
+
+            assert_eq!(block, expected);
+        }
+    }
+}
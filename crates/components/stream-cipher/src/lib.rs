@@ -1,5 +1,5 @@
 //! This crate provides a 2PC stream cipher implementation using a block cipher
-//! in counter mode.
+//! in counter mode, or ChaCha20.
 //!
 //! Each party plays a specific role, either the `StreamCipherLeader` or the
 //! `StreamCipherFollower`. Both parties work together to encrypt and decrypt
@@ -26,7 +26,8 @@ pub(crate) mod error;
 pub(crate) mod keystream;
 mod stream_cipher;
 
-pub use self::cipher::{Aes128Ctr, CtrCircuit};
+pub use self::cipher::{Aes128Ctr, ChaCha20, CtrCircuit};
+pub use circuit::chacha20_block;
 pub use config::{StreamCipherConfig, StreamCipherConfigBuilder, StreamCipherConfigBuilderError};
 pub use error::StreamCipherError;
 pub use stream_cipher::MpcStreamCipher;
@@ -195,7 +196,7 @@ where
 mod tests {
     use std::time::Duration;
 
-    use crate::cipher::Aes128Ctr;
+    use crate::cipher::{Aes128Ctr, ChaCha20};
 
     use super::*;
 
@@ -207,25 +208,33 @@ mod tests {
 
     async fn create_test_pair<C: CtrCircuit>(
         start_ctr: usize,
-        key: [u8; 16],
-        iv: [u8; 4],
+        key: &[u8],
+        iv: &[u8],
     ) -> (
         MpcStreamCipher<C, MockLeader>,
         MpcStreamCipher<C, MockFollower>,
     ) {
         let (leader_vm, follower_vm) = create_mock_deap_vm();
 
-        let leader_key = leader_vm.new_public_input::<[u8; 16]>("key").unwrap();
-        let leader_iv = leader_vm.new_public_input::<[u8; 4]>("iv").unwrap();
+        let leader_key = leader_vm
+            .new_public_array_input::<u8>("key", key.len())
+            .unwrap();
+        let leader_iv = leader_vm
+            .new_public_array_input::<u8>("iv", iv.len())
+            .unwrap();
 
-        leader_vm.assign(&leader_key, key).unwrap();
-        leader_vm.assign(&leader_iv, iv).unwrap();
+        leader_vm.assign(&leader_key, key.to_vec()).unwrap();
+        leader_vm.assign(&leader_iv, iv.to_vec()).unwrap();
 
-        let follower_key = follower_vm.new_public_input::<[u8; 16]>("key").unwrap();
-        let follower_iv = follower_vm.new_public_input::<[u8; 4]>("iv").unwrap();
+        let follower_key = follower_vm
+            .new_public_array_input::<u8>("key", key.len())
+            .unwrap();
+        let follower_iv = follower_vm
+            .new_public_array_input::<u8>("iv", iv.len())
+            .unwrap();
 
-        follower_vm.assign(&follower_key, key).unwrap();
-        follower_vm.assign(&follower_iv, iv).unwrap();
+        follower_vm.assign(&follower_key, key.to_vec()).unwrap();
+        follower_vm.assign(&follower_iv, iv.to_vec()).unwrap();
 
         let leader_config = StreamCipherConfig::builder()
             .id("test")
@@ -259,7 +268,7 @@ mod tests {
 
         let msg = b"This is a test message which will be encrypted using AES-CTR.".to_vec();
 
-        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, key, iv).await;
+        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, &key, &iv).await;
 
         let leader_fut = async {
             let leader_encrypted_msg = leader
@@ -302,6 +311,41 @@ mod tests {
         assert_eq!(follower_decrypted_msg, msg);
     }
 
+    #[rstest]
+    #[timeout(Duration::from_millis(10000))]
+    #[tokio::test]
+    #[ignore = "expensive"]
+    async fn test_stream_cipher_chacha20() {
+        let key = [1u8; 32];
+        let iv = [2u8; 12];
+        let explicit_nonce = 3u64.to_be_bytes();
+
+        let msg = b"This is a test message which will be encrypted using ChaCha20, \
+            which needs more than one keystream block."
+            .to_vec();
+
+        let ciphertext = ChaCha20::apply_keystream(&key, &iv, 1, &explicit_nonce, &msg).unwrap();
+
+        let (mut leader, mut follower) = create_test_pair::<ChaCha20>(1, &key, &iv).await;
+
+        let (leader_encrypted_msg, follower_encrypted_msg) = futures::try_join!(
+            leader.encrypt_public(explicit_nonce.to_vec(), msg.clone()),
+            follower.encrypt_public(explicit_nonce.to_vec(), msg.clone())
+        )
+        .unwrap();
+
+        assert_eq!(leader_encrypted_msg, ciphertext);
+        assert_eq!(follower_encrypted_msg, ciphertext);
+
+        let (leader_decrypted_msg, _) = futures::try_join!(
+            leader.decrypt_private(explicit_nonce.to_vec(), ciphertext.clone()),
+            follower.decrypt_blind(explicit_nonce.to_vec(), ciphertext.clone())
+        )
+        .unwrap();
+
+        assert_eq!(leader_decrypted_msg, msg);
+    }
+
     #[rstest]
     #[timeout(Duration::from_millis(10000))]
     #[tokio::test]
@@ -315,7 +359,7 @@ mod tests {
 
         let ciphertext = Aes128Ctr::apply_keystream(&key, &iv, 1, &explicit_nonce, &msg).unwrap();
 
-        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, key, iv).await;
+        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, &key, &iv).await;
 
         let leader_fut = async {
             let leader_decrypted_msg = leader
@@ -366,7 +410,7 @@ mod tests {
         let iv = [0u8; 4];
         let explicit_nonce = [0u8; 8];
 
-        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, key, iv).await;
+        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, &key, &iv).await;
 
         let leader_fut = async {
             leader
@@ -409,7 +453,7 @@ mod tests {
 
         let ciphertext = Aes128Ctr::apply_keystream(&key, &iv, 2, &explicit_nonce, &msg).unwrap();
 
-        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(2, key, iv).await;
+        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(2, &key, &iv).await;
 
         futures::try_join!(leader.decode_key_private(), follower.decode_key_blind()).unwrap();
 
@@ -441,7 +485,7 @@ mod tests {
 
         let ciphertext = Aes128Ctr::apply_keystream(&key, &iv, 1, &explicit_nonce, &msg).unwrap();
 
-        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, key, iv).await;
+        let (mut leader, mut follower) = create_test_pair::<Aes128Ctr>(1, &key, &iv).await;
 
         let leader_fut = async {
             leader.preprocess(len).await.unwrap();
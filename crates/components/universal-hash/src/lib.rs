@@ -7,6 +7,9 @@
 /// This module implements [UniversalHash] for Ghash.
 #[cfg(feature = "ghash")]
 pub mod ghash;
+/// This module implements [UniversalHash] for Poly1305.
+#[cfg(feature = "poly1305")]
+pub mod poly1305;
 
 use async_trait::async_trait;
 
@@ -0,0 +1,24 @@
+use derive_builder::Builder;
+
+#[derive(Debug, Clone, Builder)]
+/// Configuration struct for [Poly1305](crate::poly1305::Poly1305).
+pub struct Poly1305Config {
+    /// Number of keys to provision share conversions for during
+    /// preprocessing.
+    #[builder(default = "1")]
+    pub key_count: usize,
+    /// Total number of message blocks, over all keys, to provision share
+    /// conversions for during preprocessing.
+    #[builder(default = "1026")]
+    pub block_count: usize,
+    /// Maximum number of blocks supported per message.
+    #[builder(default = "1026")]
+    pub max_block_count: usize,
+}
+
+impl Poly1305Config {
+    /// Creates a new builder for the [Poly1305Config].
+    pub fn builder() -> Poly1305ConfigBuilder {
+        Poly1305ConfigBuilder::default()
+    }
+}
@@ -0,0 +1,286 @@
+//! The prime field `GF(2^130 - 5)` used by Poly1305.
+
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use itybity::{BitLength, FromBitIterator, GetBit, Lsb0, Msb0};
+use mpz_fields::Field;
+use rand::{distributions::Standard, prelude::Distribution, Rng};
+use serde::{Deserialize, Serialize};
+
+/// The prime `2^130 - 5` as little-endian 64-bit limbs.
+const MODULUS: [u64; 3] = [0xffff_ffff_ffff_fffb, 0xffff_ffff_ffff_ffff, 0x3];
+
+/// `p - 2`, the exponent used for inversion.
+const MODULUS_MINUS_TWO: [u64; 3] = [0xffff_ffff_ffff_fff9, 0xffff_ffff_ffff_ffff, 0x3];
+
+/// An element of the prime field with modulus `2^130 - 5`.
+///
+/// Elements are always fully reduced and stored as little-endian 64-bit limbs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct P1305([u64; 3]);
+
+impl P1305 {
+    /// The length of an encoded field element in bytes.
+    pub const BYTE_LEN: usize = 17;
+
+    /// Creates a new field element from a `u128`.
+    pub fn new(value: u128) -> Self {
+        Self([value as u64, (value >> 64) as u64, 0])
+    }
+
+    /// Creates a field element from little-endian bytes, reducing modulo `p`.
+    pub fn from_le_bytes_mod_order(bytes: &[u8]) -> Self {
+        Self::from_be_bytes_mod_order(&bytes.iter().rev().copied().collect::<Vec<_>>())
+    }
+
+    /// Creates a field element from big-endian bytes, reducing modulo `p`.
+    pub fn from_be_bytes_mod_order(bytes: &[u8]) -> Self {
+        bytes.iter().fold(Self::zero(), |acc, byte| {
+            acc * Self::new(256) + Self::new(*byte as u128)
+        })
+    }
+
+    /// Reduces a value which is at most a few multiples of `p`.
+    fn reduce_small(mut limbs: [u64; 3]) -> Self {
+        while cmp_limbs(&limbs, &MODULUS) != Ordering::Less {
+            limbs = sub_limbs(&limbs, &MODULUS);
+        }
+        Self(limbs)
+    }
+}
+
+/// Compares two little-endian limb arrays.
+fn cmp_limbs(a: &[u64; 3], b: &[u64; 3]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+/// Returns `a - b`, assuming `a >= b`.
+fn sub_limbs(a: &[u64; 3], b: &[u64; 3]) -> [u64; 3] {
+    let mut out = [0u64; 3];
+    let mut borrow = false;
+    for i in 0..3 {
+        let (diff, borrow_0) = a[i].overflowing_sub(b[i]);
+        let (diff, borrow_1) = diff.overflowing_sub(borrow as u64);
+        out[i] = diff;
+        borrow = borrow_0 || borrow_1;
+    }
+    out
+}
+
+impl Field for P1305 {
+    const BIT_SIZE: u32 = 130;
+
+    fn zero() -> Self {
+        Self([0; 3])
+    }
+
+    fn one() -> Self {
+        Self([1, 0, 0])
+    }
+
+    fn two_pow(rhs: u32) -> Self {
+        (0..rhs).fold(Self::one(), |acc, _| acc + acc)
+    }
+
+    fn inverse(self) -> Self {
+        // Fermat's little theorem, a^(p - 2) = a^-1.
+        let mut acc = Self::one();
+        for bit in (0..130).rev() {
+            acc = acc * acc;
+            if (MODULUS_MINUS_TWO[bit / 64] >> (bit % 64)) & 1 == 1 {
+                acc = acc * self;
+            }
+        }
+        acc
+    }
+
+    fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::BYTE_LEN);
+        bytes.extend_from_slice(&self.0[0].to_le_bytes());
+        bytes.extend_from_slice(&self.0[1].to_le_bytes());
+        bytes.push(self.0[2] as u8);
+        bytes
+    }
+
+    fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.to_le_bytes();
+        bytes.reverse();
+        bytes
+    }
+}
+
+impl Add for P1305 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // Both operands are below 2^130, so the sum can not overflow.
+        let mut out = [0u64; 3];
+        let mut carry = false;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (sum, carry_0) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, carry_1) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = carry_0 || carry_1;
+        }
+        Self::reduce_small(out)
+    }
+}
+
+impl Neg for P1305 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if self == Self::zero() {
+            self
+        } else {
+            Self(sub_limbs(&MODULUS, &self.0))
+        }
+    }
+}
+
+impl Sub for P1305 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for P1305 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut wide = [0u64; 6];
+        for i in 0..3 {
+            let mut carry = 0u128;
+            for j in 0..3 {
+                let t = wide[i + j] as u128 + self.0[i] as u128 * rhs.0[j] as u128 + carry;
+                wide[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            wide[i + 3] = carry as u64;
+        }
+
+        // Fold everything above 2^130 back in, using 2^130 = 5 mod p. The product is
+        // below 2^260, so the high part always fits into three limbs.
+        while wide[2] >> 2 != 0 || wide[3..].iter().any(|limb| *limb != 0) {
+            let high = [
+                (wide[2] >> 2) | (wide[3] << 62),
+                (wide[3] >> 2) | (wide[4] << 62),
+                (wide[4] >> 2) | (wide[5] << 62),
+            ];
+            let low = [wide[0], wide[1], wide[2] & 0x3];
+
+            let mut next = [0u64; 6];
+            let mut carry = 0u128;
+            for i in 0..3 {
+                let t = low[i] as u128 + high[i] as u128 * 5 + carry;
+                next[i] = t as u64;
+                carry = t >> 64;
+            }
+            next[3] = carry as u64;
+            wide = next;
+        }
+
+        Self::reduce_small([wide[0], wide[1], wide[2]])
+    }
+}
+
+impl PartialOrd for P1305 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for P1305 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.0, &other.0)
+    }
+}
+
+impl Distribution<P1305> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> P1305 {
+        // Rejection sampling of 130-bit values.
+        loop {
+            let limbs = [rng.gen(), rng.gen(), rng.gen::<u64>() & 0x3];
+            if cmp_limbs(&limbs, &MODULUS) == Ordering::Less {
+                return P1305(limbs);
+            }
+        }
+    }
+}
+
+impl BitLength for P1305 {
+    const BITS: usize = 130;
+}
+
+impl GetBit<Lsb0> for P1305 {
+    fn get_bit(&self, index: usize) -> bool {
+        assert!(index < Self::BITS, "index out of bounds");
+        (self.0[index / 64] >> (index % 64)) & 1 == 1
+    }
+}
+
+impl GetBit<Msb0> for P1305 {
+    fn get_bit(&self, index: usize) -> bool {
+        assert!(index < Self::BITS, "index out of bounds");
+        GetBit::<Lsb0>::get_bit(self, Self::BITS - 1 - index)
+    }
+}
+
+impl FromBitIterator for P1305 {
+    fn from_lsb0_iter(iter: impl IntoIterator<Item = bool>) -> Self {
+        let mut limbs = [0u64; 3];
+        for (i, bit) in iter.into_iter().take(Self::BITS).enumerate() {
+            limbs[i / 64] |= (bit as u64) << (i % 64);
+        }
+        Self::reduce_small(limbs)
+    }
+
+    fn from_msb0_iter(iter: impl IntoIterator<Item = bool>) -> Self {
+        let mut bits = iter.into_iter().take(Self::BITS).collect::<Vec<_>>();
+        bits.reverse();
+        Self::from_lsb0_iter(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn test_p1305_arithmetic() {
+        let mut rng = ChaCha12Rng::from_seed([0; 32]);
+
+        for _ in 0..100 {
+            let a: u64 = rng.gen();
+            let b: u64 = rng.gen();
+            assert_eq!(
+                P1305::new(a as u128) * P1305::new(b as u128),
+                P1305::new(a as u128 * b as u128)
+            );
+
+            let x: P1305 = rng.gen();
+            let y: P1305 = rng.gen();
+            assert_eq!(x * x.inverse(), P1305::one());
+            assert_eq!(x + -x, P1305::zero());
+            assert_eq!((x - y) + y, x);
+            assert_eq!(P1305::from_le_bytes_mod_order(&x.to_le_bytes()), x);
+            assert_eq!(P1305::from_be_bytes_mod_order(&x.to_be_bytes()), x);
+            assert_eq!(
+                P1305::from_lsb0_iter((0..130).map(|i| GetBit::<Lsb0>::get_bit(&x, i))),
+                x
+            );
+        }
+
+        assert_eq!(P1305::two_pow(130), P1305::new(5));
+        assert_eq!(-P1305::one() + P1305::new(6), P1305::two_pow(130));
+    }
+}
@@ -0,0 +1,100 @@
+//! Ideal Poly1305 functionality.
+
+use async_trait::async_trait;
+use mpz_common::{
+    ideal::{ideal_f2p, Alice, Bob},
+    Context,
+};
+use mpz_fields::Field;
+
+use crate::{
+    poly1305::{encode_block, P1305, BLOCK_LEN},
+    UniversalHash, UniversalHashError,
+};
+
+/// An ideal Poly1305 functionality.
+#[derive(Debug)]
+pub struct IdealPoly1305<Ctx> {
+    role: Role,
+    context: Ctx,
+}
+
+#[derive(Debug)]
+enum Role {
+    Alice(Alice<Option<P1305>>),
+    Bob(Bob<Option<P1305>>),
+}
+
+fn set_key(key: &mut Option<P1305>, alice_key: Vec<u8>, bob_key: Vec<u8>) -> ((), ()) {
+    *key = Some(
+        P1305::from_be_bytes_mod_order(&alice_key) + P1305::from_be_bytes_mod_order(&bob_key),
+    );
+    ((), ())
+}
+
+fn finalize(
+    key: &mut Option<P1305>,
+    alice_input: Vec<u8>,
+    bob_input: Vec<u8>,
+) -> (Vec<u8>, Vec<u8>) {
+    assert_eq!(&alice_input, &bob_input);
+
+    let r = key.take().expect("key is set");
+    let output = alice_input
+        .chunks(BLOCK_LEN)
+        .fold(P1305::zero(), |acc, chunk| (acc + encode_block(chunk)) * r);
+
+    (output.to_be_bytes(), P1305::zero().to_be_bytes())
+}
+
+#[async_trait]
+impl<Ctx: Context> UniversalHash for IdealPoly1305<Ctx> {
+    async fn set_key(&mut self, key: Vec<u8>) -> Result<(), UniversalHashError> {
+        if key.len() != P1305::BYTE_LEN {
+            return Err(UniversalHashError::KeyLengthError(
+                P1305::BYTE_LEN,
+                key.len(),
+            ));
+        }
+
+        match &mut self.role {
+            Role::Alice(alice) => alice.call(&mut self.context, key, set_key).await,
+            Role::Bob(bob) => bob.call(&mut self.context, key, set_key).await,
+        }
+
+        Ok(())
+    }
+
+    async fn setup(&mut self) -> Result<(), UniversalHashError> {
+        Ok(())
+    }
+
+    async fn preprocess(&mut self) -> Result<(), UniversalHashError> {
+        Ok(())
+    }
+
+    async fn finalize(&mut self, input: Vec<u8>) -> Result<Vec<u8>, UniversalHashError> {
+        Ok(match &mut self.role {
+            Role::Alice(alice) => alice.call(&mut self.context, input, finalize).await,
+            Role::Bob(bob) => bob.call(&mut self.context, input, finalize).await,
+        })
+    }
+}
+
+/// Creates an ideal Poly1305 pair.
+pub fn ideal_poly1305<Ctx: Context>(
+    context_alice: Ctx,
+    context_bob: Ctx,
+) -> (IdealPoly1305<Ctx>, IdealPoly1305<Ctx>) {
+    let (alice, bob) = ideal_f2p(None);
+    (
+        IdealPoly1305 {
+            role: Role::Alice(alice),
+            context: context_alice,
+        },
+        IdealPoly1305 {
+            role: Role::Bob(bob),
+            context: context_bob,
+        },
+    )
+}
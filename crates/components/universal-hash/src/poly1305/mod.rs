@@ -0,0 +1,261 @@
+//! This module implements the Poly1305 one-time authenticator (RFC 8439) in a
+//! secure two-party computation (2PC) setting.
+//!
+//! The parties start with additive shares of the clamped key `r` in the prime
+//! field `GF(2^130 - 5)` and at the end each gets an additive share of the
+//! polynomial evaluation `h`. Adding the second key half `s` is left to the
+//! caller, because it is an addition modulo `2^128` rather than in the field.
+//!
+//! As with GHASH, the additive share of `r` is first converted into a
+//! multiplicative share, so that shares of all powers `r^n` can be computed
+//! locally and then converted back into additive shares. Unlike GHASH,
+//! squaring is not linear in a prime field, so every power requires its own
+//! multiplicative-to-additive (M2A) conversion. Poly1305 keys are single-use,
+//! so the conversions are performed anew for every message.
+
+mod config;
+mod field;
+#[cfg(feature = "ideal")]
+mod ideal;
+
+pub use config::{Poly1305Config, Poly1305ConfigBuilder, Poly1305ConfigBuilderError};
+pub use field::P1305;
+#[cfg(feature = "ideal")]
+pub use ideal::{ideal_poly1305, IdealPoly1305};
+
+use async_trait::async_trait;
+use mpz_common::{Context, Preprocess};
+use mpz_fields::Field;
+use mpz_share_conversion::{ShareConversionError, ShareConvert};
+use std::fmt::Debug;
+use tracing::instrument;
+
+use crate::{UniversalHash, UniversalHashError};
+
+/// The Poly1305 block length in bytes.
+const BLOCK_LEN: usize = 16;
+
+/// A 2PC Poly1305 instance, used by both parties.
+///
+/// Keys and outputs are additive shares in `GF(2^130 - 5)`, encoded as
+/// 17-byte big-endian field elements.
+pub struct Poly1305<C, Ctx> {
+    /// Multiplicative share of the current key.
+    key: Option<P1305>,
+    config: Poly1305Config,
+    converter: C,
+    context: Ctx,
+}
+
+impl<C, Ctx> Poly1305<C, Ctx>
+where
+    Ctx: Context,
+    C: ShareConvert<Ctx, P1305>,
+{
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`      - The configuration for this Poly1305 instance.
+    /// * `converter`   - An instance which allows to convert multiplicative
+    ///   into additive shares and vice versa.
+    /// * `context`     - The context.
+    pub fn new(config: Poly1305Config, converter: C, context: Ctx) -> Self {
+        Self {
+            key: None,
+            config,
+            converter,
+            context,
+        }
+    }
+}
+
+impl<C, Ctx> Debug for Poly1305<C, Ctx> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Poly1305")
+            .field("config", &self.config)
+            .field("converter", &"{{ .. }}".to_string())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<Ctx, C> UniversalHash for Poly1305<C, Ctx>
+where
+    Ctx: Context,
+    C: Preprocess<Ctx, Error = ShareConversionError> + ShareConvert<Ctx, P1305> + Send,
+{
+    /// Sets the key share.
+    ///
+    /// Poly1305 keys are single-use, so a new key must be set before every
+    /// call to [`finalize`](UniversalHash::finalize).
+    #[instrument(level = "debug", fields(thread = %self.context.id()), skip_all, err)]
+    async fn set_key(&mut self, key: Vec<u8>) -> Result<(), UniversalHashError> {
+        if key.len() != P1305::BYTE_LEN {
+            return Err(UniversalHashError::KeyLengthError(
+                P1305::BYTE_LEN,
+                key.len(),
+            ));
+        }
+
+        let r_additive = P1305::from_be_bytes_mod_order(&key);
+        let r_multiplicative = self
+            .converter
+            .to_multiplicative(&mut self.context, vec![r_additive])
+            .await?;
+
+        self.key = Some(r_multiplicative[0]);
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", fields(thread = %self.context.id()), skip_all, err)]
+    async fn setup(&mut self) -> Result<(), UniversalHashError> {
+        // Every key requires one A2M, and every message block one M2A.
+        self.converter
+            .alloc(self.config.key_count + self.config.block_count);
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", fields(thread = %self.context.id()), skip_all, err)]
+    async fn preprocess(&mut self) -> Result<(), UniversalHashError> {
+        self.converter.preprocess(&mut self.context).await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", fields(thread = %self.context.id()), skip_all, err)]
+    async fn finalize(&mut self, input: Vec<u8>) -> Result<Vec<u8>, UniversalHashError> {
+        // Divide by block length and round up.
+        let block_count = input.len() / BLOCK_LEN + (input.len() % BLOCK_LEN != 0) as usize;
+
+        if block_count > self.config.max_block_count {
+            return Err(UniversalHashError::InputLengthError(input.len()));
+        }
+
+        // The key is consumed, calling finalize twice with the same key is an error.
+        let Some(r) = self.key.take() else {
+            return Err(UniversalHashError::InvalidState("Key not set".to_string()));
+        };
+
+        if block_count == 0 {
+            return Ok(P1305::zero().to_be_bytes());
+        }
+
+        // Multiplicative shares of r, r^2, ..., r^n.
+        let mul_shares = std::iter::successors(Some(r), |power| Some(*power * r))
+            .take(block_count)
+            .collect::<Vec<_>>();
+
+        let add_shares = self
+            .converter
+            .to_additive(&mut self.context, mul_shares)
+            .await?;
+
+        // The i-th of n blocks is multiplied by r^(n - i).
+        let output = input
+            .chunks(BLOCK_LEN)
+            .zip(add_shares.iter().rev())
+            .fold(P1305::zero(), |acc, (chunk, share)| {
+                acc + encode_block(chunk) * *share
+            });
+
+        Ok(output.to_be_bytes())
+    }
+}
+
+/// Encodes a message block as a field element, appending the `0x01` byte.
+fn encode_block(chunk: &[u8]) -> P1305 {
+    let mut block = chunk.to_vec();
+    block.push(1);
+    P1305::from_le_bytes_mod_order(&block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mpz_common::executor::test_st_executor;
+    use mpz_share_conversion::ideal::{ideal_share_converter, IdealShareConverter};
+    use poly1305_rc::{universal_hash::KeyInit, Poly1305 as Poly1305Reference};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+
+    fn create_pair<Ctx: Context>(
+        context_alice: Ctx,
+        context_bob: Ctx,
+    ) -> (
+        Poly1305<IdealShareConverter, Ctx>,
+        Poly1305<IdealShareConverter, Ctx>,
+    ) {
+        let (convert_a, convert_b) = ideal_share_converter();
+        let config = Poly1305Config::builder().build().unwrap();
+
+        (
+            Poly1305::new(config.clone(), convert_a, context_alice),
+            Poly1305::new(config, convert_b, context_bob),
+        )
+    }
+
+    /// Returns the clamped `r` of a Poly1305 key.
+    fn clamp(key: &[u8; 32]) -> P1305 {
+        let mut r = u128::from_le_bytes(key[..16].try_into().unwrap());
+        r &= 0x0ffffffc_0ffffffc_0ffffffc_0fffffff;
+        P1305::new(r)
+    }
+
+    #[tokio::test]
+    async fn test_poly1305_output() {
+        let (ctx_a, ctx_b) = test_st_executor(8);
+        let mut rng = ChaCha12Rng::from_seed([0; 32]);
+        let (mut alice, mut bob) = create_pair(ctx_a, ctx_b);
+
+        for len in [1, 15, 16, 17, 100] {
+            let key: [u8; 32] = rng.gen();
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+
+            let alice_key: P1305 = rng.gen();
+            let bob_key = clamp(&key) - alice_key;
+
+            tokio::try_join!(
+                alice.set_key(alice_key.to_be_bytes()),
+                bob.set_key(bob_key.to_be_bytes())
+            )
+            .unwrap();
+
+            let (alice_share, bob_share) = tokio::try_join!(
+                alice.finalize(message.clone()),
+                bob.finalize(message.clone())
+            )
+            .unwrap();
+
+            let h = P1305::from_be_bytes_mod_order(&alice_share)
+                + P1305::from_be_bytes_mod_order(&bob_share);
+            let h = u128::from_le_bytes(h.to_le_bytes()[..16].try_into().unwrap());
+            let s = u128::from_le_bytes(key[16..].try_into().unwrap());
+            let tag = h.wrapping_add(s).to_le_bytes();
+
+            let expected = Poly1305Reference::new(&key.into()).compute_unpadded(&message);
+
+            assert_eq!(tag.to_vec(), expected.into_bytes().to_vec());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poly1305_key_is_single_use() {
+        let (ctx_a, ctx_b) = test_st_executor(8);
+        let (mut alice, mut bob) = create_pair(ctx_a, ctx_b);
+
+        tokio::try_join!(
+            alice.set_key(P1305::new(1).to_be_bytes()),
+            bob.set_key(P1305::new(2).to_be_bytes())
+        )
+        .unwrap();
+
+        tokio::try_join!(alice.finalize(vec![0; 16]), bob.finalize(vec![0; 16])).unwrap();
+
+        let err = alice.finalize(vec![0; 16]).await.unwrap_err();
+        assert!(matches!(err, UniversalHashError::InvalidState(_)));
+    }
+}
@@ -30,16 +30,21 @@ use crate::{
     merkle::MerkleTree,
     presentation::PresentationBuilder,
     signing::{Signature, VerifyingKey},
-    transcript::{encoding::EncodingCommitment, hash::PlaintextHash},
+    transcript::{
+        encoding::EncodingCommitment, hash::PlaintextHash, PredicateProofBuilder,
+        PredicateStatement,
+    },
     CryptoProvider,
 };
 
 pub use builder::{AttestationBuilder, AttestationBuilderError};
-pub use config::{AttestationConfig, AttestationConfigBuilder, AttestationConfigError};
+pub use config::{
+    AttestationConfig, AttestationConfigBuilder, AttestationConfigError, ServerNamePolicy,
+};
 pub use proof::{AttestationError, AttestationProof};
 
 /// Current version of attestations.
-pub const VERSION: Version = Version(0);
+pub const VERSION: Version = Version(1);
 
 /// Unique identifier for an attestation.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -93,6 +98,45 @@ impl fmt::Display for FieldId {
     }
 }
 
+/// Ids of the fields which precede the commitments in a [`Body`].
+///
+/// The [`AttestationBuilder`] assigns the ids of a body from these, and the
+/// Prover derives the ids of its commitments from them before it receives the
+/// attestation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BodyFieldIds {
+    pub(crate) verifying_key: FieldId,
+    pub(crate) connection_info: FieldId,
+    pub(crate) server_ephemeral_key: FieldId,
+    pub(crate) cert_commitment: FieldId,
+    pub(crate) encoding_commitment: Option<FieldId>,
+    /// Id of the first commitment, the ids of the remaining fields follow
+    /// from it.
+    pub(crate) commitments: FieldId,
+}
+
+impl BodyFieldIds {
+    /// Returns the ids for a body with or without an encoding commitment.
+    pub(crate) fn new(has_encoding_commitment: bool) -> Self {
+        let mut field_id = FieldId::default();
+
+        let verifying_key = field_id.next(()).id;
+        let connection_info = field_id.next(()).id;
+        let server_ephemeral_key = field_id.next(()).id;
+        let cert_commitment = field_id.next(()).id;
+        let encoding_commitment = has_encoding_commitment.then(|| field_id.next(()).id);
+
+        Self {
+            verifying_key,
+            connection_info,
+            server_ephemeral_key,
+            cert_commitment,
+            encoding_commitment,
+            commitments: field_id,
+        }
+    }
+}
+
 /// Kind of an attestation field.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[repr(u8)]
@@ -107,8 +151,23 @@ pub enum FieldKind {
     EncodingCommitment = 0x04,
     /// Plaintext hash commitment.
     PlaintextHash = 0x05,
+    /// Predicate statement.
+    Predicate = 0x06,
+    /// Holder key.
+    HolderKey = 0x07,
+    /// Validity period.
+    Validity = 0x08,
 }
 
+/// Validity period of an attestation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validity {
+    /// UNIX time after which the attestation is expired.
+    pub not_after: u64,
+}
+
+impl_domain_separator!(Validity);
+
 /// Attestation header.
 ///
 /// See [module level documentation](crate::attestation) for more information.
@@ -135,6 +194,9 @@ pub struct Body {
     cert_commitment: Field<ServerCertCommitment>,
     encoding_commitment: Option<Field<EncodingCommitment>>,
     plaintext_hashes: Index<Field<PlaintextHash>>,
+    predicates: Vec<Field<PredicateStatement>>,
+    holder_key: Option<Field<VerifyingKey>>,
+    validity: Option<Field<Validity>>,
 }
 
 impl Body {
@@ -176,6 +238,9 @@ impl Body {
             cert_commitment,
             encoding_commitment,
             plaintext_hashes,
+            predicates,
+            holder_key,
+            validity,
         } = self;
 
         let mut fields: Vec<(FieldId, Hash)> = vec![
@@ -202,10 +267,27 @@ impl Body {
             fields.push((field.id, hasher.hash_separated(&field.data)));
         }
 
+        for field in predicates.iter() {
+            fields.push((field.id, hasher.hash_separated(&field.data)));
+        }
+
+        if let Some(holder_key) = holder_key {
+            fields.push((holder_key.id, hasher.hash_separated(&holder_key.data)));
+        }
+
+        if let Some(validity) = validity {
+            fields.push((validity.id, hasher.hash_separated(&validity.data)));
+        }
+
         fields.sort_by_key(|(id, _)| *id);
         fields
     }
 
+    /// Returns the validity period signed by the Notary, if any.
+    pub fn validity(&self) -> Option<&Validity> {
+        self.validity.as_ref().map(|field| &field.data)
+    }
+
     /// Returns the connection information.
     pub(crate) fn connection_info(&self) -> &ConnectionInfo {
         &self.connection_info.data
@@ -224,10 +306,38 @@ impl Body {
         self.encoding_commitment.as_ref().map(|field| &field.data)
     }
 
+    /// Returns the ids which the Notary assigns to the plaintext hashes and
+    /// predicates, in that order.
+    ///
+    /// These follow the last commitment to the connection, which is the
+    /// encoding commitment if present.
+    pub(crate) fn commitment_field_ids(&self) -> impl Iterator<Item = FieldId> {
+        let last = self
+            .encoding_commitment
+            .as_ref()
+            .map(|field| field.id)
+            .unwrap_or(self.cert_commitment.id);
+
+        (last.0 + 1..).map(FieldId)
+    }
+
     /// Returns the plaintext hash commitments.
     pub(crate) fn plaintext_hashes(&self) -> &Index<Field<PlaintextHash>> {
         &self.plaintext_hashes
     }
+
+    /// Returns the predicate statements.
+    pub(crate) fn predicates(&self) -> &[Field<PredicateStatement>] {
+        &self.predicates
+    }
+
+    /// Returns the key of the holder of the attestation, if any.
+    ///
+    /// A presentation of the attestation can be bound to a nonce with a
+    /// signature from this key.
+    pub fn holder_key(&self) -> Option<&VerifyingKey> {
+        self.holder_key.as_ref().map(|field| &field.data)
+    }
 }
 
 /// An attestation.
@@ -256,4 +366,9 @@ impl Attestation {
     ) -> PresentationBuilder<'a> {
         PresentationBuilder::new(provider, self)
     }
+
+    /// Returns a predicate proof builder.
+    pub fn predicate_proof_builder(&self) -> PredicateProofBuilder<'_> {
+        PredicateProofBuilder::new(&self.body)
+    }
 }
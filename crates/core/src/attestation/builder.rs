@@ -4,14 +4,15 @@ use rand::{thread_rng, Rng};
 
 use crate::{
     attestation::{
-        Attestation, AttestationConfig, Body, EncodingCommitment, FieldId, FieldKind, Header,
-        ServerCertCommitment, VERSION,
+        Attestation, AttestationConfig, Body, BodyFieldIds, EncodingCommitment, Field, FieldKind,
+        Header, ServerCertCommitment, Validity, VERSION,
     },
-    connection::{ConnectionInfo, ServerEphemKey},
+    connection::{ConnectionInfo, ServerEphemKey, ServerIdentityProof},
     hash::{HashAlgId, TypedHash},
     request::Request,
     serialize::CanonicalSerialize,
-    signing::SignatureAlgId,
+    signing::{SignatureAlgId, VerifyingKey},
+    transcript::{hash::PlaintextHash, PredicateStatement},
     CryptoProvider,
 };
 
@@ -26,6 +27,11 @@ pub struct Sign {
     cert_commitment: ServerCertCommitment,
     encoding_commitment_root: Option<TypedHash>,
     encoding_seed: Option<Vec<u8>>,
+    plaintext_hashes: Vec<PlaintextHash>,
+    predicates: Vec<PredicateStatement>,
+    holder_key: Option<VerifyingKey>,
+    server_identity: Option<ServerIdentityProof>,
+    not_after: Option<u64>,
 }
 
 /// An attestation builder.
@@ -55,6 +61,10 @@ impl<'a> AttestationBuilder<'a, Accept> {
             hash_alg,
             server_cert_commitment: cert_commitment,
             encoding_commitment_root,
+            plaintext_hashes,
+            predicates,
+            holder_key,
+            server_identity,
         } = request;
 
         if !config.supported_signature_algs().contains(&signature_alg) {
@@ -82,6 +92,48 @@ impl<'a> AttestationBuilder<'a, Accept> {
             ));
         }
 
+        if !plaintext_hashes.is_empty()
+            && !config
+                .supported_fields()
+                .contains(&FieldKind::PlaintextHash)
+        {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Request,
+                "plaintext hash commitment is not supported",
+            ));
+        }
+
+        if !predicates.is_empty() && !config.supported_fields().contains(&FieldKind::Predicate) {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Request,
+                "predicate is not supported",
+            ));
+        }
+
+        if holder_key.is_some() && !config.supported_fields().contains(&FieldKind::HolderKey) {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Request,
+                "holder key is not supported",
+            ));
+        }
+
+        if let Some(hash) = plaintext_hashes
+            .iter()
+            .find(|hash| !config.supported_hash_algs().contains(&hash.hash.alg))
+        {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Request,
+                format!("unsupported plaintext hash algorithm: {}", hash.hash.alg),
+            ));
+        }
+
+        if config.server_name_policy().is_some() && server_identity.is_none() {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Request,
+                "server identity proof is required by the server name policy",
+            ));
+        }
+
         Ok(AttestationBuilder {
             config: self.config,
             state: Sign {
@@ -92,6 +144,11 @@ impl<'a> AttestationBuilder<'a, Accept> {
                 cert_commitment,
                 encoding_commitment_root,
                 encoding_seed: None,
+                plaintext_hashes,
+                predicates,
+                holder_key,
+                server_identity,
+                not_after: None,
             },
         })
     }
@@ -116,6 +173,14 @@ impl AttestationBuilder<'_, Sign> {
         self
     }
 
+    /// Sets the UNIX time after which the attestation is expired.
+    ///
+    /// This overrides the validity period of the configuration.
+    pub fn not_after(&mut self, time: u64) -> &mut Self {
+        self.state.not_after = Some(time);
+        self
+    }
+
     /// Builds the attestation.
     pub fn build(self, provider: &CryptoProvider) -> Result<Attestation, AttestationBuilderError> {
         let Sign {
@@ -126,6 +191,11 @@ impl AttestationBuilder<'_, Sign> {
             cert_commitment,
             encoding_commitment_root,
             encoding_seed,
+            plaintext_hashes,
+            predicates,
+            holder_key,
+            server_identity,
+            not_after,
         } = self.state;
 
         let hasher = provider.hash.get(&hash_alg).map_err(|_| {
@@ -156,19 +226,82 @@ impl AttestationBuilder<'_, Sign> {
             None
         };
 
-        let mut field_id = FieldId::default();
+        let connection_info = connection_info.ok_or_else(|| {
+            AttestationBuilderError::new(ErrorKind::Field, "connection info was not set")
+        })?;
+
+        let server_ephemeral_key = server_ephemeral_key.ok_or_else(|| {
+            AttestationBuilderError::new(ErrorKind::Field, "handshake data was not set")
+        })?;
+
+        if let Some(policy) = self.config.server_name_policy() {
+            let server_identity = server_identity.ok_or_else(|| {
+                AttestationBuilderError::new(
+                    ErrorKind::Request,
+                    "server identity proof is required by the server name policy",
+                )
+            })?;
+
+            let server_name = server_identity
+                .verify_with_provider(
+                    provider,
+                    connection_info.time,
+                    &server_ephemeral_key,
+                    &cert_commitment,
+                )
+                .map_err(|err| AttestationBuilderError::new(ErrorKind::Request, err))?;
+
+            if !policy.allows(&server_name) {
+                return Err(AttestationBuilderError::new(
+                    ErrorKind::ServerName,
+                    format!("server name is not permitted: {server_name}"),
+                ));
+            }
+        }
+
+        let validity = not_after
+            .or_else(|| {
+                self.config
+                    .validity_period()
+                    .map(|period| connection_info.time.saturating_add(period))
+            })
+            .map(|not_after| Validity { not_after });
+
+        let ids = BodyFieldIds::new(encoding_commitment.is_some());
+        let mut field_id = ids.commitments;
 
         let body = Body {
-            verifying_key: field_id.next(signer.verifying_key()),
-            connection_info: field_id.next(connection_info.ok_or_else(|| {
-                AttestationBuilderError::new(ErrorKind::Field, "connection info was not set")
-            })?),
-            server_ephemeral_key: field_id.next(server_ephemeral_key.ok_or_else(|| {
-                AttestationBuilderError::new(ErrorKind::Field, "handshake data was not set")
-            })?),
-            cert_commitment: field_id.next(cert_commitment),
-            encoding_commitment: encoding_commitment.map(|commitment| field_id.next(commitment)),
-            plaintext_hashes: Default::default(),
+            verifying_key: Field {
+                id: ids.verifying_key,
+                data: signer.verifying_key(),
+            },
+            connection_info: Field {
+                id: ids.connection_info,
+                data: connection_info,
+            },
+            server_ephemeral_key: Field {
+                id: ids.server_ephemeral_key,
+                data: server_ephemeral_key,
+            },
+            cert_commitment: Field {
+                id: ids.cert_commitment,
+                data: cert_commitment,
+            },
+            encoding_commitment: ids
+                .encoding_commitment
+                .zip(encoding_commitment)
+                .map(|(id, data)| Field { id, data }),
+            plaintext_hashes: plaintext_hashes
+                .into_iter()
+                .map(|hash| field_id.next(hash))
+                .collect::<Vec<_>>()
+                .into(),
+            predicates: predicates
+                .into_iter()
+                .map(|statement| field_id.next(statement))
+                .collect(),
+            holder_key: holder_key.map(|key| field_id.next(key)),
+            validity: validity.map(|validity| field_id.next(validity)),
         };
 
         let header = Header {
@@ -202,6 +335,7 @@ enum ErrorKind {
     Config,
     Field,
     Signature,
+    ServerName,
 }
 
 impl AttestationBuilderError {
@@ -219,6 +353,12 @@ impl AttestationBuilderError {
     pub fn is_request(&self) -> bool {
         matches!(self.kind, ErrorKind::Request)
     }
+
+    /// Returns whether the error originates from a server name which is not
+    /// permitted by the server name policy.
+    pub fn is_server_name_rejected(&self) -> bool {
+        matches!(self.kind, ErrorKind::ServerName)
+    }
 }
 
 impl std::fmt::Display for AttestationBuilderError {
@@ -228,6 +368,7 @@ impl std::fmt::Display for AttestationBuilderError {
             ErrorKind::Config => f.write_str("config error")?,
             ErrorKind::Field => f.write_str("field error")?,
             ErrorKind::Signature => f.write_str("signature error")?,
+            ErrorKind::ServerName => f.write_str("server name error")?,
         }
 
         if let Some(source) = &self.source {
@@ -243,17 +384,29 @@ mod test {
     use rstest::{fixture, rstest};
     use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
 
+    use std::sync::Arc;
+
     use crate::{
-        connection::{HandshakeData, HandshakeDataV1_2},
+        attestation::ServerNamePolicy,
+        connection::{HandshakeData, HandshakeDataV1_2, ServerName},
         fixtures::{encoder_seed, encoding_provider, ConnectionFixture},
         hash::Blake3,
         request::RequestConfig,
-        transcript::{encoding::EncodingTree, Transcript, TranscriptCommitConfigBuilder},
+        transcript::{
+            encoding::EncodingTree, Direction, Transcript, TranscriptCommitConfigBuilder,
+            TranscriptCommitmentKind,
+        },
     };
 
     use super::*;
 
     fn request_and_connection() -> (Request, ConnectionFixture) {
+        request_and_connection_with_config(&RequestConfig::default())
+    }
+
+    fn request_and_connection_with_config(
+        request_config: &RequestConfig,
+    ) -> (Request, ConnectionFixture) {
         let provider = CryptoProvider::default();
 
         let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
@@ -274,6 +427,14 @@ mod test {
             .commit_sent(&(0..sent_len))
             .unwrap()
             .commit_recv(&(0..recv_len))
+            .unwrap()
+            .commit_with_kind(
+                &(0..10),
+                Direction::Received,
+                TranscriptCommitmentKind::Hash {
+                    alg: HashAlgId::SHA256,
+                },
+            )
             .unwrap();
 
         let transcripts_commitment_config = transcript_commitment_builder.build().unwrap();
@@ -287,14 +448,18 @@ mod test {
         )
         .unwrap();
 
-        let request_config = RequestConfig::default();
-        let mut request_builder = Request::builder(&request_config);
+        let mut request_builder = Request::builder(request_config);
 
         request_builder
             .server_name(server_name.clone())
             .server_cert_data(server_cert_data)
             .transcript(transcript.clone())
-            .encoding_tree(encoding_tree);
+            .encoding_tree(encoding_tree)
+            .plaintext_hashes(
+                transcripts_commitment_config
+                    .iter_hash()
+                    .map(|(idx, alg)| (idx.clone(), *alg)),
+            );
         let (request, _) = request_builder.build(&provider).unwrap();
 
         (request, ConnectionFixture::tlsnotary(transcript.length()))
@@ -370,6 +535,28 @@ mod test {
         assert!(err.is_request());
     }
 
+    #[rstest]
+    fn test_attestation_builder_accept_unsupported_plaintext_hash() {
+        let (request, _) = request_and_connection();
+
+        let attestation_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1])
+            .supported_fields([
+                FieldKind::ConnectionInfo,
+                FieldKind::ServerEphemKey,
+                FieldKind::ServerIdentityCommitment,
+                FieldKind::EncodingCommitment,
+            ])
+            .build()
+            .unwrap();
+
+        let err = Attestation::builder(&attestation_config)
+            .accept_request(request)
+            .err()
+            .unwrap();
+        assert!(err.is_request());
+    }
+
     #[rstest]
     fn test_attestation_builder_sign_missing_signer(
         default_attestation_config: &AttestationConfig,
@@ -467,4 +654,82 @@ mod test {
         let err = attestation_builder.build(crypto_provider).err().unwrap();
         assert!(matches!(err.kind, ErrorKind::Field));
     }
+
+    #[derive(Debug)]
+    struct AllowedServerNames(Vec<&'static str>);
+
+    impl ServerNamePolicy for AllowedServerNames {
+        fn allows(&self, name: &ServerName) -> bool {
+            self.0.contains(&name.as_str())
+        }
+    }
+
+    fn attest_with_server_name_policy(
+        request_config: &RequestConfig,
+        allowed: Vec<&'static str>,
+        crypto_provider: &CryptoProvider,
+    ) -> Result<Attestation, AttestationBuilderError> {
+        let (request, connection) = request_and_connection_with_config(request_config);
+
+        let attestation_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1])
+            .server_name_policy(Arc::new(AllowedServerNames(allowed)))
+            .build()
+            .unwrap();
+
+        let mut attestation_builder =
+            Attestation::builder(&attestation_config).accept_request(request)?;
+
+        let ConnectionFixture {
+            connection_info,
+            server_cert_data,
+            ..
+        } = connection;
+
+        let HandshakeData::V1_2(HandshakeDataV1_2 {
+            server_ephemeral_key,
+            ..
+        }) = server_cert_data.handshake.clone();
+
+        attestation_builder
+            .connection_info(connection_info)
+            .server_ephemeral_key(server_ephemeral_key)
+            .encoding_seed(encoder_seed().to_vec());
+
+        attestation_builder.build(crypto_provider)
+    }
+
+    #[rstest]
+    fn test_attestation_builder_server_name_policy(crypto_provider: &CryptoProvider) {
+        let mut request_config = RequestConfig::builder();
+        request_config.disclose_server_identity(true);
+        let request_config = request_config.build().unwrap();
+
+        assert!(attest_with_server_name_policy(
+            &request_config,
+            vec!["tlsnotary.org"],
+            crypto_provider
+        )
+        .is_ok());
+
+        let err =
+            attest_with_server_name_policy(&request_config, vec!["example.com"], crypto_provider)
+                .err()
+                .unwrap();
+        assert!(err.is_server_name_rejected());
+    }
+
+    #[rstest]
+    fn test_attestation_builder_server_name_policy_missing_identity(
+        crypto_provider: &CryptoProvider,
+    ) {
+        let err = attest_with_server_name_policy(
+            &RequestConfig::default(),
+            vec!["tlsnotary.org"],
+            crypto_provider,
+        )
+        .err()
+        .unwrap();
+        assert!(err.is_request());
+    }
 }
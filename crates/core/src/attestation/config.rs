@@ -1,5 +1,8 @@
+use std::{fmt::Debug, sync::Arc};
+
 use crate::{
     attestation::FieldKind,
+    connection::ServerName,
     hash::{HashAlgId, DEFAULT_SUPPORTED_HASH_ALGS},
     signing::SignatureAlgId,
 };
@@ -9,6 +12,9 @@ const DEFAULT_SUPPORTED_FIELDS: &[FieldKind] = &[
     FieldKind::ServerEphemKey,
     FieldKind::ServerIdentityCommitment,
     FieldKind::EncodingCommitment,
+    FieldKind::PlaintextHash,
+    FieldKind::Predicate,
+    FieldKind::HolderKey,
 ];
 
 #[derive(Debug)]
@@ -43,12 +49,20 @@ impl AttestationConfigError {
     }
 }
 
+/// Policy of the servers which a Notary attests to connections with.
+pub trait ServerNamePolicy: Debug + Send + Sync {
+    /// Returns whether the Notary may attest to a connection with the server.
+    fn allows(&self, name: &ServerName) -> bool;
+}
+
 /// Attestation configuration.
 #[derive(Debug, Clone)]
 pub struct AttestationConfig {
     supported_signature_algs: Vec<SignatureAlgId>,
     supported_hash_algs: Vec<HashAlgId>,
     supported_fields: Vec<FieldKind>,
+    validity_period: Option<u64>,
+    server_name_policy: Option<Arc<dyn ServerNamePolicy>>,
 }
 
 impl AttestationConfig {
@@ -68,6 +82,14 @@ impl AttestationConfig {
     pub(crate) fn supported_fields(&self) -> &[FieldKind] {
         &self.supported_fields
     }
+
+    pub(crate) fn validity_period(&self) -> Option<u64> {
+        self.validity_period
+    }
+
+    pub(crate) fn server_name_policy(&self) -> Option<&dyn ServerNamePolicy> {
+        self.server_name_policy.as_deref()
+    }
 }
 
 /// Builder for [`AttestationConfig`].
@@ -76,6 +98,8 @@ pub struct AttestationConfigBuilder {
     supported_signature_algs: Vec<SignatureAlgId>,
     supported_hash_algs: Vec<HashAlgId>,
     supported_fields: Vec<FieldKind>,
+    validity_period: Option<u64>,
+    server_name_policy: Option<Arc<dyn ServerNamePolicy>>,
 }
 
 impl Default for AttestationConfigBuilder {
@@ -84,6 +108,8 @@ impl Default for AttestationConfigBuilder {
             supported_signature_algs: Vec::default(),
             supported_hash_algs: DEFAULT_SUPPORTED_HASH_ALGS.to_vec(),
             supported_fields: DEFAULT_SUPPORTED_FIELDS.to_vec(),
+            validity_period: None,
+            server_name_policy: None,
         }
     }
 }
@@ -113,12 +139,33 @@ impl AttestationConfigBuilder {
         self
     }
 
+    /// Sets the validity period of attestations in seconds, starting from the
+    /// time of the TLS connection.
+    ///
+    /// If set, attestations contain a [`Validity`](crate::attestation::Validity)
+    /// field after which they are expired.
+    pub fn validity_period(&mut self, secs: u64) -> &mut Self {
+        self.validity_period = Some(secs);
+        self
+    }
+
+    /// Sets the policy of the servers which attestations can be issued for.
+    ///
+    /// If set, the Prover must disclose the server identity in the request,
+    /// which is verified and checked against the policy before signing.
+    pub fn server_name_policy(&mut self, policy: Arc<dyn ServerNamePolicy>) -> &mut Self {
+        self.server_name_policy = Some(policy);
+        self
+    }
+
     /// Builds the configuration.
     pub fn build(&self) -> Result<AttestationConfig, AttestationConfigError> {
         Ok(AttestationConfig {
             supported_signature_algs: self.supported_signature_algs.clone(),
             supported_hash_algs: self.supported_hash_algs.clone(),
             supported_fields: self.supported_fields.clone(),
+            validity_period: self.validity_period,
+            server_name_policy: self.server_name_policy.clone(),
         })
     }
 }
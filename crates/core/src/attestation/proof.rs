@@ -3,7 +3,7 @@ use std::fmt;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    attestation::{Attestation, Body, Header},
+    attestation::{Attestation, Body, Header, VERSION},
     hash::HashAlgorithm,
     merkle::{MerkleProof, MerkleTree},
     serialize::CanonicalSerialize,
@@ -50,6 +50,13 @@ impl AttestationProof {
     /// * `provider` - Cryptography provider.
     /// * `verifying_key` - Verifying key for the Notary signature.
     pub fn verify(self, provider: &CryptoProvider) -> Result<Attestation, AttestationError> {
+        if self.header.version != VERSION {
+            return Err(AttestationError::new(
+                ErrorKind::Version,
+                format!("unsupported attestation version: {:?}", self.header.version),
+            ));
+        }
+
         let signature_verifier = provider
             .signature
             .get(&self.signature.alg)
@@ -159,6 +166,7 @@ impl fmt::Display for AttestationError {
             ErrorKind::Provider => f.write_str("provider error")?,
             ErrorKind::Signature => f.write_str("signature error")?,
             ErrorKind::Body => f.write_str("body proof error")?,
+            ErrorKind::Version => f.write_str("version error")?,
         }
 
         if let Some(source) = &self.source {
@@ -174,4 +182,5 @@ enum ErrorKind {
     Provider,
     Signature,
     Body,
+    Version,
 }
@@ -104,6 +104,8 @@ impl fmt::Display for ServerName {
 pub enum KeyType {
     /// secp256r1.
     SECP256R1 = 0x0017,
+    /// X25519.
+    X25519 = 0x001d,
 }
 
 /// Signature scheme on the key exchange parameters.
@@ -201,14 +203,18 @@ pub struct ServerEphemKey {
 impl_domain_separator!(ServerEphemKey);
 
 impl ServerEphemKey {
-    /// Encodes the key exchange parameters as in TLS.
-    pub(crate) fn kx_params(&self) -> Vec<u8> {
-        let group = match self.typ {
+    /// Returns the named group of the key.
+    pub(crate) fn group(&self) -> NamedGroup {
+        match self.typ {
             KeyType::SECP256R1 => NamedGroup::secp256r1,
-        };
+            KeyType::X25519 => NamedGroup::X25519,
+        }
+    }
 
+    /// Encodes the key exchange parameters as in TLS.
+    pub(crate) fn kx_params(&self) -> Vec<u8> {
         let mut kx_params = Vec::new();
-        ServerECDHParams::new(group, &self.key).encode(&mut kx_params);
+        ServerECDHParams::new(self.group(), &self.key).encode(&mut kx_params);
 
         kx_params
     }
@@ -218,12 +224,14 @@ impl TryFrom<tls_core::key::PublicKey> for ServerEphemKey {
     type Error = &'static str;
 
     fn try_from(value: tls_core::key::PublicKey) -> Result<Self, Self::Error> {
-        let tls_core::msgs::enums::NamedGroup::secp256r1 = value.group else {
-            return Err("unsupported key type");
+        let typ = match value.group {
+            NamedGroup::secp256r1 => KeyType::SECP256R1,
+            NamedGroup::X25519 => KeyType::X25519,
+            _ => return Err("unsupported key type"),
         };
 
         Ok(ServerEphemKey {
-            typ: KeyType::SECP256R1,
+            typ,
             key: value.key,
         })
     }
@@ -252,10 +252,17 @@ impl<T: HashAlgorithm + ?Sized> HashAlgorithmExt for T {}
 
 /// A hash blinder.
 #[derive(Clone, Serialize, Deserialize)]
-pub(crate) struct Blinder([u8; 16]);
+pub struct Blinder([u8; 16]);
 
 opaque_debug::implement!(Blinder);
 
+impl Blinder {
+    /// Returns the blinder as bytes.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
 impl Distribution<Blinder> for Standard {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Blinder {
         let mut blinder = [0; 16];
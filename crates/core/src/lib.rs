@@ -37,13 +37,18 @@
 //! [`TranscriptCommitConfigBuilder`](crate::transcript::TranscriptCommitConfigBuilder)
 //! which is used to build up a configuration.
 //!
-//! Currently, only the
-//! [`Encoding`](crate::transcript::TranscriptCommitmentKind::Encoding)
-//! commitment kind is supported. In the future you will be able to acquire hash
-//! commitments directly to the transcript data.
+//! Two kinds of commitments are supported:
+//!
+//! - [`Encoding`](crate::transcript::TranscriptCommitmentKind::Encoding)
+//!   commitments, which are cheap to produce and are opened using Merkle proofs.
+//! - [`Hash`](crate::transcript::TranscriptCommitmentKind::Hash) commitments,
+//!   which are blinded hashes of the transcript data. These are proven to the
+//!   Notary in MPC, and are useful when the commitment needs to be checked by a
+//!   party which can only verify a plain hash, such as a smart contract.
+//!   SHA-256 and BLAKE3 hash commitments can be proven in MPC.
 //!
 //! ```no_run
-//! # use tlsn_core::transcript::{TranscriptCommitConfigBuilder, Transcript, Direction};
+//! # use tlsn_core::transcript::{TranscriptCommitConfigBuilder, TranscriptCommitmentKind, Transcript, Direction};
 //! # use tlsn_core::hash::HashAlgId;
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! # let transcript: Transcript = unimplemented!();
@@ -63,13 +68,26 @@
 //!     // Skip some bytes so it can be omitted in the presentation.
 //!     .commit_sent(&(20..sent_len))?
 //!     // Commit to all received data.
-//!     .commit_recv(&(0..recv_len))?;
+//!     .commit_recv(&(0..recv_len))?
+//!     // Commit to the first 10 bytes of received data with a SHA-256 hash.
+//!     .commit_with_kind(
+//!         &(0..10),
+//!         Direction::Received,
+//!         TranscriptCommitmentKind::Hash { alg: HashAlgId::SHA256 },
+//!     )?;
 //!
 //! let config = builder.build()?;
 //! # Ok(())
 //! # }
 //! ```
 //!
+//! The Prover can also prove [`Predicate`](crate::transcript::Predicate)s
+//! about ranges of the transcript to the Notary, such as an integer comparison
+//! or a substring match, using
+//! [`predicate`](crate::transcript::TranscriptCommitConfigBuilder::predicate).
+//! The Notary attests to the predicate without learning the data, so it can be
+//! disclosed in a presentation while the data itself remains hidden.
+//!
 //! # Requesting an attestation
 //!
 //! The first step in the attestation protocol is for the Prover to make a
@@ -148,7 +166,7 @@
 //! [`Presentation::verify`](crate::presentation::Presentation::verify).
 //!
 //! ```no_run
-//! # use tlsn_core::presentation::{Presentation, PresentationOutput};
+//! # use tlsn_core::presentation::{Presentation, PresentationOutput, VerifyOptions};
 //! # use tlsn_core::signing::VerifyingKey;
 //! # use tlsn_core::CryptoProvider;
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -164,7 +182,7 @@
 //!     connection_info,
 //!     transcript,
 //!     ..
-//! } = presentation.verify(&crypto_provider)?;
+//! } = presentation.verify(&crypto_provider, &VerifyOptions::default())?;
 //! # Ok(())
 //! # }
 //! ```
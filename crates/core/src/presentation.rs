@@ -21,16 +21,42 @@
 //! used to sign the attestation, referred to as a [`VerifyingKey`], is from a
 //! Notary they trust. See an [example](crate#verifying-a-presentation) in the
 //! crate level documentation.
+//!
+//! # Replay protection
+//!
+//! By default a presentation can be verified by anyone who obtains it. To use
+//! a presentation as a one-shot credential, a Verifier issues a random
+//! [`Nonce`] to the Prover, who binds the presentation to it by signing it with
+//! the key included in the attestation, see
+//! [`RequestConfigBuilder::holder_key`](crate::request::RequestConfigBuilder::holder_key).
+//! The signature covers the disclosed proofs, so a bound presentation can not
+//! be altered without invalidating the binding. The Verifier then requires the
+//! nonce with [`VerifyOptions::nonce`], and must not accept the same nonce
+//! twice.
+//!
+//! # Expiry
+//!
+//! A Notary may sign a [`Validity`](crate::attestation::Validity) period into
+//! an attestation. [`Presentation::verify`] rejects expired attestations
+//! according to the [`ValidityPolicy`] of its [`VerifyOptions`], which by
+//! default uses the system clock. A Verifier may set the current time, eg.
+//! from a trusted source, and require a maximum age of the connection.
 
 use std::fmt;
 
+use rand::distributions::{Distribution, Standard};
 use serde::{Deserialize, Serialize};
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use crate::{
-    attestation::{Attestation, AttestationError, AttestationProof},
+    attestation::{Attestation, AttestationError, AttestationProof, Body, Header},
     connection::{ConnectionInfo, ServerIdentityProof, ServerIdentityProofError, ServerName},
-    signing::VerifyingKey,
-    transcript::{PartialTranscript, TranscriptProof, TranscriptProofError},
+    serialize::CanonicalSerialize,
+    signing::{Signature, Signer, VerifyingKey},
+    transcript::{
+        PartialTranscript, PredicateProof, PredicateProofError, PredicateStatement,
+        TranscriptProof, TranscriptProofError,
+    },
     CryptoProvider,
 };
 
@@ -43,6 +69,65 @@ pub struct Presentation {
     attestation: AttestationProof,
     identity: Option<ServerIdentityProof>,
     transcript: Option<TranscriptProof>,
+    predicates: Option<PredicateProof>,
+    binding: Option<Binding>,
+}
+
+/// A nonce issued by a Verifier to bind a presentation to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Nonce(pub [u8; 32]);
+
+impl From<[u8; 32]> for Nonce {
+    fn from(nonce: [u8; 32]) -> Self {
+        Self(nonce)
+    }
+}
+
+impl Distribution<Nonce> for Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Nonce {
+        Nonce(self.sample(rng))
+    }
+}
+
+/// Binding of a presentation to a nonce.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Binding {
+    nonce: Nonce,
+    /// Signature of the [`BindingMessage`] by the holder key.
+    signature: Signature,
+}
+
+/// Message signed by the holder key to bind a presentation to a nonce.
+///
+/// The message covers the proofs of the presentation, so that a bound
+/// presentation can not be stripped of or extended with disclosed data.
+#[derive(Serialize)]
+struct BindingMessage<'a> {
+    domain: &'static [u8],
+    header: &'a Header,
+    nonce: &'a Nonce,
+    identity: Option<&'a ServerIdentityProof>,
+    transcript: Option<&'a TranscriptProof>,
+    predicates: Option<&'a PredicateProof>,
+}
+
+impl<'a> BindingMessage<'a> {
+    fn new(
+        header: &'a Header,
+        nonce: &'a Nonce,
+        identity: Option<&'a ServerIdentityProof>,
+        transcript: Option<&'a TranscriptProof>,
+        predicates: Option<&'a PredicateProof>,
+    ) -> Self {
+        Self {
+            domain: b"tlsn/presentation-binding",
+            header,
+            nonce,
+            identity,
+            transcript,
+            predicates,
+        }
+    }
 }
 
 impl Presentation {
@@ -60,18 +145,81 @@ impl Presentation {
     }
 
     /// Verifies the presentation.
+    ///
+    /// The attestation is checked against the validity policy of the options,
+    /// which by default rejects expired attestations using the system clock.
+    /// If the presentation is bound to a nonce, the binding is verified and
+    /// the nonce is returned in the output.
+    ///
+    /// Returns an error for which [`PresentationError::is_expired`] is true if
+    /// the attestation has expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Cryptography provider.
+    /// * `options` - Verification options.
     pub fn verify(
         self,
         provider: &CryptoProvider,
+        options: &VerifyOptions,
     ) -> Result<PresentationOutput, PresentationError> {
         let Self {
             attestation,
             identity,
             transcript,
+            predicates,
+            binding,
         } = self;
 
         let attestation = attestation.verify(provider)?;
 
+        options.policy.check(&attestation.body)?;
+
+        let nonce = binding
+            .map(|binding| {
+                let holder_key = attestation.body.holder_key().ok_or_else(|| {
+                    PresentationError::binding("attestation does not contain a holder key")
+                })?;
+
+                let verifier = provider
+                    .signature
+                    .get(&binding.signature.alg)
+                    .map_err(PresentationError::binding)?;
+
+                verifier
+                    .verify(
+                        holder_key,
+                        &CanonicalSerialize::serialize(&BindingMessage::new(
+                            &attestation.header,
+                            &binding.nonce,
+                            identity.as_ref(),
+                            transcript.as_ref(),
+                            predicates.as_ref(),
+                        )),
+                        &binding.signature.data,
+                    )
+                    .map_err(PresentationError::binding)?;
+
+                Ok::<_, PresentationError>(binding.nonce)
+            })
+            .transpose()?;
+
+        if let Some(expected) = &options.nonce {
+            match &nonce {
+                Some(nonce) if nonce == expected => {}
+                Some(_) => {
+                    return Err(PresentationError::binding(
+                        "presentation is bound to a different nonce",
+                    ))
+                }
+                None => {
+                    return Err(PresentationError::binding(
+                        "presentation is not bound to a nonce",
+                    ))
+                }
+            }
+        }
+
         let server_name = identity
             .map(|identity| {
                 identity.verify_with_provider(
@@ -87,6 +235,11 @@ impl Presentation {
             .map(|transcript| transcript.verify_with_provider(provider, &attestation.body))
             .transpose()?;
 
+        let predicates = predicates
+            .map(|predicates| predicates.verify(&attestation.body))
+            .transpose()?
+            .unwrap_or_default();
+
         let connection_info = attestation.body.connection_info().clone();
 
         Ok(PresentationOutput {
@@ -94,10 +247,102 @@ impl Presentation {
             server_name,
             connection_info,
             transcript,
+            predicates,
+            nonce,
         })
     }
 }
 
+/// Options for verifying a [`Presentation`].
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    policy: ValidityPolicy,
+    nonce: Option<Nonce>,
+}
+
+impl VerifyOptions {
+    /// Creates new options with the default validity policy and no nonce.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the validity policy for the attestation.
+    pub fn policy(mut self, policy: ValidityPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Requires the presentation to be bound to the given nonce.
+    ///
+    /// The Verifier must ensure that the nonce was issued by it and has not
+    /// been used before.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
+
+/// Policy for the validity of the attestation of a [`Presentation`].
+///
+/// The default policy checks the validity period of the attestation against
+/// the system clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidityPolicy {
+    now: u64,
+    max_age: Option<u64>,
+}
+
+impl Default for ValidityPolicy {
+    fn default() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time is after the UNIX epoch")
+            .as_secs();
+
+        Self::new(now)
+    }
+}
+
+impl ValidityPolicy {
+    /// Creates a new policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current UNIX time.
+    pub fn new(now: u64) -> Self {
+        Self { now, max_age: None }
+    }
+
+    /// Sets the maximum age of the TLS connection in seconds.
+    pub fn max_age(mut self, secs: u64) -> Self {
+        self.max_age = Some(secs);
+        self
+    }
+
+    /// Checks that the attestation is valid according to the policy.
+    fn check(&self, body: &Body) -> Result<(), PresentationError> {
+        if let Some(validity) = body.validity() {
+            if self.now > validity.not_after {
+                return Err(PresentationError::expired(format!(
+                    "attestation expired at {}",
+                    validity.not_after
+                )));
+            }
+        }
+
+        if let Some(max_age) = self.max_age {
+            let time = body.connection_info().time;
+            if self.now.saturating_sub(time) > max_age {
+                return Err(PresentationError::expired(format!(
+                    "connection at {time} is older than the maximum age of {max_age} seconds"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Output of a verified [`Presentation`].
 #[derive(Debug)]
 #[non_exhaustive]
@@ -110,6 +355,10 @@ pub struct PresentationOutput {
     pub connection_info: ConnectionInfo,
     /// Authenticated transcript data.
     pub transcript: Option<PartialTranscript>,
+    /// Predicate statements about the transcript attested to by the Notary.
+    pub predicates: Vec<PredicateStatement>,
+    /// Nonce the presentation is bound to.
+    pub nonce: Option<Nonce>,
 }
 
 /// Builder for [`Presentation`].
@@ -118,6 +367,8 @@ pub struct PresentationBuilder<'a> {
     attestation: &'a Attestation,
     identity_proof: Option<ServerIdentityProof>,
     transcript_proof: Option<TranscriptProof>,
+    predicate_proof: Option<PredicateProof>,
+    binding: Option<(Nonce, &'a dyn Signer)>,
 }
 
 impl<'a> PresentationBuilder<'a> {
@@ -127,6 +378,8 @@ impl<'a> PresentationBuilder<'a> {
             attestation,
             identity_proof: None,
             transcript_proof: None,
+            predicate_proof: None,
+            binding: None,
         }
     }
 
@@ -142,14 +395,56 @@ impl<'a> PresentationBuilder<'a> {
         self
     }
 
+    /// Includes a predicate proof.
+    pub fn predicate_proof(&mut self, proof: PredicateProof) -> &mut Self {
+        self.predicate_proof = Some(proof);
+        self
+    }
+
+    /// Binds the presentation to a nonce issued by the Verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce issued by the Verifier.
+    /// * `signer` - The signer for the holder key included in the attestation.
+    pub fn binding(&mut self, nonce: Nonce, signer: &'a dyn Signer) -> &mut Self {
+        self.binding = Some((nonce, signer));
+        self
+    }
+
     /// Builds the presentation.
     pub fn build(self) -> Result<Presentation, PresentationBuilderError> {
         let attestation = AttestationProof::new(self.provider, self.attestation)?;
 
+        let binding = self
+            .binding
+            .map(|(nonce, signer)| {
+                if self.attestation.body.holder_key() != Some(&signer.verifying_key()) {
+                    return Err(PresentationBuilderError::binding(
+                        "signer does not match the holder key of the attestation",
+                    ));
+                }
+
+                let signature = signer
+                    .sign(&CanonicalSerialize::serialize(&BindingMessage::new(
+                        &self.attestation.header,
+                        &nonce,
+                        self.identity_proof.as_ref(),
+                        self.transcript_proof.as_ref(),
+                        self.predicate_proof.as_ref(),
+                    )))
+                    .map_err(PresentationBuilderError::binding)?;
+
+                Ok(Binding { nonce, signature })
+            })
+            .transpose()?;
+
         Ok(Presentation {
             attestation,
             identity: self.identity_proof,
             transcript: self.transcript_proof,
+            predicates: self.predicate_proof,
+            binding,
         })
     }
 }
@@ -164,6 +459,19 @@ pub struct PresentationBuilderError {
 #[derive(Debug)]
 enum BuilderErrorKind {
     Attestation,
+    Binding,
+}
+
+impl PresentationBuilderError {
+    fn binding<E>(error: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self {
+            kind: BuilderErrorKind::Binding,
+            source: Some(error.into()),
+        }
+    }
 }
 
 impl fmt::Display for PresentationBuilderError {
@@ -172,6 +480,7 @@ impl fmt::Display for PresentationBuilderError {
 
         match self.kind {
             BuilderErrorKind::Attestation => f.write_str("attestation error")?,
+            BuilderErrorKind::Binding => f.write_str("binding error")?,
         }
 
         if let Some(source) = &self.source {
@@ -203,6 +512,36 @@ enum ErrorKind {
     Attestation,
     Identity,
     Transcript,
+    Predicate,
+    Binding,
+    Expired,
+}
+
+impl PresentationError {
+    fn binding<E>(error: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self {
+            kind: ErrorKind::Binding,
+            source: Some(error.into()),
+        }
+    }
+
+    fn expired<E>(error: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self {
+            kind: ErrorKind::Expired,
+            source: Some(error.into()),
+        }
+    }
+
+    /// Returns whether the error is caused by an expired attestation.
+    pub fn is_expired(&self) -> bool {
+        matches!(self.kind, ErrorKind::Expired)
+    }
 }
 
 impl fmt::Display for PresentationError {
@@ -213,6 +552,9 @@ impl fmt::Display for PresentationError {
             ErrorKind::Attestation => f.write_str("attestation error")?,
             ErrorKind::Identity => f.write_str("server identity error")?,
             ErrorKind::Transcript => f.write_str("transcript error")?,
+            ErrorKind::Predicate => f.write_str("predicate error")?,
+            ErrorKind::Binding => f.write_str("binding error")?,
+            ErrorKind::Expired => f.write_str("attestation expired")?,
         }
 
         if let Some(source) = &self.source {
@@ -249,3 +591,12 @@ impl From<TranscriptProofError> for PresentationError {
         }
     }
 }
+
+impl From<PredicateProofError> for PresentationError {
+    fn from(error: PredicateProofError) -> Self {
+        Self {
+            kind: ErrorKind::Predicate,
+            source: Some(Box::new(error)),
+        }
+    }
+}
@@ -19,10 +19,11 @@ mod config;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    attestation::Attestation,
-    connection::ServerCertCommitment,
+    attestation::{Attestation, BodyFieldIds, FieldId},
+    connection::{ServerCertCommitment, ServerIdentityProof},
     hash::{HashAlgId, TypedHash},
-    signing::SignatureAlgId,
+    signing::{SignatureAlgId, VerifyingKey},
+    transcript::{hash::PlaintextHash, PredicateStatement},
 };
 
 pub use builder::{RequestBuilder, RequestBuilderError};
@@ -35,6 +36,10 @@ pub struct Request {
     pub(crate) hash_alg: HashAlgId,
     pub(crate) server_cert_commitment: ServerCertCommitment,
     pub(crate) encoding_commitment_root: Option<TypedHash>,
+    pub(crate) plaintext_hashes: Vec<PlaintextHash>,
+    pub(crate) predicates: Vec<PredicateStatement>,
+    pub(crate) holder_key: Option<VerifyingKey>,
+    pub(crate) server_identity: Option<ServerIdentityProof>,
 }
 
 impl Request {
@@ -43,6 +48,34 @@ impl Request {
         RequestBuilder::new(config)
     }
 
+    /// Returns the requested plaintext hash commitments.
+    ///
+    /// The Notary must verify that each hash was computed over the
+    /// authenticated transcript before attesting to it.
+    pub fn plaintext_hashes(&self) -> &[PlaintextHash] {
+        &self.plaintext_hashes
+    }
+
+    /// Returns the requested predicate statements.
+    ///
+    /// The Notary must verify that each predicate holds for the authenticated
+    /// transcript before attesting to it.
+    pub fn predicates(&self) -> &[PredicateStatement] {
+        &self.predicates
+    }
+
+    /// Returns the requested holder key.
+    pub fn holder_key(&self) -> Option<&VerifyingKey> {
+        self.holder_key.as_ref()
+    }
+
+    /// Returns the server identity proof, if the Prover disclosed it.
+    ///
+    /// The Notary does not include the server identity in the attestation.
+    pub fn server_identity(&self) -> Option<&ServerIdentityProof> {
+        self.server_identity.as_ref()
+    }
+
     /// Validates the content of the attestation against this request.
     pub fn validate(&self, attestation: &Attestation) -> Result<(), InconsistentAttestation> {
         if attestation.signature.alg != self.signature_alg {
@@ -79,10 +112,83 @@ impl Request {
             }
         }
 
+        let plaintext_hashes = attestation.body.plaintext_hashes();
+        if plaintext_hashes.iter().count() != self.plaintext_hashes.len() {
+            return Err(InconsistentAttestation(format!(
+                "plaintext hashes: expected {}, got {}",
+                self.plaintext_hashes.len(),
+                plaintext_hashes.iter().count()
+            )));
+        }
+
+        let mut field_ids = attestation.body.commitment_field_ids();
+
+        // The secrets of the plaintext hashes were assigned field ids before
+        // the attestation existed, so those must agree with the body.
+        let secret_ids = field_ids_from(self.encoding_commitment_root.is_some());
+
+        for (field, ((id, secret_id), expected)) in plaintext_hashes
+            .iter()
+            .zip((&mut field_ids).zip(secret_ids).zip(&self.plaintext_hashes))
+        {
+            if id != secret_id {
+                return Err(InconsistentAttestation(format!(
+                    "plaintext hash field id: expected {secret_id}, got {id}"
+                )));
+            }
+
+            if field.id != id || &field.data != expected {
+                return Err(InconsistentAttestation(format!(
+                    "plaintext hash does not match (id: {id})"
+                )));
+            }
+        }
+
+        let predicates = attestation.body.predicates();
+        if predicates.len() != self.predicates.len() {
+            return Err(InconsistentAttestation(format!(
+                "predicates: expected {}, got {}",
+                self.predicates.len(),
+                predicates.len()
+            )));
+        }
+
+        for (field, (id, expected)) in predicates.iter().zip(field_ids.zip(&self.predicates)) {
+            if field.id != id || &field.data != expected {
+                return Err(InconsistentAttestation(format!(
+                    "predicate does not match (id: {id})"
+                )));
+            }
+        }
+
+        if attestation.body.holder_key() != self.holder_key.as_ref() {
+            return Err(InconsistentAttestation(
+                "holder key does not match".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
 
+/// Returns the field ids which the Prover expects the Notary to assign to the
+/// plaintext hashes of a request.
+///
+/// The Prover needs these for its secrets before it receives the attestation,
+/// so they are derived from the [`BodyFieldIds`] which the
+/// [`AttestationBuilder`](crate::attestation::AttestationBuilder) assigns.
+/// [`Request::validate`] checks them against the ids of the actual body.
+///
+/// # Arguments
+///
+/// * `has_encoding_commitment` - Whether the body contains an encoding
+///   commitment.
+pub(crate) fn field_ids_from(has_encoding_commitment: bool) -> impl Iterator<Item = FieldId> {
+    let start = BodyFieldIds::new(has_encoding_commitment).commitments;
+
+    (start.0..).map(FieldId)
+}
+
 /// Error for [`Request::validate`].
 #[derive(Debug, thiserror::Error)]
 #[error("inconsistent attestation: {0}")]
@@ -94,11 +200,14 @@ mod test {
 
     use crate::{
         attestation::{Attestation, AttestationConfig},
-        connection::{HandshakeData, HandshakeDataV1_2, ServerCertOpening, TranscriptLength},
+        connection::{ServerCertOpening, TranscriptLength},
         fixtures::{encoder_seed, encoding_provider, ConnectionFixture},
         hash::{Blake3, Hash, HashAlgId},
         signing::SignatureAlgId,
-        transcript::{encoding::EncodingTree, Transcript, TranscriptCommitConfigBuilder},
+        transcript::{
+            encoding::EncodingTree, Direction, Idx, Predicate, Transcript,
+            TranscriptCommitConfigBuilder,
+        },
         CryptoProvider,
     };
 
@@ -107,17 +216,12 @@ mod test {
     fn attestation(payload: (Request, ConnectionFixture)) -> Attestation {
         let (request, connection) = payload;
 
+        let server_ephemeral_key = connection.server_ephemeral_key().clone();
+
         let ConnectionFixture {
-            connection_info,
-            server_cert_data,
-            ..
+            connection_info, ..
         } = connection;
 
-        let HandshakeData::V1_2(HandshakeDataV1_2 {
-            server_ephemeral_key,
-            ..
-        }) = server_cert_data.handshake.clone();
-
         let mut provider = CryptoProvider::default();
         provider.signer.set_secp256k1(&[42u8; 32]).unwrap();
 
@@ -254,4 +358,120 @@ mod test {
         let res = request.validate(&attestation);
         assert!(res.is_err())
     }
+
+    #[test]
+    fn test_plaintext_hash_field_ids() {
+        let provider = CryptoProvider::default();
+
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+        for with_encoding_tree in [false, true] {
+            let ConnectionFixture {
+                server_name,
+                server_cert_data,
+                ..
+            } = ConnectionFixture::tlsnotary(transcript.length());
+
+            let request_config = RequestConfig::default();
+            let mut request_builder = Request::builder(&request_config);
+
+            request_builder
+                .server_name(server_name)
+                .server_cert_data(server_cert_data)
+                .transcript(transcript.clone())
+                .plaintext_hashes([
+                    ((Direction::Sent, Idx::new(0..4)), HashAlgId::SHA256),
+                    ((Direction::Received, Idx::new(0..8)), HashAlgId::SHA256),
+                ]);
+
+            if with_encoding_tree {
+                let encoding_tree = EncodingTree::new(
+                    &Blake3::default(),
+                    [&(Direction::Sent, Idx::new(4..8))],
+                    &encoding_provider(GET_WITH_HEADER, OK_JSON),
+                    &transcript.length(),
+                )
+                .unwrap();
+                request_builder.encoding_tree(encoding_tree);
+            }
+
+            let (request, secrets) = request_builder.build(&provider).unwrap();
+
+            let attestation = attestation((
+                request.clone(),
+                ConnectionFixture::tlsnotary(transcript.length()),
+            ));
+
+            request.validate(&attestation).unwrap();
+
+            // The ids of the secrets point to the hashes in the body.
+            for secret in secrets.plaintext_hashes() {
+                let field = attestation
+                    .body
+                    .plaintext_hashes()
+                    .get_by_field_id(&secret.commitment)
+                    .unwrap();
+
+                assert_eq!(field.data.direction, secret.direction);
+                assert_eq!(field.data.idx, secret.idx);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrong_predicates() {
+        let provider = CryptoProvider::default();
+
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+        let ConnectionFixture {
+            server_name,
+            server_cert_data,
+            ..
+        } = ConnectionFixture::tlsnotary(transcript.length());
+
+        let request_config = RequestConfig::default();
+        let mut request_builder = Request::builder(&request_config);
+
+        request_builder
+            .server_name(server_name)
+            .server_cert_data(server_cert_data)
+            .transcript(transcript.clone())
+            .predicates([
+                PredicateStatement {
+                    direction: Direction::Sent,
+                    idx: Idx::new(0..3),
+                    predicate: Predicate::Eq(b"GET".to_vec()),
+                },
+                PredicateStatement {
+                    direction: Direction::Received,
+                    idx: Idx::new(9..12),
+                    predicate: Predicate::Lt(300),
+                },
+            ]);
+
+        let (request, _) = request_builder.build(&provider).unwrap();
+
+        let attestation = attestation((
+            request.clone(),
+            ConnectionFixture::tlsnotary(transcript.length()),
+        ));
+
+        assert!(request.validate(&attestation).is_ok());
+
+        // A different predicate.
+        let mut wrong_request = request.clone();
+        wrong_request.predicates[1].predicate = Predicate::Lt(200);
+        assert!(wrong_request.validate(&attestation).is_err());
+
+        // The predicates in a different order.
+        let mut wrong_request = request.clone();
+        wrong_request.predicates.reverse();
+        assert!(wrong_request.validate(&attestation).is_err());
+
+        // A missing predicate.
+        let mut wrong_request = request;
+        wrong_request.predicates.pop();
+        assert!(wrong_request.validate(&attestation).is_err());
+    }
 }
@@ -1,9 +1,14 @@
 use crate::{
-    connection::{ServerCertData, ServerCertOpening, ServerName},
-    index::Index,
-    request::{Request, RequestConfig},
+    connection::{ServerCertData, ServerCertOpening, ServerIdentityProof, ServerName},
+    hash::{Blinded, Blinder, HashAlgId, HashAlgorithmExt, TypedHash},
+    request::{field_ids_from, Request, RequestConfig},
     secrets::Secrets,
-    transcript::{encoding::EncodingTree, Transcript},
+    signing::VerifyingKey,
+    transcript::{
+        encoding::EncodingTree,
+        hash::{PlaintextHash, PlaintextHashSecret},
+        Direction, Idx, PredicateStatement, Transcript,
+    },
     CryptoProvider,
 };
 
@@ -13,6 +18,9 @@ pub struct RequestBuilder<'a> {
     server_name: Option<ServerName>,
     server_cert_data: Option<ServerCertData>,
     encoding_tree: Option<EncodingTree>,
+    plaintext_hashes: Vec<((Direction, Idx), HashAlgId)>,
+    predicates: Vec<PredicateStatement>,
+    holder_key: Option<VerifyingKey>,
     transcript: Option<Transcript>,
 }
 
@@ -24,6 +32,9 @@ impl<'a> RequestBuilder<'a> {
             server_name: None,
             server_cert_data: None,
             encoding_tree: None,
+            plaintext_hashes: Vec::new(),
+            predicates: Vec::new(),
+            holder_key: None,
             transcript: None,
         }
     }
@@ -46,6 +57,39 @@ impl<'a> RequestBuilder<'a> {
         self
     }
 
+    /// Sets the plaintext hash commitments.
+    ///
+    /// # Arguments
+    ///
+    /// * `hashes` - The transcript indices to commit to, along with the hash
+    ///   algorithm to use for each commitment.
+    pub fn plaintext_hashes(
+        &mut self,
+        hashes: impl IntoIterator<Item = ((Direction, Idx), HashAlgId)>,
+    ) -> &mut Self {
+        self.plaintext_hashes = hashes.into_iter().collect();
+        self
+    }
+
+    /// Sets the predicate statements.
+    pub fn predicates(
+        &mut self,
+        predicates: impl IntoIterator<Item = PredicateStatement>,
+    ) -> &mut Self {
+        self.predicates = predicates.into_iter().collect();
+        self
+    }
+
+    /// Sets the holder key.
+    ///
+    /// The Notary includes the key in the attestation, which allows the Prover
+    /// to bind a presentation to a nonce issued by a Verifier by signing it
+    /// with the corresponding signing key.
+    pub fn holder_key(&mut self, key: VerifyingKey) -> &mut Self {
+        self.holder_key = Some(key);
+        self
+    }
+
     /// Sets the transcript.
     pub fn transcript(&mut self, transcript: Transcript) -> &mut Self {
         self.transcript = Some(transcript);
@@ -62,6 +106,9 @@ impl<'a> RequestBuilder<'a> {
             server_name,
             server_cert_data,
             encoding_tree,
+            plaintext_hashes,
+            predicates,
+            holder_key,
             transcript,
         } = self;
 
@@ -87,18 +134,74 @@ impl<'a> RequestBuilder<'a> {
 
         let encoding_commitment_root = encoding_tree.as_ref().map(|tree| tree.root());
 
+        let server_identity = config
+            .disclose_server_identity()
+            .then(|| ServerIdentityProof::new(server_name.clone(), server_cert_opening.clone()));
+
+        let mut hashes = Vec::with_capacity(plaintext_hashes.len());
+        let mut hash_secrets = Vec::with_capacity(plaintext_hashes.len());
+        for (((direction, idx), alg), id) in plaintext_hashes
+            .into_iter()
+            .zip(field_ids_from(encoding_commitment_root.is_some()))
+        {
+            let hasher = provider.hash.get(&alg).map_err(|_| {
+                RequestBuilderError::new(format!("unsupported hash algorithm: {alg}"))
+            })?;
+
+            let (_, data) = transcript
+                .get(direction, &idx)
+                .ok_or_else(|| {
+                    RequestBuilderError::new(format!(
+                        "plaintext hash index is out of bounds of the {direction} transcript"
+                    ))
+                })?
+                .into_parts();
+
+            let blinder: Blinder = rand::random();
+            let hash = hasher.hash_canonical(&Blinded::new_with_blinder(data, blinder.clone()));
+
+            hashes.push(PlaintextHash {
+                direction,
+                idx: idx.clone(),
+                hash: TypedHash { alg, value: hash },
+            });
+            hash_secrets.push(PlaintextHashSecret {
+                direction,
+                idx,
+                commitment: id,
+                blinder,
+            });
+        }
+
+        for statement in &predicates {
+            let holds = transcript
+                .get(statement.direction, &statement.idx)
+                .is_some_and(|data| statement.predicate.eval(data.data()));
+
+            if !holds {
+                return Err(RequestBuilderError::new(format!(
+                    "predicate does not hold for ranges in {} transcript: {}",
+                    statement.direction, statement.predicate
+                )));
+            }
+        }
+
         let request = Request {
             signature_alg,
             hash_alg,
             server_cert_commitment,
             encoding_commitment_root,
+            plaintext_hashes: hashes,
+            predicates,
+            holder_key,
+            server_identity,
         };
 
         let secrets = Secrets {
             server_name,
             server_cert_opening,
             encoding_tree,
-            plaintext_hashes: Index::default(),
+            plaintext_hashes: hash_secrets.into(),
             transcript,
         };
 
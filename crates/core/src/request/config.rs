@@ -1,10 +1,15 @@
-use crate::{hash::HashAlgId, signing::SignatureAlgId};
+use crate::{
+    hash::HashAlgId,
+    signing::{SignatureAlgId, VerifyingKey},
+};
 
 /// Request configuration.
 #[derive(Debug, Clone)]
 pub struct RequestConfig {
     signature_alg: SignatureAlgId,
     hash_alg: HashAlgId,
+    disclose_server_identity: bool,
+    holder_key: Option<VerifyingKey>,
 }
 
 impl Default for RequestConfig {
@@ -28,6 +33,16 @@ impl RequestConfig {
     pub fn hash_alg(&self) -> &HashAlgId {
         &self.hash_alg
     }
+
+    /// Returns whether the server identity is disclosed to the Notary.
+    pub fn disclose_server_identity(&self) -> bool {
+        self.disclose_server_identity
+    }
+
+    /// Returns the holder key to include in the attestation.
+    pub fn holder_key(&self) -> Option<&VerifyingKey> {
+        self.holder_key.as_ref()
+    }
 }
 
 /// Builder for [`RequestConfig`].
@@ -35,6 +50,8 @@ impl RequestConfig {
 pub struct RequestConfigBuilder {
     signature_alg: SignatureAlgId,
     hash_alg: HashAlgId,
+    disclose_server_identity: bool,
+    holder_key: Option<VerifyingKey>,
 }
 
 impl Default for RequestConfigBuilder {
@@ -42,6 +59,8 @@ impl Default for RequestConfigBuilder {
         Self {
             signature_alg: SignatureAlgId::SECP256K1,
             hash_alg: HashAlgId::BLAKE3,
+            disclose_server_identity: false,
+            holder_key: None,
         }
     }
 }
@@ -59,11 +78,33 @@ impl RequestConfigBuilder {
         self
     }
 
+    /// Sets whether to disclose the server identity to the Notary.
+    ///
+    /// Notaries which only attest to connections with certain servers require
+    /// a proof of the server identity in the request. The server identity is
+    /// still not included in the attestation.
+    pub fn disclose_server_identity(&mut self, disclose: bool) -> &mut Self {
+        self.disclose_server_identity = disclose;
+        self
+    }
+
+    /// Sets the holder key to include in the attestation.
+    ///
+    /// This allows the Prover to bind presentations of the attestation to a
+    /// nonce issued by a Verifier, see
+    /// [`PresentationBuilder::binding`](crate::presentation::PresentationBuilder::binding).
+    pub fn holder_key(&mut self, key: VerifyingKey) -> &mut Self {
+        self.holder_key = Some(key);
+        self
+    }
+
     /// Builds the config.
     pub fn build(self) -> Result<RequestConfig, RequestConfigBuilderError> {
         Ok(RequestConfig {
             signature_alg: self.signature_alg,
             hash_alg: self.hash_alg,
+            disclose_server_identity: self.disclose_server_identity,
+            holder_key: self.holder_key,
         })
     }
 }
@@ -31,6 +31,11 @@ impl Secrets {
         &self.transcript
     }
 
+    /// Returns the secrets of the plaintext hash commitments.
+    pub fn plaintext_hashes(&self) -> impl Iterator<Item = &PlaintextHashSecret> {
+        self.plaintext_hashes.iter()
+    }
+
     /// Returns a server identity proof.
     pub fn identity_proof(&self) -> ServerIdentityProof {
         ServerIdentityProof::new(self.server_name.clone(), self.server_cert_opening.clone())
@@ -21,6 +21,9 @@
 //! To configure the transcript commitments, use the
 //! [`TranscriptCommitConfigBuilder`].
 //!
+//! The Prover can also have the Notary attest to [`Predicate`]s over parts of
+//! the transcript, which are proven without revealing the data.
+//!
 //! ## Selective Disclosure
 //!
 //! Using a [`TranscriptProof`] a Prover can selectively disclose parts of a
@@ -34,7 +37,8 @@
 mod commit;
 #[doc(hidden)]
 pub mod encoding;
-pub(crate) mod hash;
+pub mod hash;
+mod predicate;
 mod proof;
 
 use std::{fmt, ops::Range};
@@ -48,6 +52,10 @@ pub use commit::{
     TranscriptCommitConfig, TranscriptCommitConfigBuilder, TranscriptCommitConfigBuilderError,
     TranscriptCommitmentKind,
 };
+pub use predicate::{
+    regex_dfa, Predicate, PredicateProof, PredicateProofBuilder, PredicateProofBuilderError,
+    PredicateProofError, PredicateStatement,
+};
 pub use proof::{
     TranscriptProof, TranscriptProofBuilder, TranscriptProofBuilderError, TranscriptProofError,
 };
@@ -443,6 +451,12 @@ impl Idx {
     }
 }
 
+impl ToRangeSet<usize> for Idx {
+    fn to_range_set(&self) -> RangeSet<usize> {
+        self.0.clone()
+    }
+}
+
 /// Builder for [`Idx`].
 #[derive(Debug, Default)]
 pub struct IdxBuilder(RangeSet<usize>);
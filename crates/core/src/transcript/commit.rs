@@ -7,7 +7,7 @@ use utils::range::ToRangeSet;
 
 use crate::{
     hash::HashAlgId,
-    transcript::{Direction, Idx, Transcript},
+    transcript::{Direction, Idx, Predicate, PredicateStatement, Transcript},
 };
 
 /// Kind of transcript commitment.
@@ -27,6 +27,7 @@ pub enum TranscriptCommitmentKind {
 pub struct TranscriptCommitConfig {
     encoding_hash_alg: HashAlgId,
     commits: Vec<((Direction, Idx), TranscriptCommitmentKind)>,
+    predicates: Vec<PredicateStatement>,
 }
 
 impl TranscriptCommitConfig {
@@ -62,6 +63,11 @@ impl TranscriptCommitConfig {
             _ => None,
         })
     }
+
+    /// Returns an iterator over the predicate statements.
+    pub fn iter_predicates(&self) -> impl Iterator<Item = &PredicateStatement> {
+        self.predicates.iter()
+    }
 }
 
 /// A builder for [`TranscriptCommitConfig`].
@@ -74,6 +80,7 @@ pub struct TranscriptCommitConfigBuilder<'a> {
     encoding_hash_alg: HashAlgId,
     default_kind: TranscriptCommitmentKind,
     commits: HashSet<((Direction, Idx), TranscriptCommitmentKind)>,
+    predicates: Vec<PredicateStatement>,
 }
 
 impl<'a> TranscriptCommitConfigBuilder<'a> {
@@ -84,6 +91,7 @@ impl<'a> TranscriptCommitConfigBuilder<'a> {
             encoding_hash_alg: HashAlgId::BLAKE3,
             default_kind: TranscriptCommitmentKind::Encoding,
             commits: HashSet::default(),
+            predicates: Vec::new(),
         }
     }
 
@@ -169,11 +177,61 @@ impl<'a> TranscriptCommitConfigBuilder<'a> {
         self.commit(ranges, Direction::Received)
     }
 
+    /// Adds a predicate to be proven about the transcript.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - The ranges of the data.
+    /// * `direction` - The direction of the transcript.
+    /// * `predicate` - The predicate which holds for the data.
+    pub fn predicate(
+        &mut self,
+        ranges: &dyn ToRangeSet<usize>,
+        direction: Direction,
+        predicate: Predicate,
+    ) -> Result<&mut Self, TranscriptCommitConfigBuilderError> {
+        let idx = Idx::new(ranges.to_range_set());
+
+        let Some(data) = self.transcript.get(direction, &idx) else {
+            return Err(TranscriptCommitConfigBuilderError::new(
+                ErrorKind::Index,
+                format!(
+                    "range is out of bounds of the transcript ({}): {} > {}",
+                    direction,
+                    idx.end(),
+                    self.transcript.len_of_direction(direction)
+                ),
+            ));
+        };
+
+        if !predicate.eval(data.data()) {
+            return Err(TranscriptCommitConfigBuilderError::new(
+                ErrorKind::Predicate,
+                format!(
+                    "predicate does not hold for ranges in {direction} transcript: {predicate}"
+                ),
+            ));
+        }
+
+        let statement = PredicateStatement {
+            direction,
+            idx,
+            predicate,
+        };
+
+        if !self.predicates.contains(&statement) {
+            self.predicates.push(statement);
+        }
+
+        Ok(self)
+    }
+
     /// Builds the configuration.
     pub fn build(self) -> Result<TranscriptCommitConfig, TranscriptCommitConfigBuilderError> {
         Ok(TranscriptCommitConfig {
             encoding_hash_alg: self.encoding_hash_alg,
             commits: Vec::from_iter(self.commits),
+            predicates: self.predicates,
         })
     }
 }
@@ -200,12 +258,14 @@ impl TranscriptCommitConfigBuilderError {
 #[derive(Debug)]
 enum ErrorKind {
     Index,
+    Predicate,
 }
 
 impl fmt::Display for TranscriptCommitConfigBuilderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.kind {
             ErrorKind::Index => f.write_str("index error")?,
+            ErrorKind::Predicate => f.write_str("predicate error")?,
         }
 
         if let Some(source) = &self.source {
@@ -218,6 +278,8 @@ impl fmt::Display for TranscriptCommitConfigBuilderError {
 
 #[cfg(test)]
 mod tests {
+    use utils::range::RangeSet;
+
     use super::*;
 
     #[test]
@@ -231,4 +293,30 @@ mod tests {
         assert!(builder.commit_sent(&(10..15)).is_err());
         assert!(builder.commit_recv(&(10..15)).is_err());
     }
+
+    #[test]
+    fn test_predicate() {
+        let transcript = Transcript::new(b"balance: 1234", b"date: 2005-12-31");
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        assert!(builder
+            .predicate(&(9..13), Direction::Sent, Predicate::Gt(1000))
+            .is_ok());
+        assert!(builder
+            .predicate(
+                &RangeSet::from([6..10, 11..13, 14..16]),
+                Direction::Received,
+                Predicate::Lt(20060101)
+            )
+            .is_ok());
+        assert!(builder
+            .predicate(&(9..13), Direction::Sent, Predicate::Lt(1000))
+            .is_err());
+        assert!(builder
+            .predicate(&(9..15), Direction::Sent, Predicate::Gt(1000))
+            .is_err());
+
+        let config = builder.build().unwrap();
+        assert_eq!(config.iter_predicates().count(), 2);
+    }
 }
@@ -1,3 +1,15 @@
+//! Plaintext hash commitments.
+//!
+//! A plaintext hash commitment is a hash of a blinded subsequence of the
+//! transcript. The pre-image of the hash is the canonical serialization of the
+//! blinded plaintext, ie. `len || plaintext || blinder` where `len` is the
+//! ULEB128 encoded length of the plaintext and `blinder` is a 16 byte random
+//! value known only to the Prover.
+//!
+//! Because the Notary never sees the plaintext, the Prover must prove to the
+//! Notary that the hash was computed over the authenticated transcript before
+//! the Notary will attest to it.
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -11,7 +23,7 @@ use crate::{
 
 /// Hash of plaintext in the transcript.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub(crate) struct PlaintextHash {
+pub struct PlaintextHash {
     /// Direction of the plaintext.
     pub direction: Direction,
     /// Index of plaintext.
@@ -24,13 +36,51 @@ impl_domain_separator!(PlaintextHash);
 
 /// Secret data for a plaintext hash commitment.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct PlaintextHashSecret {
+pub struct PlaintextHashSecret {
     pub(crate) direction: Direction,
     pub(crate) idx: Idx,
     pub(crate) commitment: FieldId,
     pub(crate) blinder: Blinder,
 }
 
+impl PlaintextHashSecret {
+    /// Returns the direction of the plaintext.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the index of the plaintext.
+    pub fn idx(&self) -> &Idx {
+        &self.idx
+    }
+
+    /// Returns the blinder of the hash pre-image.
+    pub fn blinder(&self) -> &Blinder {
+        &self.blinder
+    }
+}
+
+/// Returns the bytes which precede the plaintext in the pre-image of a
+/// [`PlaintextHash`].
+///
+/// # Arguments
+///
+/// * `len` - The length of the plaintext.
+pub fn preimage_prefix(len: usize) -> Vec<u8> {
+    // ULEB128 encoding of the length, as used by the canonical serialization.
+    let mut len = len as u64;
+    let mut prefix = Vec::new();
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            prefix.push(byte);
+            return prefix;
+        }
+        prefix.push(byte | 0x80);
+    }
+}
+
 /// Proof of the plaintext of a hash.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct PlaintextHashProof {
@@ -97,3 +147,27 @@ impl From<InvalidSubsequence> for PlaintextHashProofError {
         Self(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::hash::{HashAlgorithm, Sha256};
+
+    #[test]
+    fn test_preimage_prefix() {
+        let sha256 = Sha256::default();
+
+        for len in [0, 1, 127, 128, 300, 16384] {
+            let data = vec![42u8; len];
+            let blinded = Blinded::new(data.clone());
+            let (_, blinder) = blinded.clone().into_parts();
+
+            let mut preimage = preimage_prefix(len);
+            preimage.extend_from_slice(&data);
+            preimage.extend_from_slice(blinder.as_bytes());
+
+            assert_eq!(sha256.hash(&preimage), sha256.hash_canonical(&blinded));
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Predicates over transcript data.
+//!
+//! A predicate is a statement about a subsequence of the transcript, such as
+//! "the balance is greater than 1000", which the Prover proves to the Notary in
+//! zero-knowledge during notarization. The Notary attests to the statement
+//! without ever learning the data, and the Prover can later include it in a
+//! presentation using a [`PredicateProof`].
+//!
+//! The data a predicate is evaluated over is the concatenation of the ranges of
+//! its index. This makes it possible to skip separators, eg. a date
+//! `2005-12-31` can be compared as the integer `20051231` by excluding the
+//! dashes from the index.
+//!
+//! A [`Predicate::Regex`] is proven by compiling the expression to a DFA with
+//! [`regex_dfa`], which the circuit evaluates over the data.
+
+use std::fmt;
+
+use regex_automata::{
+    dfa::{dense, Automaton, StartKind},
+    nfa::thompson,
+    util::{start, syntax},
+    Anchored, MatchKind,
+};
+use serde::{Deserialize, Serialize};
+use utils::range::ToRangeSet;
+
+use crate::{
+    attestation::{Body, FieldId},
+    hash::impl_domain_separator,
+    transcript::{Direction, Idx},
+};
+
+/// A predicate over transcript data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Predicate {
+    /// The data is equal to the value.
+    Eq(Vec<u8>),
+    /// The data contains the value.
+    Contains(Vec<u8>),
+    /// The data is a decimal integer less than the value.
+    Lt(u64),
+    /// The data is a decimal integer less than or equal to the value.
+    Lte(u64),
+    /// The data is a decimal integer greater than the value.
+    Gt(u64),
+    /// The data is a decimal integer greater than or equal to the value.
+    Gte(u64),
+    /// The data matches the regular expression.
+    ///
+    /// The expression must match all of the data. It is matched against bytes,
+    /// so Unicode classes are not supported.
+    Regex(String),
+}
+
+impl Predicate {
+    /// Returns whether the predicate is valid for data of the given length.
+    ///
+    /// A predicate which is not valid can never be satisfied.
+    pub fn is_valid(&self, len: usize) -> bool {
+        match self {
+            Predicate::Eq(value) => value.len() == len,
+            Predicate::Contains(value) => !value.is_empty() && value.len() <= len,
+            Predicate::Lt(_) | Predicate::Lte(_) | Predicate::Gt(_) | Predicate::Gte(_) => len > 0,
+            Predicate::Regex(pattern) => regex_dfa(pattern).is_some(),
+        }
+    }
+
+    /// Evaluates the predicate on the provided data.
+    ///
+    /// Integer predicates require the data to consist of ASCII digits only,
+    /// leading zeros are permitted.
+    pub fn eval(&self, data: &[u8]) -> bool {
+        if !self.is_valid(data.len()) {
+            return false;
+        }
+
+        match self {
+            Predicate::Eq(value) => data == value.as_slice(),
+            Predicate::Contains(value) => data
+                .windows(value.len())
+                .any(|window| window == value.as_slice()),
+            Predicate::Lt(value) => cmp_decimal(data, *value).is_some_and(|ord| ord.is_lt()),
+            Predicate::Lte(value) => cmp_decimal(data, *value).is_some_and(|ord| ord.is_le()),
+            Predicate::Gt(value) => cmp_decimal(data, *value).is_some_and(|ord| ord.is_gt()),
+            Predicate::Gte(value) => cmp_decimal(data, *value).is_some_and(|ord| ord.is_ge()),
+            Predicate::Regex(pattern) => {
+                regex_dfa(pattern).is_some_and(|dfa| regex_matches(&dfa, data))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Predicate::Eq(value) => write!(f, "== {:?}", String::from_utf8_lossy(value)),
+            Predicate::Contains(value) => {
+                write!(f, "contains {:?}", String::from_utf8_lossy(value))
+            }
+            Predicate::Lt(value) => write!(f, "< {value}"),
+            Predicate::Lte(value) => write!(f, "<= {value}"),
+            Predicate::Gt(value) => write!(f, "> {value}"),
+            Predicate::Gte(value) => write!(f, ">= {value}"),
+            Predicate::Regex(pattern) => write!(f, "matches {pattern:?}"),
+        }
+    }
+}
+
+/// Compares a decimal integer in ASCII with a value, returning `None` if the
+/// data is not a decimal integer.
+fn cmp_decimal(data: &[u8], value: u64) -> Option<std::cmp::Ordering> {
+    if data.is_empty() || !data.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    let start = data
+        .iter()
+        .position(|byte| *byte != b'0')
+        .unwrap_or(data.len() - 1);
+    let digits = &data[start..];
+    let value = value.to_string();
+
+    Some(
+        digits
+            .len()
+            .cmp(&value.len())
+            .then_with(|| digits.cmp(value.as_bytes())),
+    )
+}
+
+/// Maximum size of the DFA of a [`Predicate::Regex`] in bytes.
+///
+/// The circuit which evaluates the DFA grows with its number of states, so
+/// larger expressions are not supported.
+const REGEX_DFA_SIZE_LIMIT: usize = 1 << 16;
+
+/// Compiles the expression of a [`Predicate::Regex`] to a DFA, returning `None`
+/// if the expression is invalid or too large.
+///
+/// The DFA only has an anchored start state, and reports all matches. The
+/// expression matches all of the data if the state which is reached after
+/// the data and the end of input transition is a match state.
+pub fn regex_dfa(pattern: &str) -> Option<dense::DFA<Vec<u32>>> {
+    dense::Builder::new()
+        .configure(
+            dense::Config::new()
+                .match_kind(MatchKind::All)
+                .start_kind(StartKind::Anchored)
+                .dfa_size_limit(Some(REGEX_DFA_SIZE_LIMIT))
+                .determinize_size_limit(Some(REGEX_DFA_SIZE_LIMIT)),
+        )
+        .syntax(syntax::Config::new().unicode(false).utf8(false))
+        .thompson(thompson::Config::new().utf8(false))
+        .build(pattern)
+        .ok()
+}
+
+/// Returns whether the DFA matches all of the data.
+fn regex_matches(dfa: &dense::DFA<Vec<u32>>, data: &[u8]) -> bool {
+    let Ok(mut state) = dfa.start_state(&start::Config::new().anchored(Anchored::Yes)) else {
+        return false;
+    };
+
+    for byte in data {
+        state = dfa.next_state(state, *byte);
+    }
+
+    dfa.is_match_state(dfa.next_eoi_state(state))
+}
+
+/// A predicate statement about the transcript attested to by a Notary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PredicateStatement {
+    /// Direction of the data.
+    pub direction: Direction,
+    /// Index of the data.
+    pub idx: Idx,
+    /// The predicate which holds for the data.
+    pub predicate: Predicate,
+}
+
+impl_domain_separator!(PredicateStatement);
+
+/// Proof of predicate statements attested to by a Notary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PredicateProof {
+    ids: Vec<FieldId>,
+}
+
+impl PredicateProof {
+    /// Verifies the proof, returning the proven statements.
+    ///
+    /// # Arguments
+    ///
+    /// * `attestation_body` - The attestation body to verify against.
+    pub(crate) fn verify(
+        self,
+        attestation_body: &Body,
+    ) -> Result<Vec<PredicateStatement>, PredicateProofError> {
+        self.ids
+            .iter()
+            .map(|id| {
+                attestation_body
+                    .predicates()
+                    .iter()
+                    .find(|field| &field.id == id)
+                    .map(|field| field.data.clone())
+                    .ok_or_else(|| {
+                        PredicateProofError(format!(
+                            "attestation is missing predicate statement (id: {id})"
+                        ))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Builder for [`PredicateProof`].
+#[derive(Debug)]
+pub struct PredicateProofBuilder<'a> {
+    attestation_body: &'a Body,
+    ids: Vec<FieldId>,
+}
+
+impl<'a> PredicateProofBuilder<'a> {
+    pub(crate) fn new(attestation_body: &'a Body) -> Self {
+        Self {
+            attestation_body,
+            ids: Vec::new(),
+        }
+    }
+
+    /// Reveals a predicate statement.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - The ranges of the statement.
+    /// * `direction` - The direction of the transcript.
+    /// * `predicate` - The predicate of the statement.
+    pub fn reveal(
+        &mut self,
+        ranges: &dyn ToRangeSet<usize>,
+        direction: Direction,
+        predicate: &Predicate,
+    ) -> Result<&mut Self, PredicateProofBuilderError> {
+        let idx = Idx::new(ranges.to_range_set());
+
+        let field = self
+            .attestation_body
+            .predicates()
+            .iter()
+            .find(|field| {
+                field.data.direction == direction
+                    && field.data.idx == idx
+                    && &field.data.predicate == predicate
+            })
+            .ok_or_else(|| {
+                PredicateProofBuilderError(format!(
+                    "attestation is missing predicate statement for ranges in {direction} transcript: {predicate}"
+                ))
+            })?;
+
+        if !self.ids.contains(&field.id) {
+            self.ids.push(field.id);
+        }
+
+        Ok(self)
+    }
+
+    /// Builds the predicate proof.
+    pub fn build(self) -> PredicateProof {
+        PredicateProof { ids: self.ids }
+    }
+}
+
+/// Error for [`PredicateProofBuilder`].
+#[derive(Debug, thiserror::Error)]
+#[error("predicate proof builder error: {0}")]
+pub struct PredicateProofBuilderError(String);
+
+/// Error for [`PredicateProof`].
+#[derive(Debug, thiserror::Error)]
+#[error("invalid predicate proof: {0}")]
+pub struct PredicateProofError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predicate_eq() {
+        assert!(Predicate::Eq(b"abc".to_vec()).eval(b"abc"));
+        assert!(!Predicate::Eq(b"abc".to_vec()).eval(b"abd"));
+        assert!(!Predicate::Eq(b"abc".to_vec()).eval(b"abcd"));
+    }
+
+    #[test]
+    fn test_predicate_contains() {
+        assert!(Predicate::Contains(b"bc".to_vec()).eval(b"abcd"));
+        assert!(!Predicate::Contains(b"ce".to_vec()).eval(b"abcd"));
+        assert!(!Predicate::Contains(Vec::new()).eval(b"abcd"));
+    }
+
+    #[test]
+    fn test_predicate_int() {
+        assert!(Predicate::Gt(1000).eval(b"1001"));
+        assert!(!Predicate::Gt(1000).eval(b"1000"));
+        assert!(Predicate::Gte(1000).eval(b"1000"));
+        assert!(Predicate::Lt(20060101).eval(b"20051231"));
+        assert!(!Predicate::Lt(20060101).eval(b"20060101"));
+        assert!(Predicate::Lte(20060101).eval(b"20060101"));
+        assert!(Predicate::Lt(100).eval(b"0099"));
+        assert!(Predicate::Gt(0).eval(b"1"));
+        assert!(!Predicate::Gt(0).eval(b"000"));
+        assert!(!Predicate::Gt(5).eval(b"1a"));
+        assert!(!Predicate::Gt(5).eval(b""));
+    }
+
+    #[test]
+    fn test_predicate_regex() {
+        let date = Predicate::Regex(r"[0-9]{4}-[0-9]{2}-[0-9]{2}".to_string());
+        assert!(date.eval(b"2005-12-31"));
+        assert!(!date.eval(b"2005-12-3"));
+        // The expression must match all of the data.
+        assert!(!date.eval(b"2005-12-311"));
+        assert!(!date.eval(b"x2005-12-31"));
+
+        let alternation = Predicate::Regex("abc|abcd".to_string());
+        assert!(alternation.eval(b"abc"));
+        assert!(alternation.eval(b"abcd"));
+        assert!(!alternation.eval(b"abcde"));
+
+        // Bytes which are not UTF-8 can be matched.
+        assert!(Predicate::Regex(r"\xFF.".to_string()).eval(b"\xffa"));
+
+        let invalid = Predicate::Regex("(".to_string());
+        assert!(!invalid.is_valid(1));
+        assert!(!invalid.eval(b"("));
+    }
+}
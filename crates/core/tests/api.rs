@@ -2,11 +2,14 @@ use tlsn_core::{
     attestation::{Attestation, AttestationConfig},
     connection::{HandshakeData, HandshakeDataV1_2},
     fixtures::{self, encoder_seed, ConnectionFixture},
-    hash::Blake3,
-    presentation::PresentationOutput,
+    hash::{Blake3, HashAlgId},
+    presentation::{Nonce, Presentation, PresentationOutput, ValidityPolicy, VerifyOptions},
     request::{Request, RequestConfig},
-    signing::SignatureAlgId,
-    transcript::{encoding::EncodingTree, Direction, Transcript, TranscriptCommitConfigBuilder},
+    signing::{Secp256k1Signer, SignatureAlgId, Signer},
+    transcript::{
+        encoding::EncodingTree, Direction, Idx, Predicate, PredicateStatement, Transcript,
+        TranscriptCommitConfigBuilder, TranscriptCommitmentKind,
+    },
     CryptoProvider,
 };
 use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
@@ -115,7 +118,9 @@ fn test_api() {
         connection_info: presented_connection_info,
         transcript: presented_transcript,
         ..
-    } = presentation.verify(&provider).unwrap();
+    } = presentation
+        .verify(&provider, &VerifyOptions::default())
+        .unwrap();
 
     assert_eq!(presented_server_name.unwrap(), server_name);
     assert_eq!(presented_connection_info, connection_info);
@@ -131,3 +136,453 @@ fn test_api() {
         secrets.transcript().received()
     );
 }
+
+/// Tests that plaintext hash commitments can be attested to and opened in a
+/// presentation.
+#[test]
+fn test_api_plaintext_hash() {
+    let mut provider = CryptoProvider::default();
+    provider.signer.set_secp256k1(&[42u8; 32]).unwrap();
+
+    let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+    let (sent_len, recv_len) = transcript.len();
+
+    let ConnectionFixture {
+        server_name,
+        connection_info,
+        server_cert_data,
+    } = ConnectionFixture::tlsnotary(transcript.length());
+
+    let HandshakeData::V1_2(HandshakeDataV1_2 {
+        server_ephemeral_key,
+        ..
+    }) = server_cert_data.handshake.clone()
+    else {
+        unreachable!()
+    };
+
+    let kind = TranscriptCommitmentKind::Hash {
+        alg: HashAlgId::SHA256,
+    };
+
+    let mut transcript_commitment_builder = TranscriptCommitConfigBuilder::new(&transcript);
+    transcript_commitment_builder
+        .commit_with_kind(&(0..sent_len), Direction::Sent, kind)
+        .unwrap()
+        .commit_with_kind(&(10..recv_len), Direction::Received, kind)
+        .unwrap();
+
+    let transcripts_commitment_config = transcript_commitment_builder.build().unwrap();
+
+    let request_config = RequestConfig::default();
+    let mut request_builder = Request::builder(&request_config);
+
+    request_builder
+        .server_name(server_name.clone())
+        .server_cert_data(server_cert_data)
+        .transcript(transcript)
+        .plaintext_hashes(
+            transcripts_commitment_config
+                .iter_hash()
+                .map(|(idx, alg)| (idx.clone(), *alg)),
+        );
+
+    let (request, secrets) = request_builder.build(&provider).unwrap();
+
+    assert_eq!(request.plaintext_hashes().len(), 2);
+    assert_eq!(secrets.plaintext_hashes().count(), 2);
+
+    let attestation_config = AttestationConfig::builder()
+        .supported_signature_algs([SignatureAlgId::SECP256K1])
+        .build()
+        .unwrap();
+
+    let mut attestation_builder = Attestation::builder(&attestation_config)
+        .accept_request(request.clone())
+        .unwrap();
+
+    attestation_builder
+        .connection_info(connection_info.clone())
+        .server_ephemeral_key(server_ephemeral_key);
+
+    let attestation = attestation_builder.build(&provider).unwrap();
+
+    request.validate(&attestation).unwrap();
+
+    let mut transcript_proof_builder = secrets.transcript_proof_builder();
+
+    transcript_proof_builder
+        .reveal_with_kind(&(0..sent_len), Direction::Sent, kind)
+        .unwrap()
+        .reveal_with_kind(&(10..recv_len), Direction::Received, kind)
+        .unwrap();
+
+    let transcript_proof = transcript_proof_builder.build().unwrap();
+
+    let mut builder = attestation.presentation_builder(&provider);
+
+    builder.identity_proof(secrets.identity_proof());
+    builder.transcript_proof(transcript_proof);
+
+    let presentation = builder.build().unwrap();
+
+    let PresentationOutput {
+        transcript: presented_transcript,
+        ..
+    } = presentation
+        .verify(&provider, &VerifyOptions::default())
+        .unwrap();
+
+    let presented_transcript = presented_transcript.unwrap();
+
+    assert_eq!(
+        presented_transcript.sent_unsafe(),
+        secrets.transcript().sent()
+    );
+    assert_eq!(
+        presented_transcript.received_authed(),
+        &Idx::new(10..recv_len)
+    );
+    assert_eq!(
+        &presented_transcript.received_unsafe()[10..],
+        &secrets.transcript().received()[10..]
+    );
+}
+
+/// Tests that predicates can be attested to and proven in a presentation.
+#[test]
+fn test_api_predicates() {
+    let mut provider = CryptoProvider::default();
+    provider.signer.set_secp256k1(&[42u8; 32]).unwrap();
+
+    let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+    let ConnectionFixture {
+        server_name,
+        connection_info,
+        server_cert_data,
+    } = ConnectionFixture::tlsnotary(transcript.length());
+
+    let HandshakeData::V1_2(HandshakeDataV1_2 {
+        server_ephemeral_key,
+        ..
+    }) = server_cert_data.handshake.clone()
+    else {
+        unreachable!()
+    };
+
+    // The status code and the value of "bazz" in the response.
+    let status = 9..12;
+    let bazz_start = OK_JSON
+        .windows(3)
+        .position(|window| window == b"123")
+        .unwrap();
+    let bazz = bazz_start..bazz_start + 3;
+
+    let mut transcript_commitment_builder = TranscriptCommitConfigBuilder::new(&transcript);
+    transcript_commitment_builder
+        .predicate(&(0..3), Direction::Sent, Predicate::Eq(b"GET".to_vec()))
+        .unwrap()
+        .predicate(&status, Direction::Received, Predicate::Lt(300))
+        .unwrap()
+        .predicate(&bazz, Direction::Received, Predicate::Gt(100))
+        .unwrap();
+
+    // A predicate which does not hold is rejected.
+    assert!(transcript_commitment_builder
+        .predicate(&bazz, Direction::Received, Predicate::Gt(123))
+        .is_err());
+
+    let transcripts_commitment_config = transcript_commitment_builder.build().unwrap();
+
+    let request_config = RequestConfig::default();
+    let mut request_builder = Request::builder(&request_config);
+
+    request_builder
+        .server_name(server_name.clone())
+        .server_cert_data(server_cert_data.clone())
+        .transcript(transcript.clone())
+        .predicates(transcripts_commitment_config.iter_predicates().cloned());
+
+    let (request, secrets) = request_builder.build(&provider).unwrap();
+
+    assert_eq!(request.predicates().len(), 3);
+
+    let attestation_config = AttestationConfig::builder()
+        .supported_signature_algs([SignatureAlgId::SECP256K1])
+        .build()
+        .unwrap();
+
+    let mut attestation_builder = Attestation::builder(&attestation_config)
+        .accept_request(request.clone())
+        .unwrap();
+
+    attestation_builder
+        .connection_info(connection_info.clone())
+        .server_ephemeral_key(server_ephemeral_key);
+
+    let attestation = attestation_builder.build(&provider).unwrap();
+
+    request.validate(&attestation).unwrap();
+
+    // The Prover reveals only two of the predicates.
+    let mut predicate_proof_builder = attestation.predicate_proof_builder();
+    predicate_proof_builder
+        .reveal(&status, Direction::Received, &Predicate::Lt(300))
+        .unwrap()
+        .reveal(&bazz, Direction::Received, &Predicate::Gt(100))
+        .unwrap();
+
+    // A predicate which was not attested to can not be revealed.
+    assert!(predicate_proof_builder
+        .reveal(&bazz, Direction::Received, &Predicate::Gt(123))
+        .is_err());
+
+    let predicate_proof = predicate_proof_builder.build();
+
+    let mut builder = attestation.presentation_builder(&provider);
+    builder
+        .identity_proof(secrets.identity_proof())
+        .predicate_proof(predicate_proof);
+
+    let presentation = builder.build().unwrap();
+
+    let PresentationOutput {
+        transcript: presented_transcript,
+        predicates,
+        ..
+    } = presentation
+        .verify(&provider, &VerifyOptions::default())
+        .unwrap();
+
+    // The predicates are proven without revealing the data.
+    assert!(presented_transcript.is_none());
+    assert_eq!(
+        predicates,
+        vec![
+            PredicateStatement {
+                direction: Direction::Received,
+                idx: Idx::new(status),
+                predicate: Predicate::Lt(300),
+            },
+            PredicateStatement {
+                direction: Direction::Received,
+                idx: Idx::new(bazz.clone()),
+                predicate: Predicate::Gt(100),
+            },
+        ]
+    );
+
+    // The Prover can not request an attestation to a predicate which does not
+    // hold.
+    let mut request_builder = Request::builder(&request_config);
+    request_builder
+        .server_name(server_name)
+        .server_cert_data(server_cert_data)
+        .transcript(transcript)
+        .predicates([PredicateStatement {
+            direction: Direction::Received,
+            idx: Idx::new(bazz),
+            predicate: Predicate::Gt(123),
+        }]);
+
+    assert!(request_builder.build(&provider).is_err());
+}
+
+/// Tests that a presentation can be bound to a nonce issued by the verifier.
+#[test]
+fn test_api_presentation_binding() {
+    let mut provider = CryptoProvider::default();
+    provider.signer.set_secp256k1(&[42u8; 32]).unwrap();
+
+    // Key of the Prover which is included in the attestation.
+    let holder = Secp256k1Signer::new(&[7u8; 32]).unwrap();
+
+    let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+    let ConnectionFixture {
+        server_name,
+        connection_info,
+        server_cert_data,
+    } = ConnectionFixture::tlsnotary(transcript.length());
+
+    let HandshakeData::V1_2(HandshakeDataV1_2 {
+        server_ephemeral_key,
+        ..
+    }) = server_cert_data.handshake.clone()
+    else {
+        unreachable!()
+    };
+
+    let request_config = RequestConfig::default();
+    let mut request_builder = Request::builder(&request_config);
+
+    request_builder
+        .server_name(server_name)
+        .server_cert_data(server_cert_data)
+        .transcript(transcript)
+        .holder_key(holder.verifying_key());
+
+    let (request, secrets) = request_builder.build(&provider).unwrap();
+
+    let attestation_config = AttestationConfig::builder()
+        .supported_signature_algs([SignatureAlgId::SECP256K1])
+        .build()
+        .unwrap();
+
+    let mut attestation_builder = Attestation::builder(&attestation_config)
+        .accept_request(request.clone())
+        .unwrap();
+
+    attestation_builder
+        .connection_info(connection_info)
+        .server_ephemeral_key(server_ephemeral_key);
+
+    let attestation = attestation_builder.build(&provider).unwrap();
+
+    request.validate(&attestation).unwrap();
+    assert_eq!(attestation.body.holder_key(), Some(&holder.verifying_key()));
+
+    // Verifier issues a nonce.
+    let nonce: Nonce = rand::random();
+
+    let mut builder = attestation.presentation_builder(&provider);
+    builder
+        .identity_proof(secrets.identity_proof())
+        .binding(nonce.clone(), &holder);
+    let presentation = builder.build().unwrap();
+
+    let output = presentation
+        .clone()
+        .verify(&provider, &VerifyOptions::new().nonce(nonce.clone()))
+        .unwrap();
+    assert_eq!(output.nonce, Some(nonce.clone()));
+
+    // A presentation bound to another nonce is rejected.
+    assert!(presentation
+        .clone()
+        .verify(&provider, &VerifyOptions::new().nonce(rand::random()))
+        .is_err());
+
+    // A presentation which is not bound to a nonce is rejected.
+    let mut builder = attestation.presentation_builder(&provider);
+    builder.identity_proof(secrets.identity_proof());
+    let unbound = builder.build().unwrap();
+    assert!(unbound
+        .clone()
+        .verify(&provider, &VerifyOptions::new().nonce(rand::random()))
+        .is_err());
+
+    // The binding can not be moved to a presentation with other proofs. The
+    // binding is the last field of a presentation, so it is moved by splicing
+    // the encoded presentations.
+    let other = attestation.presentation_builder(&provider).build().unwrap();
+
+    let bound = bincode::serialize(&presentation).unwrap();
+    let unbound = bincode::serialize(&unbound).unwrap();
+    let other = bincode::serialize(&other).unwrap();
+
+    let mut forged = other[..other.len() - 1].to_vec();
+    forged.extend_from_slice(&bound[unbound.len() - 1..]);
+    let forged: Presentation = bincode::deserialize(&forged).unwrap();
+
+    assert!(forged
+        .verify(&provider, &VerifyOptions::new().nonce(nonce))
+        .is_err());
+
+    // Only the holder can bind a presentation.
+    let other = Secp256k1Signer::new(&[8u8; 32]).unwrap();
+    let mut builder = attestation.presentation_builder(&provider);
+    builder.binding(rand::random(), &other);
+    assert!(builder.build().is_err());
+}
+
+/// Tests that an expired attestation is rejected by the verifier.
+#[test]
+fn test_api_attestation_expiry() {
+    let mut provider = CryptoProvider::default();
+    provider.signer.set_secp256k1(&[42u8; 32]).unwrap();
+
+    let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+
+    let ConnectionFixture {
+        server_name,
+        connection_info,
+        server_cert_data,
+    } = ConnectionFixture::tlsnotary(transcript.length());
+
+    let HandshakeData::V1_2(HandshakeDataV1_2 {
+        server_ephemeral_key,
+        ..
+    }) = server_cert_data.handshake.clone()
+    else {
+        unreachable!()
+    };
+    let time = connection_info.time;
+
+    let request_config = RequestConfig::default();
+    let mut request_builder = Request::builder(&request_config);
+
+    request_builder
+        .server_name(server_name)
+        .server_cert_data(server_cert_data)
+        .transcript(transcript);
+
+    let (request, secrets) = request_builder.build(&provider).unwrap();
+
+    // Notary signs attestations which are valid for 60 seconds.
+    let attestation_config = AttestationConfig::builder()
+        .supported_signature_algs([SignatureAlgId::SECP256K1])
+        .validity_period(60)
+        .build()
+        .unwrap();
+
+    let mut attestation_builder = Attestation::builder(&attestation_config)
+        .accept_request(request)
+        .unwrap();
+
+    attestation_builder
+        .connection_info(connection_info)
+        .server_ephemeral_key(server_ephemeral_key);
+
+    let attestation = attestation_builder.build(&provider).unwrap();
+
+    assert_eq!(attestation.body.validity().unwrap().not_after, time + 60);
+
+    let mut builder = attestation.presentation_builder(&provider);
+    builder.identity_proof(secrets.identity_proof());
+    let presentation = builder.build().unwrap();
+
+    presentation
+        .clone()
+        .verify(
+            &provider,
+            &VerifyOptions::new().policy(ValidityPolicy::new(time + 10)),
+        )
+        .unwrap();
+
+    let err = presentation
+        .clone()
+        .verify(
+            &provider,
+            &VerifyOptions::new().policy(ValidityPolicy::new(time + 61)),
+        )
+        .unwrap_err();
+    assert!(err.is_expired());
+
+    // By default the attestation is checked against the system clock.
+    let err = presentation
+        .clone()
+        .verify(&provider, &VerifyOptions::default())
+        .unwrap_err();
+    assert!(err.is_expired());
+
+    // The verifier may require a fresher connection than the notary.
+    let err = presentation
+        .verify(
+            &provider,
+            &VerifyOptions::new().policy(ValidityPolicy::new(time + 10).max_age(5)),
+        )
+        .unwrap_err();
+    assert!(err.is_expired());
+}
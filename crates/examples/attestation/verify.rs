@@ -5,7 +5,7 @@
 use std::time::Duration;
 
 use tlsn_core::{
-    presentation::{Presentation, PresentationOutput},
+    presentation::{Presentation, PresentationOutput, VerifyOptions},
     signing::VerifyingKey,
     CryptoProvider,
 };
@@ -33,7 +33,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         connection_info,
         transcript,
         ..
-    } = presentation.verify(&provider).unwrap();
+    } = presentation
+        .verify(&provider, &VerifyOptions::default())
+        .unwrap();
 
     // The time at which the connection was started.
     let time = chrono::DateTime::UNIX_EPOCH + Duration::from_secs(connection_info.time);
@@ -0,0 +1,282 @@
+//! Support for the chunked transfer coding.
+//!
+//! A body with the chunked transfer coding is framed into chunks, each
+//! preceded by a line containing its size. The framing is interleaved with the
+//! payload in the transcript, so content which spans multiple chunks, such as
+//! a JSON document, is parsed from the decoded payload and its ranges are
+//! mapped back onto the transcript.
+
+use std::ops::Range;
+
+use tlsn_core::transcript::{Direction, Transcript, TranscriptCommitConfigBuilder};
+use utils::range::{RangeSet, ToRangeSet, Union};
+
+use crate::json::{JsonCommit, JsonCommitError, JsonValue};
+
+/// A chunk of a body with the chunked transfer coding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// The range of the chunk size line, including the CRLF.
+    pub size_line: Range<usize>,
+    /// The range of the chunk data.
+    pub data: Range<usize>,
+}
+
+/// A body with the chunked transfer coding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkedBody {
+    span: Range<usize>,
+    chunks: Vec<Chunk>,
+}
+
+impl ChunkedBody {
+    /// Parses a chunked body.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The body, starting with the first chunk.
+    /// * `offset` - The offset of the body in the transcript, which is added to
+    ///   all ranges.
+    pub fn parse(src: &[u8], offset: usize) -> Result<Self, ChunkedBodyError> {
+        let mut chunks = Vec::new();
+        let mut pos = 0;
+
+        loop {
+            let line_end = find_crlf(src, pos)?;
+            let size = parse_chunk_size(&src[pos..line_end])?;
+            let data_start = line_end + 2;
+
+            if size == 0 {
+                // The last chunk is followed by an optional trailer section
+                // which is terminated by an empty line.
+                let mut pos = data_start;
+                loop {
+                    let line_end = find_crlf(src, pos)?;
+                    if line_end == pos {
+                        pos += 2;
+                        break;
+                    }
+                    pos = line_end + 2;
+                }
+
+                return Ok(Self {
+                    span: offset..offset + pos,
+                    chunks,
+                });
+            }
+
+            let data_end = data_start
+                .checked_add(size)
+                .filter(|data_end| data_end.checked_add(2).is_some_and(|end| end <= src.len()))
+                .ok_or_else(|| ChunkedBodyError::new("chunk data is incomplete"))?;
+
+            if &src[data_end..data_end + 2] != b"\r\n" {
+                return Err(ChunkedBodyError::new(
+                    "chunk data is not terminated by CRLF",
+                ));
+            }
+
+            chunks.push(Chunk {
+                size_line: offset + pos..offset + data_start,
+                data: offset + data_start..offset + data_end,
+            });
+
+            pos = data_end + 2;
+        }
+    }
+
+    /// Returns the range of the entire body, including the framing.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the chunks of the body, excluding the last chunk.
+    pub fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+
+    /// Returns the length of the payload.
+    pub fn payload_len(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.data.len()).sum()
+    }
+
+    /// Returns the ranges of the payload, excluding the framing.
+    pub fn payload_ranges(&self) -> RangeSet<usize> {
+        self.chunks
+            .iter()
+            .fold(RangeSet::default(), |ranges, chunk| {
+                ranges.union(&RangeSet::from(chunk.data.clone()))
+            })
+    }
+
+    /// Returns the payload of the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The body, the same as provided to [`ChunkedBody::parse`].
+    pub fn payload(&self, src: &[u8]) -> Vec<u8> {
+        let offset = self.span.start;
+        self.chunks
+            .iter()
+            .flat_map(|chunk| &src[chunk.data.start - offset..chunk.data.end - offset])
+            .copied()
+            .collect()
+    }
+
+    /// Maps ranges of the payload to ranges of the transcript.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - The ranges relative to the start of the payload.
+    pub fn map(&self, ranges: &dyn ToRangeSet<usize>) -> Result<RangeSet<usize>, ChunkedBodyError> {
+        let ranges = ranges.to_range_set();
+        if ranges.end().unwrap_or_default() > self.payload_len() {
+            return Err(ChunkedBodyError::new(
+                "range is out of bounds of the payload",
+            ));
+        }
+
+        let mut mapped = RangeSet::default();
+        for range in ranges.iter_ranges() {
+            let mut payload_start = 0;
+            for chunk in &self.chunks {
+                let payload_end = payload_start + chunk.data.len();
+                let start = range.start.max(payload_start);
+                let end = range.end.min(payload_end);
+                if start < end {
+                    let start = chunk.data.start + (start - payload_start);
+                    let end = chunk.data.start + (end - payload_start);
+                    mapped = mapped.union(&RangeSet::from(start..end));
+                }
+                payload_start = payload_end;
+            }
+        }
+
+        Ok(mapped)
+    }
+}
+
+/// Commits to a JSON value which was parsed from the payload of a chunked
+/// body.
+///
+/// The committer commits to the value as if the payload was the transcript,
+/// and the ranges of its commitments are then mapped onto the transcript.
+///
+/// # Arguments
+///
+/// * `builder` - The transcript commitment builder.
+/// * `committer` - The JSON committer.
+/// * `body` - The chunked body.
+/// * `payload` - The payload of the body, which the value was parsed from.
+/// * `value` - The JSON value.
+/// * `direction` - The direction of the body.
+pub(crate) fn commit_json(
+    builder: &mut TranscriptCommitConfigBuilder,
+    committer: &mut dyn JsonCommit,
+    body: &ChunkedBody,
+    payload: &[u8],
+    value: &JsonValue,
+    direction: Direction,
+) -> Result<(), JsonCommitError> {
+    let payload = match direction {
+        Direction::Sent => Transcript::new(payload, Vec::new()),
+        Direction::Received => Transcript::new(Vec::new(), payload),
+    };
+
+    let mut payload_builder = TranscriptCommitConfigBuilder::new(&payload);
+    committer.commit_value(&mut payload_builder, value, direction)?;
+    let config = payload_builder
+        .build()
+        .map_err(|e| JsonCommitError::new_with_source("failed to commit to payload", e))?;
+
+    for (direction, idx) in config.iter_encoding() {
+        let ranges = body
+            .map(idx)
+            .map_err(|e| JsonCommitError::new_with_source("failed to map payload ranges", e))?;
+        builder
+            .commit(&ranges, *direction)
+            .map_err(|e| JsonCommitError::new_with_source("failed to commit to payload", e))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the index of the next CRLF at or after `pos`.
+fn find_crlf(src: &[u8], pos: usize) -> Result<usize, ChunkedBodyError> {
+    src.get(pos..)
+        .and_then(|rest| rest.windows(2).position(|window| window == b"\r\n"))
+        .map(|idx| pos + idx)
+        .ok_or_else(|| ChunkedBodyError::new("line is not terminated by CRLF"))
+}
+
+/// Parses the size of a chunk from its size line, ignoring any extensions.
+fn parse_chunk_size(line: &[u8]) -> Result<usize, ChunkedBodyError> {
+    let size = line
+        .split(|byte| *byte == b';')
+        .next()
+        .expect("split yields at least one item");
+    let size = std::str::from_utf8(size)
+        .map_err(|_| ChunkedBodyError::new("chunk size is not valid UTF-8"))?
+        .trim_matches([' ', '\t']);
+
+    if size.is_empty() || !size.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(ChunkedBodyError::new(format!("invalid chunk size: {size}")));
+    }
+
+    usize::from_str_radix(size, 16)
+        .map_err(|_| ChunkedBodyError::new(format!("chunk size is too large: {size}")))
+}
+
+/// Error for [`ChunkedBody`].
+#[derive(Debug, thiserror::Error)]
+#[error("chunked body error: {0}")]
+pub struct ChunkedBodyError(String);
+
+impl ChunkedBodyError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] =
+        b"5;ext=1\r\n{\"a\":\r\n7\r\n \"hello\r\n2\r\n\"}\r\n0\r\nExpires: never\r\n\r\n";
+
+    #[test]
+    fn test_chunked_body_parse() {
+        let body = ChunkedBody::parse(BODY, 10).unwrap();
+
+        assert_eq!(body.span(), 10..10 + BODY.len());
+        assert_eq!(body.chunks().len(), 3);
+        assert_eq!(body.chunks()[0].size_line, 10..19);
+        assert_eq!(body.chunks()[0].data, 19..24);
+        assert_eq!(body.payload(BODY), b"{\"a\": \"hello\"}");
+    }
+
+    #[test]
+    fn test_chunked_body_map() {
+        let body = ChunkedBody::parse(BODY, 0).unwrap();
+
+        // `"hello"` spans the second and third chunk.
+        let mapped = body.map(&(6..13)).unwrap();
+        let data = mapped
+            .iter_ranges()
+            .flat_map(|range| &BODY[range])
+            .copied()
+            .collect::<Vec<_>>();
+
+        assert_eq!(mapped.len_ranges(), 2);
+        assert_eq!(data, b"\"hello\"");
+        assert!(body.map(&(0..15)).is_err());
+    }
+
+    #[test]
+    fn test_chunked_body_parse_invalid() {
+        assert!(ChunkedBody::parse(b"5\r\nabc\r\n0\r\n\r\n", 0).is_err());
+        assert!(ChunkedBody::parse(b"x\r\n\r\n", 0).is_err());
+        assert!(ChunkedBody::parse(b"0\r\n", 0).is_err());
+    }
+}
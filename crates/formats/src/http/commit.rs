@@ -1,10 +1,17 @@
 use std::error::Error;
 
+use bytes::Bytes;
 use spansy::Spanned;
 use tlsn_core::transcript::{Direction, TranscriptCommitConfigBuilder};
+use utils::range::ToRangeSet;
 
 use crate::{
-    http::{Body, BodyContent, Header, HttpTranscript, MessageKind, Request, Response, Target},
+    http::{
+        chunked,
+        form::{is_urlencoded, media_type, multipart_boundary},
+        is_chunked, Body, BodyContent, ChunkedBody, ContentEncoding, Header, HttpTranscript,
+        MessageKind, MultipartForm, Request, Response, Target, TargetComponents, UrlEncodedForm,
+    },
     json::{DefaultJsonCommitter, JsonCommit},
 };
 
@@ -155,7 +162,10 @@ pub trait HttpCommit {
 
     /// Commits to a request target.
     ///
-    /// The default implementation commits to the target as a whole.
+    /// The default implementation commits to the target as a whole, and the
+    /// target excluding the query parameters. Additionally, it commits to each
+    /// path segment, each query parameter excluding the value and each value
+    /// separately, along with the remaining separators.
     ///
     /// # Arguments
     ///
@@ -178,6 +188,53 @@ pub trait HttpCommit {
             )
         })?;
 
+        let components = TargetComponents::from_target(target).map_err(|e| {
+            HttpCommitError::new_with_source(
+                MessageKind::Request,
+                "failed to parse target in request",
+                e,
+            )
+        })?;
+
+        let mut commit = |ranges: &dyn ToRangeSet<usize>, msg: String| {
+            builder
+                .commit(ranges, direction)
+                .map(|_| ())
+                .map_err(|e| HttpCommitError::new_with_source(MessageKind::Request, msg, e))
+        };
+
+        if !components.params().is_empty() {
+            commit(
+                &components.without_params(),
+                "failed to commit to target excluding query parameters".into(),
+            )?;
+        }
+
+        let separators = components.without_components();
+        if !separators.is_empty() {
+            commit(&separators, "failed to commit to target separators".into())?;
+        }
+
+        for segment in components.segments() {
+            commit(segment, "failed to commit to target path segment".into())?;
+        }
+
+        for param in components.params() {
+            commit(
+                &param.without_value(),
+                format!("failed to commit to \"{}\" query parameter", param.name),
+            )?;
+            if !param.value_span.is_empty() {
+                commit(
+                    &param.value_span,
+                    format!(
+                        "failed to commit to \"{}\" query parameter value",
+                        param.name
+                    ),
+                )?;
+            }
+        }
+
         Ok(())
     }
 
@@ -231,6 +288,14 @@ pub trait HttpCommit {
     /// the format type of the body. If the format of the body is unknown,
     /// it commits to the body as a whole.
     ///
+    /// A URL-encoded or multipart form body is committed to as a whole, and
+    /// the name and value of each field separately.
+    ///
+    /// If the body has the chunked transfer coding, it commits to the body as a
+    /// whole and to the payload excluding the framing. A JSON or form payload
+    /// is additionally committed to as above, with commitments which may span
+    /// multiple chunks.
+    ///
     /// # Arguments
     ///
     /// * `builder` - The transcript commitment builder.
@@ -244,6 +309,16 @@ pub trait HttpCommit {
         parent: &Request,
         body: &Body,
     ) -> Result<(), HttpCommitError> {
+        if is_chunked(&parent.headers) {
+            return commit_chunked_body(
+                builder,
+                direction,
+                MessageKind::Request,
+                &parent.headers,
+                body,
+            );
+        }
+
         match &body.content {
             BodyContent::Json(body) => {
                 DefaultJsonCommitter::default()
@@ -257,13 +332,23 @@ pub trait HttpCommit {
                     })?;
             }
             body => {
-                builder.commit(body, direction).map_err(|e| {
-                    HttpCommitError::new_with_source(
-                        MessageKind::Request,
-                        "failed to commit to unknown content body",
-                        e,
-                    )
-                })?;
+                if !commit_form_body(
+                    builder,
+                    direction,
+                    MessageKind::Request,
+                    &parent.headers,
+                    body.span().as_bytes(),
+                    body.to_range_set().min().unwrap_or_default(),
+                    None,
+                )? {
+                    builder.commit(body, direction).map_err(|e| {
+                        HttpCommitError::new_with_source(
+                            MessageKind::Request,
+                            "failed to commit to unknown content body",
+                            e,
+                        )
+                    })?;
+                }
             }
         }
 
@@ -368,6 +453,14 @@ pub trait HttpCommit {
     /// the format type of the body. If the format of the body is unknown,
     /// it commits to the body as a whole.
     ///
+    /// A URL-encoded or multipart form body is committed to as a whole, and
+    /// the name and value of each field separately.
+    ///
+    /// If the body has the chunked transfer coding, it commits to the body as a
+    /// whole and to the payload excluding the framing. A JSON or form payload
+    /// is additionally committed to as above, with commitments which may span
+    /// multiple chunks.
+    ///
     /// # Arguments
     ///
     /// * `builder` - The transcript commitment builder.
@@ -381,6 +474,16 @@ pub trait HttpCommit {
         parent: &Response,
         body: &Body,
     ) -> Result<(), HttpCommitError> {
+        if is_chunked(&parent.headers) {
+            return commit_chunked_body(
+                builder,
+                direction,
+                MessageKind::Response,
+                &parent.headers,
+                body,
+            );
+        }
+
         match &body.content {
             BodyContent::Json(body) => {
                 DefaultJsonCommitter::default()
@@ -394,13 +497,23 @@ pub trait HttpCommit {
                     })?;
             }
             body => {
-                builder.commit(body, direction).map_err(|e| {
-                    HttpCommitError::new_with_source(
-                        MessageKind::Request,
-                        "failed to commit to unknown content body",
-                        e,
-                    )
-                })?;
+                if !commit_form_body(
+                    builder,
+                    direction,
+                    MessageKind::Response,
+                    &parent.headers,
+                    body.span().as_bytes(),
+                    body.to_range_set().min().unwrap_or_default(),
+                    None,
+                )? {
+                    builder.commit(body, direction).map_err(|e| {
+                        HttpCommitError::new_with_source(
+                            MessageKind::Response,
+                            "failed to commit to unknown content body",
+                            e,
+                        )
+                    })?;
+                }
             }
         }
 
@@ -408,6 +521,181 @@ pub trait HttpCommit {
     }
 }
 
+/// Commits to a body with the chunked transfer coding.
+fn commit_chunked_body(
+    builder: &mut TranscriptCommitConfigBuilder,
+    direction: Direction,
+    kind: MessageKind,
+    headers: &[Header],
+    body: &Body,
+) -> Result<(), HttpCommitError> {
+    let src = body.content.span().as_bytes();
+    let offset = body.content.to_range_set().min().unwrap_or_default();
+    let chunked_body = ChunkedBody::parse(src, offset)
+        .map_err(|e| HttpCommitError::new_with_source(kind, "failed to parse chunked body", e))?;
+
+    builder.commit(&body.content, direction).map_err(|e| {
+        HttpCommitError::new_with_source(kind, "failed to commit to chunked body", e)
+    })?;
+
+    if chunked_body.payload_len() == 0 {
+        return Ok(());
+    }
+
+    builder
+        .commit(&chunked_body.payload_ranges(), direction)
+        .map_err(|e| {
+            HttpCommitError::new_with_source(kind, "failed to commit to chunked body payload", e)
+        })?;
+
+    // A compressed payload can only be disclosed as a whole, whether it is
+    // compressed by the content coding or by a transfer coding stacked with
+    // chunked, eg. `gzip, chunked`.
+    let encoding = ContentEncoding::from_headers(headers)
+        .map_err(|e| HttpCommitError::new_with_source(kind, "invalid content encoding", e))?;
+    let transfer_encoding = ContentEncoding::from_transfer_encoding(headers)
+        .map_err(|e| HttpCommitError::new_with_source(kind, "invalid transfer encoding", e))?;
+    if encoding.is_some() || transfer_encoding.is_some() {
+        return Ok(());
+    }
+
+    let payload = chunked_body.payload(src);
+    if !is_json(headers) {
+        // A form is parsed from the payload and its ranges are mapped across
+        // the chunks.
+        return commit_form_body(
+            builder,
+            direction,
+            kind,
+            headers,
+            &payload,
+            0,
+            Some(&chunked_body),
+        )
+        .map(|_| ());
+    }
+
+    let value = spansy::json::parse(Bytes::from(payload.clone())).map_err(|e| {
+        HttpCommitError::new_with_source(kind, "failed to parse chunked JSON body", e)
+    })?;
+
+    chunked::commit_json(
+        builder,
+        &mut DefaultJsonCommitter::default(),
+        &chunked_body,
+        &payload,
+        &value,
+        direction,
+    )
+    .map_err(|e| HttpCommitError::new_with_source(kind, "failed to commit to chunked JSON body", e))
+}
+
+/// Commits to a form body, returning whether the body is a form.
+///
+/// # Arguments
+///
+/// * `builder` - The transcript commitment builder.
+/// * `direction` - The direction of the message.
+/// * `kind` - The kind of the message.
+/// * `headers` - The headers of the message.
+/// * `src` - The form data.
+/// * `offset` - The offset of the form data in the transcript.
+/// * `chunked_body` - The chunked body which the form data is the payload of,
+///   if any. Ranges of the form are mapped across its chunks.
+fn commit_form_body(
+    builder: &mut TranscriptCommitConfigBuilder,
+    direction: Direction,
+    kind: MessageKind,
+    headers: &[Header],
+    src: &[u8],
+    offset: usize,
+    chunked_body: Option<&ChunkedBody>,
+) -> Result<bool, HttpCommitError> {
+    // A compressed form can only be disclosed as a whole.
+    if !matches!(ContentEncoding::from_headers(headers), Ok(None)) {
+        return Ok(false);
+    }
+
+    let mut commit = |ranges: &dyn ToRangeSet<usize>, msg: String| {
+        let ranges = match chunked_body {
+            Some(chunked_body) => chunked_body
+                .map(ranges)
+                .map_err(|e| HttpCommitError::new_with_source(kind, msg.clone(), e))?,
+            None => ranges.to_range_set(),
+        };
+
+        builder
+            .commit(&ranges, direction)
+            .map(|_| ())
+            .map_err(|e| HttpCommitError::new_with_source(kind, msg, e))
+    };
+
+    if is_urlencoded(headers) {
+        let form = UrlEncodedForm::parse(src, offset).map_err(|e| {
+            HttpCommitError::new_with_source(kind, "failed to parse URL-encoded body", e)
+        })?;
+
+        commit(&form.span(), "failed to commit to URL-encoded body".into())?;
+
+        let without_fields = form.without_fields();
+        if !without_fields.is_empty() {
+            commit(
+                &without_fields,
+                "failed to commit to form separators".into(),
+            )?;
+        }
+
+        for field in form.fields() {
+            commit(
+                &field.without_value(),
+                format!("failed to commit to \"{}\" field name", field.name),
+            )?;
+            if !field.value_span.is_empty() {
+                commit(
+                    &field.value_span,
+                    format!("failed to commit to \"{}\" field value", field.name),
+                )?;
+            }
+        }
+    } else if let Some(boundary) = multipart_boundary(headers) {
+        let form = MultipartForm::parse(src, offset, &boundary).map_err(|e| {
+            HttpCommitError::new_with_source(kind, "failed to parse multipart body", e)
+        })?;
+
+        commit(&form.span(), "failed to commit to multipart body".into())?;
+        commit(
+            &form.without_parts(),
+            "failed to commit to form boundaries".into(),
+        )?;
+
+        for (idx, part) in form.parts().iter().enumerate() {
+            let name = part.name.clone().unwrap_or_else(|| idx.to_string());
+            if !part.without_body().is_empty() {
+                commit(
+                    &part.without_body(),
+                    format!("failed to commit to \"{name}\" part headers"),
+                )?;
+            }
+            if !part.body.is_empty() {
+                commit(
+                    &part.body,
+                    format!("failed to commit to \"{name}\" part body"),
+                )?;
+            }
+        }
+    } else {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// Returns whether the headers specify JSON content.
+fn is_json(headers: &[Header]) -> bool {
+    media_type(headers)
+        .is_some_and(|media_type| media_type.eq_ignore_ascii_case("application/json"))
+}
+
 /// The default HTTP committer.
 #[derive(Debug, Default, Clone)]
 pub struct DefaultHttpCommitter {}
@@ -418,15 +706,61 @@ impl HttpCommit for DefaultHttpCommitter {}
 mod tests {
     use super::*;
     use rstest::*;
-    use spansy::http::{parse_request, parse_response};
-    use tlsn_core::transcript::Transcript;
+    use spansy::http::parse_request;
+    use std::ops::Range;
+    use tlsn_core::transcript::{Idx, Transcript, TranscriptCommitConfig};
     use tlsn_data_fixtures::http as fixtures;
+    use utils::range::{Difference, RangeSet};
+
+    /// Commits to a transcript with the default committer.
+    fn commit(transcript: &Transcript) -> TranscriptCommitConfig {
+        let http_transcript = HttpTranscript::parse(transcript).unwrap();
+        let mut builder = TranscriptCommitConfigBuilder::new(transcript);
+
+        DefaultHttpCommitter::default()
+            .commit_transcript(&mut builder, &http_transcript)
+            .unwrap();
+
+        builder.build().unwrap()
+    }
+
+    /// Returns whether exactly the ranges are committed to.
+    fn is_committed(
+        config: &TranscriptCommitConfig,
+        direction: Direction,
+        ranges: &dyn ToRangeSet<usize>,
+    ) -> bool {
+        let idx = Idx::new(ranges.to_range_set());
+        config
+            .iter_encoding()
+            .any(|(committed_direction, committed_idx)| {
+                *committed_direction == direction && *committed_idx == idx
+            })
+    }
+
+    /// Returns the range of the first occurrence of `needle` in `src`.
+    fn find(src: &[u8], needle: &[u8]) -> Range<usize> {
+        let start = src
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .unwrap();
+        start..start + needle.len()
+    }
 
     #[rstest]
     #[case::get_empty(fixtures::request::GET_EMPTY)]
     #[case::get_empty_header(fixtures::request::GET_EMPTY_HEADER)]
     #[case::get_with_header(fixtures::request::GET_WITH_HEADER)]
     #[case::post_json(fixtures::request::POST_JSON)]
+    #[case::get_with_query(
+        b"GET /v1/accounts/123?access_token=secret&page=2 HTTP/1.1\r\nHost: localhost\r\n\r\n"
+    )]
+    #[case::post_urlencoded(
+        b"POST /login HTTP/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: 26\r\n\r\naccount=12345&password=abc"
+    )]
+    #[case::post_multipart(
+        b"POST /login HTTP/1.1\r\nContent-Type: multipart/form-data; boundary=XyZ\r\nContent-Length: 140\r\n\r\n--XyZ\r\nContent-Disposition: form-data; name=\"account\"\r\n\r\n12345\r\n--XyZ\r\nContent-Disposition: form-data; name=\"password\"\r\n\r\nhunter2\r\n--XyZ--\r\n"
+    )]
     fn test_http_default_commit_request(#[case] src: &'static [u8]) {
         let transcript = Transcript::new(src, []);
         let request = parse_request(src).unwrap();
@@ -445,16 +779,159 @@ mod tests {
     #[case::empty_header(fixtures::response::OK_EMPTY_HEADER)]
     #[case::json(fixtures::response::OK_JSON)]
     #[case::text(fixtures::response::OK_TEXT)]
+    #[case::chunked_json(CHUNKED_JSON)]
+    #[case::chunked_gzip(
+        b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: gzip, chunked\r\n\r\n4\r\n\x1f\x8b\x08\x00\r\n0\r\n\r\n"
+    )]
     fn test_http_default_commit_response(#[case] src: &'static [u8]) {
         let transcript = Transcript::new([], src);
-        let response = parse_response(src).unwrap();
+        let http_transcript = HttpTranscript::parse(&transcript).unwrap();
         let mut committer = DefaultHttpCommitter::default();
         let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
 
         committer
-            .commit_response(&mut builder, Direction::Received, &response)
+            .commit_transcript(&mut builder, &http_transcript)
             .unwrap();
 
         builder.build().unwrap();
     }
+
+    const CHUNKED_JSON: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n{\"a\":\r\n7\r\n \"hello\r\n2\r\n\"}\r\n0\r\n\r\n";
+
+    #[test]
+    fn test_http_default_commit_chunked_response() {
+        let config = commit(&Transcript::new([], CHUNKED_JSON));
+        let committed = config
+            .iter_encoding()
+            .map(|(_, idx)| {
+                idx.iter_ranges()
+                    .flat_map(|range| &CHUNKED_JSON[range])
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // The payload without the framing and the value spanning two chunks.
+        assert!(committed.contains(&b"{\"a\": \"hello\"}".to_vec()));
+        assert!(committed.contains(&b"\"hello\"".to_vec()));
+        // The framing is never committed to separately from the payload.
+        assert!(!committed.contains(&b"7\r\n".to_vec()));
+    }
+
+    #[test]
+    fn test_http_default_commit_target() {
+        let src =
+            b"GET /v1/accounts/123?access_token=secret&page=2 HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let config = commit(&Transcript::new(src, []));
+
+        // Each path segment is committed to separately.
+        for segment in [&b"v1"[..], b"accounts", b"123"] {
+            assert!(is_committed(&config, Direction::Sent, &find(src, segment)));
+        }
+
+        // The value of each query parameter is committed to separately from
+        // its name, so that the access token can remain hidden.
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &find(src, b"access_token=")
+        ));
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &find(src, b"secret")
+        ));
+        assert!(!is_committed(
+            &config,
+            Direction::Sent,
+            &find(src, b"access_token=secret")
+        ));
+
+        let page = find(src, b"page=2");
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &(page.start..page.end - 1)
+        ));
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &(page.end - 1..page.end)
+        ));
+
+        // The target as a whole, and excluding the query parameters.
+        let target = find(src, b"/v1/accounts/123?access_token=secret&page=2");
+        assert!(is_committed(&config, Direction::Sent, &target));
+        let without_params = RangeSet::from(target)
+            .difference(&find(src, b"access_token=secret"))
+            .difference(&page);
+        assert!(is_committed(&config, Direction::Sent, &without_params));
+    }
+
+    #[test]
+    fn test_http_default_commit_urlencoded_form() {
+        let src = b"POST /login HTTP/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: 26\r\n\r\naccount=12345&password=abc";
+        let config = commit(&Transcript::new(src, []));
+
+        // The name and value of each field are committed to separately.
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &find(src, b"account=")
+        ));
+        assert!(is_committed(&config, Direction::Sent, &find(src, b"12345")));
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &find(src, b"password=")
+        ));
+        assert!(is_committed(&config, Direction::Sent, &find(src, b"abc")));
+        assert!(is_committed(&config, Direction::Sent, &find(src, b"&")));
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &find(src, b"account=12345&password=abc")
+        ));
+    }
+
+    #[test]
+    fn test_http_default_commit_multipart_form() {
+        let src = b"POST /login HTTP/1.1\r\nContent-Type: multipart/form-data; boundary=XyZ\r\nContent-Length: 140\r\n\r\n--XyZ\r\nContent-Disposition: form-data; name=\"account\"\r\n\r\n12345\r\n--XyZ\r\nContent-Disposition: form-data; name=\"password\"\r\n\r\nhunter2\r\n--XyZ--\r\n";
+        let config = commit(&Transcript::new(src, []));
+
+        // The headers and body of each part are committed to separately.
+        for (headers, body) in [
+            (
+                &b"Content-Disposition: form-data; name=\"account\"\r\n\r\n"[..],
+                &b"12345"[..],
+            ),
+            (
+                &b"Content-Disposition: form-data; name=\"password\"\r\n\r\n"[..],
+                &b"hunter2"[..],
+            ),
+        ] {
+            assert!(is_committed(&config, Direction::Sent, &find(src, headers)));
+            assert!(is_committed(&config, Direction::Sent, &find(src, body)));
+        }
+    }
+
+    #[test]
+    fn test_http_default_commit_chunked_form() {
+        // The name of the password field spans both chunks.
+        let src = b"POST /login HTTP/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\nTransfer-Encoding: chunked\r\n\r\n12\r\naccount=12345&pass\r\n8\r\nword=abc\r\n0\r\n\r\n";
+        let config = commit(&Transcript::new(src, []));
+
+        assert!(is_committed(
+            &config,
+            Direction::Sent,
+            &find(src, b"account=")
+        ));
+        assert!(is_committed(&config, Direction::Sent, &find(src, b"12345")));
+        assert!(is_committed(&config, Direction::Sent, &find(src, b"abc")));
+
+        let pass = find(src, b"pass\r\n");
+        let word = find(src, b"word=");
+        let name = RangeSet::from([pass.start..pass.start + 4, word]);
+        assert!(is_committed(&config, Direction::Sent, &name));
+    }
 }
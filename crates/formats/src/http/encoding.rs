@@ -0,0 +1,274 @@
+//! Support for the gzip and deflate content codings.
+//!
+//! A compressed body can not be selectively disclosed, as any byte of the
+//! compressed data may depend on all of the preceding content. Instead, the
+//! prover discloses the compressed body as a whole and the verifier decodes it
+//! to check statements about the decompressed content.
+
+use std::io::Read;
+
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use spansy::Spanned;
+
+use crate::http::{is_chunked, Body, ChunkedBody, Header};
+
+/// A content coding of an HTTP body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContentEncoding {
+    /// The gzip coding.
+    Gzip,
+    /// The deflate coding.
+    Deflate,
+}
+
+impl ContentEncoding {
+    /// Returns the content coding of a message from its headers.
+    ///
+    /// Returns `None` if the body is not encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the message.
+    pub fn from_headers(headers: &[Header]) -> Result<Option<Self>, DecodeError> {
+        let Some(header) = super::find_header(headers, "content-encoding") else {
+            return Ok(None);
+        };
+
+        let value = std::str::from_utf8(header.value.span().as_bytes())
+            .map_err(|_| DecodeError::new("content encoding is not valid UTF-8"))?;
+
+        Self::from_codings(value.split(','))
+    }
+
+    /// Returns the coding which is applied by the transfer encoding of a
+    /// message, in addition to the chunked transfer coding.
+    ///
+    /// Returns `None` if no such coding is applied, eg. for `Transfer-Encoding:
+    /// chunked`. A stacked coding such as `gzip, chunked` returns the gzip
+    /// coding.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the message.
+    pub fn from_transfer_encoding(headers: &[Header]) -> Result<Option<Self>, DecodeError> {
+        let Some(header) = super::find_header(headers, "transfer-encoding") else {
+            return Ok(None);
+        };
+
+        let value = std::str::from_utf8(header.value.span().as_bytes())
+            .map_err(|_| DecodeError::new("transfer encoding is not valid UTF-8"))?;
+
+        Self::from_transfer_codings(value)
+    }
+
+    /// Parses the value of a transfer encoding header, excluding the final
+    /// chunked coding.
+    fn from_transfer_codings(value: &str) -> Result<Option<Self>, DecodeError> {
+        let mut codings = value.split(',').map(str::trim).collect::<Vec<_>>();
+        if codings
+            .last()
+            .is_some_and(|coding| coding.eq_ignore_ascii_case("chunked"))
+        {
+            codings.pop();
+        }
+
+        if codings
+            .iter()
+            .any(|coding| coding.eq_ignore_ascii_case("chunked"))
+        {
+            return Err(DecodeError::new("chunked must be the last transfer coding"));
+        }
+
+        Self::from_codings(codings)
+    }
+
+    /// Parses a list of codings, which may contain at most one gzip or deflate
+    /// coding.
+    fn from_codings<'a>(
+        codings: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Option<Self>, DecodeError> {
+        let mut encoding = None;
+        for coding in codings.into_iter().map(str::trim) {
+            let coding = match coding.to_ascii_lowercase().as_str() {
+                "" | "identity" => continue,
+                "gzip" | "x-gzip" => ContentEncoding::Gzip,
+                "deflate" => ContentEncoding::Deflate,
+                coding => {
+                    return Err(DecodeError::new(format!(
+                        "unsupported content coding: {coding}"
+                    )))
+                }
+            };
+
+            if encoding.replace(coding).is_some() {
+                return Err(DecodeError::new(
+                    "multiple content codings are not supported",
+                ));
+            }
+        }
+
+        Ok(encoding)
+    }
+
+    /// Decodes the content.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The encoded content.
+    /// * `limit` - The maximum length of the decoded content.
+    pub fn decode(&self, data: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+        match self {
+            ContentEncoding::Gzip => read_to_end(GzDecoder::new(data), limit),
+            // The deflate coding is defined as the zlib format, but some
+            // servers send raw deflate data instead.
+            ContentEncoding::Deflate => read_to_end(ZlibDecoder::new(data), limit)
+                .or_else(|_| read_to_end(DeflateDecoder::new(data), limit)),
+        }
+    }
+}
+
+/// Decodes the content of a body, removing the chunked framing, the transfer
+/// coding and the content coding if present.
+///
+/// This is used by a verifier to check statements about the decompressed
+/// content of a body which was disclosed as a whole.
+///
+/// # Arguments
+///
+/// * `headers` - The headers of the message.
+/// * `body` - The body of the message.
+/// * `limit` - The maximum length of the decoded content.
+pub fn decode_body(headers: &[Header], body: &Body, limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let src = body.content.span().as_bytes();
+    let data = if is_chunked(headers) {
+        ChunkedBody::parse(src, 0)
+            .map_err(|e| DecodeError::new(e.to_string()))?
+            .payload(src)
+    } else {
+        src.to_vec()
+    };
+
+    // The transfer coding is applied on top of the content coding.
+    let data = match ContentEncoding::from_transfer_encoding(headers)? {
+        Some(encoding) => encoding.decode(&data, limit)?,
+        None => data,
+    };
+
+    match ContentEncoding::from_headers(headers)? {
+        Some(encoding) => encoding.decode(&data, limit),
+        None if data.len() > limit => Err(DecodeError::new(format!(
+            "content exceeds the limit of {limit} bytes"
+        ))),
+        None => Ok(data),
+    }
+}
+
+fn read_to_end(reader: impl Read, limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut decoded = Vec::new();
+    reader
+        .take((limit as u64).saturating_add(1))
+        .read_to_end(&mut decoded)
+        .map_err(|e| DecodeError::new(format!("failed to decode content: {e}")))?;
+
+    if decoded.len() > limit {
+        return Err(DecodeError::new(format!(
+            "decoded content exceeds the limit of {limit} bytes"
+        )));
+    }
+
+    Ok(decoded)
+}
+
+/// Error for [`ContentEncoding`].
+#[derive(Debug, thiserror::Error)]
+#[error("decode error: {0}")]
+pub struct DecodeError(String);
+
+impl DecodeError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{
+        write::{GzEncoder, ZlibEncoder},
+        Compression,
+    };
+
+    use super::*;
+    use crate::http::parse_response;
+
+    const CONTENT: &[u8] = b"{\"hello\": \"world\"}";
+
+    #[test]
+    fn test_decode_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CONTENT).unwrap();
+        let data = encoder.finish().unwrap();
+
+        assert_eq!(ContentEncoding::Gzip.decode(&data, 1024).unwrap(), CONTENT);
+        assert!(ContentEncoding::Gzip.decode(&data, 4).is_err());
+    }
+
+    #[test]
+    fn test_decode_deflate() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CONTENT).unwrap();
+        let data = encoder.finish().unwrap();
+
+        assert_eq!(
+            ContentEncoding::Deflate.decode(&data, 1024).unwrap(),
+            CONTENT
+        );
+    }
+
+    #[test]
+    fn test_decode_body_stacked_transfer_codings() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CONTENT).unwrap();
+        let data = encoder.finish().unwrap();
+
+        let src = [
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip, chunked\r\n\r\n".as_slice(),
+            format!("{:x}\r\n", data.len()).as_bytes(),
+            &data,
+            b"\r\n0\r\n\r\n",
+        ]
+        .concat();
+        let response = parse_response(&src).unwrap();
+
+        assert_eq!(
+            ContentEncoding::from_transfer_encoding(&response.headers).unwrap(),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(
+            decode_body(&response.headers, response.body.as_ref().unwrap(), 1024).unwrap(),
+            CONTENT
+        );
+    }
+
+    #[test]
+    fn test_transfer_codings() {
+        assert_eq!(
+            ContentEncoding::from_transfer_codings("chunked").unwrap(),
+            None
+        );
+        assert_eq!(
+            ContentEncoding::from_transfer_codings("gzip, chunked").unwrap(),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(
+            ContentEncoding::from_transfer_codings("deflate").unwrap(),
+            Some(ContentEncoding::Deflate)
+        );
+
+        for value in ["chunked, gzip", "br, chunked", "gzip, deflate, chunked"] {
+            assert!(ContentEncoding::from_transfer_codings(value).is_err());
+        }
+    }
+}
@@ -0,0 +1,446 @@
+//! Support for form bodies.
+//!
+//! Form posts use either the `application/x-www-form-urlencoded` or the
+//! `multipart/form-data` content type. The fields of both are parsed into
+//! ranges of the transcript so that the name and value of each field can be
+//! committed to separately.
+
+use std::ops::Range;
+
+use spansy::Spanned;
+use utils::range::{Difference, RangeSet};
+
+use crate::http::{find_header, Header};
+
+/// A field of a URL-encoded form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormField {
+    /// The decoded name of the field.
+    pub name: String,
+    /// The range of the entire field, i.e. `name=value`.
+    pub span: Range<usize>,
+    /// The range of the encoded name.
+    pub name_span: Range<usize>,
+    /// The range of the encoded value.
+    pub value_span: Range<usize>,
+}
+
+impl FormField {
+    /// Returns the ranges of the field excluding the value.
+    pub fn without_value(&self) -> RangeSet<usize> {
+        RangeSet::from(self.span.clone()).difference(&self.value_span)
+    }
+}
+
+/// A body with the `application/x-www-form-urlencoded` content type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlEncodedForm {
+    span: Range<usize>,
+    fields: Vec<FormField>,
+}
+
+impl UrlEncodedForm {
+    /// Parses a URL-encoded form.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The body.
+    /// * `offset` - The offset of the body in the transcript, which is added to
+    ///   all ranges.
+    pub fn parse(src: &[u8], offset: usize) -> Result<Self, FormError> {
+        let mut fields = Vec::new();
+        let mut pos = 0;
+
+        for pair in src.split(|byte| *byte == b'&') {
+            let start = pos;
+            pos += pair.len() + 1;
+
+            // Empty pairs, e.g. in `a=1&&b=2`, are ignored.
+            if pair.is_empty() {
+                continue;
+            }
+
+            let name_len = pair
+                .iter()
+                .position(|byte| *byte == b'=')
+                .unwrap_or(pair.len());
+            let value_start = (name_len + 1).min(pair.len());
+
+            fields.push(FormField {
+                name: percent_decode(&pair[..name_len])?,
+                span: offset + start..offset + start + pair.len(),
+                name_span: offset + start..offset + start + name_len,
+                value_span: offset + start + value_start..offset + start + pair.len(),
+            });
+        }
+
+        Ok(Self {
+            span: offset..offset + src.len(),
+            fields,
+        })
+    }
+
+    /// Returns the range of the entire form.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the fields of the form.
+    pub fn fields(&self) -> &[FormField] {
+        &self.fields
+    }
+
+    /// Returns the first field with the given name.
+    pub fn get(&self, name: &str) -> Option<&FormField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Returns the ranges of the form excluding the fields, i.e. the
+    /// separators.
+    pub fn without_fields(&self) -> RangeSet<usize> {
+        self.fields
+            .iter()
+            .fold(RangeSet::from(self.span.clone()), |ranges, field| {
+                ranges.difference(&field.span)
+            })
+    }
+}
+
+/// A header of a part of a multipart form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartHeader {
+    /// The name of the header.
+    pub name: String,
+    /// The range of the header line, excluding the CRLF.
+    pub span: Range<usize>,
+    /// The range of the header value.
+    pub value_span: Range<usize>,
+}
+
+/// A part of a multipart form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartPart {
+    /// The name of the field, from the `Content-Disposition` header.
+    pub name: Option<String>,
+    /// The file name, from the `Content-Disposition` header.
+    pub filename: Option<String>,
+    /// The range of the entire part, including the headers.
+    pub span: Range<usize>,
+    /// The headers of the part.
+    pub headers: Vec<PartHeader>,
+    /// The range of the body of the part.
+    pub body: Range<usize>,
+}
+
+impl MultipartPart {
+    /// Returns the range of the part excluding the body.
+    pub fn without_body(&self) -> Range<usize> {
+        self.span.start..self.body.start
+    }
+}
+
+/// A body with the `multipart/form-data` content type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartForm {
+    span: Range<usize>,
+    parts: Vec<MultipartPart>,
+}
+
+impl MultipartForm {
+    /// Parses a multipart form.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The body.
+    /// * `offset` - The offset of the body in the transcript, which is added to
+    ///   all ranges.
+    /// * `boundary` - The boundary of the parts.
+    pub fn parse(src: &[u8], offset: usize, boundary: &str) -> Result<Self, FormError> {
+        if boundary.is_empty() {
+            return Err(FormError::new("boundary is empty"));
+        }
+
+        let delimiter = [b"\r\n--", boundary.as_bytes()].concat();
+
+        // The first delimiter may be preceded by a preamble.
+        let mut pos = if src.starts_with(&delimiter[2..]) {
+            delimiter.len() - 2
+        } else {
+            find(src, &delimiter, 0)
+                .ok_or_else(|| FormError::new("body does not contain the boundary"))?
+                + delimiter.len()
+        };
+
+        let mut parts = Vec::new();
+        loop {
+            if src[pos..].starts_with(b"--") {
+                break;
+            }
+
+            // Transport padding may follow the delimiter.
+            while matches!(src.get(pos), Some(b' ' | b'\t')) {
+                pos += 1;
+            }
+            if !src[pos..].starts_with(b"\r\n") {
+                return Err(FormError::new("boundary is not terminated by CRLF"));
+            }
+            let start = pos + 2;
+
+            let end = find(src, &delimiter, start)
+                .ok_or_else(|| FormError::new("part is not terminated by a boundary"))?;
+            parts.push(parse_part(&src[start..end], offset + start)?);
+
+            pos = end + delimiter.len();
+        }
+
+        Ok(Self {
+            span: offset..offset + src.len(),
+            parts,
+        })
+    }
+
+    /// Returns the range of the entire form.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the parts of the form.
+    pub fn parts(&self) -> &[MultipartPart] {
+        &self.parts
+    }
+
+    /// Returns the first part with the given field name.
+    pub fn get(&self, name: &str) -> Option<&MultipartPart> {
+        self.parts
+            .iter()
+            .find(|part| part.name.as_deref() == Some(name))
+    }
+
+    /// Returns the ranges of the form excluding the parts, i.e. the
+    /// boundaries.
+    pub fn without_parts(&self) -> RangeSet<usize> {
+        self.parts
+            .iter()
+            .fold(RangeSet::from(self.span.clone()), |ranges, part| {
+                ranges.difference(&part.span)
+            })
+    }
+}
+
+/// Parses a part of a multipart form, excluding the boundaries.
+fn parse_part(src: &[u8], offset: usize) -> Result<MultipartPart, FormError> {
+    let mut headers = Vec::new();
+    let mut pos = 0;
+
+    loop {
+        let line_end = find(src, b"\r\n", pos)
+            .ok_or_else(|| FormError::new("part headers are not terminated"))?;
+        if line_end == pos {
+            pos += 2;
+            break;
+        }
+
+        let line = &src[pos..line_end];
+        let colon = line
+            .iter()
+            .position(|byte| *byte == b':')
+            .ok_or_else(|| FormError::new("part header is missing a colon"))?;
+        let name = std::str::from_utf8(&line[..colon])
+            .map_err(|_| FormError::new("part header name is not valid UTF-8"))?
+            .trim()
+            .to_string();
+
+        let mut value_start = pos + colon + 1;
+        while value_start < line_end && matches!(src[value_start], b' ' | b'\t') {
+            value_start += 1;
+        }
+
+        headers.push(PartHeader {
+            name,
+            span: offset + pos..offset + line_end,
+            value_span: offset + value_start..offset + line_end,
+        });
+
+        pos = line_end + 2;
+    }
+
+    let disposition = headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case("content-disposition"))
+        .map(|header| {
+            let value = header.value_span.start - offset..header.value_span.end - offset;
+            String::from_utf8_lossy(&src[value]).into_owned()
+        });
+
+    Ok(MultipartPart {
+        name: disposition
+            .as_deref()
+            .and_then(|value| parameter(value, "name")),
+        filename: disposition
+            .as_deref()
+            .and_then(|value| parameter(value, "filename")),
+        span: offset..offset + src.len(),
+        headers,
+        body: offset + pos..offset + src.len(),
+    })
+}
+
+/// Returns the value of a parameter of a header value, e.g. the `name` in
+/// `form-data; name="field"`.
+fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Returns whether the headers specify the URL-encoded form content type.
+pub(crate) fn is_urlencoded(headers: &[Header]) -> bool {
+    media_type(headers).is_some_and(|media_type| {
+        media_type.eq_ignore_ascii_case("application/x-www-form-urlencoded")
+    })
+}
+
+/// Returns the boundary if the headers specify the multipart form content
+/// type.
+pub(crate) fn multipart_boundary(headers: &[Header]) -> Option<String> {
+    if !media_type(headers)
+        .is_some_and(|media_type| media_type.eq_ignore_ascii_case("multipart/form-data"))
+    {
+        return None;
+    }
+
+    let header = find_header(headers, "content-type")?;
+    let value = std::str::from_utf8(header.value.span().as_bytes()).ok()?;
+    parameter(value, "boundary")
+}
+
+/// Returns the media type of the `Content-Type` header, without parameters.
+pub(crate) fn media_type(headers: &[Header]) -> Option<&str> {
+    let header = find_header(headers, "content-type")?;
+    std::str::from_utf8(header.value.span().as_bytes())
+        .ok()?
+        .split(';')
+        .next()
+        .map(str::trim)
+}
+
+/// Returns the index of the first occurrence of `needle` at or after `pos`.
+pub(crate) fn find(src: &[u8], needle: &[u8], pos: usize) -> Option<usize> {
+    src.get(pos..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|idx| pos + idx)
+}
+
+/// Decodes a percent-encoded form name.
+fn percent_decode(src: &[u8]) -> Result<String, FormError> {
+    let mut decoded = Vec::with_capacity(src.len());
+    let mut bytes = src.iter();
+
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = [*bytes.next().unwrap_or(&0), *bytes.next().unwrap_or(&0)];
+                let byte = std::str::from_utf8(&hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| FormError::new("invalid percent-encoding"))?;
+                decoded.push(byte);
+            }
+            byte => decoded.push(*byte),
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| FormError::new("form name is not valid UTF-8"))
+}
+
+/// Error for [`UrlEncodedForm`] and [`MultipartForm`].
+#[derive(Debug, thiserror::Error)]
+#[error("form error: {0}")]
+pub struct FormError(String);
+
+impl FormError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::range::ToRangeSet;
+
+    const MULTIPART: &[u8] = b"preamble\r\n--XyZ\r\nContent-Disposition: form-data; name=\"account\"\r\n\r\n12345\r\n--XyZ\r\nContent-Disposition: form-data; name=\"password\"\r\n\r\nhunter2\r\n--XyZ\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nline 1\r\nline 2\r\n--XyZ--\r\n";
+
+    fn data(src: &[u8], ranges: &dyn ToRangeSet<usize>) -> String {
+        ranges
+            .to_range_set()
+            .iter_ranges()
+            .map(|range| std::str::from_utf8(&src[range]).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_urlencoded_parse() {
+        let src = b"account=12345&&pass%20word=hunter2&empty&a+b=";
+        let form = UrlEncodedForm::parse(src, 10).unwrap();
+
+        let names = form
+            .fields()
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["account", "pass word", "empty", "a b"]);
+
+        let field = form.get("pass word").unwrap();
+        assert_eq!(field.value_span, 10 + 27..10 + 34);
+        assert_eq!(field.without_value().len(), "pass%20word=".len());
+
+        let field = form.get("empty").unwrap();
+        assert!(field.value_span.is_empty());
+        assert_eq!(field.name_span, field.span);
+
+        assert_eq!(form.without_fields().len(), 4);
+    }
+
+    #[test]
+    fn test_urlencoded_parse_invalid() {
+        assert!(UrlEncodedForm::parse(b"a%2=1", 0).is_err());
+        assert!(UrlEncodedForm::parse(b"%ff=1", 0).is_err());
+    }
+
+    #[test]
+    fn test_multipart_parse() {
+        let form = MultipartForm::parse(MULTIPART, 0, "XyZ").unwrap();
+
+        assert_eq!(form.parts().len(), 3);
+
+        let part = form.get("account").unwrap();
+        assert_eq!(data(MULTIPART, &part.body), "12345");
+        assert!(part.filename.is_none());
+
+        let part = form.get("password").unwrap();
+        assert_eq!(data(MULTIPART, &part.body), "hunter2");
+
+        let part = form.get("file").unwrap();
+        assert_eq!(part.filename.as_deref(), Some("a.txt"));
+        assert_eq!(part.headers.len(), 2);
+        assert_eq!(data(MULTIPART, &part.headers[1].value_span), "text/plain");
+        assert_eq!(data(MULTIPART, &part.body), "line 1\r\nline 2");
+
+        let structure = data(MULTIPART, &form.without_parts());
+        assert!(structure.starts_with("preamble\r\n--XyZ\r\n"));
+        assert!(structure.ends_with("\r\n--XyZ--\r\n"));
+        assert!(!structure.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_multipart_parse_invalid() {
+        assert!(MultipartForm::parse(MULTIPART, 0, "abc").is_err());
+        assert!(MultipartForm::parse(&MULTIPART[..60], 0, "XyZ").is_err());
+    }
+}
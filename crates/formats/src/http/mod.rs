@@ -1,9 +1,22 @@
 //! Tooling for working with HTTP data.
 
+mod chunked;
 mod commit;
+mod encoding;
+mod form;
+mod partial;
+mod target;
 
 use bytes::Bytes;
+pub use chunked::{Chunk, ChunkedBody, ChunkedBodyError};
 pub use commit::{DefaultHttpCommitter, HttpCommit, HttpCommitError};
+pub use encoding::{decode_body, ContentEncoding, DecodeError};
+pub use form::{FormError, FormField, MultipartForm, MultipartPart, PartHeader, UrlEncodedForm};
+pub use partial::{
+    PartialBody, PartialHeader, PartialHttpTranscript, PartialRequest, PartialResponse, Redactable,
+};
+use spansy::Spanned;
+pub use target::TargetComponents;
 
 #[doc(hidden)]
 pub use spansy::http;
@@ -46,3 +59,22 @@ impl HttpTranscript {
         })
     }
 }
+
+/// Returns the first header with the given name, ignoring case.
+pub(crate) fn find_header<'a>(headers: &'a [Header], name: &str) -> Option<&'a Header> {
+    headers
+        .iter()
+        .find(|header| header.name.as_str().eq_ignore_ascii_case(name))
+}
+
+/// Returns whether the headers specify the chunked transfer coding.
+pub(crate) fn is_chunked(headers: &[Header]) -> bool {
+    find_header(headers, "transfer-encoding").is_some_and(|header| {
+        std::str::from_utf8(header.value.span().as_bytes()).is_ok_and(|value| {
+            value
+                .rsplit(',')
+                .next()
+                .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"))
+        })
+    })
+}
@@ -0,0 +1,539 @@
+//! Structured view of partially revealed HTTP transcripts.
+//!
+//! A verifier receives a [`PartialTranscript`] in which only the revealed
+//! ranges are authenticated. [`PartialHttpTranscript`] parses the HTTP
+//! messages from it, marking everything which is hidden by redacted ranges as
+//! [`Redactable::Redacted`], so that disclosed data can be consumed without
+//! writing parsers which tolerate holes.
+//!
+//! # Message boundaries
+//!
+//! The body of a message is delimited using the revealed `Content-Length` or
+//! `Transfer-Encoding: chunked` header. If neither is revealed, a request is
+//! assumed to have no body, and the body of a response is assumed to extend to
+//! the end of the received data.
+
+use serde::Serialize;
+use tlsn_core::transcript::{Idx, PartialTranscript};
+
+use crate::{
+    http::{ChunkedBody, MessageKind},
+    json::PartialJson,
+};
+
+/// A value which is either revealed or redacted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Redactable<T> {
+    /// The value is revealed.
+    Revealed(T),
+    /// The value is redacted, in part or entirely.
+    Redacted,
+}
+
+impl<T> Redactable<T> {
+    /// Returns the value if it is revealed.
+    pub fn revealed(&self) -> Option<&T> {
+        match self {
+            Redactable::Revealed(value) => Some(value),
+            Redactable::Redacted => None,
+        }
+    }
+
+    /// Returns whether the value is redacted.
+    pub fn is_redacted(&self) -> bool {
+        matches!(self, Redactable::Redacted)
+    }
+}
+
+/// A partially revealed header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartialHeader {
+    /// The name of the header.
+    pub name: Redactable<String>,
+    /// The value of the header.
+    pub value: Redactable<String>,
+}
+
+/// A partially revealed body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartialBody {
+    /// A JSON body.
+    Json(PartialJson),
+    /// A body of any other content type, as a sequence of revealed and
+    /// redacted segments.
+    Raw(Vec<Redactable<Vec<u8>>>),
+}
+
+/// A partially revealed HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartialRequest {
+    /// The method of the request.
+    pub method: Redactable<String>,
+    /// The target of the request.
+    pub target: Redactable<String>,
+    /// The headers of the request. A redacted header may stand for any
+    /// number of headers.
+    pub headers: Vec<PartialHeader>,
+    /// The body of the request.
+    pub body: Option<PartialBody>,
+}
+
+/// A partially revealed HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartialResponse {
+    /// The status code of the response.
+    pub status: Redactable<u16>,
+    /// The reason phrase of the response.
+    pub reason: Redactable<String>,
+    /// The headers of the response. A redacted header may stand for any
+    /// number of headers.
+    pub headers: Vec<PartialHeader>,
+    /// The body of the response.
+    pub body: Option<PartialBody>,
+}
+
+impl PartialRequest {
+    /// Returns the first header with the given revealed name.
+    pub fn header(&self, name: &str) -> Option<&PartialHeader> {
+        find_header(&self.headers, name)
+    }
+}
+
+impl PartialResponse {
+    /// Returns the first header with the given revealed name.
+    pub fn header(&self, name: &str) -> Option<&PartialHeader> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A partially revealed HTTP transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartialHttpTranscript {
+    /// The requests sent to the server.
+    pub requests: Vec<PartialRequest>,
+    /// The responses received from the server.
+    pub responses: Vec<PartialResponse>,
+}
+
+impl PartialHttpTranscript {
+    /// Parses the HTTP messages from a verified partial transcript.
+    ///
+    /// # Arguments
+    ///
+    /// * `transcript` - The partial transcript, e.g. from a verified
+    ///   presentation.
+    pub fn parse(transcript: &PartialTranscript) -> Self {
+        let sent = Masked::new(transcript.sent_unsafe(), transcript.sent_authed());
+        let received = Masked::new(transcript.received_unsafe(), transcript.received_authed());
+
+        let requests = parse_messages(&sent, MessageKind::Request)
+            .into_iter()
+            .map(|message| {
+                let (method, target) = message.start_line.request();
+                PartialRequest {
+                    method,
+                    target,
+                    headers: message.headers,
+                    body: message.body,
+                }
+            })
+            .collect();
+
+        let responses = parse_messages(&received, MessageKind::Response)
+            .into_iter()
+            .map(|message| {
+                let (status, reason) = message.start_line.response();
+                PartialResponse {
+                    status,
+                    reason,
+                    headers: message.headers,
+                    body: message.body,
+                }
+            })
+            .collect();
+
+        Self {
+            requests,
+            responses,
+        }
+    }
+}
+
+/// Data along with whether each byte is authenticated.
+struct Masked<'a> {
+    data: &'a [u8],
+    authed: Vec<bool>,
+}
+
+impl<'a> Masked<'a> {
+    fn new(data: &'a [u8], idx: &Idx) -> Self {
+        let mut authed = vec![false; data.len()];
+        for range in idx.iter_ranges() {
+            authed[range].fill(true);
+        }
+        Self { data, authed }
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn is_authed(&self, start: usize, end: usize) -> bool {
+        self.authed[start..end].iter().all(|authed| *authed)
+    }
+
+    /// Returns the position of the next authenticated byte at or after `pos`
+    /// which satisfies the predicate, before `end`.
+    fn find(&self, pos: usize, end: usize, f: impl Fn(u8) -> bool) -> Option<usize> {
+        (pos..end).find(|i| self.authed[*i] && f(self.data[*i]))
+    }
+
+    /// Returns the position of the next authenticated CRLF at or after `pos`.
+    fn find_crlf(&self, pos: usize) -> Option<usize> {
+        (pos..self.len().saturating_sub(1))
+            .find(|i| self.data[*i..*i + 2] == *b"\r\n" && self.authed[*i] && self.authed[*i + 1])
+    }
+
+    /// Returns the end of the hole starting at `pos`.
+    fn hole_end(&self, pos: usize, end: usize) -> usize {
+        (pos..end).find(|i| self.authed[*i]).unwrap_or(end)
+    }
+
+    /// Returns the text in the range, trimmed of whitespace, if it is entirely
+    /// revealed.
+    fn text(&self, mut start: usize, mut end: usize) -> Redactable<String> {
+        while start < end && self.authed[start] && self.data[start].is_ascii_whitespace() {
+            start += 1;
+        }
+        while end > start && self.authed[end - 1] && self.data[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
+
+        if !self.is_authed(start, end) {
+            return Redactable::Redacted;
+        }
+
+        match std::str::from_utf8(&self.data[start..end]) {
+            Ok(text) => Redactable::Revealed(text.to_string()),
+            Err(_) => Redactable::Redacted,
+        }
+    }
+
+    /// Returns the bytes and mask of the given ranges.
+    fn extract(&self, ranges: &[std::ops::Range<usize>]) -> (Vec<u8>, Vec<bool>) {
+        ranges
+            .iter()
+            .flat_map(|range| range.clone())
+            .map(|i| (self.data[i], self.authed[i]))
+            .unzip()
+    }
+}
+
+/// The start line of a message, split into its parts.
+struct StartLine(Vec<Redactable<String>>);
+
+impl StartLine {
+    fn request(self) -> (Redactable<String>, Redactable<String>) {
+        let mut parts = self.0.into_iter();
+        (
+            parts.next().unwrap_or(Redactable::Redacted),
+            parts.next().unwrap_or(Redactable::Redacted),
+        )
+    }
+
+    fn response(self) -> (Redactable<u16>, Redactable<String>) {
+        let mut parts = self.0.into_iter().skip(1);
+        let status = match parts.next() {
+            Some(Redactable::Revealed(status)) => status
+                .parse()
+                .map(Redactable::Revealed)
+                .unwrap_or(Redactable::Redacted),
+            _ => Redactable::Redacted,
+        };
+        (status, parts.next().unwrap_or(Redactable::Redacted))
+    }
+}
+
+struct Message {
+    start_line: StartLine,
+    headers: Vec<PartialHeader>,
+    body: Option<PartialBody>,
+}
+
+fn parse_messages(src: &Masked<'_>, kind: MessageKind) -> Vec<Message> {
+    let mut messages = Vec::new();
+    let mut pos = 0;
+
+    while pos < src.len() {
+        let (message, end) = parse_message(src, pos, kind);
+        messages.push(message);
+        pos = end;
+    }
+
+    messages
+}
+
+fn parse_message(src: &Masked<'_>, start: usize, kind: MessageKind) -> (Message, usize) {
+    let mut headers = Vec::new();
+
+    // Start line.
+    let line_end = src.find_crlf(start).unwrap_or(src.len());
+    let mut pos = start;
+    let start_line = if src.authed[start] {
+        pos = (line_end + 2).min(src.len());
+        parse_start_line(src, start, line_end)
+    } else {
+        // The start line is redacted, along with its CRLF if the hole
+        // extends into the headers.
+        let hole_end = src.hole_end(start, line_end);
+        if hole_end == line_end {
+            pos = (line_end + 2).min(src.len());
+        } else {
+            headers.push(PartialHeader {
+                name: Redactable::Redacted,
+                value: Redactable::Redacted,
+            });
+            pos = hole_end;
+        }
+        StartLine(Vec::new())
+    };
+
+    // Headers.
+    let mut complete = false;
+    while pos < src.len() {
+        let Some(line_end) = src.find_crlf(pos) else {
+            parse_header_line(src, pos, src.len(), &mut headers);
+            pos = src.len();
+            break;
+        };
+
+        if line_end == pos {
+            pos += 2;
+            complete = true;
+            break;
+        }
+
+        parse_header_line(src, pos, line_end, &mut headers);
+        pos = line_end + 2;
+    }
+
+    if !complete {
+        return (
+            Message {
+                start_line,
+                headers,
+                body: None,
+            },
+            pos,
+        );
+    }
+
+    // Body.
+    let header = |name| find_header(&headers, name).and_then(|h| h.value.revealed());
+    let is_chunked = header("transfer-encoding")
+        .is_some_and(|value| value.to_ascii_lowercase().contains("chunked"));
+    let content_length = header("content-length").and_then(|value| value.parse::<usize>().ok());
+    let is_json = header("content-type").is_some_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case("application/json"))
+    }) && header("content-encoding").is_none();
+
+    let (ranges, end) = if is_chunked {
+        match parse_chunked(src, pos) {
+            Some((ranges, end)) => (ranges, end),
+            None => (vec![pos..src.len()], src.len()),
+        }
+    } else if let Some(content_length) = content_length {
+        let end = pos.saturating_add(content_length).min(src.len());
+        (vec![pos..end], end)
+    } else if kind == MessageKind::Request || pos == src.len() {
+        (Vec::new(), pos)
+    } else {
+        (vec![pos..src.len()], src.len())
+    };
+
+    let body = (!ranges.is_empty()).then(|| {
+        let (data, authed) = src.extract(&ranges);
+        if is_json {
+            PartialBody::Json(PartialJson::parse(&data, &authed))
+        } else {
+            PartialBody::Raw(segments(&data, &authed))
+        }
+    });
+
+    (
+        Message {
+            start_line,
+            headers,
+            body,
+        },
+        end,
+    )
+}
+
+fn parse_start_line(src: &Masked<'_>, start: usize, end: usize) -> StartLine {
+    let mut parts = Vec::new();
+    let mut pos = start;
+    // The last part, i.e. the reason phrase, may contain spaces.
+    while parts.len() < 2 {
+        let Some(space) = src.find(pos, end, |byte| byte == b' ') else {
+            break;
+        };
+        parts.push(src.text(pos, space));
+        pos = space + 1;
+    }
+    parts.push(src.text(pos, end));
+
+    StartLine(parts)
+}
+
+fn parse_header_line(src: &Masked<'_>, start: usize, end: usize, headers: &mut Vec<PartialHeader>) {
+    let mut start = start;
+
+    // A hole at the start of a line contains redacted headers, possibly
+    // including their CRLFs.
+    if !src.authed[start] {
+        headers.push(PartialHeader {
+            name: Redactable::Redacted,
+            value: Redactable::Redacted,
+        });
+        start = src.hole_end(start, end);
+        if start == end {
+            return;
+        }
+    }
+
+    match src.find(start, end, |byte| byte == b':') {
+        Some(colon) => headers.push(PartialHeader {
+            name: src.text(start, colon),
+            value: src.text(colon + 1, end),
+        }),
+        None if headers.last().is_some_and(|h| h.name.is_redacted()) => {}
+        None => headers.push(PartialHeader {
+            name: Redactable::Redacted,
+            value: Redactable::Redacted,
+        }),
+    }
+}
+
+/// Parses a chunked body, returning the ranges of the payload and the end of
+/// the body if the framing is revealed.
+fn parse_chunked(src: &Masked<'_>, pos: usize) -> Option<(Vec<std::ops::Range<usize>>, usize)> {
+    let body = ChunkedBody::parse(&src.data[pos..], pos).ok()?;
+
+    let framing_authed = body
+        .chunks()
+        .iter()
+        .all(|chunk| src.is_authed(chunk.size_line.start, chunk.size_line.end))
+        && src.is_authed(
+            body.chunks().last().map_or(pos, |chunk| chunk.data.end),
+            body.span().end,
+        );
+    if !framing_authed {
+        return None;
+    }
+
+    let ranges = body
+        .chunks()
+        .iter()
+        .map(|chunk| chunk.data.clone())
+        .collect();
+    Some((ranges, body.span().end))
+}
+
+/// Splits data into revealed and redacted segments.
+fn segments(data: &[u8], authed: &[bool]) -> Vec<Redactable<Vec<u8>>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let end = (start..data.len())
+            .find(|i| authed[*i] != authed[start])
+            .unwrap_or(data.len());
+        segments.push(if authed[start] {
+            Redactable::Revealed(data[start..end].to_vec())
+        } else {
+            Redactable::Redacted
+        });
+        start = end;
+    }
+    segments
+}
+
+fn find_header<'a>(headers: &'a [PartialHeader], name: &str) -> Option<&'a PartialHeader> {
+    headers.iter().find(|header| {
+        header
+            .name
+            .revealed()
+            .is_some_and(|n| n.eq_ignore_ascii_case(name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tlsn_core::transcript::Transcript;
+    use tlsn_data_fixtures::http as fixtures;
+    use utils::range::{ToRangeSet, Union};
+
+    use crate::http::{BodyContent, HttpTranscript};
+
+    #[test]
+    fn test_partial_http_transcript() {
+        let transcript = Transcript::new(fixtures::request::POST_JSON, fixtures::response::OK_JSON);
+        let http = HttpTranscript::parse(&transcript).unwrap();
+        let response = &http.responses[0];
+
+        let mut recv = response.without_data().to_range_set();
+        for header in &response.headers {
+            if header.name.as_str().eq_ignore_ascii_case("cookie") {
+                recv = recv.union(&header.without_value().to_range_set());
+            } else {
+                recv = recv.union(&header.to_range_set());
+            }
+        }
+        let BodyContent::Json(body) = &response.body.as_ref().unwrap().content else {
+            panic!("expected JSON body");
+        };
+        recv = recv.union(&body.without_pairs().to_range_set());
+        for kv in &body.elems {
+            if kv.key.as_str() == "foo" {
+                recv = recv.union(&kv.without_value().to_range_set());
+            } else {
+                recv = recv.union(&kv.to_range_set());
+            }
+        }
+
+        let partial = transcript.to_partial(Idx::new(0..transcript.sent().len()), Idx::new(recv));
+        let partial = PartialHttpTranscript::parse(&partial);
+
+        let request = &partial.requests[0];
+        assert_eq!(request.method.revealed().unwrap(), "POST");
+        assert_eq!(request.target.revealed().unwrap(), "/hello");
+
+        let response = &partial.responses[0];
+        assert_eq!(response.status, Redactable::Revealed(200));
+        assert!(response.header("cookie").unwrap().value.is_redacted());
+        assert_eq!(
+            response
+                .header("content-type")
+                .unwrap()
+                .value
+                .revealed()
+                .unwrap(),
+            "application/json"
+        );
+
+        let Some(PartialBody::Json(json)) = &response.body else {
+            panic!("expected JSON body");
+        };
+        assert!(json.get("foo").unwrap().is_redacted());
+        assert_eq!(
+            json.get("bazz"),
+            Some(&PartialJson::Number("123".to_string()))
+        );
+    }
+}
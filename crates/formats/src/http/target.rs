@@ -0,0 +1,209 @@
+//! Support for request targets.
+//!
+//! A request target is split into the segments of its path and the parameters
+//! of its query string, so that e.g. the path can be revealed while an access
+//! token in the query string remains hidden.
+
+use std::ops::Range;
+
+use spansy::Spanned;
+use utils::range::{Difference, RangeSet, ToRangeSet};
+
+use crate::http::{form::find, FormError, FormField, Target, UrlEncodedForm};
+
+/// The components of a request target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetComponents {
+    span: Range<usize>,
+    path: Range<usize>,
+    segments: Vec<Range<usize>>,
+    query: Option<Range<usize>>,
+    params: Vec<FormField>,
+}
+
+impl TargetComponents {
+    /// Splits a request target into its components.
+    pub fn from_target(target: &Target) -> Result<Self, FormError> {
+        let offset = target.to_range_set().min().unwrap_or_default();
+        Self::parse(target.span().as_bytes(), offset)
+    }
+
+    /// Splits a request target into its components.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The request target.
+    /// * `offset` - The offset of the target in the transcript, which is added
+    ///   to all ranges.
+    pub fn parse(src: &[u8], offset: usize) -> Result<Self, FormError> {
+        let path_start = if src.starts_with(b"/") {
+            Some(0)
+        } else {
+            // The path of an absolute-form target starts after the authority.
+            find(src, b"://", 0).map(|scheme_end| {
+                let authority = scheme_end + 3;
+                src[authority..]
+                    .iter()
+                    .position(|byte| matches!(byte, b'/' | b'?'))
+                    .map_or(src.len(), |idx| authority + idx)
+            })
+        };
+
+        // Authority-form and asterisk-form targets have no components.
+        let Some(path_start) = path_start else {
+            return Ok(Self {
+                span: offset..offset + src.len(),
+                path: offset + src.len()..offset + src.len(),
+                segments: Vec::new(),
+                query: None,
+                params: Vec::new(),
+            });
+        };
+
+        let path_end = src[path_start..]
+            .iter()
+            .position(|byte| matches!(byte, b'?' | b'#'))
+            .map_or(src.len(), |idx| path_start + idx);
+
+        let mut segments = Vec::new();
+        let mut pos = path_start;
+        for segment in src[path_start..path_end].split(|byte| *byte == b'/') {
+            if !segment.is_empty() {
+                segments.push(offset + pos..offset + pos + segment.len());
+            }
+            pos += segment.len() + 1;
+        }
+
+        let (query, params) = if src.get(path_end) == Some(&b'?') {
+            let query_end = src[path_end..]
+                .iter()
+                .position(|byte| *byte == b'#')
+                .map_or(src.len(), |idx| path_end + idx);
+            let query = &src[path_end + 1..query_end];
+            let form = UrlEncodedForm::parse(query, offset + path_end + 1)?;
+
+            (
+                Some(offset + path_end + 1..offset + query_end),
+                form.fields().to_vec(),
+            )
+        } else {
+            (None, Vec::new())
+        };
+
+        Ok(Self {
+            span: offset..offset + src.len(),
+            path: offset + path_start..offset + path_end,
+            segments,
+            query,
+            params,
+        })
+    }
+
+    /// Returns the range of the entire target.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Returns the range of the path.
+    pub fn path(&self) -> Range<usize> {
+        self.path.clone()
+    }
+
+    /// Returns the ranges of the non-empty path segments.
+    pub fn segments(&self) -> &[Range<usize>] {
+        &self.segments
+    }
+
+    /// Returns the range of the query string, excluding the `?`.
+    pub fn query(&self) -> Option<Range<usize>> {
+        self.query.clone()
+    }
+
+    /// Returns the parameters of the query string.
+    pub fn params(&self) -> &[FormField] {
+        &self.params
+    }
+
+    /// Returns the first query parameter with the given name.
+    pub fn param(&self, name: &str) -> Option<&FormField> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Returns the ranges of the target excluding the query parameters.
+    pub fn without_params(&self) -> RangeSet<usize> {
+        self.params
+            .iter()
+            .fold(RangeSet::from(self.span.clone()), |ranges, param| {
+                ranges.difference(&param.span)
+            })
+    }
+
+    /// Returns the ranges of the target excluding the path segments and query
+    /// parameters, e.g. the `/` and `?` separators.
+    pub fn without_components(&self) -> RangeSet<usize> {
+        self.segments
+            .iter()
+            .fold(self.without_params(), |ranges, segment| {
+                ranges.difference(segment)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(src: &[u8], ranges: &dyn ToRangeSet<usize>) -> String {
+        ranges
+            .to_range_set()
+            .iter_ranges()
+            .map(|range| std::str::from_utf8(&src[range]).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_target_parse() {
+        let src = b"/v1/accounts/123?access_token=secret&page=2";
+        let target = TargetComponents::parse(src, 0).unwrap();
+
+        assert_eq!(data(src, &target.path()), "/v1/accounts/123");
+        let segments = target
+            .segments()
+            .iter()
+            .map(|segment| data(src, segment))
+            .collect::<Vec<_>>();
+        assert_eq!(segments, ["v1", "accounts", "123"]);
+
+        assert_eq!(
+            data(src, &target.query().unwrap()),
+            "access_token=secret&page=2"
+        );
+        let param = target.param("access_token").unwrap();
+        assert_eq!(data(src, &param.value_span), "secret");
+
+        assert_eq!(data(src, &target.without_params()), "/v1/accounts/123?&");
+        assert_eq!(data(src, &target.without_components()), "///?&");
+    }
+
+    #[test]
+    fn test_target_parse_absolute_form() {
+        let src = b"http://example.com/a//b?x=1";
+        let target = TargetComponents::parse(src, 5).unwrap();
+
+        assert_eq!(target.path(), 5 + 18..5 + 23);
+        assert_eq!(target.segments().len(), 2);
+        assert_eq!(target.params().len(), 1);
+    }
+
+    #[test]
+    fn test_target_parse_without_path() {
+        let target = TargetComponents::parse(b"*", 0).unwrap();
+        assert!(target.path().is_empty());
+        assert!(target.segments().is_empty());
+        assert!(target.query().is_none());
+
+        let target = TargetComponents::parse(b"http://example.com?x=1", 0).unwrap();
+        assert!(target.path().is_empty());
+        assert_eq!(target.params().len(), 1);
+    }
+}
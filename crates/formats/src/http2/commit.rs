@@ -0,0 +1,355 @@
+use std::error::Error;
+
+use tlsn_core::transcript::{Direction, TranscriptCommitConfigBuilder};
+use utils::range::RangeSet;
+
+use crate::{
+    http::MessageKind,
+    http2::{HeaderField, Http2Message, Http2Transcript},
+};
+
+/// HTTP/2 commitment error.
+#[derive(Debug, thiserror::Error)]
+#[error("http2 commit error: {msg}")]
+pub struct Http2CommitError {
+    stream_id: Option<u32>,
+    record_kind: MessageKind,
+    msg: String,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl Http2CommitError {
+    /// Creates a new HTTP/2 commitment error.
+    ///
+    /// # Arguments
+    ///
+    /// * `record_kind` - The kind of the record (request or response).
+    /// * `msg` - The error message.
+    pub fn new(record_kind: MessageKind, msg: impl Into<String>) -> Self {
+        Self {
+            stream_id: None,
+            record_kind,
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    /// Creates a new HTTP/2 commitment error with a source.
+    ///
+    /// # Arguments
+    ///
+    /// * `record_kind` - The kind of the record (request or response).
+    /// * `msg` - The error message.
+    /// * `source` - The source error.
+    pub fn new_with_source<E>(record_kind: MessageKind, msg: impl Into<String>, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self {
+            stream_id: None,
+            record_kind,
+            msg: msg.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Sets the id of the stream of the request or response.
+    pub fn set_stream_id(&mut self, stream_id: u32) {
+        self.stream_id = Some(stream_id);
+    }
+
+    /// Returns the id of the stream of the request or response, if set.
+    pub fn stream_id(&self) -> Option<u32> {
+        self.stream_id
+    }
+
+    /// Returns the error message.
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    /// Returns the kind of record (request or response).
+    pub fn record_kind(&self) -> &MessageKind {
+        &self.record_kind
+    }
+}
+
+/// An HTTP/2 data committer.
+#[allow(unused_variables)]
+pub trait Http2Commit {
+    /// Commits to an HTTP/2 transcript.
+    ///
+    /// The default implementation commits to each request and response in the
+    /// transcript separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `transcript` - The transcript to commit.
+    fn commit_transcript(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        transcript: &Http2Transcript,
+    ) -> Result<(), Http2CommitError> {
+        for request in &transcript.requests {
+            self.commit_request(builder, Direction::Sent, request)
+                .map_err(|mut e| {
+                    e.set_stream_id(request.stream_id);
+                    e
+                })?;
+        }
+
+        for response in &transcript.responses {
+            self.commit_response(builder, Direction::Received, response)
+                .map_err(|mut e| {
+                    e.set_stream_id(response.stream_id);
+                    e
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Commits to a request.
+    ///
+    /// The default implementation commits to all frames of the request, and
+    /// the frames excluding the header fields and data. Additionally, it
+    /// commits to the header fields and data separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the request (sent or received).
+    /// * `request` - The request to commit to.
+    fn commit_request(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        request: &Http2Message,
+    ) -> Result<(), Http2CommitError> {
+        commit_message(builder, direction, MessageKind::Request, request)?;
+
+        for header in request.headers.iter().chain(&request.trailers) {
+            self.commit_request_header(builder, direction, request, header)?;
+        }
+
+        if !request.data.is_empty() {
+            self.commit_request_data(builder, direction, request, &request.data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Commits to a request header field.
+    ///
+    /// The default implementation commits to the entire field, and the field
+    /// excluding the literal value.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the request (sent or received).
+    /// * `parent` - The parent request.
+    /// * `header` - The header field to commit to.
+    fn commit_request_header(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        parent: &Http2Message,
+        header: &HeaderField,
+    ) -> Result<(), Http2CommitError> {
+        commit_header(builder, direction, MessageKind::Request, header)
+    }
+
+    /// Commits to the data of a request.
+    ///
+    /// The default implementation commits to the data as a whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the request (sent or received).
+    /// * `parent` - The parent request.
+    /// * `data` - The ranges of the data to commit to.
+    fn commit_request_data(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        parent: &Http2Message,
+        data: &RangeSet<usize>,
+    ) -> Result<(), Http2CommitError> {
+        builder.commit(data, direction).map_err(|e| {
+            Http2CommitError::new_with_source(
+                MessageKind::Request,
+                "failed to commit to request data",
+                e,
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// Commits to a response.
+    ///
+    /// The default implementation commits to all frames of the response, and
+    /// the frames excluding the header fields and data. Additionally, it
+    /// commits to the header fields, including those of informational
+    /// responses, and data separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the response (sent or received).
+    /// * `response` - The response to commit to.
+    fn commit_response(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        response: &Http2Message,
+    ) -> Result<(), Http2CommitError> {
+        commit_message(builder, direction, MessageKind::Response, response)?;
+
+        for header in response
+            .informational
+            .iter()
+            .flatten()
+            .chain(&response.headers)
+            .chain(&response.trailers)
+        {
+            self.commit_response_header(builder, direction, response, header)?;
+        }
+
+        if !response.data.is_empty() {
+            self.commit_response_data(builder, direction, response, &response.data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Commits to a response header field.
+    ///
+    /// The default implementation commits to the entire field, and the field
+    /// excluding the literal value.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the response (sent or received).
+    /// * `parent` - The parent response.
+    /// * `header` - The header field to commit to.
+    fn commit_response_header(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        parent: &Http2Message,
+        header: &HeaderField,
+    ) -> Result<(), Http2CommitError> {
+        commit_header(builder, direction, MessageKind::Response, header)
+    }
+
+    /// Commits to the data of a response.
+    ///
+    /// The default implementation commits to the data as a whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the response (sent or received).
+    /// * `parent` - The parent response.
+    /// * `data` - The ranges of the data to commit to.
+    fn commit_response_data(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        parent: &Http2Message,
+        data: &RangeSet<usize>,
+    ) -> Result<(), Http2CommitError> {
+        builder.commit(data, direction).map_err(|e| {
+            Http2CommitError::new_with_source(
+                MessageKind::Response,
+                "failed to commit to response data",
+                e,
+            )
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Commits to all frames of a message, and the frames excluding the header
+/// fields and data.
+fn commit_message(
+    builder: &mut TranscriptCommitConfigBuilder,
+    direction: Direction,
+    kind: MessageKind,
+    message: &Http2Message,
+) -> Result<(), Http2CommitError> {
+    builder.commit(&message.span, direction).map_err(|e| {
+        Http2CommitError::new_with_source(kind, "failed to commit to entire message", e)
+    })?;
+
+    let without_data = message.without_data();
+    if !without_data.is_empty() {
+        builder.commit(&without_data, direction).map_err(|e| {
+            Http2CommitError::new_with_source(kind, "failed to commit to message excluding data", e)
+        })?;
+    }
+
+    Ok(())
+}
+
+fn commit_header(
+    builder: &mut TranscriptCommitConfigBuilder,
+    direction: Direction,
+    kind: MessageKind,
+    header: &HeaderField,
+) -> Result<(), Http2CommitError> {
+    builder.commit(&header.span, direction).map_err(|e| {
+        Http2CommitError::new_with_source(
+            kind,
+            format!("failed to commit to \"{}\" header", header.name),
+            e,
+        )
+    })?;
+
+    let without_value = header.without_value();
+    if header.value_span.is_some() && !without_value.is_empty() {
+        builder.commit(&without_value, direction).map_err(|e| {
+            Http2CommitError::new_with_source(
+                kind,
+                format!(
+                    "failed to commit to \"{}\" header excluding value",
+                    header.name
+                ),
+                e,
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The default HTTP/2 committer.
+#[derive(Debug, Default, Clone)]
+pub struct DefaultHttp2Committer {}
+
+impl Http2Commit for DefaultHttp2Committer {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http2::tests::transcript;
+
+    #[test]
+    fn test_http2_default_commit_transcript() {
+        let transcript = transcript();
+        let http2 = Http2Transcript::parse(&transcript).unwrap();
+        let mut committer = DefaultHttp2Committer::default();
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        committer.commit_transcript(&mut builder, &http2).unwrap();
+
+        builder.build().unwrap();
+    }
+}
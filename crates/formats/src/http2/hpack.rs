@@ -0,0 +1,327 @@
+//! HPACK header block decoding.
+//!
+//! In addition to the decoded header fields, the decoder returns the range of
+//! each field representation within the header block, so that fields can be
+//! mapped back onto the transcript.
+
+use std::{collections::VecDeque, ops::Range};
+
+use hpack::huffman::HuffmanDecoder;
+
+use crate::http2::Http2ParseError;
+
+/// The initial size of the dynamic table.
+const DEFAULT_TABLE_SIZE: usize = 4096;
+
+/// The overhead of each entry in the dynamic table.
+const ENTRY_OVERHEAD: usize = 32;
+
+/// The static table, see RFC 7541 Appendix A.
+const STATIC_TABLE: [(&str, &str); 61] = [
+    (":authority", ""),
+    (":method", "GET"),
+    (":method", "POST"),
+    (":path", "/"),
+    (":path", "/index.html"),
+    (":scheme", "http"),
+    (":scheme", "https"),
+    (":status", "200"),
+    (":status", "204"),
+    (":status", "206"),
+    (":status", "304"),
+    (":status", "400"),
+    (":status", "404"),
+    (":status", "500"),
+    ("accept-charset", ""),
+    ("accept-encoding", "gzip, deflate"),
+    ("accept-language", ""),
+    ("accept-ranges", ""),
+    ("accept", ""),
+    ("access-control-allow-origin", ""),
+    ("age", ""),
+    ("allow", ""),
+    ("authorization", ""),
+    ("cache-control", ""),
+    ("content-disposition", ""),
+    ("content-encoding", ""),
+    ("content-language", ""),
+    ("content-length", ""),
+    ("content-location", ""),
+    ("content-range", ""),
+    ("content-type", ""),
+    ("cookie", ""),
+    ("date", ""),
+    ("etag", ""),
+    ("expect", ""),
+    ("expires", ""),
+    ("from", ""),
+    ("host", ""),
+    ("if-match", ""),
+    ("if-modified-since", ""),
+    ("if-none-match", ""),
+    ("if-range", ""),
+    ("if-unmodified-since", ""),
+    ("last-modified", ""),
+    ("link", ""),
+    ("location", ""),
+    ("max-forwards", ""),
+    ("proxy-authenticate", ""),
+    ("proxy-authorization", ""),
+    ("range", ""),
+    ("referer", ""),
+    ("refresh", ""),
+    ("retry-after", ""),
+    ("server", ""),
+    ("set-cookie", ""),
+    ("strict-transport-security", ""),
+    ("transfer-encoding", ""),
+    ("user-agent", ""),
+    ("vary", ""),
+    ("via", ""),
+    ("www-authenticate", ""),
+];
+
+/// A header field decoded from a header block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DecodedField {
+    pub(crate) name: Vec<u8>,
+    pub(crate) value: Vec<u8>,
+    /// The range of the field representation in the header block.
+    pub(crate) range: Range<usize>,
+    /// The range of the literal value in the header block, including its
+    /// length prefix.
+    pub(crate) value_range: Option<Range<usize>>,
+    /// Whether the representation refers to an entry of the header table.
+    pub(crate) indexed: bool,
+}
+
+/// An HPACK decoder.
+///
+/// A separate decoder must be used for each direction of a connection, as
+/// each direction has its own dynamic table.
+#[derive(Debug)]
+pub(crate) struct Decoder {
+    table: VecDeque<(Vec<u8>, Vec<u8>)>,
+    size: usize,
+    max_size: usize,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self {
+            table: VecDeque::new(),
+            size: 0,
+            max_size: DEFAULT_TABLE_SIZE,
+        }
+    }
+}
+
+impl Decoder {
+    /// Decodes a header block.
+    pub(crate) fn decode(&mut self, block: &[u8]) -> Result<Vec<DecodedField>, Http2ParseError> {
+        let mut fields = Vec::new();
+        let mut pos = 0;
+
+        while pos < block.len() {
+            let start = pos;
+            let byte = block[pos];
+
+            if byte & 0x80 != 0 {
+                // Indexed header field.
+                let index = decode_int(block, &mut pos, 7)?;
+                let (name, value) = self.get(index)?;
+                fields.push(DecodedField {
+                    name,
+                    value,
+                    range: start..pos,
+                    value_range: None,
+                    indexed: true,
+                });
+            } else if byte & 0xe0 == 0x20 {
+                // Dynamic table size update.
+                let max_size = decode_int(block, &mut pos, 5)?;
+                self.max_size = max_size;
+                self.evict(0);
+            } else {
+                // Literal header field, with incremental indexing if the prefix
+                // is `01`, otherwise without indexing or never indexed.
+                let incremental = byte & 0xc0 == 0x40;
+                let prefix = if incremental { 6 } else { 4 };
+
+                let name_index = decode_int(block, &mut pos, prefix)?;
+                let name = if name_index == 0 {
+                    decode_string(block, &mut pos)?
+                } else {
+                    self.get(name_index)?.0
+                };
+
+                let value_start = pos;
+                let value = decode_string(block, &mut pos)?;
+
+                if incremental {
+                    self.insert(name.clone(), value.clone());
+                }
+
+                fields.push(DecodedField {
+                    name,
+                    value,
+                    range: start..pos,
+                    value_range: Some(value_start..pos),
+                    indexed: name_index != 0,
+                });
+            }
+        }
+
+        Ok(fields)
+    }
+
+    /// Returns the entry of the header table at the given index.
+    fn get(&self, index: usize) -> Result<(Vec<u8>, Vec<u8>), Http2ParseError> {
+        match index {
+            0 => Err(Http2ParseError::new("invalid header table index: 0")),
+            1..=61 => {
+                let (name, value) = STATIC_TABLE[index - 1];
+                Ok((name.as_bytes().to_vec(), value.as_bytes().to_vec()))
+            }
+            _ => self.table.get(index - 62).cloned().ok_or_else(|| {
+                Http2ParseError::new(format!("invalid header table index: {index}"))
+            }),
+        }
+    }
+
+    /// Inserts an entry into the dynamic table.
+    fn insert(&mut self, name: Vec<u8>, value: Vec<u8>) {
+        let size = name.len() + value.len() + ENTRY_OVERHEAD;
+        self.evict(size);
+
+        // An entry which is larger than the table empties the table.
+        if size <= self.max_size {
+            self.size += size;
+            self.table.push_front((name, value));
+        }
+    }
+
+    /// Evicts entries until an entry of the given size fits into the table.
+    fn evict(&mut self, size: usize) {
+        while self.size + size > self.max_size {
+            let Some((name, value)) = self.table.pop_back() else {
+                break;
+            };
+            self.size -= name.len() + value.len() + ENTRY_OVERHEAD;
+        }
+    }
+}
+
+/// Decodes an integer with the given prefix length, see RFC 7541 Section 5.1.
+fn decode_int(block: &[u8], pos: &mut usize, prefix: u8) -> Result<usize, Http2ParseError> {
+    let mask = (1u8 << prefix) - 1;
+    let mut value = (next_byte(block, pos)? & mask) as usize;
+    if value < mask as usize {
+        return Ok(value);
+    }
+
+    let mut shift = 0;
+    loop {
+        let byte = next_byte(block, pos)?;
+        value = ((byte & 0x7f) as usize)
+            .checked_shl(shift)
+            .and_then(|byte| value.checked_add(byte))
+            .filter(|_| shift < 28)
+            .ok_or_else(|| Http2ParseError::new("integer overflow in header block"))?;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+/// Decodes a string literal, see RFC 7541 Section 5.2.
+fn decode_string(block: &[u8], pos: &mut usize) -> Result<Vec<u8>, Http2ParseError> {
+    let huffman = block
+        .get(*pos)
+        .map(|byte| byte & 0x80 != 0)
+        .ok_or_else(|| Http2ParseError::new("header block is incomplete"))?;
+    let len = decode_int(block, pos, 7)?;
+
+    let data = pos
+        .checked_add(len)
+        .and_then(|end| block.get(*pos..end))
+        .ok_or_else(|| Http2ParseError::new("header block is incomplete"))?;
+    *pos += len;
+
+    if huffman {
+        HuffmanDecoder::new()
+            .decode(data)
+            .map_err(|e| Http2ParseError::new(format!("invalid huffman string: {e:?}")))
+    } else {
+        Ok(data.to_vec())
+    }
+}
+
+fn next_byte(block: &[u8], pos: &mut usize) -> Result<u8, Http2ParseError> {
+    let byte = *block
+        .get(*pos)
+        .ok_or_else(|| Http2ParseError::new("header block is incomplete"))?;
+    *pos += 1;
+    Ok(byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(fields: &[DecodedField]) -> Vec<(&[u8], &[u8])> {
+        fields
+            .iter()
+            .map(|field| (field.name.as_slice(), field.value.as_slice()))
+            .collect()
+    }
+
+    // RFC 7541 Appendix C.3.
+    #[test]
+    fn test_decode_without_huffman() {
+        let mut decoder = Decoder::default();
+
+        let block = b"\x82\x86\x84\x41\x0fwww.example.com";
+        let decoded = decoder.decode(block).unwrap();
+        assert_eq!(
+            fields(&decoded),
+            [
+                (&b":method"[..], &b"GET"[..]),
+                (b":scheme", b"http"),
+                (b":path", b"/"),
+                (b":authority", b"www.example.com"),
+            ]
+        );
+        assert_eq!(decoded[3].range, 3..block.len());
+        assert_eq!(decoded[3].value_range, Some(4..block.len()));
+
+        // The authority is now in the dynamic table.
+        let block = b"\x82\x86\x84\xbe\x58\x08no-cache";
+        let decoded = decoder.decode(block).unwrap();
+        assert_eq!(decoded[3].value, b"www.example.com");
+        assert!(decoded[3].indexed);
+        assert_eq!(
+            (decoded[4].name.as_slice(), decoded[4].value.as_slice()),
+            (&b"cache-control"[..], &b"no-cache"[..])
+        );
+    }
+
+    // RFC 7541 Appendix C.4.1.
+    #[test]
+    fn test_decode_with_huffman() {
+        let mut decoder = Decoder::default();
+
+        let block = b"\x82\x86\x84\x41\x8c\xf1\xe3\xc2\xe5\xf2\x3a\x6b\xa0\xab\x90\xf4\xff";
+        let decoded = decoder.decode(block).unwrap();
+        assert_eq!(decoded[3].value, b"www.example.com");
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(Decoder::default().decode(b"\x80").is_err());
+        assert!(Decoder::default().decode(b"\xbe").is_err());
+        assert!(Decoder::default().decode(b"\x41\x0fwww").is_err());
+    }
+}
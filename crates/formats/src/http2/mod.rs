@@ -0,0 +1,530 @@
+//! Tooling for working with HTTP/2 data.
+//!
+//! An HTTP/2 transcript is parsed by decoding the frames of each direction of
+//! the connection and the HPACK header blocks of each stream. Header fields
+//! and the payload of DATA frames are mapped back onto the transcript so that
+//! they can be selectively disclosed.
+//!
+//! # HPACK
+//!
+//! Header fields are committed to as their encoded representation. A field
+//! whose representation refers to an entry of the header table, see
+//! [`HeaderField::indexed`], can only be interpreted by a verifier with
+//! knowledge of the table, which may require disclosing earlier header blocks.
+//! Fields which are encoded as literals without Huffman coding can be
+//! interpreted on their own.
+
+mod commit;
+mod hpack;
+
+use std::{collections::HashMap, ops::Range};
+
+pub use commit::{DefaultHttp2Committer, Http2Commit, Http2CommitError};
+use tlsn_core::transcript::Transcript;
+use utils::range::{Difference, RangeSet, Union};
+
+/// The connection preface sent by the client.
+pub const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// The length of a frame header.
+const FRAME_HEADER_LEN: usize = 9;
+
+const FRAME_DATA: u8 = 0x0;
+const FRAME_HEADERS: u8 = 0x1;
+const FRAME_PUSH_PROMISE: u8 = 0x5;
+const FRAME_CONTINUATION: u8 = 0x9;
+
+const FLAG_END_HEADERS: u8 = 0x4;
+const FLAG_PADDED: u8 = 0x8;
+const FLAG_PRIORITY: u8 = 0x20;
+
+/// A header field of an HTTP/2 message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderField {
+    /// The name of the header field.
+    pub name: String,
+    /// The value of the header field.
+    pub value: Vec<u8>,
+    /// The ranges of the encoded representation of the field.
+    pub span: RangeSet<usize>,
+    /// The ranges of the literal value of the field, if it is encoded as a
+    /// literal.
+    pub value_span: Option<RangeSet<usize>>,
+    /// Whether the representation refers to an entry of the HPACK header
+    /// table.
+    pub indexed: bool,
+}
+
+impl HeaderField {
+    /// Returns the ranges of the field excluding the literal value.
+    pub fn without_value(&self) -> RangeSet<usize> {
+        match &self.value_span {
+            Some(value_span) => self.span.difference(value_span),
+            None => self.span.clone(),
+        }
+    }
+}
+
+/// An HTTP/2 message, which is either a request or a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Http2Message {
+    /// The id of the stream of the message.
+    pub stream_id: u32,
+    /// The header fields, including pseudo-header fields.
+    ///
+    /// For a response, these are the fields of the final response.
+    pub headers: Vec<HeaderField>,
+    /// The header fields of each informational (1xx) response which preceded
+    /// the final response, eg. `100 Continue`.
+    pub informational: Vec<Vec<HeaderField>>,
+    /// The trailer fields.
+    pub trailers: Vec<HeaderField>,
+    /// The ranges of the payload of the DATA frames, excluding padding.
+    pub data: RangeSet<usize>,
+    /// The ranges of all frames of the stream.
+    pub span: RangeSet<usize>,
+}
+
+impl Http2Message {
+    fn new(stream_id: u32) -> Self {
+        Self {
+            stream_id,
+            headers: Vec::new(),
+            informational: Vec::new(),
+            trailers: Vec::new(),
+            data: RangeSet::default(),
+            span: RangeSet::default(),
+        }
+    }
+
+    /// Returns the first header field with the given name.
+    pub fn header(&self, name: &str) -> Option<&HeaderField> {
+        self.headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns whether the header fields are those of an informational (1xx)
+    /// response.
+    fn is_informational(&self) -> bool {
+        self.header(":status")
+            .is_some_and(|status| status.value.len() == 3 && status.value[0] == b'1')
+    }
+
+    /// Returns the ranges of the message excluding the header fields, trailer
+    /// fields and data.
+    ///
+    /// This consists of the frame headers and padding.
+    pub fn without_data(&self) -> RangeSet<usize> {
+        self.headers
+            .iter()
+            .chain(self.informational.iter().flatten())
+            .chain(&self.trailers)
+            .fold(self.span.difference(&self.data), |ranges, field| {
+                ranges.difference(&field.span)
+            })
+    }
+}
+
+/// An HTTP/2 transcript.
+#[derive(Debug)]
+pub struct Http2Transcript {
+    /// The requests sent to the server.
+    pub requests: Vec<Http2Message>,
+    /// The responses received from the server.
+    pub responses: Vec<Http2Message>,
+}
+
+impl Http2Transcript {
+    /// Parses the HTTP/2 transcript from the provided transcript.
+    ///
+    /// The sent data must start with the connection preface.
+    pub fn parse(transcript: &Transcript) -> Result<Self, Http2ParseError> {
+        let sent = transcript.sent();
+        if !sent.starts_with(PREFACE) {
+            return Err(Http2ParseError::new(
+                "sent data does not start with the connection preface",
+            ));
+        }
+
+        Ok(Self {
+            requests: parse_messages(sent, PREFACE.len())?,
+            responses: parse_messages(transcript.received(), 0)?,
+        })
+    }
+}
+
+/// A frame of an HTTP/2 connection.
+struct Frame {
+    kind: u8,
+    flags: u8,
+    stream_id: u32,
+    /// The range of the entire frame.
+    span: Range<usize>,
+    /// The range of the frame payload.
+    payload: Range<usize>,
+}
+
+/// A header block which may span multiple frames.
+struct HeaderBlock {
+    stream_id: u32,
+    /// Whether the block belongs to a PUSH_PROMISE frame.
+    promise: bool,
+    fragments: Vec<Range<usize>>,
+}
+
+/// Parses the messages from one direction of a connection.
+fn parse_messages(src: &[u8], start: usize) -> Result<Vec<Http2Message>, Http2ParseError> {
+    let mut decoder = hpack::Decoder::default();
+    let mut messages: Vec<Http2Message> = Vec::new();
+    let mut streams = HashMap::new();
+    let mut block: Option<HeaderBlock> = None;
+
+    let mut pos = start;
+    while pos < src.len() {
+        let frame = parse_frame(src, pos)?;
+        pos = frame.span.end;
+
+        // Connection-level frames do not belong to any message.
+        if frame.stream_id == 0 {
+            if block.is_some() {
+                return Err(Http2ParseError::new("header block was interrupted"));
+            }
+            continue;
+        }
+
+        if let Some(pending) = &mut block {
+            if frame.kind != FRAME_CONTINUATION || pending.stream_id != frame.stream_id {
+                return Err(Http2ParseError::new("header block was interrupted"));
+            }
+            pending.fragments.push(frame.payload.clone());
+        } else {
+            match frame.kind {
+                FRAME_CONTINUATION => {
+                    return Err(Http2ParseError::new("unexpected CONTINUATION frame"))
+                }
+                FRAME_HEADERS => {
+                    let mut payload = unpad(src, &frame)?;
+                    if frame.flags & FLAG_PRIORITY != 0 {
+                        payload.start += 5;
+                        if payload.start > payload.end {
+                            return Err(Http2ParseError::new("HEADERS frame is too short"));
+                        }
+                    }
+                    block = Some(HeaderBlock {
+                        stream_id: frame.stream_id,
+                        promise: false,
+                        fragments: vec![payload],
+                    });
+                }
+                FRAME_PUSH_PROMISE => {
+                    let mut payload = unpad(src, &frame)?;
+                    payload.start += 4;
+                    if payload.start > payload.end {
+                        return Err(Http2ParseError::new("PUSH_PROMISE frame is too short"));
+                    }
+                    block = Some(HeaderBlock {
+                        stream_id: frame.stream_id,
+                        promise: true,
+                        fragments: vec![payload],
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Promised requests are not part of the transcript, but their header
+        // blocks still have to be decoded to keep the header table in sync.
+        if frame.kind != FRAME_PUSH_PROMISE && !matches!(&block, Some(pending) if pending.promise) {
+            let idx = *streams.entry(frame.stream_id).or_insert_with(|| {
+                messages.push(Http2Message::new(frame.stream_id));
+                messages.len() - 1
+            });
+            let message = &mut messages[idx];
+            message.span = message.span.union(&RangeSet::from(frame.span.clone()));
+
+            if frame.kind == FRAME_DATA {
+                let payload = unpad(src, &frame)?;
+                message.data = message.data.union(&RangeSet::from(payload));
+            }
+        }
+
+        if frame.flags & FLAG_END_HEADERS != 0
+            && matches!(
+                frame.kind,
+                FRAME_HEADERS | FRAME_PUSH_PROMISE | FRAME_CONTINUATION
+            )
+        {
+            let Some(pending) = block.take() else {
+                continue;
+            };
+
+            let fields = decode_block(&mut decoder, src, &pending.fragments)?;
+            if pending.promise {
+                continue;
+            }
+
+            // A response may be preceded by any number of informational
+            // responses on the same stream, each with its own header block.
+            let message = &mut messages[streams[&pending.stream_id]];
+            if message.headers.is_empty() {
+                message.headers = fields;
+            } else if message.is_informational() {
+                let informational = std::mem::replace(&mut message.headers, fields);
+                message.informational.push(informational);
+            } else {
+                message.trailers.extend(fields);
+            }
+        }
+    }
+
+    if block.is_some() {
+        return Err(Http2ParseError::new("header block is incomplete"));
+    }
+
+    // Streams without any header fields, e.g. a stream which was only
+    // reset, are not messages.
+    messages.retain(|message| !message.headers.is_empty());
+
+    Ok(messages)
+}
+
+/// Parses the frame starting at `pos`.
+fn parse_frame(src: &[u8], pos: usize) -> Result<Frame, Http2ParseError> {
+    let header = src
+        .get(pos..pos + FRAME_HEADER_LEN)
+        .ok_or_else(|| Http2ParseError::new("frame header is incomplete"))?;
+
+    let len = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+    let stream_id = u32::from_be_bytes([header[5], header[6], header[7], header[8]]) & 0x7fff_ffff;
+
+    let payload = pos + FRAME_HEADER_LEN..pos + FRAME_HEADER_LEN + len;
+    if payload.end > src.len() {
+        return Err(Http2ParseError::new("frame payload is incomplete"));
+    }
+
+    Ok(Frame {
+        kind: header[3],
+        flags: header[4],
+        stream_id,
+        span: pos..payload.end,
+        payload,
+    })
+}
+
+/// Returns the payload of a frame excluding padding.
+fn unpad(src: &[u8], frame: &Frame) -> Result<Range<usize>, Http2ParseError> {
+    let mut payload = frame.payload.clone();
+    if frame.flags & FLAG_PADDED == 0 {
+        return Ok(payload);
+    }
+
+    let pad_len = *src
+        .get(payload.start)
+        .filter(|_| !payload.is_empty())
+        .ok_or_else(|| Http2ParseError::new("padded frame is empty"))? as usize;
+
+    payload.start += 1;
+    if payload.len() < pad_len {
+        return Err(Http2ParseError::new("padding exceeds frame payload"));
+    }
+    payload.end -= pad_len;
+
+    Ok(payload)
+}
+
+/// Decodes a header block and maps its fields onto the transcript.
+fn decode_block(
+    decoder: &mut hpack::Decoder,
+    src: &[u8],
+    fragments: &[Range<usize>],
+) -> Result<Vec<HeaderField>, Http2ParseError> {
+    let block = fragments
+        .iter()
+        .flat_map(|fragment| &src[fragment.clone()])
+        .copied()
+        .collect::<Vec<_>>();
+
+    decoder
+        .decode(&block)?
+        .into_iter()
+        .map(|field| {
+            let name = String::from_utf8(field.name)
+                .map_err(|_| Http2ParseError::new("header name is not valid UTF-8"))?;
+
+            Ok(HeaderField {
+                name,
+                value: field.value,
+                span: map_fragments(fragments, field.range),
+                value_span: field
+                    .value_range
+                    .map(|value_range| map_fragments(fragments, value_range)),
+                indexed: field.indexed,
+            })
+        })
+        .collect()
+}
+
+/// Maps a range of a header block onto the fragments it was assembled from.
+fn map_fragments(fragments: &[Range<usize>], range: Range<usize>) -> RangeSet<usize> {
+    let mut mapped = RangeSet::default();
+    let mut block_start = 0;
+    for fragment in fragments {
+        let block_end = block_start + fragment.len();
+        let start = range.start.max(block_start);
+        let end = range.end.min(block_end);
+        if start < end {
+            let start = fragment.start + (start - block_start);
+            let end = fragment.start + (end - block_start);
+            mapped = mapped.union(&RangeSet::from(start..end));
+        }
+        block_start = block_end;
+    }
+
+    mapped
+}
+
+/// Error for [`Http2Transcript`].
+#[derive(Debug, thiserror::Error)]
+#[error("http2 parse error: {0}")]
+pub struct Http2ParseError(String);
+
+impl Http2ParseError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn frame(kind: u8, flags: u8, stream_id: u32, payload: &[u8]) -> Vec<u8> {
+        let mut frame = (payload.len() as u32).to_be_bytes()[1..].to_vec();
+        frame.extend([kind, flags]);
+        frame.extend(stream_id.to_be_bytes());
+        frame.extend(payload);
+        frame
+    }
+
+    pub(crate) fn transcript() -> Transcript {
+        let mut sent = PREFACE.to_vec();
+        // SETTINGS
+        sent.extend(frame(0x4, 0, 0, &[]));
+        // :method GET, :scheme https, :path /, authorization: secret
+        sent.extend(frame(
+            FRAME_HEADERS,
+            FLAG_END_HEADERS | 0x1,
+            1,
+            b"\x82\x87\x84\x00\x0dauthorization\x06secret",
+        ));
+
+        let mut recv = frame(0x4, 0, 0, &[]);
+        // :status 200, split across a HEADERS and a CONTINUATION frame
+        recv.extend(frame(
+            FRAME_HEADERS,
+            FLAG_PADDED,
+            1,
+            b"\x02\x88\x5f\x10app\0\0",
+        ));
+        recv.extend(frame(
+            FRAME_CONTINUATION,
+            FLAG_END_HEADERS,
+            1,
+            b"lication/json",
+        ));
+        recv.extend(frame(FRAME_DATA, 0x1, 1, b"{\"a\":1}"));
+
+        Transcript::new(sent, recv)
+    }
+
+    #[test]
+    fn test_parse_transcript() {
+        let transcript = transcript();
+        let parsed = Http2Transcript::parse(&transcript).unwrap();
+
+        assert_eq!(parsed.requests.len(), 1);
+        let request = &parsed.requests[0];
+        assert_eq!(request.stream_id, 1);
+        assert_eq!(request.headers.len(), 4);
+        assert!(request.data.is_empty());
+
+        let authorization = request.header("authorization").unwrap();
+        assert_eq!(authorization.value, b"secret");
+        assert!(!authorization.indexed);
+        let value_span = authorization.value_span.clone().unwrap();
+        let value = value_span
+            .iter_ranges()
+            .flat_map(|range| &transcript.sent()[range])
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(value, b"\x06secret");
+
+        let response = &parsed.responses[0];
+        let content_type = response.header("content-type").unwrap();
+        assert_eq!(content_type.value, b"application/json");
+        assert!(content_type.indexed);
+        assert_eq!(content_type.span.len_ranges(), 2);
+
+        let data = response
+            .data
+            .iter_ranges()
+            .flat_map(|range| &transcript.received()[range])
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(data, b"{\"a\":1}");
+    }
+
+    #[test]
+    fn test_parse_transcript_invalid() {
+        assert!(Http2Transcript::parse(&Transcript::new(b"GET / HTTP/1.1\r\n\r\n", [])).is_err());
+
+        let mut sent = PREFACE.to_vec();
+        sent.extend(frame(FRAME_HEADERS, 0, 1, b"\x82"));
+        sent.extend(frame(FRAME_DATA, 0, 1, b"data"));
+        assert!(Http2Transcript::parse(&Transcript::new(sent, [])).is_err());
+    }
+
+    #[test]
+    fn test_parse_informational_response() {
+        let mut sent = PREFACE.to_vec();
+        // :method POST, :scheme https, :path /, expect: 100-continue
+        sent.extend(frame(
+            FRAME_HEADERS,
+            FLAG_END_HEADERS,
+            1,
+            b"\x83\x87\x84\x00\x06expect\x0c100-continue",
+        ));
+        sent.extend(frame(FRAME_DATA, 0x1, 1, b"data"));
+
+        // :status 100
+        let mut recv = frame(FRAME_HEADERS, FLAG_END_HEADERS, 1, b"\x08\x03100");
+        // :status 200
+        recv.extend(frame(FRAME_HEADERS, FLAG_END_HEADERS, 1, b"\x88"));
+        recv.extend(frame(FRAME_DATA, 0, 1, b"ok"));
+        // grpc-status: 0
+        recv.extend(frame(
+            FRAME_HEADERS,
+            FLAG_END_HEADERS | 0x1,
+            1,
+            b"\x00\x0bgrpc-status\x010",
+        ));
+
+        let transcript = Transcript::new(sent, recv);
+        let parsed = Http2Transcript::parse(&transcript).unwrap();
+
+        let response = &parsed.responses[0];
+        assert_eq!(response.header(":status").unwrap().value, b"200");
+        assert_eq!(response.informational.len(), 1);
+        assert_eq!(response.informational[0][0].name, ":status");
+        assert_eq!(response.informational[0][0].value, b"100");
+        assert_eq!(response.trailers.len(), 1);
+        assert_eq!(response.trailers[0].name, "grpc-status");
+
+        // The informational response is not part of the frame headers.
+        assert!(response
+            .without_data()
+            .iter_ranges()
+            .all(|range| range.len() == FRAME_HEADER_LEN));
+    }
+}
@@ -1,6 +1,8 @@
 //! Tooling for working with JSON data.
 
 mod commit;
+mod partial;
+mod path;
 
 use spansy::json;
 
@@ -8,3 +10,5 @@ pub use commit::{DefaultJsonCommitter, JsonCommit, JsonCommitError};
 pub use json::{
     Array, Bool, JsonKey, JsonValue, JsonVisit, KeyValue, Null, Number, Object, String,
 };
+pub use partial::{JsonFragment, PartialJson, PartialMember};
+pub use path::{JsonMatch, JsonPath, JsonPathError, JsonTarget, PathSegment};
@@ -0,0 +1,465 @@
+//! Parsing of partially revealed JSON documents.
+//!
+//! A verifier only learns the revealed ranges of a JSON document, with the
+//! redacted ranges in between. The parser tolerates these holes and marks the
+//! values which are hidden by them as [`PartialJson::Redacted`].
+
+use serde::Serialize;
+
+/// A partially revealed JSON value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "value")]
+pub enum PartialJson {
+    /// An object.
+    Object(Vec<PartialMember>),
+    /// An array, with redacted elements marked as [`PartialJson::Redacted`].
+    Array(Vec<PartialJson>),
+    /// A string.
+    String(String),
+    /// A number.
+    Number(String),
+    /// A boolean.
+    Bool(bool),
+    /// A null value.
+    Null,
+    /// A value of which only a prefix is revealed.
+    ///
+    /// This is the case for a number which is directly followed by redacted
+    /// data, as the number may continue in the redacted data.
+    Truncated(String),
+    /// A redacted value.
+    Redacted,
+    /// Revealed fragments of a document whose structure is redacted.
+    ///
+    /// This is the case if only individual values are revealed, without the
+    /// enclosing objects and arrays. The position of the fragments in the
+    /// document is unknown.
+    Fragments(Vec<JsonFragment>),
+}
+
+impl PartialJson {
+    /// Returns the value of the given key if this is an object and the key is
+    /// revealed.
+    pub fn get(&self, key: &str) -> Option<&PartialJson> {
+        let PartialJson::Object(members) = self else {
+            return None;
+        };
+
+        members.iter().find_map(|member| match member {
+            PartialMember::Pair { key: k, value } if k == key => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns whether the value is redacted.
+    pub fn is_redacted(&self) -> bool {
+        matches!(self, PartialJson::Redacted)
+    }
+}
+
+/// A member of a partially revealed JSON object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum PartialMember {
+    /// A key-value pair, whose value may be redacted.
+    Pair {
+        /// The key.
+        key: String,
+        /// The value.
+        value: PartialJson,
+    },
+    /// One or more redacted key-value pairs.
+    Redacted,
+}
+
+/// A revealed fragment of a JSON document whose structure is redacted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum JsonFragment {
+    /// A key-value pair.
+    Pair {
+        /// The key.
+        key: String,
+        /// The value.
+        value: PartialJson,
+    },
+    /// A value.
+    Value {
+        /// The value.
+        value: PartialJson,
+    },
+}
+
+impl PartialJson {
+    /// Parses a partially revealed JSON document.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The document, with arbitrary bytes in place of redacted data.
+    /// * `authed` - Whether each byte of the document is revealed.
+    pub fn parse(data: &[u8], authed: &[bool]) -> Self {
+        debug_assert_eq!(data.len(), authed.len());
+
+        let mut parser = Parser {
+            data,
+            authed,
+            pos: 0,
+        };
+
+        if let Some(value) = parser.parse_value() {
+            parser.skip_ws();
+            if parser.pos == data.len() {
+                return value;
+            }
+        }
+
+        parser.pos = 0;
+        parser.parse_fragments()
+    }
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    authed: &'a [bool],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn at_hole(&self) -> bool {
+        self.pos < self.data.len() && !self.authed[self.pos]
+    }
+
+    fn skip_hole(&mut self) {
+        while self.at_hole() {
+            self.pos += 1;
+        }
+    }
+
+    /// Returns the next byte if it is revealed.
+    fn peek(&self) -> Option<u8> {
+        (self.pos < self.data.len() && self.authed[self.pos]).then(|| self.data[self.pos])
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_value(&mut self) -> Option<PartialJson> {
+        self.skip_ws();
+        if self.at_hole() {
+            self.skip_hole();
+            return Some(PartialJson::Redacted);
+        }
+
+        match self.peek()? {
+            b'{' => self.parse_object(),
+            b'[' => self.parse_array(),
+            b'"' => self.parse_string(true).map(|string| match string {
+                Some(string) => PartialJson::String(string),
+                None => PartialJson::Redacted,
+            }),
+            b't' => self.parse_literal("true", PartialJson::Bool(true)),
+            b'f' => self.parse_literal("false", PartialJson::Bool(false)),
+            b'n' => self.parse_literal("null", PartialJson::Null),
+            b'-' | b'0'..=b'9' => Some(self.parse_number()),
+            _ => None,
+        }
+    }
+
+    fn parse_object(&mut self) -> Option<PartialJson> {
+        self.pos += 1;
+        let mut members = Vec::new();
+
+        loop {
+            self.skip_ws();
+            if self.at_hole() {
+                self.skip_hole();
+                members.push(PartialMember::Redacted);
+                continue;
+            }
+
+            match self.peek()? {
+                b'}' => {
+                    self.pos += 1;
+                    return Some(PartialJson::Object(members));
+                }
+                b',' => self.pos += 1,
+                b'"' => {
+                    let (key, value) = self.parse_pair()?;
+                    members.push(PartialMember::Pair { key, value });
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn parse_pair(&mut self) -> Option<(String, PartialJson)> {
+        let key = self.parse_string(false)??;
+        self.skip_ws();
+        if self.at_hole() {
+            self.skip_hole();
+            return Some((key, PartialJson::Redacted));
+        }
+
+        if self.peek()? != b':' {
+            return None;
+        }
+        self.pos += 1;
+
+        Some((key, self.parse_value()?))
+    }
+
+    fn parse_array(&mut self) -> Option<PartialJson> {
+        self.pos += 1;
+        let mut elems = Vec::new();
+
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return Some(PartialJson::Array(elems));
+                }
+                Some(b',') => self.pos += 1,
+                _ => elems.push(self.parse_value()?),
+            }
+        }
+    }
+
+    /// Parses a string, returning `Some(None)` if the string contains
+    /// redacted data, which is only allowed if `allow_holes` is set.
+    fn parse_string(&mut self, allow_holes: bool) -> Option<Option<String>> {
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        let mut redacted = false;
+
+        loop {
+            if self.at_hole() {
+                if !allow_holes {
+                    return None;
+                }
+                self.skip_hole();
+                redacted = true;
+                continue;
+            }
+
+            match self.peek()? {
+                b'"' => {
+                    self.pos += 1;
+                    break;
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    if self.at_hole() {
+                        // The escaped character is redacted.
+                        continue;
+                    }
+                    let escaped = self.peek()?;
+                    self.pos += 1;
+                    unescape(escaped, self, &mut bytes)?;
+                }
+                byte => {
+                    self.pos += 1;
+                    bytes.push(byte);
+                }
+            }
+        }
+
+        if redacted {
+            return Some(None);
+        }
+
+        String::from_utf8(bytes).ok().map(Some)
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: PartialJson) -> Option<PartialJson> {
+        for byte in literal.bytes() {
+            if self.at_hole() {
+                self.skip_hole();
+                return Some(PartialJson::Redacted);
+            }
+            if self.peek()? != byte {
+                return None;
+            }
+            self.pos += 1;
+        }
+
+        Some(value)
+    }
+
+    fn parse_number(&mut self) -> PartialJson {
+        let start = self.pos;
+        while matches!(
+            self.peek(),
+            Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')
+        ) {
+            self.pos += 1;
+        }
+
+        let number = String::from_utf8_lossy(&self.data[start..self.pos]).into_owned();
+        if self.at_hole() {
+            PartialJson::Truncated(number)
+        } else {
+            PartialJson::Number(number)
+        }
+    }
+
+    /// Parses the revealed fragments of a document whose structure is
+    /// redacted.
+    fn parse_fragments(&mut self) -> PartialJson {
+        let mut fragments = Vec::new();
+
+        while self.pos < self.data.len() {
+            if self.at_hole() {
+                self.skip_hole();
+                continue;
+            }
+
+            let start = self.pos;
+            let fragment = match self.peek() {
+                Some(b'"') => self.parse_string(false).flatten().map(|string| {
+                    self.skip_ws();
+                    if self.peek() == Some(b':') {
+                        self.pos += 1;
+                        self.parse_value()
+                            .map(|value| JsonFragment::Pair { key: string, value })
+                    } else {
+                        Some(JsonFragment::Value {
+                            value: PartialJson::String(string),
+                        })
+                    }
+                }),
+                Some(b'{' | b'[' | b't' | b'f' | b'n' | b'-' | b'0'..=b'9') => self
+                    .parse_value()
+                    .map(|value| Some(JsonFragment::Value { value })),
+                _ => None,
+            };
+
+            match fragment {
+                Some(Some(fragment)) => fragments.push(fragment),
+                _ => self.pos = start + 1,
+            }
+        }
+
+        PartialJson::Fragments(fragments)
+    }
+}
+
+/// Unescapes the escaped character of an escape sequence.
+fn unescape(escaped: u8, parser: &mut Parser<'_>, bytes: &mut Vec<u8>) -> Option<()> {
+    let byte = match escaped {
+        b'"' => b'"',
+        b'\\' => b'\\',
+        b'/' => b'/',
+        b'b' => 0x08,
+        b'f' => 0x0c,
+        b'n' => b'\n',
+        b'r' => b'\r',
+        b't' => b'\t',
+        b'u' => {
+            let mut code = parse_hex4(parser)?;
+            // Combine surrogate pairs.
+            if (0xd800..0xdc00).contains(&code) {
+                if parser.peek()? != b'\\' {
+                    return None;
+                }
+                parser.pos += 1;
+                if parser.peek()? != b'u' {
+                    return None;
+                }
+                parser.pos += 1;
+                let low = parse_hex4(parser)?;
+                code = 0x10000 + ((code - 0xd800) << 10) + low.checked_sub(0xdc00)?;
+            }
+
+            let mut buf = [0; 4];
+            bytes.extend(char::from_u32(code)?.encode_utf8(&mut buf).as_bytes());
+            return Some(());
+        }
+        _ => return None,
+    };
+
+    bytes.push(byte);
+    Some(())
+}
+
+fn parse_hex4(parser: &mut Parser<'_>) -> Option<u32> {
+    let mut code = 0;
+    for _ in 0..4 {
+        let digit = (parser.peek()? as char).to_digit(16)?;
+        parser.pos += 1;
+        code = code * 16 + digit;
+    }
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a document in which `~` marks redacted bytes.
+    fn parse(doc: &str) -> PartialJson {
+        let authed = doc.bytes().map(|byte| byte != b'~').collect::<Vec<_>>();
+        PartialJson::parse(doc.as_bytes(), &authed)
+    }
+
+    #[test]
+    fn test_parse_complete() {
+        let value = parse(r#"{"a": [1, "xé", true, null], "b": {"c": -1.5e3}}"#);
+
+        assert_eq!(
+            value.get("a"),
+            Some(&PartialJson::Array(vec![
+                PartialJson::Number("1".to_string()),
+                PartialJson::String("xé".to_string()),
+                PartialJson::Bool(true),
+                PartialJson::Null,
+            ]))
+        );
+        assert_eq!(
+            value.get("b").and_then(|b| b.get("c")),
+            Some(&PartialJson::Number("-1.5e3".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_redacted_values() {
+        let value = parse(r#"{"a": ~~~~, "b": "~~", ~~~~~~~~~~~~, "d": [1,~~~]}"#);
+
+        assert!(value.get("a").unwrap().is_redacted());
+        assert!(value.get("b").unwrap().is_redacted());
+        assert_eq!(
+            value.get("d"),
+            Some(&PartialJson::Array(vec![
+                PartialJson::Number("1".to_string()),
+                PartialJson::Redacted,
+            ]))
+        );
+
+        let PartialJson::Object(members) = value else {
+            panic!("expected object");
+        };
+        assert_eq!(members[2], PartialMember::Redacted);
+    }
+
+    #[test]
+    fn test_parse_fragments() {
+        let value = parse(r#"~~~~~~~~~"id": 42~~~~~~~~~~~~"name": "alice"~~~"#);
+
+        assert_eq!(
+            value,
+            PartialJson::Fragments(vec![
+                JsonFragment::Pair {
+                    key: "id".to_string(),
+                    value: PartialJson::Truncated("42".to_string()),
+                },
+                JsonFragment::Pair {
+                    key: "name".to_string(),
+                    value: PartialJson::String("alice".to_string()),
+                },
+            ])
+        );
+    }
+}
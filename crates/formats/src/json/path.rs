@@ -0,0 +1,458 @@
+//! Path-based addressing of JSON values.
+//!
+//! A [`JsonPath`] selects values in a JSON document, using a subset of the
+//! JSONPath syntax:
+//!
+//! - `$` selects the root value.
+//! - `.key` or `['key']` selects the value of a key in an object.
+//! - `[0]` selects an element of an array.
+//! - `.*` or `[*]` selects all values of an object or all elements of an
+//!   array.
+//!
+//! For example, `$.accounts[*].balance` selects the balance of every account.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use spansy::json::{JsonValue, KeyValue};
+use tlsn_core::transcript::{
+    Direction, Idx, TranscriptCommitConfigBuilder, TranscriptProofBuilder,
+};
+use utils::range::{RangeSet, ToRangeSet, Union};
+
+/// A segment of a [`JsonPath`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// The value of a key in an object.
+    Key(String),
+    /// An element of an array.
+    Index(usize),
+    /// All values of an object or all elements of an array.
+    Wildcard,
+}
+
+/// The part of a matched value to select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonTarget {
+    /// The key of a key-value pair.
+    Key,
+    /// The value.
+    Value,
+    /// The key-value pair, or the value if it is not part of a key-value pair,
+    /// e.g. an element of an array.
+    Pair,
+}
+
+/// A path to values in a JSON document.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    /// Returns the segments of the path.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Returns the values matched by the path.
+    ///
+    /// Returns an error if the path does not match any value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The root value.
+    pub fn resolve<'a>(&self, value: &'a JsonValue) -> Result<Vec<JsonMatch<'a>>, JsonPathError> {
+        let mut matches = vec![JsonMatch { pair: None, value }];
+
+        for (idx, segment) in self.segments.iter().enumerate() {
+            // Values matched by a wildcard which can not be traversed further
+            // are skipped.
+            let lenient = self.segments[..idx].contains(&PathSegment::Wildcard);
+            let mut next = Vec::new();
+            for m in &matches {
+                match (segment, m.value) {
+                    (PathSegment::Key(key), JsonValue::Object(object)) => {
+                        next.extend(
+                            object
+                                .elems
+                                .iter()
+                                .filter(|kv| kv.key.as_str() == key.as_str())
+                                .map(|kv| JsonMatch {
+                                    pair: Some(kv),
+                                    value: &kv.value,
+                                }),
+                        );
+                    }
+                    (PathSegment::Index(index), JsonValue::Array(array)) => {
+                        next.extend(
+                            array
+                                .elems
+                                .get(*index)
+                                .map(|value| JsonMatch { pair: None, value }),
+                        );
+                    }
+                    (PathSegment::Wildcard, JsonValue::Object(object)) => {
+                        next.extend(object.elems.iter().map(|kv| JsonMatch {
+                            pair: Some(kv),
+                            value: &kv.value,
+                        }));
+                    }
+                    (PathSegment::Wildcard, JsonValue::Array(array)) => {
+                        next.extend(
+                            array
+                                .elems
+                                .iter()
+                                .map(|value| JsonMatch { pair: None, value }),
+                        );
+                    }
+                    _ if lenient => {}
+                    (segment, value) => {
+                        return Err(JsonPathError::new(format!(
+                            "{} expects {} at {}, found {}",
+                            self,
+                            expected(segment),
+                            self.prefix(idx),
+                            kind(value)
+                        )))
+                    }
+                }
+            }
+
+            if next.is_empty() {
+                return Err(JsonPathError::new(format!(
+                    "{} not found: no value matches {} at {}",
+                    self,
+                    display_segment(segment),
+                    self.prefix(idx)
+                )));
+            }
+
+            matches = next;
+        }
+
+        Ok(matches)
+    }
+
+    /// Returns the index of the values matched by the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The root value.
+    /// * `target` - The part of the matched values to select.
+    pub fn select(&self, value: &JsonValue, target: JsonTarget) -> Result<Idx, JsonPathError> {
+        self.resolve(value)?
+            .iter()
+            .try_fold(RangeSet::default(), |ranges, m| {
+                m.ranges(target)
+                    .map(|match_ranges| ranges.union(&match_ranges))
+                    .ok_or_else(|| {
+                        JsonPathError::new(format!("{self} matches a value which has no key"))
+                    })
+            })
+            .map(Idx::new)
+    }
+
+    /// Commits to the values matched by the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `value` - The root value.
+    /// * `target` - The part of the matched values to commit to.
+    /// * `direction` - The direction of the data (sent or received).
+    pub fn commit(
+        &self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        value: &JsonValue,
+        target: JsonTarget,
+        direction: Direction,
+    ) -> Result<(), JsonPathError> {
+        let idx = self.select(value, target)?;
+        builder.commit(&idx, direction).map_err(|e| {
+            JsonPathError::new_with_source(format!("failed to commit to {self}"), e)
+        })?;
+
+        Ok(())
+    }
+
+    /// Reveals the values matched by the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript proof builder.
+    /// * `value` - The root value.
+    /// * `target` - The part of the matched values to reveal.
+    /// * `direction` - The direction of the data (sent or received).
+    pub fn reveal(
+        &self,
+        builder: &mut TranscriptProofBuilder<'_>,
+        value: &JsonValue,
+        target: JsonTarget,
+        direction: Direction,
+    ) -> Result<(), JsonPathError> {
+        let idx = self.select(value, target)?;
+        builder
+            .reveal(&idx, direction)
+            .map_err(|e| JsonPathError::new_with_source(format!("failed to reveal {self}"), e))?;
+
+        Ok(())
+    }
+
+    /// Returns the path consisting of the first `len` segments.
+    fn prefix(&self, len: usize) -> JsonPath {
+        JsonPath {
+            segments: self.segments[..len].to_vec(),
+        }
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = JsonPathError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let invalid = || JsonPathError::new(format!("invalid JSON path: {path}"));
+
+        let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix('.') {
+                let end = tail.find(['.', '[']).unwrap_or(tail.len());
+                let (key, tail) = tail.split_at(end);
+                segments.push(match key {
+                    "" => return Err(invalid()),
+                    "*" => PathSegment::Wildcard,
+                    key => PathSegment::Key(key.to_string()),
+                });
+                rest = tail;
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let quote = tail.chars().next().filter(|c| *c == '\'' || *c == '"');
+                if let Some(quote) = quote {
+                    let (key, tail) = tail[1..].split_once(quote).ok_or_else(invalid)?;
+                    segments.push(PathSegment::Key(key.to_string()));
+                    rest = tail.strip_prefix(']').ok_or_else(invalid)?;
+                } else {
+                    let (index, tail) = tail.split_once(']').ok_or_else(invalid)?;
+                    segments.push(match index {
+                        "*" => PathSegment::Wildcard,
+                        index => PathSegment::Index(index.parse().map_err(|_| invalid())?),
+                    });
+                    rest = tail;
+                }
+            } else {
+                return Err(invalid());
+            }
+        }
+
+        Ok(Self { segments })
+    }
+}
+
+impl TryFrom<String> for JsonPath {
+    type Error = JsonPathError;
+
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        path.parse()
+    }
+}
+
+impl From<JsonPath> for String {
+    fn from(path: JsonPath) -> Self {
+        path.to_string()
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "$")?;
+        for segment in &self.segments {
+            write!(f, "{}", display_segment(segment))?;
+        }
+        Ok(())
+    }
+}
+
+/// A value matched by a [`JsonPath`].
+#[derive(Debug, Clone, Copy)]
+pub struct JsonMatch<'a> {
+    pair: Option<&'a KeyValue>,
+    value: &'a JsonValue,
+}
+
+impl<'a> JsonMatch<'a> {
+    /// Returns the matched value.
+    pub fn value(&self) -> &'a JsonValue {
+        self.value
+    }
+
+    /// Returns the key-value pair of the matched value, if it is the value of
+    /// a key in an object.
+    pub fn pair(&self) -> Option<&'a KeyValue> {
+        self.pair
+    }
+
+    /// Returns the ranges of the given part of the matched value.
+    ///
+    /// Returns `None` if the key is selected but the value is not part of a
+    /// key-value pair.
+    pub fn ranges(&self, target: JsonTarget) -> Option<RangeSet<usize>> {
+        match (target, self.pair) {
+            (JsonTarget::Key, pair) => pair.map(|kv| kv.key.to_range_set()),
+            (JsonTarget::Value, _) | (JsonTarget::Pair, None) => Some(self.value.to_range_set()),
+            (JsonTarget::Pair, Some(kv)) => Some(kv.to_range_set()),
+        }
+    }
+}
+
+fn display_segment(segment: &PathSegment) -> String {
+    match segment {
+        PathSegment::Key(key) if !key.is_empty() && !key.contains(['.', '[', ']', '\'']) => {
+            format!(".{key}")
+        }
+        PathSegment::Key(key) => format!("[\"{key}\"]"),
+        PathSegment::Index(index) => format!("[{index}]"),
+        PathSegment::Wildcard => "[*]".to_string(),
+    }
+}
+
+fn expected(segment: &PathSegment) -> &'static str {
+    match segment {
+        PathSegment::Key(_) => "an object",
+        PathSegment::Index(_) => "an array",
+        PathSegment::Wildcard => "an object or array",
+    }
+}
+
+fn kind(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Object(_) => "an object",
+        JsonValue::Array(_) => "an array",
+        JsonValue::String(_) => "a string",
+        JsonValue::Number(_) => "a number",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Null(_) => "null",
+    }
+}
+
+/// Error for [`JsonPath`].
+#[derive(Debug, thiserror::Error)]
+#[error("json path error: {msg}")]
+pub struct JsonPathError {
+    msg: String,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl JsonPathError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self {
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    fn new_with_source<E>(msg: impl Into<String>, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self {
+            msg: msg.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Returns the error message.
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use rstest::*;
+
+    use super::*;
+
+    const DOC: &str =
+        r#"{"accounts": [{"id": 1, "balance": 100}, {"id": 2, "balance": 250}], "a.b": true}"#;
+
+    fn selected(path: &str, target: JsonTarget) -> Vec<String> {
+        let value = spansy::json::parse(Bytes::from(DOC)).unwrap();
+        let idx = path
+            .parse::<JsonPath>()
+            .unwrap()
+            .select(&value, target)
+            .unwrap();
+        idx.iter_ranges()
+            .map(|range| DOC[range].to_string())
+            .collect()
+    }
+
+    #[rstest]
+    #[case::root("$")]
+    #[case::key("$.accounts")]
+    #[case::index("$.accounts[1].balance")]
+    #[case::wildcard("$.accounts[*].id")]
+    #[case::quoted("$[\"a.b\"]")]
+    fn test_json_path_roundtrip(#[case] path: &str) {
+        assert_eq!(path.parse::<JsonPath>().unwrap().to_string(), path);
+    }
+
+    #[rstest]
+    #[case::no_root("accounts")]
+    #[case::empty_key("$.")]
+    #[case::bad_index("$[x]")]
+    #[case::unclosed("$['a")]
+    fn test_json_path_invalid(#[case] path: &str) {
+        assert!(path.parse::<JsonPath>().is_err());
+    }
+
+    #[test]
+    fn test_json_path_select() {
+        assert_eq!(
+            selected("$.accounts[1].balance", JsonTarget::Value),
+            ["250"]
+        );
+
+        let balances = selected("$.accounts[*].balance", JsonTarget::Pair);
+        assert_eq!(balances.len(), 2);
+        assert!(balances[0].contains("balance") && balances[0].ends_with("100"));
+        assert!(balances[1].contains("balance") && balances[1].ends_with("250"));
+
+        assert_eq!(selected("$['a.b']", JsonTarget::Value), ["true"]);
+    }
+
+    #[test]
+    fn test_json_path_missing() {
+        let value = spansy::json::parse(Bytes::from(DOC)).unwrap();
+
+        let err = "$.accounts[2]"
+            .parse::<JsonPath>()
+            .unwrap()
+            .resolve(&value)
+            .unwrap_err();
+        assert_eq!(
+            err.msg(),
+            "$.accounts[2] not found: no value matches [2] at $.accounts"
+        );
+
+        let err = "$.accounts.id"
+            .parse::<JsonPath>()
+            .unwrap()
+            .resolve(&value)
+            .unwrap_err();
+        assert_eq!(
+            err.msg(),
+            "$.accounts.id expects an object at $.accounts, found an array"
+        );
+
+        assert!("$.accounts[0]"
+            .parse::<JsonPath>()
+            .unwrap()
+            .select(&value, JsonTarget::Key)
+            .is_err());
+    }
+}
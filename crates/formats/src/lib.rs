@@ -13,7 +13,10 @@
 #![forbid(unsafe_code)]
 
 pub mod http;
+pub mod http2;
 pub mod json;
+pub mod policy;
+pub mod websocket;
 
 #[doc(hidden)]
 pub use spansy;
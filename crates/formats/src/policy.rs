@@ -0,0 +1,399 @@
+//! Declarative redaction policies for HTTP transcripts.
+//!
+//! A [`RedactionPolicy`] describes which parts of the requests and responses in
+//! an [`HttpTranscript`] are revealed, so that it can be shipped as a config
+//! file instead of code. The same policy is applied when committing to the
+//! transcript and when building the transcript proof, which ensures that the
+//! revealed ranges are always covered by a commitment.
+//!
+//! # Example
+//!
+//! A policy which reveals the request line and all request headers except
+//! `Authorization` and `Cookie`, and two fields of the JSON response body:
+//!
+//! ```yaml
+//! request:
+//!   start_line: true
+//!   headers:
+//!     default: reveal
+//!     hide: [authorization, cookie]
+//! response:
+//!   start_line: true
+//!   body:
+//!     json_paths: ["$.data.user.id", "$.balance"]
+//! ```
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use tlsn_core::transcript::{Direction, TranscriptCommitConfigBuilder, TranscriptProofBuilder};
+use utils::range::{RangeSet, ToRangeSet, Union};
+
+use crate::{
+    http::{Body, BodyContent, Header, HttpTranscript},
+    json::{JsonPath, JsonTarget},
+};
+
+/// A redaction policy for an HTTP transcript.
+///
+/// The policy is applied to every request and response in the transcript.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionPolicy {
+    /// The policy for requests.
+    pub request: MessagePolicy,
+    /// The policy for responses.
+    pub response: MessagePolicy,
+}
+
+/// A redaction policy for an HTTP message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessagePolicy {
+    /// Whether to reveal the request or status line, along with the structure
+    /// of the message excluding the headers and body.
+    pub start_line: bool,
+    /// The policy for the headers.
+    pub headers: HeaderPolicy,
+    /// The policy for the body.
+    pub body: BodyPolicy,
+}
+
+/// What to reveal of a header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderAction {
+    /// Reveal the entire header.
+    Reveal,
+    /// Reveal the header excluding the value.
+    RevealName,
+    /// Hide the header.
+    #[default]
+    Hide,
+}
+
+/// A redaction policy for headers.
+///
+/// Header names are matched case-insensitively. If a header is listed more
+/// than once, `hide` takes precedence over `reveal_name`, which takes
+/// precedence over `reveal`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeaderPolicy {
+    /// The action for headers which are not listed.
+    pub default: HeaderAction,
+    /// Headers which are revealed.
+    pub reveal: Vec<String>,
+    /// Headers which are revealed excluding the value.
+    pub reveal_name: Vec<String>,
+    /// Headers which are hidden.
+    pub hide: Vec<String>,
+}
+
+impl HeaderPolicy {
+    /// Returns the action for the header with the given name.
+    pub fn action(&self, name: &str) -> HeaderAction {
+        let listed = |names: &[String]| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+
+        if listed(&self.hide) {
+            HeaderAction::Hide
+        } else if listed(&self.reveal_name) {
+            HeaderAction::RevealName
+        } else if listed(&self.reveal) {
+            HeaderAction::Reveal
+        } else {
+            self.default
+        }
+    }
+}
+
+/// A redaction policy for a body.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BodyPolicy {
+    /// Whether to reveal the entire body.
+    pub reveal: bool,
+    /// Paths of JSON values to reveal, e.g. `$.data.items[*].id`.
+    ///
+    /// If the path ends in a key, the key-value pair is revealed.
+    pub json_paths: Vec<JsonPath>,
+}
+
+/// The ranges of a transcript which are revealed by a policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RevealedRanges {
+    /// The revealed ranges of the sent data.
+    pub sent: RangeSet<usize>,
+    /// The revealed ranges of the received data.
+    pub received: RangeSet<usize>,
+}
+
+impl RedactionPolicy {
+    /// Returns the ranges of the transcript which are revealed by the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `transcript` - The HTTP transcript.
+    pub fn revealed(&self, transcript: &HttpTranscript) -> Result<RevealedRanges, PolicyError> {
+        let mut sent = RangeSet::default();
+        for (idx, request) in transcript.requests.iter().enumerate() {
+            if self.request.start_line {
+                sent = sent.union(&request.without_data().to_range_set());
+                sent = sent.union(&request.request.target.to_range_set());
+            }
+
+            sent = sent.union(&self.request.headers.revealed(&request.headers));
+
+            if let Some(body) = &request.body {
+                let ranges = self.request.body.revealed(body).map_err(|mut e| {
+                    e.msg = format!("request {idx}: {}", e.msg);
+                    e
+                })?;
+                sent = sent.union(&ranges);
+            }
+        }
+
+        let mut received = RangeSet::default();
+        for (idx, response) in transcript.responses.iter().enumerate() {
+            if self.response.start_line {
+                received = received.union(&response.without_data().to_range_set());
+            }
+
+            received = received.union(&self.response.headers.revealed(&response.headers));
+
+            if let Some(body) = &response.body {
+                let ranges = self.response.body.revealed(body).map_err(|mut e| {
+                    e.msg = format!("response {idx}: {}", e.msg);
+                    e
+                })?;
+                received = received.union(&ranges);
+            }
+        }
+
+        Ok(RevealedRanges { sent, received })
+    }
+
+    /// Commits to the ranges of the transcript which are revealed by the
+    /// policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `transcript` - The HTTP transcript.
+    pub fn commit(
+        &self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        transcript: &HttpTranscript,
+    ) -> Result<(), PolicyError> {
+        let revealed = self.revealed(transcript)?;
+        for (ranges, direction) in [
+            (revealed.sent, Direction::Sent),
+            (revealed.received, Direction::Received),
+        ] {
+            if !ranges.is_empty() {
+                builder.commit(&ranges, direction).map_err(|e| {
+                    PolicyError::new_with_source(
+                        format!("failed to commit to revealed {direction} data"),
+                        e,
+                    )
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reveals the ranges of the transcript which are revealed by the policy.
+    ///
+    /// The ranges must have been committed to using [`RedactionPolicy::commit`]
+    /// with the same policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript proof builder.
+    /// * `transcript` - The HTTP transcript.
+    pub fn reveal(
+        &self,
+        builder: &mut TranscriptProofBuilder<'_>,
+        transcript: &HttpTranscript,
+    ) -> Result<(), PolicyError> {
+        let revealed = self.revealed(transcript)?;
+        for (ranges, direction) in [
+            (revealed.sent, Direction::Sent),
+            (revealed.received, Direction::Received),
+        ] {
+            if !ranges.is_empty() {
+                builder.reveal(&ranges, direction).map_err(|e| {
+                    PolicyError::new_with_source(format!("failed to reveal {direction} data"), e)
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl HeaderPolicy {
+    fn revealed(&self, headers: &[Header]) -> RangeSet<usize> {
+        headers.iter().fold(RangeSet::default(), |ranges, header| {
+            match self.action(header.name.as_str()) {
+                HeaderAction::Reveal => ranges.union(&header.to_range_set()),
+                HeaderAction::RevealName => ranges.union(&header.without_value().to_range_set()),
+                HeaderAction::Hide => ranges,
+            }
+        })
+    }
+}
+
+impl BodyPolicy {
+    fn revealed(&self, body: &Body) -> Result<RangeSet<usize>, PolicyError> {
+        if self.reveal {
+            return Ok(body.content.to_range_set());
+        }
+
+        if self.json_paths.is_empty() {
+            return Ok(RangeSet::default());
+        }
+
+        let BodyContent::Json(value) = &body.content else {
+            return Err(PolicyError::new("body is not JSON"));
+        };
+
+        self.json_paths
+            .iter()
+            .try_fold(RangeSet::default(), |ranges, path| {
+                path.select(value, JsonTarget::Pair)
+                    .map(|idx| ranges.union(&idx.to_range_set()))
+                    .map_err(|e| PolicyError::new_with_source("failed to select JSON path", e))
+            })
+    }
+}
+
+/// Error for [`RedactionPolicy`].
+#[derive(Debug, thiserror::Error)]
+#[error("redaction policy error: {msg}")]
+pub struct PolicyError {
+    msg: String,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl PolicyError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self {
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    fn new_with_source<E>(msg: impl Into<String>, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self {
+            msg: msg.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Returns the error message.
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tlsn_core::transcript::Transcript;
+    use tlsn_data_fixtures::http as fixtures;
+
+    fn transcript() -> Transcript {
+        Transcript::new(fixtures::request::POST_JSON, fixtures::response::OK_JSON)
+    }
+
+    fn revealed_data(data: &[u8], ranges: &RangeSet<usize>) -> String {
+        ranges
+            .iter_ranges()
+            .map(|range| std::str::from_utf8(&data[range]).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_policy_revealed() {
+        let policy: RedactionPolicy = serde_json::from_str(
+            r#"{
+                "request": {
+                    "start_line": true,
+                    "headers": { "default": "reveal", "reveal_name": ["content-length"] }
+                },
+                "response": {
+                    "headers": { "hide": ["Cookie"], "reveal": ["cookie", "content-type"] },
+                    "body": { "json_paths": ["$.bazz", "$.buzz[1]"] }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let transcript = transcript();
+        let http = HttpTranscript::parse(&transcript).unwrap();
+        let revealed = policy.revealed(&http).unwrap();
+
+        let sent = revealed_data(transcript.sent(), &revealed.sent);
+        assert!(sent.starts_with("POST /hello HTTP/1.1"));
+        assert!(sent.contains("Host: localhost"));
+        assert!(sent.contains("Content-Length: "));
+        assert!(!sent.contains("44"));
+        assert!(!sent.contains("foo"));
+
+        let received = revealed_data(transcript.received(), &revealed.received);
+        assert!(!received.contains("very-secret-cookie"));
+        assert!(received.contains("Content-Type: application/json"));
+        assert!(received.contains("\"bazz\": 123"));
+        assert!(received.contains("\"5\""));
+        assert!(!received.contains("bar"));
+    }
+
+    #[test]
+    fn test_policy_commit() {
+        let policy = RedactionPolicy {
+            response: MessagePolicy {
+                start_line: true,
+                body: BodyPolicy {
+                    json_paths: vec!["$.foo".parse().unwrap()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let transcript = transcript();
+        let http = HttpTranscript::parse(&transcript).unwrap();
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        policy.commit(&mut builder, &http).unwrap();
+
+        builder.build().unwrap();
+    }
+
+    #[test]
+    fn test_policy_missing_json_path() {
+        let policy = RedactionPolicy {
+            response: MessagePolicy {
+                body: BodyPolicy {
+                    json_paths: vec!["$.foo.bar".parse().unwrap()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let transcript = transcript();
+        let http = HttpTranscript::parse(&transcript).unwrap();
+
+        let err = policy.revealed(&http).unwrap_err();
+        assert!(err.msg().starts_with("response 0"));
+    }
+}
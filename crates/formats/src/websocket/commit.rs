@@ -0,0 +1,223 @@
+use std::error::Error;
+
+use tlsn_core::transcript::{Direction, Idx, TranscriptCommitConfigBuilder};
+
+use crate::{
+    http::{DefaultHttpCommitter, HttpCommit},
+    websocket::{WebSocketMessage, WebSocketTranscript},
+};
+
+/// WebSocket commitment error.
+#[derive(Debug, thiserror::Error)]
+#[error("websocket commit error: {msg}")]
+pub struct WebSocketCommitError {
+    message: Option<usize>,
+    msg: String,
+    #[source]
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl WebSocketCommitError {
+    /// Creates a new WebSocket commitment error.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The error message.
+    pub fn new(msg: impl Into<String>) -> Self {
+        Self {
+            message: None,
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    /// Creates a new WebSocket commitment error with a source.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The error message.
+    /// * `source` - The source error.
+    pub fn new_with_source<E>(msg: impl Into<String>, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self {
+            message: None,
+            msg: msg.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Sets the index of the message in its direction of the transcript.
+    pub fn set_message(&mut self, message: usize) {
+        self.message = Some(message);
+    }
+
+    /// Returns the index of the message in its direction of the transcript,
+    /// if set.
+    pub fn message(&self) -> Option<usize> {
+        self.message
+    }
+
+    /// Returns the error message.
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+}
+
+/// A WebSocket data committer.
+#[allow(unused_variables)]
+pub trait WebSocketCommit {
+    /// Commits to a WebSocket transcript.
+    ///
+    /// The default implementation commits to the handshake, and each message
+    /// in the transcript separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `transcript` - The transcript to commit.
+    fn commit_transcript(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        transcript: &WebSocketTranscript,
+    ) -> Result<(), WebSocketCommitError> {
+        self.commit_handshake(builder, transcript)?;
+
+        for (direction, messages) in [
+            (Direction::Sent, &transcript.sent),
+            (Direction::Received, &transcript.received),
+        ] {
+            for (idx, message) in messages.iter().enumerate() {
+                self.commit_message(builder, direction, message)
+                    .map_err(|mut e| {
+                        e.set_message(idx);
+                        e
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Commits to the HTTP upgrade handshake.
+    ///
+    /// The default implementation commits to the request and response using
+    /// the [`DefaultHttpCommitter`].
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `transcript` - The transcript of the handshake.
+    fn commit_handshake(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        transcript: &WebSocketTranscript,
+    ) -> Result<(), WebSocketCommitError> {
+        let mut committer = DefaultHttpCommitter::default();
+
+        committer
+            .commit_request(builder, Direction::Sent, &transcript.handshake_request)
+            .map_err(|e| {
+                WebSocketCommitError::new_with_source("failed to commit to upgrade request", e)
+            })?;
+        committer
+            .commit_response(builder, Direction::Received, &transcript.handshake_response)
+            .map_err(|e| {
+                WebSocketCommitError::new_with_source("failed to commit to upgrade response", e)
+            })?;
+
+        Ok(())
+    }
+
+    /// Commits to a message.
+    ///
+    /// The default implementation commits to all frames of the message, and
+    /// the frame headers excluding the masking keys. Additionally, it commits
+    /// to the payload separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the message (sent or received).
+    /// * `message` - The message to commit to.
+    fn commit_message(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        message: &WebSocketMessage,
+    ) -> Result<(), WebSocketCommitError> {
+        commit_idx(builder, direction, &message.idx(), "entire message")?;
+        commit_idx(
+            builder,
+            direction,
+            &message.header_idx(),
+            "message frame headers",
+        )?;
+
+        let payload = message.payload_idx();
+        if !payload.is_empty() {
+            self.commit_payload(builder, direction, message, &payload)?;
+        }
+
+        Ok(())
+    }
+
+    /// Commits to the payload of a message.
+    ///
+    /// The default implementation commits to the payload as a whole, including
+    /// the masking keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The transcript commitment builder.
+    /// * `direction` - The direction of the message (sent or received).
+    /// * `parent` - The parent message.
+    /// * `payload` - The index of the payload to commit to.
+    fn commit_payload(
+        &mut self,
+        builder: &mut TranscriptCommitConfigBuilder,
+        direction: Direction,
+        parent: &WebSocketMessage,
+        payload: &Idx,
+    ) -> Result<(), WebSocketCommitError> {
+        commit_idx(builder, direction, payload, "message payload")
+    }
+}
+
+fn commit_idx(
+    builder: &mut TranscriptCommitConfigBuilder,
+    direction: Direction,
+    idx: &Idx,
+    what: &str,
+) -> Result<(), WebSocketCommitError> {
+    builder.commit(idx, direction).map_err(|e| {
+        WebSocketCommitError::new_with_source(format!("failed to commit to {what}"), e)
+    })?;
+
+    Ok(())
+}
+
+/// The default WebSocket committer.
+#[derive(Debug, Default, Clone)]
+pub struct DefaultWebSocketCommitter {}
+
+impl WebSocketCommit for DefaultWebSocketCommitter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::websocket::tests::transcript;
+
+    #[test]
+    fn test_websocket_default_commit_transcript() {
+        let transcript = transcript();
+        let ws = WebSocketTranscript::parse(&transcript).unwrap();
+        let mut committer = DefaultWebSocketCommitter::default();
+        let mut builder = TranscriptCommitConfigBuilder::new(&transcript);
+
+        committer.commit_transcript(&mut builder, &ws).unwrap();
+
+        builder.build().unwrap();
+    }
+}
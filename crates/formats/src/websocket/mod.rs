@@ -0,0 +1,487 @@
+//! Tooling for working with WebSocket data.
+//!
+//! A WebSocket connection starts with an HTTP upgrade handshake, after which
+//! both directions of the transcript consist of WebSocket frames. The frames
+//! are parsed into messages, whose boundaries are exposed as transcript
+//! indices so that they can be committed to and revealed.
+//!
+//! # Masking
+//!
+//! Frames sent by the client are masked with a key which is included in the
+//! frame header. To read a masked payload, a verifier needs the masking key in
+//! addition to the payload, so [`WebSocketMessage::payload_idx`] includes the
+//! masking keys of the frames. Revealed payload data can be unmasked with
+//! [`WebSocketFrame::unmask`].
+//!
+//! # Ordering
+//!
+//! Messages are ordered by their first frame. Control frames may be
+//! interleaved with the frames of a fragmented message, in which case they
+//! follow that message even though they completed before it.
+
+mod commit;
+
+use std::ops::Range;
+
+pub use commit::{DefaultWebSocketCommitter, WebSocketCommit, WebSocketCommitError};
+use tlsn_core::transcript::{Idx, Transcript};
+use utils::range::{RangeSet, ToRangeSet, Union};
+
+use crate::http::{parse_request, parse_response, Request, Response};
+
+/// The opcode of a WebSocket frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    /// A continuation frame of a fragmented message.
+    Continuation,
+    /// A text frame.
+    Text,
+    /// A binary frame.
+    Binary,
+    /// A close frame.
+    Close,
+    /// A ping frame.
+    Ping,
+    /// A pong frame.
+    Pong,
+    /// A reserved opcode.
+    Reserved(u8),
+}
+
+impl Opcode {
+    fn from_u8(opcode: u8) -> Self {
+        match opcode {
+            0x0 => Opcode::Continuation,
+            0x1 => Opcode::Text,
+            0x2 => Opcode::Binary,
+            0x8 => Opcode::Close,
+            0x9 => Opcode::Ping,
+            0xa => Opcode::Pong,
+            opcode => Opcode::Reserved(opcode),
+        }
+    }
+
+    /// Returns whether the opcode is a control opcode.
+    pub fn is_control(&self) -> bool {
+        match self {
+            Opcode::Close | Opcode::Ping | Opcode::Pong => true,
+            Opcode::Reserved(opcode) => *opcode >= 0x8,
+            _ => false,
+        }
+    }
+}
+
+/// A WebSocket frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketFrame {
+    /// Whether this is the final frame of a message.
+    pub fin: bool,
+    /// The opcode of the frame.
+    pub opcode: Opcode,
+    /// The range of the entire frame.
+    pub span: Range<usize>,
+    /// The range of the masking key, if the frame is masked.
+    pub mask_key: Option<Range<usize>>,
+    /// The range of the payload.
+    pub payload: Range<usize>,
+}
+
+impl WebSocketFrame {
+    /// Returns the range of the frame header, excluding the masking key.
+    pub fn header(&self) -> Range<usize> {
+        self.span.start
+            ..self
+                .mask_key
+                .as_ref()
+                .map_or(self.payload.start, |key| key.start)
+    }
+
+    /// Returns the unmasked payload data.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The data of the direction of the frame, i.e. the sent or
+    ///   received data of the transcript.
+    pub fn unmask(&self, src: &[u8]) -> Vec<u8> {
+        let payload = &src[self.payload.clone()];
+        match &self.mask_key {
+            Some(key) => {
+                let key = &src[key.clone()];
+                payload
+                    .iter()
+                    .enumerate()
+                    .map(|(i, byte)| byte ^ key[i % 4])
+                    .collect()
+            }
+            None => payload.to_vec(),
+        }
+    }
+}
+
+/// A WebSocket message, consisting of one or more frames.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketMessage {
+    /// The opcode of the message, i.e. the opcode of the first frame.
+    pub opcode: Opcode,
+    /// The frames of the message.
+    pub frames: Vec<WebSocketFrame>,
+}
+
+impl WebSocketMessage {
+    /// Returns whether the final frame of the message is in the transcript.
+    pub fn is_complete(&self) -> bool {
+        self.frames.last().is_some_and(|frame| frame.fin)
+    }
+
+    /// Returns the index of the entire message.
+    pub fn idx(&self) -> Idx {
+        self.collect(|frame| vec![frame.span.clone()])
+    }
+
+    /// Returns the index of the frame headers, excluding the masking keys.
+    pub fn header_idx(&self) -> Idx {
+        self.collect(|frame| vec![frame.header()])
+    }
+
+    /// Returns the index of the payload, including the masking keys which are
+    /// needed to unmask it.
+    pub fn payload_idx(&self) -> Idx {
+        self.collect(|frame| {
+            frame
+                .mask_key
+                .iter()
+                .cloned()
+                .chain([frame.payload.clone()])
+                .collect()
+        })
+    }
+
+    /// Returns the unmasked payload data of the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The data of the direction of the message.
+    pub fn data(&self, src: &[u8]) -> Vec<u8> {
+        self.frames
+            .iter()
+            .flat_map(|frame| frame.unmask(src))
+            .collect()
+    }
+
+    /// Maps ranges of the message data to the index of the transcript,
+    /// including the masking keys of the frames which contain the data.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - The ranges relative to the start of the message data.
+    pub fn map(&self, ranges: &dyn ToRangeSet<usize>) -> Result<Idx, WebSocketParseError> {
+        let ranges = ranges.to_range_set();
+        let len = self.frames.iter().map(|frame| frame.payload.len()).sum();
+        if ranges.end().unwrap_or_default() > len {
+            return Err(WebSocketParseError::new(
+                "range is out of bounds of the message data",
+            ));
+        }
+
+        let mut mapped = RangeSet::default();
+        for range in ranges.iter_ranges() {
+            let mut data_start = 0;
+            for frame in &self.frames {
+                let data_end = data_start + frame.payload.len();
+                let start = range.start.max(data_start);
+                let end = range.end.min(data_end);
+                if start < end {
+                    let start = frame.payload.start + (start - data_start);
+                    let end = frame.payload.start + (end - data_start);
+                    mapped = mapped.union(&RangeSet::from(start..end));
+                    if let Some(key) = &frame.mask_key {
+                        mapped = mapped.union(&RangeSet::from(key.clone()));
+                    }
+                }
+                data_start = data_end;
+            }
+        }
+
+        Ok(Idx::new(mapped))
+    }
+
+    fn collect(&self, f: impl Fn(&WebSocketFrame) -> Vec<Range<usize>>) -> Idx {
+        Idx::new(
+            self.frames
+                .iter()
+                .flat_map(f)
+                .filter(|range| !range.is_empty())
+                .fold(RangeSet::default(), |ranges, range| {
+                    ranges.union(&RangeSet::from(range))
+                }),
+        )
+    }
+}
+
+/// A WebSocket transcript.
+#[derive(Debug)]
+pub struct WebSocketTranscript {
+    /// The upgrade request sent to the server.
+    pub handshake_request: Request,
+    /// The upgrade response received from the server.
+    pub handshake_response: Response,
+    /// The messages sent to the server, ordered by their first frame.
+    pub sent: Vec<WebSocketMessage>,
+    /// The messages received from the server, ordered by their first frame.
+    pub received: Vec<WebSocketMessage>,
+}
+
+impl WebSocketTranscript {
+    /// Parses the WebSocket transcript from the provided transcript.
+    ///
+    /// The transcript must start with the HTTP upgrade handshake.
+    pub fn parse(transcript: &Transcript) -> Result<Self, WebSocketParseError> {
+        let request_end = handshake_end(transcript.sent())?;
+        let response_end = handshake_end(transcript.received())?;
+
+        let handshake_request = parse_request(&transcript.sent()[..request_end])
+            .map_err(|e| WebSocketParseError::new(format!("invalid upgrade request: {e}")))?;
+        let handshake_response = parse_response(&transcript.received()[..response_end])
+            .map_err(|e| WebSocketParseError::new(format!("invalid upgrade response: {e}")))?;
+
+        if !transcript.received().starts_with(b"HTTP/1.1 101") {
+            return Err(WebSocketParseError::new("server did not switch protocols"));
+        }
+
+        Ok(Self {
+            handshake_request,
+            handshake_response,
+            sent: parse_messages(transcript.sent(), request_end)?,
+            received: parse_messages(transcript.received(), response_end)?,
+        })
+    }
+}
+
+/// Returns the end of the HTTP handshake message.
+fn handshake_end(src: &[u8]) -> Result<usize, WebSocketParseError> {
+    src.windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|pos| pos + 4)
+        .ok_or_else(|| WebSocketParseError::new("handshake is incomplete"))
+}
+
+/// Parses the messages of one direction, starting at `pos`.
+fn parse_messages(
+    src: &[u8],
+    mut pos: usize,
+) -> Result<Vec<WebSocketMessage>, WebSocketParseError> {
+    let mut messages: Vec<WebSocketMessage> = Vec::new();
+    // The index of a fragmented data message which is not yet complete.
+    let mut pending: Option<usize> = None;
+
+    while pos < src.len() {
+        let frame = parse_frame(src, pos)?;
+        pos = frame.span.end;
+
+        if frame.opcode.is_control() {
+            // Control frames may be interleaved with the frames of a
+            // fragmented message.
+            messages.push(WebSocketMessage {
+                opcode: frame.opcode,
+                frames: vec![frame],
+            });
+            continue;
+        }
+
+        // The message keeps the position of its first frame, so that the
+        // order of the messages does not depend on when they complete.
+        let idx = match (pending.take(), frame.opcode) {
+            (Some(idx), Opcode::Continuation) => {
+                messages[idx].frames.push(frame);
+                idx
+            }
+            (None, Opcode::Continuation) => {
+                return Err(WebSocketParseError::new("unexpected continuation frame"))
+            }
+            (Some(_), _) => {
+                return Err(WebSocketParseError::new(
+                    "fragmented message was interrupted",
+                ))
+            }
+            (None, opcode) => {
+                messages.push(WebSocketMessage {
+                    opcode,
+                    frames: vec![frame],
+                });
+                messages.len() - 1
+            }
+        };
+
+        if !messages[idx].is_complete() {
+            pending = Some(idx);
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Parses the frame starting at `pos`.
+fn parse_frame(src: &[u8], pos: usize) -> Result<WebSocketFrame, WebSocketParseError> {
+    let incomplete = || WebSocketParseError::new("frame is incomplete");
+
+    let header = src.get(pos..pos + 2).ok_or_else(incomplete)?;
+    let fin = header[0] & 0x80 != 0;
+    let opcode = Opcode::from_u8(header[0] & 0x0f);
+    let masked = header[1] & 0x80 != 0;
+
+    let mut cursor = pos + 2;
+    let len = match header[1] & 0x7f {
+        126 => {
+            let bytes = src.get(cursor..cursor + 2).ok_or_else(incomplete)?;
+            cursor += 2;
+            u16::from_be_bytes([bytes[0], bytes[1]]) as u64
+        }
+        127 => {
+            let bytes = src.get(cursor..cursor + 8).ok_or_else(incomplete)?;
+            cursor += 8;
+            u64::from_be_bytes(bytes.try_into().expect("slice has length 8"))
+        }
+        len => len as u64,
+    };
+
+    let mask_key = masked.then(|| cursor..cursor + 4);
+    if masked {
+        cursor += 4;
+    }
+
+    let payload_end = usize::try_from(len)
+        .ok()
+        .and_then(|len| cursor.checked_add(len))
+        .filter(|end| *end <= src.len())
+        .ok_or_else(incomplete)?;
+
+    Ok(WebSocketFrame {
+        fin,
+        opcode,
+        span: pos..payload_end,
+        mask_key,
+        payload: cursor..payload_end,
+    })
+}
+
+/// Error for [`WebSocketTranscript`].
+#[derive(Debug, thiserror::Error)]
+#[error("websocket parse error: {0}")]
+pub struct WebSocketParseError(String);
+
+impl WebSocketParseError {
+    fn new(msg: impl Into<String>) -> Self {
+        Self(msg.into())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    const REQUEST: &[u8] = b"GET /chat HTTP/1.1\r\nHost: example.com\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n";
+    const RESPONSE: &[u8] =
+        b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n";
+
+    fn frame(fin: bool, opcode: u8, mask: Option<[u8; 4]>, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![(fin as u8) << 7 | opcode];
+        let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+        if payload.len() < 126 {
+            frame.push(mask_bit | payload.len() as u8);
+        } else {
+            frame.push(mask_bit | 126);
+            frame.extend((payload.len() as u16).to_be_bytes());
+        }
+
+        match mask {
+            Some(key) => {
+                frame.extend(key);
+                frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ key[i % 4]));
+            }
+            None => frame.extend(payload),
+        }
+        frame
+    }
+
+    pub(crate) fn transcript() -> Transcript {
+        let key = [0x12, 0x34, 0x56, 0x78];
+
+        let mut sent = REQUEST.to_vec();
+        sent.extend(frame(true, 0x1, Some(key), b"{\"op\": \"subscribe\"}"));
+
+        let mut recv = RESPONSE.to_vec();
+        recv.extend(frame(false, 0x1, None, b"{\"price\": "));
+        recv.extend(frame(true, 0x9, None, b"ping"));
+        recv.extend(frame(true, 0x0, None, &[b'1'; 200]));
+        recv.extend(frame(true, 0x2, None, &[0xff; 3]));
+
+        Transcript::new(sent, recv)
+    }
+
+    #[test]
+    fn test_parse_transcript() {
+        let transcript = transcript();
+        let ws = WebSocketTranscript::parse(&transcript).unwrap();
+
+        assert_eq!(ws.sent.len(), 1);
+        let message = &ws.sent[0];
+        assert_eq!(message.opcode, Opcode::Text);
+        assert_eq!(message.data(transcript.sent()), b"{\"op\": \"subscribe\"}");
+        // The masking key is part of the payload index.
+        assert_eq!(message.payload_idx().len(), 4 + 19);
+
+        // The ping is interleaved with the fragmented message, which keeps the
+        // position of its first frame.
+        assert_eq!(ws.received.len(), 3);
+        let message = &ws.received[0];
+        assert_eq!(message.opcode, Opcode::Text);
+        assert_eq!(message.frames.len(), 2);
+        assert!(message.is_complete());
+        assert_eq!(message.data(transcript.received()).len(), 10 + 200);
+        assert_eq!(ws.received[1].opcode, Opcode::Ping);
+        assert_eq!(ws.received[1].data(transcript.received()), b"ping");
+        assert_eq!(ws.received[2].opcode, Opcode::Binary);
+
+        let starts = ws
+            .received
+            .iter()
+            .map(|message| message.frames[0].span.start)
+            .collect::<Vec<_>>();
+        assert!(starts.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_message_map() {
+        let transcript = transcript();
+        let ws = WebSocketTranscript::parse(&transcript).unwrap();
+
+        // `"op"` in the masked message.
+        let message = &ws.sent[0];
+        let idx = message.map(&(1..5)).unwrap();
+        let frame = &message.frames[0];
+        assert_eq!(idx.count(), 2);
+        assert_eq!(idx.start(), frame.mask_key.as_ref().unwrap().start);
+
+        // Data spanning both frames of the fragmented message.
+        let message = &ws.received[0];
+        let idx = message.map(&(8..12)).unwrap();
+        assert_eq!(idx.len(), 4);
+        assert_eq!(idx.count(), 2);
+
+        assert!(message.map(&(0..1000)).is_err());
+    }
+
+    #[test]
+    fn test_parse_transcript_invalid() {
+        let mut recv = RESPONSE.to_vec();
+        recv.extend(frame(true, 0x0, None, b"data"));
+        assert!(WebSocketTranscript::parse(&Transcript::new(REQUEST, recv)).is_err());
+
+        let recv = b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+        assert!(WebSocketTranscript::parse(&Transcript::new(REQUEST, recv)).is_err());
+
+        let mut recv = RESPONSE.to_vec();
+        recv.extend(&frame(true, 0x1, None, b"data")[..4]);
+        assert!(WebSocketTranscript::parse(&Transcript::new(REQUEST, recv)).is_err());
+    }
+}
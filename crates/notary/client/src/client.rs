@@ -33,6 +33,10 @@ pub struct NotarizationRequest {
     max_sent_data: usize,
     /// Maximum number of bytes that can be received.
     max_recv_data: usize,
+    /// Name of the server to notarize a session with, which is required by
+    /// notary servers that only allow some servers.
+    #[builder(setter(into, strip_option), default)]
+    server_name: Option<String>,
 }
 
 impl NotarizationRequest {
@@ -126,6 +130,10 @@ pub struct NotaryClient {
     /// in notary server.
     #[builder(setter(into, strip_option), default)]
     api_key: Option<String>,
+    /// JSON web token used to call notary server endpoints if JWT
+    /// authorization is enabled in notary server.
+    #[builder(setter(into, strip_option), default)]
+    token: Option<String>,
 }
 
 impl NotaryClientBuilder {
@@ -241,6 +249,7 @@ impl NotaryClient {
                     client_type: ClientType::Tcp,
                     max_sent_data: Some(notarization_request.max_sent_data),
                     max_recv_data: Some(notarization_request.max_recv_data),
+                    server_name: notarization_request.server_name.clone(),
                 })
                 .map_err(|err| {
                     error!("Failed to serialise http request for configuration");
@@ -262,6 +271,11 @@ impl NotaryClient {
                     configuration_request_builder.header("Authorization", api_key);
             }
 
+            if let Some(token) = &self.token {
+                configuration_request_builder = configuration_request_builder
+                    .header("Authorization", format!("Bearer {token}"));
+            }
+
             let configuration_request = configuration_request_builder
                 .body(Either::Left(Full::new(Bytes::from(
                     configuration_request_payload,
@@ -322,14 +336,21 @@ impl NotaryClient {
 
             // Send notarization request via HTTP, where the underlying TCP/TLS connection
             // will be extracted later.
+            let mut notarization_uri = format!(
+                "{http_scheme}://{}:{}/notarize?sessionId={}",
+                self.host, self.port, &configuration_response_payload_parsed.session_id
+            );
+            // The token is passed as a query parameter, as it cannot be passed as a
+            // header by WebSocket clients.
+            if let Some(token) = &self.token {
+                notarization_uri.push_str(&format!("&token={token}"));
+            }
+
             let notarization_request = Request::builder()
                 // Need to specify the session_id so that notary server knows the right
                 // configuration to use as the configuration is set in the previous
                 // HTTP call.
-                .uri(format!(
-                    "{http_scheme}://{}:{}/notarize?sessionId={}",
-                    self.host, self.port, &configuration_response_payload_parsed.session_id
-                ))
+                .uri(notarization_uri)
                 .method("GET")
                 .header("Host", &self.host)
                 .header("Connection", "Upgrade")
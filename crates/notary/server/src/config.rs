@@ -15,6 +15,55 @@ pub struct NotaryServerProperties {
     pub logging: LoggingProperties,
     /// Setting for authorization
     pub authorization: AuthorizationProperties,
+    /// Setting for storage of notarization sessions
+    #[serde(default)]
+    pub session_store: SessionStoreProperties,
+    /// Setting for limiting concurrent notarizations
+    #[serde(default)]
+    pub concurrency: ConcurrencyProperties,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ConcurrencyProperties {
+    /// Maximum number of notarizations that can run at the same time. If not
+    /// set, the number of concurrent notarizations is not limited
+    pub max_concurrent_notarizations: Option<usize>,
+    /// Maximum number of notarizations that can wait for one of the running
+    /// notarizations to finish, beyond which new sessions are rejected
+    pub max_queued_notarizations: usize,
+    /// Number of seconds after which rejected provers are asked to retry
+    pub retry_after: u64,
+}
+
+impl Default for ConcurrencyProperties {
+    fn default() -> Self {
+        Self {
+            max_concurrent_notarizations: None,
+            max_queued_notarizations: 0,
+            retry_after: 30,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct SessionStoreProperties {
+    /// Backend used to store sessions
+    pub backend: SessionStoreBackend,
+    /// Directory to store sessions in when using the file backend, which can be
+    /// shared by multiple notary instances
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionStoreBackend {
+    /// Sessions are kept in memory and lost on restart
+    #[default]
+    Memory,
+    /// Sessions are kept as files in a directory
+    File,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -22,8 +71,42 @@ pub struct NotaryServerProperties {
 pub struct AuthorizationProperties {
     /// Switch to turn on or off auth middleware
     pub enabled: bool,
+    /// Mechanism used to authorize requests
+    #[serde(default)]
+    pub mode: AuthorizationMode,
     /// File path of the whitelist API key csv
+    #[serde(default)]
     pub whitelist_csv_path: String,
+    /// Setting for authorization using JSON web tokens
+    #[serde(default)]
+    pub jwt: JwtProperties,
+}
+
+#[derive(Clone, Debug, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuthorizationMode {
+    /// Requests are authorized with API keys in the whitelist csv
+    #[default]
+    Whitelist,
+    /// Requests are authorized with JSON web tokens signed by an external
+    /// issuer
+    Jwt,
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct JwtProperties {
+    /// File path of the public key (in PEM format) used to verify tokens
+    pub public_key_pem_path: Option<String>,
+    /// Algorithm of the tokens signed with the public key, e.g. ES256
+    pub algorithm: Option<String>,
+    /// File path of the JSON web key set used to verify tokens, as an
+    /// alternative to a single public key
+    pub jwks_path: Option<String>,
+    /// Issuer that tokens must have been issued by, if any
+    pub issuer: Option<String>,
+    /// Audience that tokens must have been issued for, if any
+    pub audience: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -33,6 +116,31 @@ pub struct NotarizationProperties {
     pub max_sent_data: usize,
     /// Global limit for maximum number of bytes that can be received
     pub max_recv_data: usize,
+    /// Number of seconds after which a session id created by the /session API
+    /// expires if it has not been used to start a notarization
+    pub session_ttl: u64,
+    /// Number of seconds after the TLS connection after which attestations
+    /// expire. If not set, attestations do not expire
+    pub attestation_validity: Option<u64>,
+    /// Policy of the servers which the notary attests to connections with
+    #[serde(default)]
+    pub server_name_policy: ServerNamePolicyProperties,
+}
+
+/// Server names are matched against patterns, which are either an exact name,
+/// a wildcard name where each `*` matches any characters within a label, e.g.
+/// `*.example.com`, or a regular expression prefixed with `regex:`
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct ServerNamePolicyProperties {
+    /// Patterns of the server names which are allowed. If empty, all server
+    /// names which are not denied are allowed
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Patterns of the server names which are denied, which takes precedence
+    /// over the allowed ones
+    #[serde(default)]
+    pub deny: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -64,6 +172,10 @@ pub struct TLSProperties {
 pub struct NotarySigningKeyProperties {
     pub private_key_pem_path: String,
     pub public_key_pem_path: String,
+    /// File path of the keyring csv, which lists multiple keys with validity
+    /// windows to allow key rotation. If set, it is used instead of the single
+    /// key above
+    pub keyring_csv_path: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -10,10 +10,28 @@ use serde::{Deserialize, Serialize};
 pub struct InfoResponse {
     /// Current version of notary-server
     pub version: String,
-    /// Public key of the notary signing key
+    /// Public key of the notary signing key which is currently used
     pub public_key: String,
+    /// Current and previous notary signing keys
+    pub keys: Vec<KeyInfo>,
     /// Current git commit hash of notary-server
     pub git_commit_hash: String,
     /// Current git commit timestamp of notary-server
     pub git_commit_timestamp: String,
 }
+
+/// Notary signing key published by the /info API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyInfo {
+    /// Unique identifier of the key
+    pub key_id: String,
+    /// Public key of the key
+    pub public_key: String,
+    /// RFC 3339 timestamp from which the key is used for signing
+    pub not_before: Option<String>,
+    /// RFC 3339 timestamp from which the key is no longer used for signing
+    pub not_after: Option<String>,
+    /// Whether the key is currently used for signing
+    pub current: bool,
+}
@@ -1,14 +1,157 @@
+use chrono::{DateTime, Utc};
+use eyre::{eyre, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+
+use crate::store::Session;
+
+/// Number of seconds in an hour
+const HOUR: u64 = 60 * 60;
+/// Number of seconds in a day
+const DAY: u64 = 24 * HOUR;
 
 /// Structure of each whitelisted record of the API key whitelist for
 /// authorization purpose
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct AuthorizationWhitelistRecord {
     pub name: String,
     pub api_key: String,
     pub created_at: String,
+    /// Maximum number of sessions that can be created with this API key in
+    /// the last hour
+    #[serde(default)]
+    pub max_sessions_per_hour: Option<usize>,
+    /// Maximum number of sessions that can be created with this API key in
+    /// the last day
+    #[serde(default)]
+    pub max_sessions_per_day: Option<usize>,
+    /// Maximum data that can be sent by the prover in each session
+    #[serde(default)]
+    pub max_sent_data: Option<usize>,
+    /// Maximum data that can be received by the prover in each session
+    #[serde(default)]
+    pub max_recv_data: Option<usize>,
+    /// Semicolon separated names of the servers that provers can notarize
+    /// sessions with. If not set, any server is allowed
+    #[serde(default)]
+    pub allowed_server_names: Option<String>,
+    /// RFC 3339 timestamp from which the API key is no longer valid
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+impl AuthorizationWhitelistRecord {
+    /// Checks that the policy of the record is well-formed
+    pub fn validate(&self) -> Result<()> {
+        if let Some(expires_at) = &self.expires_at {
+            parse_timestamp(expires_at)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the hex encoded SHA-256 hash of the API key, which identifies
+    /// the key without revealing it
+    pub fn api_key_hash(&self) -> String {
+        hex::encode(Sha256::digest(self.api_key.as_bytes()))
+    }
+
+    /// Returns whether the API key has expired at the given time
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        match self.expires_at.as_deref().map(parse_timestamp) {
+            Some(Ok(expires_at)) => now >= expires_at,
+            // Records are validated when loaded, but never accept a key whose expiry
+            // cannot be determined
+            Some(Err(_)) => true,
+            None => false,
+        }
+    }
+
+    /// Returns whether provers can notarize sessions with the given server
+    pub fn allows_server_name(&self, server_name: Option<&str>) -> bool {
+        self.policy().allows_server_name(server_name)
+    }
+
+    /// Returns the policy of the API key
+    pub fn policy(&self) -> AuthorizationPolicy {
+        AuthorizationPolicy {
+            max_sent_data: self.max_sent_data,
+            max_recv_data: self.max_recv_data,
+            allowed_server_names: self.allowed_server_names.as_ref().map(|names| {
+                names
+                    .split(';')
+                    .map(|name| name.trim().to_string())
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Claims of the JSON web tokens accepted in the JWT authorization mode
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct JwtClaims {
+    /// Subject the token was issued to
+    #[serde(default)]
+    pub sub: Option<String>,
+    /// Unix timestamp in seconds from which the token is no longer valid
+    pub exp: u64,
+    /// Maximum data that can be sent by the prover in each session
+    #[serde(default)]
+    pub max_sent_data: Option<usize>,
+    /// Maximum data that can be received by the prover in each session
+    #[serde(default)]
+    pub max_recv_data: Option<usize>,
+    /// Names of the servers that provers can notarize sessions with, which
+    /// are checked against the server identity disclosed by the prover. If
+    /// not set, any server is allowed
+    #[serde(default)]
+    pub server_names: Option<Vec<String>>,
+}
+
+impl JwtClaims {
+    /// Returns the policy of the token
+    pub fn policy(&self) -> AuthorizationPolicy {
+        AuthorizationPolicy {
+            max_sent_data: self.max_sent_data,
+            max_recv_data: self.max_recv_data,
+            allowed_server_names: self.server_names.clone(),
+        }
+    }
+}
+
+/// Limits of the sessions which can be created with an API key or token
+#[derive(Clone, Debug, Default)]
+pub struct AuthorizationPolicy {
+    /// Maximum data that can be sent by the prover in each session
+    pub max_sent_data: Option<usize>,
+    /// Maximum data that can be received by the prover in each session
+    pub max_recv_data: Option<usize>,
+    /// Names of the servers that provers can notarize sessions with. If not
+    /// set, any server is allowed
+    pub allowed_server_names: Option<Vec<String>>,
+}
+
+impl AuthorizationPolicy {
+    /// Returns whether provers can notarize sessions with the given server
+    pub fn allows_server_name(&self, server_name: Option<&str>) -> bool {
+        let Some(allowed_server_names) = &self.allowed_server_names else {
+            return true;
+        };
+        let Some(server_name) = server_name else {
+            return false;
+        };
+        allowed_server_names
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(server_name))
+    }
+
+    /// Returns whether the policy allows a session
+    pub fn allows_session(&self, session: &Session) -> bool {
+        self.allows_server_name(session.server_name.as_deref())
+            && !matches!(self.max_sent_data, Some(max) if session.max_sent_data > max)
+            && !matches!(self.max_recv_data, Some(max) if session.max_recv_data > max)
+    }
 }
 
 /// Convert whitelist data structure from vector to hashmap using api_key as the
@@ -22,3 +165,130 @@ pub fn authorization_whitelist_vec_into_hashmap(
     });
     hashmap
 }
+
+/// Usage of the whitelisted API keys, which is kept apart from the whitelist
+/// so that it survives hot reloads of the whitelist
+#[derive(Debug, Default)]
+pub struct AuthorizationUsage {
+    /// Unix timestamps of the sessions created in the last day, per API key
+    sessions: HashMap<String, VecDeque<u64>>,
+}
+
+impl AuthorizationUsage {
+    /// Records a session created with the API key at the given unix timestamp,
+    /// unless it exceeds the session limits of the key
+    pub fn record_session(
+        &mut self,
+        record: &AuthorizationWhitelistRecord,
+        now: u64,
+    ) -> Result<(), String> {
+        let sessions = self.sessions.entry(record.api_key.clone()).or_default();
+        // Forget the sessions which no longer count towards any limit
+        while sessions
+            .front()
+            .is_some_and(|created_at| created_at.saturating_add(DAY) <= now)
+        {
+            sessions.pop_front();
+        }
+
+        if let Some(max) = record.max_sessions_per_day {
+            if sessions.len() >= max {
+                return Err("Maximum number of sessions per day is reached.".to_string());
+            }
+        }
+        if let Some(max) = record.max_sessions_per_hour {
+            let last_hour = sessions
+                .iter()
+                .filter(|created_at| created_at.saturating_add(HOUR) > now)
+                .count();
+            if last_hour >= max {
+                return Err("Maximum number of sessions per hour is reached.".to_string());
+            }
+        }
+
+        sessions.push_back(now);
+        Ok(())
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|err| eyre!("Invalid RFC 3339 timestamp {timestamp}: {err}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record() -> AuthorizationWhitelistRecord {
+        AuthorizationWhitelistRecord {
+            name: "test-name".to_string(),
+            api_key: "test-api-key".to_string(),
+            created_at: "2023-10-18T07:38:53Z".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_session_limits() {
+        let record = AuthorizationWhitelistRecord {
+            max_sessions_per_hour: Some(2),
+            max_sessions_per_day: Some(3),
+            ..record()
+        };
+        let mut usage = AuthorizationUsage::default();
+
+        assert!(usage.record_session(&record, 0).is_ok());
+        assert!(usage.record_session(&record, 1).is_ok());
+        assert!(usage.record_session(&record, 2).is_err());
+        // The hourly limit resets after an hour, but not the daily one
+        assert!(usage.record_session(&record, HOUR).is_ok());
+        assert!(usage.record_session(&record, 2 * HOUR).is_err());
+        assert!(usage.record_session(&record, DAY + 1).is_ok());
+    }
+
+    #[test]
+    fn test_record_expiry() {
+        let record = AuthorizationWhitelistRecord {
+            expires_at: Some("2024-01-01T00:00:00Z".to_string()),
+            ..record()
+        };
+        assert!(record.validate().is_ok());
+        assert!(!record.is_expired(parse_timestamp("2023-12-31T23:59:59Z").unwrap()));
+        assert!(record.is_expired(parse_timestamp("2024-01-01T00:00:00Z").unwrap()));
+
+        let record = AuthorizationWhitelistRecord {
+            expires_at: Some("tomorrow".to_string()),
+            ..record
+        };
+        assert!(record.validate().is_err());
+    }
+
+    #[test]
+    fn test_record_api_key_hash() {
+        let hash = record().api_key_hash();
+        assert_eq!(hash.len(), 64);
+        assert!(!hash.contains("test-api-key"));
+
+        let other = AuthorizationWhitelistRecord {
+            api_key: "other-api-key".to_string(),
+            ..record()
+        };
+        assert_ne!(other.api_key_hash(), hash);
+    }
+
+    #[test]
+    fn test_record_allowed_server_names() {
+        assert!(record().allows_server_name(None));
+
+        let record = AuthorizationWhitelistRecord {
+            allowed_server_names: Some("api.example.com; Example.org".to_string()),
+            ..record()
+        };
+        assert!(record.allows_server_name(Some("api.example.com")));
+        assert!(record.allows_server_name(Some("example.org")));
+        assert!(!record.allows_server_name(Some("example.com")));
+        assert!(!record.allows_server_name(None));
+    }
+}
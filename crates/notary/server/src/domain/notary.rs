@@ -3,9 +3,17 @@ use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
 };
-use tlsn_core::CryptoProvider;
 
-use crate::{config::NotarizationProperties, domain::auth::AuthorizationWhitelistRecord};
+use crate::{
+    config::NotarizationProperties,
+    domain::auth::{AuthorizationUsage, AuthorizationWhitelistRecord},
+    jwt::JwtAuthorizer,
+    keyring::Keyring,
+    limiter::NotarizationLimiter,
+    metrics::Metrics,
+    policy::ServerNameFilter,
+    store::SessionStore,
+};
 
 /// Response object of the /session API
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +32,10 @@ pub struct NotarizationSessionRequest {
     pub max_sent_data: Option<usize>,
     /// Maximum data that can be received by the prover
     pub max_recv_data: Option<usize>,
+    /// Name of the server that the prover will notarize a session with, which
+    /// is required if the API key only allows some servers
+    #[serde(default)]
+    pub server_name: Option<String>,
 }
 
 /// Request query of the /notarize API
@@ -32,6 +44,32 @@ pub struct NotarizationSessionRequest {
 pub struct NotarizationRequestQuery {
     /// Session id that is returned from /session API
     pub session_id: String,
+    /// Token which authorizes the notarization in the JWT authorization mode
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Response object of the /session/{id}/status API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotarizationSessionStatusResponse {
+    /// Status of the notarization
+    #[serde(flatten)]
+    pub status: SessionStatus,
+}
+
+/// Status of a notarization which has been started using the /notarize API
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum SessionStatus {
+    /// The notarization is waiting for a running notarization to finish
+    #[serde(rename_all = "camelCase")]
+    Queued {
+        /// Number of notarizations queued ahead of this one
+        position: usize,
+    },
+    /// The notarization is running
+    Running,
 }
 
 /// Types of client that the prover is using
@@ -47,25 +85,48 @@ pub enum ClientType {
 /// Global data that needs to be shared with the axum handlers
 #[derive(Clone, Debug)]
 pub struct NotaryGlobals {
-    pub crypto_provider: Arc<CryptoProvider>,
+    /// Keyring of the notary signing keys
+    pub keyring: Arc<Mutex<Keyring>>,
     pub notarization_config: NotarizationProperties,
-    /// A temporary storage to store session_id
-    pub store: Arc<Mutex<HashMap<String, ()>>>,
+    /// Storage of the sessions created by the /session API
+    pub store: Arc<dyn SessionStore>,
     /// Whitelist of API keys for authorization purpose
     pub authorization_whitelist: Option<Arc<Mutex<HashMap<String, AuthorizationWhitelistRecord>>>>,
+    /// Usage of the whitelisted API keys, which survives reloads of the
+    /// whitelist
+    pub authorization_usage: Arc<Mutex<AuthorizationUsage>>,
+    /// Verifier of the tokens in the JWT authorization mode
+    pub jwt_authorizer: Option<Arc<JwtAuthorizer>>,
+    /// Metrics exposed by the /metrics API
+    pub metrics: Metrics,
+    /// Limiter of concurrent notarizations
+    pub limiter: NotarizationLimiter,
+    /// Policy of the servers which the notary attests to connections with, if
+    /// any is configured
+    pub server_name_policy: Option<Arc<ServerNameFilter>>,
 }
 
 impl NotaryGlobals {
     pub fn new(
-        crypto_provider: Arc<CryptoProvider>,
+        keyring: Arc<Mutex<Keyring>>,
         notarization_config: NotarizationProperties,
+        store: Arc<dyn SessionStore>,
         authorization_whitelist: Option<Arc<Mutex<HashMap<String, AuthorizationWhitelistRecord>>>>,
+        jwt_authorizer: Option<Arc<JwtAuthorizer>>,
+        metrics: Metrics,
+        limiter: NotarizationLimiter,
+        server_name_policy: Option<Arc<ServerNameFilter>>,
     ) -> Self {
         Self {
-            crypto_provider,
+            keyring,
             notarization_config,
-            store: Default::default(),
+            store,
             authorization_whitelist,
+            authorization_usage: Default::default(),
+            jwt_authorizer,
+            metrics,
+            limiter,
+            server_name_policy,
         }
     }
 }
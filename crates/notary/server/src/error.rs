@@ -1,4 +1,4 @@
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum_core::response::{IntoResponse as AxumCoreIntoResponse, Response};
 use eyre::Report;
 use std::error::Error;
@@ -18,11 +18,21 @@ pub enum NotaryServerError {
     BadProverRequest(String),
     #[error("Unauthorized request from prover: {0}")]
     UnauthorizedProverRequest(String),
+    #[error("Too many requests from prover: {0}")]
+    TooManyProverRequests(String),
+    #[error("Notary server is busy: {0}")]
+    Busy(String, u64),
+    #[error("Server name is not permitted by the notary policy: {0}")]
+    ServerNameRejected(Box<dyn Error + Send + 'static>),
 }
 
 impl From<VerifierError> for NotaryServerError {
     fn from(error: VerifierError) -> Self {
-        Self::Notarization(Box::new(error))
+        if error.is_server_name_rejected() {
+            Self::ServerNameRejected(Box::new(error))
+        } else {
+            Self::Notarization(Box::new(error))
+        }
     }
 }
 
@@ -50,6 +60,22 @@ impl AxumCoreIntoResponse for NotaryServerError {
                 unauthorized_request_error.to_string(),
             )
                 .into_response(),
+            too_many_requests_error @ NotaryServerError::TooManyProverRequests(_) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                too_many_requests_error.to_string(),
+            )
+                .into_response(),
+            server_name_rejected_error @ NotaryServerError::ServerNameRejected(_) => (
+                StatusCode::FORBIDDEN,
+                server_name_rejected_error.to_string(),
+            )
+                .into_response(),
+            busy_error @ NotaryServerError::Busy(_, retry_after) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                busy_error.to_string(),
+            )
+                .into_response(),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Something wrong happened.",
@@ -0,0 +1,179 @@
+//! Authorization using JSON web tokens.
+//!
+//! Tokens are signed by an external issuer, and verified against either a
+//! single public key or a JSON web key set configured in the notary server.
+
+use std::{fs, str::FromStr};
+
+use eyre::{eyre, Result};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+
+use crate::{config::JwtProperties, domain::auth::JwtClaims};
+
+/// Algorithm of tokens signed with a configured public key, if not set
+const DEFAULT_ALGORITHM: Algorithm = Algorithm::ES256;
+
+/// Keys used to verify tokens
+enum Keys {
+    PublicKey(DecodingKey, Algorithm),
+    Jwks(JwkSet),
+}
+
+/// Verifier of JSON web tokens.
+pub struct JwtAuthorizer {
+    keys: Keys,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl std::fmt::Debug for JwtAuthorizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtAuthorizer")
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish_non_exhaustive()
+    }
+}
+
+impl JwtAuthorizer {
+    /// Loads the keys used to verify tokens.
+    pub fn load(config: &JwtProperties) -> Result<Self> {
+        let keys = match (&config.public_key_pem_path, &config.jwks_path) {
+            (Some(public_key_pem_path), None) => {
+                let algorithm = match &config.algorithm {
+                    Some(algorithm) => Algorithm::from_str(algorithm)
+                        .map_err(|err| eyre!("Invalid JWT algorithm {algorithm}: {err}"))?,
+                    None => DEFAULT_ALGORITHM,
+                };
+                let pem = fs::read(public_key_pem_path)
+                    .map_err(|err| eyre!("Failed to read JWT public key: {err}"))?;
+                let key = match algorithm {
+                    Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(&pem),
+                    Algorithm::EdDSA => DecodingKey::from_ed_pem(&pem),
+                    Algorithm::RS256
+                    | Algorithm::RS384
+                    | Algorithm::RS512
+                    | Algorithm::PS256
+                    | Algorithm::PS384
+                    | Algorithm::PS512 => DecodingKey::from_rsa_pem(&pem),
+                    _ => return Err(eyre!("JWT algorithm {algorithm:?} is not supported")),
+                }
+                .map_err(|err| eyre!("Failed to parse JWT public key: {err}"))?;
+
+                Keys::PublicKey(key, algorithm)
+            }
+            (None, Some(jwks_path)) => {
+                let jwks = fs::read(jwks_path)
+                    .map_err(|err| eyre!("Failed to read JWKS file: {err}"))?;
+                let jwks: JwkSet = serde_json::from_slice(&jwks)
+                    .map_err(|err| eyre!("Failed to parse JWKS file: {err}"))?;
+
+                Keys::Jwks(jwks)
+            }
+            _ => {
+                return Err(eyre!(
+                    "Exactly one of public-key-pem-path and jwks-path must be configured for JWT authorization"
+                ))
+            }
+        };
+
+        Ok(Self {
+            keys,
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+        })
+    }
+
+    /// Verifies a token, returning its claims.
+    pub fn verify(&self, token: &str) -> Result<JwtClaims> {
+        let (key, algorithm) = match &self.keys {
+            Keys::PublicKey(key, algorithm) => (key.clone(), *algorithm),
+            Keys::Jwks(jwks) => {
+                let header = decode_header(token).map_err(|err| eyre!("Malformed token: {err}"))?;
+                let jwk = match &header.kid {
+                    Some(kid) => jwks.find(kid),
+                    // Tokens without a key id can only be verified with a single key
+                    None if jwks.keys.len() == 1 => jwks.keys.first(),
+                    None => None,
+                }
+                .ok_or_else(|| eyre!("Token is not signed with a known key"))?;
+
+                (
+                    DecodingKey::from_jwk(jwk).map_err(|err| eyre!("Invalid JWK: {err}"))?,
+                    jwk_algorithm(jwk, header.alg)?,
+                )
+            }
+        };
+
+        let mut validation = Validation::new(algorithm);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        decode::<JwtClaims>(token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|err| eyre!("Invalid token: {err}"))
+    }
+}
+
+/// Returns the algorithm which tokens signed with the key must use.
+fn jwk_algorithm(jwk: &Jwk, header_algorithm: Algorithm) -> Result<Algorithm> {
+    // Never let the token choose the algorithm if the key specifies one
+    let algorithm = match jwk.common.key_algorithm {
+        Some(key_algorithm) => Algorithm::from_str(&key_algorithm.to_string())
+            .map_err(|err| eyre!("JWK algorithm {key_algorithm} is not supported: {err}"))?,
+        None => header_algorithm,
+    };
+    // Symmetric algorithms would let anyone who knows the key issue tokens
+    if matches!(
+        algorithm,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        return Err(eyre!("Symmetric JWT algorithms are not supported"));
+    }
+    Ok(algorithm)
+}
+
+#[cfg(test)]
+mod test {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    use super::*;
+
+    fn token(claims: &JwtClaims) -> String {
+        let key = EncodingKey::from_ec_pem(&fs::read("./fixture/auth/jwt.key").unwrap()).unwrap();
+        encode(&Header::new(Algorithm::ES256), claims, &key).unwrap()
+    }
+
+    #[test]
+    fn test_verify_token() {
+        let authorizer = JwtAuthorizer::load(&JwtProperties {
+            public_key_pem_path: Some("./fixture/auth/jwt.pub".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let claims = JwtClaims {
+            sub: Some("test-subject".to_string()),
+            exp: u64::MAX / 2,
+            max_sent_data: Some(4096),
+            ..Default::default()
+        };
+
+        let verified = authorizer.verify(&token(&claims)).unwrap();
+        assert_eq!(verified.sub, claims.sub);
+        assert_eq!(verified.max_sent_data, Some(4096));
+
+        // Expired tokens are rejected
+        let expired = JwtClaims { exp: 1, ..claims };
+        assert!(authorizer.verify(&token(&expired)).is_err());
+        assert!(authorizer.verify("not-a-token").is_err());
+    }
+}
@@ -0,0 +1,277 @@
+//! Keyring of attestation keys, which allows the notary to rotate its signing
+//! key without downtime.
+//!
+//! Each key has a validity window. Attestations are signed with the current
+//! key, which is the most recent key that is valid, while the verifying keys of
+//! the current and previous keys remain published so that verifiers can still
+//! check attestations signed before a rotation.
+
+use std::{collections::HashSet, fmt, sync::Arc};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use eyre::{ensure, eyre, Result};
+use pkcs8::DecodePrivateKey;
+use serde::Deserialize;
+use tlsn_core::CryptoProvider;
+use tracing::debug;
+use zeroize::Zeroize;
+
+use crate::{
+    config::NotarySigningKeyProperties, domain::KeyInfo, signing::AttestationKey,
+    util::parse_csv_file,
+};
+
+/// Key id of the key configured without a keyring
+const DEFAULT_KEY_ID: &str = "default";
+
+/// Structure of each record of the keyring csv
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct KeyringRecord {
+    /// Unique identifier of the key
+    pub key_id: String,
+    pub private_key_pem_path: String,
+    pub public_key_pem_path: String,
+    /// RFC 3339 timestamp from which the key can be used, empty if the key is
+    /// valid from the start
+    pub not_before: Option<String>,
+    /// RFC 3339 timestamp from which the key can no longer be used, empty if
+    /// the key does not expire
+    pub not_after: Option<String>,
+}
+
+/// Attestation key in the keyring
+struct KeyringEntry {
+    key_id: String,
+    public_key: String,
+    not_before: Option<DateTime<Utc>>,
+    not_after: Option<DateTime<Utc>>,
+    crypto_provider: Arc<CryptoProvider>,
+}
+
+impl KeyringEntry {
+    fn load(record: &KeyringRecord) -> Result<Self> {
+        let not_before = record
+            .not_before
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?;
+        let not_after = record
+            .not_after
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?;
+        if let (Some(not_before), Some(not_after)) = (not_before, not_after) {
+            ensure!(
+                not_before < not_after,
+                "Validity window of key {} is empty",
+                record.key_id
+            );
+        }
+
+        let attestation_key = load_attestation_key(&record.private_key_pem_path)?;
+        let public_key = std::fs::read_to_string(&record.public_key_pem_path).map_err(|err| {
+            eyre!(
+                "Failed to load public key of notary signing key {}: {err}",
+                record.key_id
+            )
+        })?;
+
+        Ok(Self {
+            key_id: record.key_id.clone(),
+            public_key,
+            not_before,
+            not_after,
+            crypto_provider: Arc::new(build_crypto_provider(attestation_key)),
+        })
+    }
+
+    /// Returns whether the key has become valid at the given time.
+    fn is_published(&self, now: DateTime<Utc>) -> bool {
+        !matches!(self.not_before, Some(not_before) if now < not_before)
+    }
+
+    /// Returns whether the key can be used to sign at the given time.
+    fn is_valid(&self, now: DateTime<Utc>) -> bool {
+        self.is_published(now) && !matches!(self.not_after, Some(not_after) if now >= not_after)
+    }
+}
+
+impl fmt::Debug for KeyringEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyringEntry")
+            .field("key_id", &self.key_id)
+            .field("not_before", &self.not_before)
+            .field("not_after", &self.not_after)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Keyring of attestation keys with validity windows
+#[derive(Debug)]
+pub struct Keyring {
+    entries: Vec<KeyringEntry>,
+}
+
+impl Keyring {
+    /// Loads the keyring csv if it is configured, otherwise a keyring with the
+    /// single configured key which is always valid.
+    pub fn load(config: &NotarySigningKeyProperties) -> Result<Self> {
+        let records = match &config.keyring_csv_path {
+            Some(keyring_csv_path) => parse_csv_file::<KeyringRecord>(keyring_csv_path)
+                .map_err(|err| eyre!("Failed to parse keyring csv: {:?}", err))?,
+            None => vec![KeyringRecord {
+                key_id: DEFAULT_KEY_ID.to_string(),
+                private_key_pem_path: config.private_key_pem_path.clone(),
+                public_key_pem_path: config.public_key_pem_path.clone(),
+                not_before: None,
+                not_after: None,
+            }],
+        };
+
+        let mut key_ids = HashSet::new();
+        for record in &records {
+            ensure!(
+                key_ids.insert(&record.key_id),
+                "Duplicate key id in keyring: {}",
+                record.key_id
+            );
+        }
+
+        let keyring = Self {
+            entries: records
+                .iter()
+                .map(KeyringEntry::load)
+                .collect::<Result<_>>()?,
+        };
+
+        ensure!(
+            keyring.current_entry(Utc::now()).is_some(),
+            "Keyring does not contain a key which is currently valid"
+        );
+
+        debug!(?keyring, "Successfully loaded notary server's keyring!");
+
+        Ok(keyring)
+    }
+
+    /// Returns the crypto provider which signs with the current key.
+    pub fn crypto_provider(&self, now: DateTime<Utc>) -> Option<Arc<CryptoProvider>> {
+        self.current_entry(now)
+            .map(|entry| entry.crypto_provider.clone())
+    }
+
+    /// Returns the public key of the current key.
+    pub fn public_key(&self, now: DateTime<Utc>) -> Option<&str> {
+        self.current_entry(now)
+            .map(|entry| entry.public_key.as_str())
+    }
+
+    /// Returns the current and previous keys, which verifiers may encounter in
+    /// attestations.
+    pub fn published_keys(&self, now: DateTime<Utc>) -> Vec<KeyInfo> {
+        let current_key_id = self.current_entry(now).map(|entry| entry.key_id.as_str());
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_published(now))
+            .map(|entry| KeyInfo {
+                key_id: entry.key_id.clone(),
+                public_key: entry.public_key.clone(),
+                not_before: entry.not_before.map(format_timestamp),
+                not_after: entry.not_after.map(format_timestamp),
+                current: Some(entry.key_id.as_str()) == current_key_id,
+            })
+            .collect()
+    }
+
+    /// Returns the valid key which became valid most recently.
+    fn current_entry(&self, now: DateTime<Utc>) -> Option<&KeyringEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_valid(now))
+            .max_by_key(|entry| entry.not_before)
+    }
+}
+
+/// Load notary signing key for attestations from static file
+fn load_attestation_key(private_key_pem_path: &str) -> Result<AttestationKey> {
+    debug!("Loading notary server's signing key");
+
+    let mut pem = std::fs::read_to_string(private_key_pem_path)
+        .map_err(|err| eyre!("Failed to read notary signing key file: {err}"))?;
+
+    let key = AttestationKey::from_pkcs8_pem(&pem)
+        .map_err(|err| eyre!("Failed to load notary signing key for notarization: {err}"))?;
+
+    pem.zeroize();
+
+    debug!("Successfully loaded notary server's signing key!");
+    Ok(key)
+}
+
+fn build_crypto_provider(attestation_key: AttestationKey) -> CryptoProvider {
+    let mut provider = CryptoProvider::default();
+    provider.signer.set_signer(attestation_key.into_signer());
+    provider
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|err| eyre!("Invalid RFC 3339 timestamp {timestamp}: {err}"))
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_load_keyring_single_key() {
+        let config = NotarySigningKeyProperties {
+            private_key_pem_path: "./fixture/notary/notary.key".to_string(),
+            public_key_pem_path: "./fixture/notary/notary.pub".to_string(),
+            keyring_csv_path: None,
+        };
+        let keyring = Keyring::load(&config).unwrap();
+
+        let now = Utc::now();
+        let keys = keyring.published_keys(now);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key_id, DEFAULT_KEY_ID);
+        assert!(keys[0].current);
+        assert!(keyring.crypto_provider(now).is_some());
+    }
+
+    #[test]
+    fn test_keyring_rotation() {
+        let config = NotarySigningKeyProperties {
+            keyring_csv_path: Some("./fixture/notary/keyring.csv".to_string()),
+            ..Default::default()
+        };
+        let keyring = Keyring::load(&config).unwrap();
+
+        // Before the rotation only the first key is published
+        let now = parse_timestamp("2030-01-01T00:00:00Z").unwrap();
+        let keys = keyring.published_keys(now);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key_id, "notary-2024");
+        assert!(keys[0].current);
+
+        // After the rotation the next key is current, while the previous key
+        // remains published
+        let now = parse_timestamp("2099-06-01T00:00:00Z").unwrap();
+        let keys = keyring.published_keys(now);
+        assert_eq!(keys.len(), 2);
+        assert!(!keys[0].current);
+        assert_eq!(keys[1].key_id, "notary-2099");
+        assert!(keys[1].current);
+        assert_eq!(
+            keyring.public_key(now),
+            Some(std::fs::read_to_string("./fixture/notary/notary-next.pub").unwrap()).as_deref()
+        );
+    }
+}
@@ -1,22 +1,35 @@
 mod config;
 mod domain;
 mod error;
+mod jwt;
+mod keyring;
+mod limiter;
+mod metrics;
 mod middleware;
+mod policy;
 mod server;
 mod server_tracing;
 mod service;
 mod signing;
+mod store;
 mod util;
 
 pub use config::{
-    AuthorizationProperties, LoggingProperties, NotarizationProperties, NotaryServerProperties,
-    NotarySigningKeyProperties, ServerProperties, TLSProperties,
+    AuthorizationMode, AuthorizationProperties, ConcurrencyProperties, JwtProperties,
+    LoggingProperties, NotarizationProperties, NotaryServerProperties, NotarySigningKeyProperties,
+    ServerNamePolicyProperties, ServerProperties, SessionStoreBackend, SessionStoreProperties,
+    TLSProperties,
 };
 pub use domain::{
+    auth::JwtClaims,
     cli::CliFields,
-    notary::{ClientType, NotarizationSessionRequest, NotarizationSessionResponse},
+    notary::{
+        ClientType, NotarizationSessionRequest, NotarizationSessionResponse,
+        NotarizationSessionStatusResponse, SessionStatus,
+    },
 };
 pub use error::NotaryServerError;
 pub use server::{read_pem_file, run_server};
 pub use server_tracing::init_tracing;
+pub use store::{FileSessionStore, MemorySessionStore, Session, SessionStore};
 pub use util::parse_config_file;
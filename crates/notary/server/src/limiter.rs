@@ -0,0 +1,245 @@
+//! Limiting of concurrent notarizations.
+//!
+//! Each notarization is CPU and memory heavy, so only a configured number of
+//! them run at the same time. Provers connecting to /notarize while all slots
+//! are taken wait in a bounded first-in first-out queue, and are rejected once
+//! the queue is full.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::Notify;
+
+use crate::{config::ConcurrencyProperties, domain::notary::SessionStatus};
+
+/// Limits the number of concurrent notarizations.
+#[derive(Clone, Debug)]
+pub struct NotarizationLimiter {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    config: ConcurrencyProperties,
+    state: Mutex<State>,
+    /// Notified whenever a slot or the front of the queue may have changed
+    notify: Notify,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Session ids of the running notarizations
+    running: HashSet<String>,
+    /// Session ids of the queued notarizations, in order of arrival
+    queue: VecDeque<String>,
+}
+
+impl State {
+    fn has_free_slot(&self, config: &ConcurrencyProperties) -> bool {
+        match config.max_concurrent_notarizations {
+            Some(max) => self.running.len() < max,
+            None => true,
+        }
+    }
+
+    fn is_full(&self, config: &ConcurrencyProperties) -> bool {
+        !self.has_free_slot(config) && self.queue.len() >= config.max_queued_notarizations
+    }
+}
+
+impl NotarizationLimiter {
+    /// Creates a new limiter.
+    pub fn new(config: ConcurrencyProperties) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                config,
+                state: Mutex::new(State::default()),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// Returns the number of seconds after which rejected provers should
+    /// retry.
+    pub fn retry_after(&self) -> u64 {
+        self.inner.config.retry_after
+    }
+
+    /// Returns whether a new notarization would be rejected, i.e. all slots
+    /// are taken and the queue is full.
+    pub fn is_full(&self) -> bool {
+        self.inner.state.lock().unwrap().is_full(&self.inner.config)
+    }
+
+    /// Adds a notarization to the end of the queue, returning a ticket to wait
+    /// for a free slot, or `None` if the limiter is full.
+    pub fn enqueue(&self, session_id: &str) -> Option<QueueTicket> {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.is_full(&self.inner.config) {
+            return None;
+        }
+        state.queue.push_back(session_id.to_string());
+
+        Some(QueueTicket {
+            limiter: self.clone(),
+            session_id: session_id.to_string(),
+            started: false,
+        })
+    }
+
+    /// Returns the status of a notarization, or `None` if it is neither
+    /// running nor queued.
+    pub fn status(&self, session_id: &str) -> Option<SessionStatus> {
+        let state = self.inner.state.lock().unwrap();
+        if state.running.contains(session_id) {
+            return Some(SessionStatus::Running);
+        }
+        state
+            .queue
+            .iter()
+            .position(|queued| queued == session_id)
+            .map(|position| SessionStatus::Queued { position })
+    }
+
+    /// Starts the notarization if it is at the front of the queue and a slot
+    /// is free.
+    fn try_start(&self, session_id: &str) -> bool {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.queue.front().map(String::as_str) != Some(session_id)
+            || !state.has_free_slot(&self.inner.config)
+        {
+            return false;
+        }
+        state.queue.pop_front();
+        state.running.insert(session_id.to_string());
+        // The next notarization in the queue may be able to start as well
+        self.inner.notify.notify_waiters();
+
+        true
+    }
+}
+
+/// A notarization waiting in the queue.
+///
+/// The notarization is removed from the queue if the ticket is dropped before
+/// the notarization starts.
+#[derive(Debug)]
+pub struct QueueTicket {
+    limiter: NotarizationLimiter,
+    session_id: String,
+    started: bool,
+}
+
+impl QueueTicket {
+    /// Waits until the notarization can start, returning a permit which frees
+    /// the slot when dropped.
+    pub async fn start(mut self) -> NotarizationPermit {
+        loop {
+            let notified = self.limiter.inner.notify.notified();
+            tokio::pin!(notified);
+            // Register for notifications before checking, so that a slot freed in
+            // between is not missed
+            notified.as_mut().enable();
+
+            if self.limiter.try_start(&self.session_id) {
+                self.started = true;
+                return NotarizationPermit {
+                    limiter: self.limiter.clone(),
+                    session_id: std::mem::take(&mut self.session_id),
+                };
+            }
+
+            notified.await;
+        }
+    }
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        if self.started {
+            return;
+        }
+        let mut state = self.limiter.inner.state.lock().unwrap();
+        state.queue.retain(|queued| queued != &self.session_id);
+        self.limiter.inner.notify.notify_waiters();
+    }
+}
+
+/// A running notarization, which frees its slot when dropped.
+#[derive(Debug)]
+pub struct NotarizationPermit {
+    limiter: NotarizationLimiter,
+    session_id: String,
+}
+
+impl Drop for NotarizationPermit {
+    fn drop(&mut self) {
+        let mut state = self.limiter.inner.state.lock().unwrap();
+        state.running.remove(&self.session_id);
+        self.limiter.inner.notify.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn limiter(max_concurrent: usize, max_queued: usize) -> NotarizationLimiter {
+        NotarizationLimiter::new(ConcurrencyProperties {
+            max_concurrent_notarizations: Some(max_concurrent),
+            max_queued_notarizations: max_queued,
+            retry_after: 30,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_limiter_queues_and_rejects() {
+        let limiter = limiter(1, 1);
+
+        let first = limiter.enqueue("first").unwrap().start().await;
+        assert_eq!(limiter.status("first"), Some(SessionStatus::Running));
+
+        let second = limiter.enqueue("second").unwrap();
+        assert_eq!(
+            limiter.status("second"),
+            Some(SessionStatus::Queued { position: 0 })
+        );
+        assert!(limiter.is_full());
+        assert!(limiter.enqueue("third").is_none());
+
+        let second = tokio::spawn(second.start());
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!second.is_finished());
+
+        drop(first);
+        let _second = tokio::time::timeout(Duration::from_secs(1), second)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(limiter.status("first"), None);
+        assert_eq!(limiter.status("second"), Some(SessionStatus::Running));
+    }
+
+    #[tokio::test]
+    async fn test_limiter_dropped_ticket_leaves_queue() {
+        let limiter = limiter(1, 2);
+
+        let _first = limiter.enqueue("first").unwrap().start().await;
+        let second = limiter.enqueue("second").unwrap();
+        let _third = limiter.enqueue("third").unwrap();
+        assert_eq!(
+            limiter.status("third"),
+            Some(SessionStatus::Queued { position: 1 })
+        );
+
+        drop(second);
+        assert_eq!(
+            limiter.status("third"),
+            Some(SessionStatus::Queued { position: 0 })
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Prometheus metrics exposed by the /metrics API.
+
+use std::{
+    fmt::{self, Debug},
+    time::Instant,
+};
+
+use eyre::eyre;
+use prometheus::{
+    exponential_buckets, Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+use tlsn_core::connection::TranscriptLength;
+
+use crate::{domain::notary::ClientType, error::NotaryServerError, store::Session};
+
+/// Metrics of the notary server.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    sessions_started: IntCounterVec,
+    sessions_completed: IntCounterVec,
+    sessions_failed: IntCounterVec,
+    active_sessions: IntGauge,
+    notarization_duration: HistogramVec,
+    bytes_transferred: HistogramVec,
+    max_data: HistogramVec,
+    auth_rejections: IntCounterVec,
+}
+
+impl Metrics {
+    /// Creates the metrics and registers them in a new registry.
+    pub fn new() -> Result<Self, NotaryServerError> {
+        let registry = Registry::new_custom(Some("notary".to_string()), None)
+            .map_err(|err| eyre!("Failed to create metrics registry: {err}"))?;
+
+        let sessions_started = IntCounterVec::new(
+            Opts::new("sessions_started_total", "Number of notarizations started"),
+            &["client_type"],
+        )
+        .map_err(metric_error)?;
+        let sessions_completed = IntCounterVec::new(
+            Opts::new(
+                "sessions_completed_total",
+                "Number of notarizations completed successfully",
+            ),
+            &["client_type"],
+        )
+        .map_err(metric_error)?;
+        let sessions_failed = IntCounterVec::new(
+            Opts::new("sessions_failed_total", "Number of notarizations failed"),
+            &["client_type"],
+        )
+        .map_err(metric_error)?;
+        let active_sessions = IntGauge::new(
+            "active_sessions",
+            "Number of notarizations currently in progress",
+        )
+        .map_err(metric_error)?;
+        let notarization_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "notarization_duration_seconds",
+                "Duration of notarizations in seconds",
+            )
+            .buckets(exponential_buckets(1.0, 2.0, 10).map_err(metric_error)?),
+            &["client_type", "outcome"],
+        )
+        .map_err(metric_error)?;
+        let bytes_transferred = HistogramVec::new(
+            HistogramOpts::new(
+                "bytes_transferred",
+                "Number of TLS application data bytes transferred by the prover per notarization",
+            )
+            .buckets(exponential_buckets(256.0, 4.0, 8).map_err(metric_error)?),
+            &["direction"],
+        )
+        .map_err(metric_error)?;
+        let max_data = HistogramVec::new(
+            HistogramOpts::new(
+                "max_data_bytes",
+                "Maximum data that can be transferred by the prover per notarization",
+            )
+            .buckets(exponential_buckets(256.0, 4.0, 8).map_err(metric_error)?),
+            &["direction"],
+        )
+        .map_err(metric_error)?;
+        let auth_rejections = IntCounterVec::new(
+            Opts::new(
+                "auth_rejections_total",
+                "Number of requests rejected by the authorization middleware",
+            ),
+            &["reason"],
+        )
+        .map_err(metric_error)?;
+
+        for collector in [
+            Box::new(sessions_started.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(sessions_completed.clone()),
+            Box::new(sessions_failed.clone()),
+            Box::new(active_sessions.clone()),
+            Box::new(notarization_duration.clone()),
+            Box::new(bytes_transferred.clone()),
+            Box::new(max_data.clone()),
+            Box::new(auth_rejections.clone()),
+        ] {
+            registry.register(collector).map_err(metric_error)?;
+        }
+
+        Ok(Self {
+            registry,
+            sessions_started,
+            sessions_completed,
+            sessions_failed,
+            active_sessions,
+            notarization_duration,
+            bytes_transferred,
+            max_data,
+            auth_rejections,
+        })
+    }
+
+    /// Records the start of a notarization, returning a handle to record its
+    /// outcome.
+    pub fn start_session(&self, client_type: ClientType, session: &Session) -> SessionMetrics {
+        let client_type = client_type_label(&client_type);
+
+        self.sessions_started
+            .with_label_values(&[client_type])
+            .inc();
+        self.active_sessions.inc();
+        self.max_data
+            .with_label_values(&["sent"])
+            .observe(session.max_sent_data as f64);
+        self.max_data
+            .with_label_values(&["recv"])
+            .observe(session.max_recv_data as f64);
+
+        SessionMetrics {
+            metrics: self.clone(),
+            client_type,
+            start: Instant::now(),
+            finished: false,
+        }
+    }
+
+    /// Records a request rejected by the authorization middleware.
+    pub fn auth_rejected(&self, reason: &str) {
+        self.auth_rejections.with_label_values(&[reason]).inc();
+    }
+
+    /// Encodes the metrics in the Prometheus text format.
+    pub fn encode(&self) -> Result<String, NotaryServerError> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(|err| eyre!("Failed to encode metrics: {err}"))?;
+
+        String::from_utf8(buffer).map_err(|err| eyre!("Failed to encode metrics: {err}").into())
+    }
+}
+
+impl Debug for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metrics").finish_non_exhaustive()
+    }
+}
+
+/// Handle to record the outcome of a notarization.
+///
+/// The notarization is recorded as failed if the handle is dropped before
+/// either outcome is recorded, e.g. if the notarization task panics.
+#[derive(Debug)]
+pub struct SessionMetrics {
+    metrics: Metrics,
+    client_type: &'static str,
+    start: Instant,
+    finished: bool,
+}
+
+impl SessionMetrics {
+    /// Records a successful notarization.
+    pub fn completed(mut self, transcript_length: &TranscriptLength) {
+        self.metrics
+            .bytes_transferred
+            .with_label_values(&["sent"])
+            .observe(transcript_length.sent as f64);
+        self.metrics
+            .bytes_transferred
+            .with_label_values(&["recv"])
+            .observe(transcript_length.received as f64);
+
+        self.finish(true);
+    }
+
+    /// Records a failed notarization.
+    pub fn failed(mut self) {
+        self.finish(false);
+    }
+
+    fn finish(&mut self, success: bool) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+
+        let (counter, outcome) = if success {
+            (&self.metrics.sessions_completed, "completed")
+        } else {
+            (&self.metrics.sessions_failed, "failed")
+        };
+        counter.with_label_values(&[self.client_type]).inc();
+        self.metrics
+            .notarization_duration
+            .with_label_values(&[self.client_type, outcome])
+            .observe(self.start.elapsed().as_secs_f64());
+        self.metrics.active_sessions.dec();
+    }
+}
+
+impl Drop for SessionMetrics {
+    fn drop(&mut self) {
+        self.finish(false);
+    }
+}
+
+fn client_type_label(client_type: &ClientType) -> &'static str {
+    match client_type {
+        ClientType::Tcp => "tcp",
+        ClientType::Websocket => "websocket",
+    }
+}
+
+fn metric_error(err: prometheus::Error) -> NotaryServerError {
+    eyre!("Failed to create metric: {err}").into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_session_metrics() {
+        let metrics = Metrics::new().unwrap();
+        let session = Session::new(4096, 16384, 60);
+
+        let tcp = metrics.start_session(ClientType::Tcp, &session);
+        let websocket = metrics.start_session(ClientType::Websocket, &session);
+        assert_eq!(metrics.active_sessions.get(), 2);
+
+        tcp.failed();
+        // Dropping the handle without an outcome records a failure
+        drop(websocket);
+        assert_eq!(metrics.active_sessions.get(), 0);
+        assert_eq!(
+            metrics
+                .sessions_failed
+                .with_label_values(&["websocket"])
+                .get(),
+            1
+        );
+
+        let completed = metrics.start_session(ClientType::Tcp, &session);
+        completed.completed(&TranscriptLength {
+            sent: 100,
+            received: 1000,
+        });
+        assert_eq!(
+            metrics
+                .bytes_transferred
+                .with_label_values(&["recv"])
+                .get_sample_sum(),
+            1000.0
+        );
+
+        metrics.auth_rejected("invalid_api_key");
+        let encoded = metrics.encode().unwrap();
+        assert!(encoded.contains("notary_sessions_started_total{client_type=\"tcp\"} 2"));
+        assert!(encoded.contains("notary_auth_rejections_total{reason=\"invalid_api_key\"} 1"));
+    }
+}
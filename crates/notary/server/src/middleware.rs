@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use axum::http::{header, request::Parts};
 use axum_core::extract::{FromRef, FromRequestParts};
+use chrono::Utc;
 use std::collections::HashMap;
 use tracing::{error, trace};
 
@@ -22,30 +23,70 @@ where
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let notary_globals = NotaryGlobals::from_ref(state);
-        let Some(whitelist) = notary_globals.authorization_whitelist else {
-            trace!("Skipping authorization as whitelist is not set.");
-            return Ok(Self);
-        };
         let auth_header = parts
             .headers
             .get(header::AUTHORIZATION)
             .and_then(|value| std::str::from_utf8(value.as_bytes()).ok());
 
+        if let Some(jwt_authorizer) = &notary_globals.jwt_authorizer {
+            let Some(auth_header) = auth_header else {
+                let err_msg = "Missing token.".to_string();
+                error!(err_msg);
+                notary_globals.metrics.auth_rejected("missing_token");
+                return Err(NotaryServerError::UnauthorizedProverRequest(err_msg));
+            };
+            let token = auth_header
+                .strip_prefix("Bearer ")
+                .unwrap_or(auth_header)
+                .trim();
+            return match jwt_authorizer.verify(token) {
+                Ok(claims) => {
+                    trace!(subject = ?claims.sub, "Request authorized.");
+                    // Make the claims available to the handlers so that they can enforce
+                    // them
+                    parts.extensions.insert(claims);
+                    Ok(Self)
+                }
+                Err(err) => {
+                    let err_msg = err.to_string();
+                    error!(err_msg);
+                    notary_globals.metrics.auth_rejected("invalid_token");
+                    Err(NotaryServerError::UnauthorizedProverRequest(err_msg))
+                }
+            };
+        }
+
+        let Some(whitelist) = notary_globals.authorization_whitelist else {
+            trace!("Skipping authorization as whitelist is not set.");
+            return Ok(Self);
+        };
+
         match auth_header {
             Some(auth_header) => {
                 let whitelist = whitelist.lock().unwrap();
-                if api_key_is_valid(auth_header, &whitelist) {
-                    trace!("Request authorized.");
-                    Ok(Self)
-                } else {
+                if !api_key_is_valid(auth_header, &whitelist) {
                     let err_msg = "Invalid API key.".to_string();
                     error!(err_msg);
-                    Err(NotaryServerError::UnauthorizedProverRequest(err_msg))
+                    notary_globals.metrics.auth_rejected("invalid_api_key");
+                    return Err(NotaryServerError::UnauthorizedProverRequest(err_msg));
+                }
+                let record = &whitelist[auth_header];
+                if record.is_expired(Utc::now()) {
+                    let err_msg = "Expired API key.".to_string();
+                    error!(err_msg);
+                    notary_globals.metrics.auth_rejected("expired_api_key");
+                    return Err(NotaryServerError::UnauthorizedProverRequest(err_msg));
                 }
+                trace!("Request authorized.");
+                // Make the record available to the handlers so that they can enforce its
+                // policy
+                parts.extensions.insert(record.clone());
+                Ok(Self)
             }
             None => {
                 let err_msg = "Missing API key.".to_string();
                 error!(err_msg);
+                notary_globals.metrics.auth_rejected("missing_api_key");
                 Err(NotaryServerError::UnauthorizedProverRequest(err_msg))
             }
         }
@@ -74,16 +115,19 @@ mod test {
                 name: "test-name-0".to_string(),
                 api_key: "test-api-key-0".to_string(),
                 created_at: "2023-10-18T07:38:53Z".to_string(),
+                ..Default::default()
             },
             AuthorizationWhitelistRecord {
                 name: "test-name-1".to_string(),
                 api_key: "test-api-key-1".to_string(),
                 created_at: "2023-10-11T07:38:53Z".to_string(),
+                ..Default::default()
             },
             AuthorizationWhitelistRecord {
                 name: "test-name-2".to_string(),
                 api_key: "test-api-key-2".to_string(),
                 created_at: "2022-10-11T07:38:53Z".to_string(),
+                ..Default::default()
             },
         ])
     }
@@ -0,0 +1,224 @@
+//! Policy of the servers which the notary attests to connections with.
+//!
+//! Provers must disclose the identity of the server in the attestation request
+//! when a policy is configured, or when the API key or token which authorized
+//! the session only allows some servers, which the notary verifies before
+//! checking the server name against the policy.
+
+use std::sync::Arc;
+
+use eyre::{eyre, Result};
+use regex::{Regex, RegexBuilder};
+use tlsn_core::{attestation::ServerNamePolicy, connection::ServerName};
+
+use crate::{config::ServerNamePolicyProperties, domain::auth::AuthorizationPolicy};
+
+/// Prefix of the patterns which are regular expressions
+const REGEX_PREFIX: &str = "regex:";
+
+/// Pattern of server names
+#[derive(Debug)]
+enum Pattern {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn parse(pattern: &str) -> Result<Self> {
+        let regex = if let Some(regex) = pattern.strip_prefix(REGEX_PREFIX) {
+            // The whole server name must match
+            format!("^(?:{regex})$")
+        } else if pattern.contains('*') {
+            let labels = pattern
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join("[^.]+");
+            format!("^{labels}$")
+        } else {
+            return Ok(Self::Exact(pattern.to_string()));
+        };
+
+        RegexBuilder::new(&regex)
+            .case_insensitive(true)
+            .build()
+            .map(Self::Regex)
+            .map_err(|err| eyre!("Invalid server name pattern {pattern}: {err}"))
+    }
+
+    fn matches(&self, server_name: &str) -> bool {
+        match self {
+            Self::Exact(name) => name.eq_ignore_ascii_case(server_name),
+            Self::Regex(regex) => regex.is_match(server_name),
+        }
+    }
+}
+
+/// Filter of server names, compiled from the configured policy.
+#[derive(Debug)]
+pub struct ServerNameFilter {
+    allow: Vec<Pattern>,
+    deny: Vec<Pattern>,
+}
+
+impl ServerNameFilter {
+    /// Compiles the configured policy, returning `None` if it does not
+    /// restrict any server names.
+    pub fn load(config: &ServerNamePolicyProperties) -> Result<Option<Self>> {
+        if config.allow.is_empty() && config.deny.is_empty() {
+            return Ok(None);
+        }
+
+        let parse = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| Pattern::parse(pattern))
+                .collect::<Result<Vec<_>>>()
+        };
+
+        Ok(Some(Self {
+            allow: parse(&config.allow)?,
+            deny: parse(&config.deny)?,
+        }))
+    }
+}
+
+impl ServerNamePolicy for ServerNameFilter {
+    fn allows(&self, name: &ServerName) -> bool {
+        let name = name.as_str();
+        if self.deny.iter().any(|pattern| pattern.matches(name)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|pattern| pattern.matches(name))
+    }
+}
+
+/// Policy of the servers which the notary attests to in a session, combining
+/// the configured policy with the server names allowed by the API key or token
+/// which authorized the session.
+#[derive(Debug)]
+pub struct SessionServerNamePolicy {
+    filter: Option<Arc<ServerNameFilter>>,
+    authorization: Option<AuthorizationPolicy>,
+}
+
+impl SessionServerNamePolicy {
+    /// Creates the policy of a session, returning `None` if it does not
+    /// restrict any server names.
+    pub fn new(
+        filter: Option<Arc<ServerNameFilter>>,
+        authorization: Option<AuthorizationPolicy>,
+    ) -> Option<Self> {
+        let authorization = authorization.filter(|policy| policy.allowed_server_names.is_some());
+        if filter.is_none() && authorization.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            filter,
+            authorization,
+        })
+    }
+}
+
+impl ServerNamePolicy for SessionServerNamePolicy {
+    fn allows(&self, name: &ServerName) -> bool {
+        if let Some(filter) = &self.filter {
+            if !filter.allows(name) {
+                return false;
+            }
+        }
+        match &self.authorization {
+            Some(policy) => policy.allows_server_name(Some(name.as_str())),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn filter(allow: &[&str], deny: &[&str]) -> ServerNameFilter {
+        ServerNameFilter::load(&ServerNamePolicyProperties {
+            allow: allow.iter().map(|pattern| pattern.to_string()).collect(),
+            deny: deny.iter().map(|pattern| pattern.to_string()).collect(),
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    fn allows(filter: &ServerNameFilter, name: &str) -> bool {
+        filter.allows(&ServerName::from(name))
+    }
+
+    #[test]
+    fn test_server_name_filter() {
+        let filter = filter(
+            &[
+                "api.example.com",
+                "*.partner.org",
+                r"regex:api[0-9]+\.example\.net",
+            ],
+            &["internal.partner.org"],
+        );
+
+        assert!(allows(&filter, "api.example.com"));
+        assert!(allows(&filter, "API.Example.com"));
+        assert!(!allows(&filter, "www.example.com"));
+        // Wildcards match a single label
+        assert!(allows(&filter, "api.partner.org"));
+        assert!(!allows(&filter, "partner.org"));
+        assert!(!allows(&filter, ".partner.org"));
+        assert!(!allows(&filter, "v1.api.partner.org"));
+        // Regular expressions must match the whole name
+        assert!(allows(&filter, "api42.example.net"));
+        assert!(!allows(&filter, "api42.example.net.evil.com"));
+        // Denied names take precedence
+        assert!(!allows(&filter, "internal.partner.org"));
+    }
+
+    #[test]
+    fn test_server_name_filter_deny_only() {
+        let filter = filter(&[], &["*.evil.com"]);
+        assert!(allows(&filter, "example.com"));
+        assert!(!allows(&filter, "www.evil.com"));
+    }
+
+    #[test]
+    fn test_server_name_filter_config() {
+        assert!(
+            ServerNameFilter::load(&ServerNamePolicyProperties::default())
+                .unwrap()
+                .is_none()
+        );
+        assert!(ServerNameFilter::load(&ServerNamePolicyProperties {
+            allow: vec!["regex:(".to_string()],
+            deny: vec![],
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_session_server_name_policy() {
+        let authorization = AuthorizationPolicy {
+            allowed_server_names: Some(vec!["api.example.com".to_string()]),
+            ..Default::default()
+        };
+
+        // The verified server name must be allowed by the authorization, whatever
+        // name was declared when creating the session
+        let policy = SessionServerNamePolicy::new(None, Some(authorization.clone())).unwrap();
+        assert!(policy.allows(&ServerName::from("API.example.com")));
+        assert!(!policy.allows(&ServerName::from("www.example.com")));
+
+        // And by the configured policy
+        let filter = Arc::new(filter(&[], &["api.example.com"]));
+        let policy = SessionServerNamePolicy::new(Some(filter), Some(authorization)).unwrap();
+        assert!(!policy.allows(&ServerName::from("api.example.com")));
+
+        // Authorizations which allow any server do not restrict the session
+        assert!(SessionServerNamePolicy::new(None, Some(AuthorizationPolicy::default())).is_none());
+        assert!(SessionServerNamePolicy::new(None, None).is_none());
+    }
+}
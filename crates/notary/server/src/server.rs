@@ -1,11 +1,12 @@
 use axum::{
     extract::Request,
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware::from_extractor_with_state,
     response::{Html, IntoResponse},
     routing::{get, post},
     Json, Router,
 };
+use chrono::Utc;
 use eyre::{ensure, eyre, Result};
 use futures_util::future::poll_fn;
 use hyper::{body::Incoming, server::conn::http1};
@@ -13,7 +14,6 @@ use hyper_util::rt::TokioIo;
 use notify::{
     event::ModifyKind, Error, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
-use pkcs8::DecodePrivateKey;
 use rustls::{Certificate, PrivateKey, ServerConfig};
 use std::{
     collections::HashMap,
@@ -23,26 +23,30 @@ use std::{
     path::Path,
     pin::Pin,
     sync::{Arc, Mutex},
+    time::Duration,
 };
-use tlsn_core::CryptoProvider;
-use tokio::{fs::File, io::AsyncReadExt, net::TcpListener};
+use tokio::{fs::File, net::TcpListener};
 use tokio_rustls::TlsAcceptor;
 use tower_http::cors::CorsLayer;
 use tower_service::Service;
 use tracing::{debug, error, info};
-use zeroize::Zeroize;
 
 use crate::{
-    config::{NotaryServerProperties, NotarySigningKeyProperties},
+    config::{AuthorizationMode, NotaryServerProperties},
     domain::{
         auth::{authorization_whitelist_vec_into_hashmap, AuthorizationWhitelistRecord},
         notary::NotaryGlobals,
         InfoResponse,
     },
     error::NotaryServerError,
+    jwt::JwtAuthorizer,
+    keyring::Keyring,
+    limiter::NotarizationLimiter,
+    metrics::Metrics,
     middleware::AuthorizationMiddleware,
-    service::{initialize, upgrade_protocol},
-    signing::AttestationKey,
+    policy::ServerNameFilter,
+    service::{initialize, session_status, upgrade_protocol},
+    store::{load_session_store, reap_expired_sessions},
     util::parse_csv_file,
 };
 
@@ -50,9 +54,13 @@ use crate::{
 /// both TCP and WebSocket clients
 #[tracing::instrument(skip(config))]
 pub async fn run_server(config: &NotaryServerProperties) -> Result<(), NotaryServerError> {
-    // Load the private key for notarized transcript signing
-    let attestation_key = load_attestation_key(&config.notary_key).await?;
-    let crypto_provider = build_crypto_provider(attestation_key);
+    // Load the keyring of private keys for notarized transcript signing
+    let keyring = Arc::new(Mutex::new(Keyring::load(&config.notary_key)?));
+    // Enable hot reload if keyring csv is configured
+    let keyring_watcher = watch_and_reload_keyring(config.clone(), keyring.clone())?;
+    if keyring_watcher.is_some() {
+        debug!("Successfully setup watcher for hot reload of keyring!");
+    }
 
     // Build TLS acceptor if it is turned on
     let tls_acceptor = if !config.tls.enabled {
@@ -87,6 +95,19 @@ pub async fn run_server(config: &NotaryServerProperties) -> Result<(), NotarySer
         debug!("Successfully setup watcher for hot reload of authorization whitelist!");
     }
 
+    // Load the keys to verify tokens if JWT authorization is turned on
+    let jwt_authorizer =
+        if config.authorization.enabled && config.authorization.mode == AuthorizationMode::Jwt {
+            Some(Arc::new(JwtAuthorizer::load(&config.authorization.jwt)?))
+        } else {
+            None
+        };
+
+    // Compile the policy of the servers which the notary attests to connections
+    // with, so that invalid patterns are rejected on startup
+    let server_name_policy =
+        ServerNameFilter::load(&config.notarization.server_name_policy)?.map(Arc::new);
+
     let notary_address = SocketAddr::new(
         IpAddr::V4(config.server.host.parse().map_err(|err| {
             eyre!("Failed to parse notary host address from server config: {err}")
@@ -99,34 +120,58 @@ pub async fn run_server(config: &NotaryServerProperties) -> Result<(), NotarySer
 
     info!("Listening for TCP traffic at {}", notary_address);
 
+    let store = load_session_store(&config.session_store).await?;
+    // Periodically remove sessions which were never used to start a notarization,
+    // checking at least once a minute
+    tokio::spawn(reap_expired_sessions(
+        store.clone(),
+        Duration::from_secs(config.notarization.session_ttl.clamp(1, 60)),
+    ));
+
     let protocol = Arc::new(http1::Builder::new());
+    let metrics = Metrics::new()?;
     let notary_globals = NotaryGlobals::new(
-        Arc::new(crypto_provider),
+        keyring.clone(),
         config.notarization.clone(),
+        store,
         authorization_whitelist,
+        jwt_authorizer,
+        metrics.clone(),
+        NotarizationLimiter::new(config.concurrency.clone()),
+        server_name_policy,
     );
 
     // Parameters needed for the info endpoint
-    let public_key = std::fs::read_to_string(&config.notary_key.public_key_pem_path)
-        .map_err(|err| eyre!("Failed to load notary public signing key for notarization: {err}"))?;
     let version = env!("CARGO_PKG_VERSION").to_string();
     let git_commit_hash = env!("GIT_COMMIT_HASH").to_string();
     let git_commit_timestamp = env!("GIT_COMMIT_TIMESTAMP").to_string();
 
-    // Parameters needed for the root / endpoint
+    // Parameters needed for the root / endpoint, the public key is filled in per
+    // request as it changes when the keyring rotates
     let html_string = config.server.html_info.clone();
-    let html_info = Html(
-        html_string
-            .replace("{version}", &version)
-            .replace("{git_commit_hash}", &git_commit_hash)
-            .replace("{git_commit_timestamp}", &git_commit_timestamp)
-            .replace("{public_key}", &public_key),
-    );
+    let html_info = html_string
+        .replace("{version}", &version)
+        .replace("{git_commit_hash}", &git_commit_hash)
+        .replace("{git_commit_timestamp}", &git_commit_timestamp);
 
+    let root_keyring = keyring.clone();
+    let info_keyring = keyring;
     let router = Router::new()
         .route(
             "/",
-            get(|| async move { (StatusCode::OK, html_info).into_response() }),
+            get(|| async move {
+                let public_key = root_keyring
+                    .lock()
+                    .unwrap()
+                    .public_key(Utc::now())
+                    .unwrap_or_default()
+                    .to_string();
+                (
+                    StatusCode::OK,
+                    Html(html_info.replace("{public_key}", &public_key)),
+                )
+                    .into_response()
+            }),
         )
         .route(
             "/healthcheck",
@@ -135,11 +180,20 @@ pub async fn run_server(config: &NotaryServerProperties) -> Result<(), NotarySer
         .route(
             "/info",
             get(|| async move {
+                let now = Utc::now();
+                let keyring = info_keyring.lock().unwrap();
+                let Some(public_key) = keyring.public_key(now) else {
+                    return NotaryServerError::Unexpected(eyre!(
+                        "Keyring does not contain a key which is currently valid"
+                    ))
+                    .into_response();
+                };
                 (
                     StatusCode::OK,
                     Json(InfoResponse {
                         version,
-                        public_key,
+                        public_key: public_key.to_string(),
+                        keys: keyring.published_keys(now),
                         git_commit_hash,
                         git_commit_timestamp,
                     }),
@@ -148,6 +202,7 @@ pub async fn run_server(config: &NotaryServerProperties) -> Result<(), NotarySer
             }),
         )
         .route("/session", post(initialize))
+        .route("/session/:session_id/status", get(session_status))
         // Not applying auth middleware to /notarize endpoint for now as we can rely on our
         // short-lived session id generated from /session endpoint, as it is not possible
         // to use header for API key for websocket /notarize endpoint due to browser restriction
@@ -158,6 +213,25 @@ pub async fn run_server(config: &NotaryServerProperties) -> Result<(), NotarySer
             NotaryGlobals,
         >(notary_globals.clone()))
         .route("/notarize", get(upgrade_protocol))
+        // Not applying auth middleware to /metrics endpoint so that it can be scraped
+        // without an API key
+        .route(
+            "/metrics",
+            get(|| async move {
+                match metrics.encode() {
+                    Ok(encoded) => (
+                        StatusCode::OK,
+                        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+                        encoded,
+                    )
+                        .into_response(),
+                    Err(err) => {
+                        error!("{err}");
+                        err.into_response()
+                    }
+                }
+            }),
+        )
         .layer(CorsLayer::permissive())
         .with_state(notary_globals);
 
@@ -224,32 +298,6 @@ pub async fn run_server(config: &NotaryServerProperties) -> Result<(), NotarySer
     }
 }
 
-fn build_crypto_provider(attestation_key: AttestationKey) -> CryptoProvider {
-    let mut provider = CryptoProvider::default();
-    provider.signer.set_signer(attestation_key.into_signer());
-    provider
-}
-
-/// Load notary signing key for attestations from static file
-async fn load_attestation_key(config: &NotarySigningKeyProperties) -> Result<AttestationKey> {
-    debug!("Loading notary server's signing key");
-
-    let mut file = File::open(&config.private_key_pem_path).await?;
-    let mut pem = String::new();
-    file.read_to_string(&mut pem)
-        .await
-        .map_err(|_| eyre!("pem file does not contain valid UTF-8"))?;
-
-    let key = AttestationKey::from_pkcs8_pem(&pem)
-        .map_err(|err| eyre!("Failed to load notary signing key for notarization: {err}"))?;
-
-    pem.zeroize();
-
-    debug!("Successfully loaded notary server's signing key!");
-
-    Ok(key)
-}
-
 /// Read a PEM-formatted file and return its buffer reader
 pub async fn read_pem_file(file_path: &str) -> Result<BufReader<StdFile>> {
     let key_file = File::open(file_path).await?.into_std().await;
@@ -288,12 +336,24 @@ fn load_authorization_whitelist(
     let authorization_whitelist = if !config.authorization.enabled {
         debug!("Skipping authorization as it is turned off.");
         None
+    } else if config.authorization.mode != AuthorizationMode::Whitelist {
+        debug!("Skipping authorization whitelist as another authorization mode is used.");
+        None
     } else {
         // Load the csv
         let whitelist_csv = parse_csv_file::<AuthorizationWhitelistRecord>(
             &config.authorization.whitelist_csv_path,
         )
         .map_err(|err| eyre!("Failed to parse authorization whitelist csv: {:?}", err))?;
+        // Reject the whitelist if the policy of any API key is malformed
+        for record in &whitelist_csv {
+            record.validate().map_err(|err| {
+                eyre!(
+                    "Invalid policy of API key {} in authorization whitelist csv: {err}",
+                    record.name
+                )
+            })?;
+        }
         // Convert the whitelist record into hashmap for faster lookup
         let whitelist_hashmap = authorization_whitelist_vec_into_hashmap(whitelist_csv);
         Some(whitelist_hashmap)
@@ -361,9 +421,60 @@ fn watch_and_reload_authorization_whitelist(
     Ok(watcher)
 }
 
+// Setup a watcher to detect any changes to the keyring csv
+// When the csv file is modified, the watcher thread will reload the keyring, so
+// that keys can be rotated without restarting the server
+fn watch_and_reload_keyring(
+    config: NotaryServerProperties,
+    keyring: Arc<Mutex<Keyring>>,
+) -> Result<Option<RecommendedWatcher>> {
+    // Only setup the watcher if keyring csv is configured
+    let Some(keyring_csv_path) = config.notary_key.keyring_csv_path.clone() else {
+        return Ok(None);
+    };
+
+    // Setup watcher by giving it a function that will be triggered when an event is
+    // detected
+    let mut watcher = RecommendedWatcher::new(
+        move |event: Result<Event, Error>| {
+            match event {
+                Ok(event) => {
+                    // Only reload keyring if it's an event that modified the file data
+                    if let EventKind::Modify(ModifyKind::Data(_)) = event.kind {
+                        debug!("Keyring is modified");
+                        match Keyring::load(&config.notary_key) {
+                            Ok(new_keyring) => {
+                                *keyring.lock().unwrap() = new_keyring;
+                                info!("Successfully reloaded keyring!");
+                            }
+                            // Keep using the previous keyring if the new one is invalid, and ensure
+                            // that error from reloading doesn't bring the server down
+                            Err(err) => error!("Failed to reload keyring: {err}"),
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("Error occured when watcher detected an event: {err}")
+                }
+            }
+        },
+        notify::Config::default(),
+    )
+    .map_err(|err| eyre!("Error occured when setting up watcher for hot reload: {err}"))?;
+
+    // Start watcher to listen to any changes on the keyring file
+    watcher
+        .watch(Path::new(&keyring_csv_path), RecursiveMode::Recursive)
+        .map_err(|err| eyre!("Error occured when starting up watcher for hot reload: {err}"))?;
+
+    // Need to return the watcher to parent function, else it will be dropped and
+    // stop listening
+    Ok(Some(watcher))
+}
+
 #[cfg(test)]
 mod test {
-    use std::{fs::OpenOptions, time::Duration};
+    use std::fs::OpenOptions;
 
     use csv::WriterBuilder;
 
@@ -380,15 +491,6 @@ mod test {
         assert!(result.is_ok(), "Could not load tls private key and cert");
     }
 
-    #[tokio::test]
-    async fn test_load_attestation_key() {
-        let config = NotarySigningKeyProperties {
-            private_key_pem_path: "./fixture/notary/notary.key".to_string(),
-            public_key_pem_path: "./fixture/notary/notary.pub".to_string(),
-        };
-        load_attestation_key(&config).await.unwrap();
-    }
-
     #[tokio::test]
     async fn test_watch_and_reload_authorization_whitelist() {
         // Clone fixture auth whitelist for testing
@@ -401,6 +503,7 @@ mod test {
             authorization: AuthorizationProperties {
                 enabled: true,
                 whitelist_csv_path,
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -423,6 +526,7 @@ mod test {
             name: "unit-test-name".to_string(),
             api_key: "unit-test-api-key".to_string(),
             created_at: "unit-test-created-at".to_string(),
+            ..Default::default()
         };
         let file = OpenOptions::new()
             .append(true)
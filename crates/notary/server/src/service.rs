@@ -6,13 +6,15 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use axum::{
-    extract::{rejection::JsonRejection, FromRequestParts, Query, State},
+    extract::{rejection::JsonRejection, Extension, FromRequestParts, Path, Query, State},
     http::{header, request::Parts, StatusCode},
     response::{IntoResponse, Json, Response},
 };
 use axum_macros::debug_handler;
+use chrono::Utc;
+use eyre::eyre;
 use tlsn_common::config::ProtocolConfigValidator;
-use tlsn_core::{attestation::AttestationConfig, CryptoProvider};
+use tlsn_core::{attestation::AttestationConfig, connection::TranscriptLength, CryptoProvider};
 use tlsn_verifier::{Verifier, VerifierConfig};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::compat::TokioAsyncReadCompatExt;
@@ -20,16 +22,21 @@ use tracing::{debug, error, info, trace};
 use uuid::Uuid;
 
 use crate::{
-    domain::notary::{
-        NotarizationRequestQuery, NotarizationSessionRequest, NotarizationSessionResponse,
-        NotaryGlobals,
+    domain::{
+        auth::{AuthorizationPolicy, AuthorizationWhitelistRecord, JwtClaims},
+        notary::{
+            ClientType, NotarizationRequestQuery, NotarizationSessionRequest,
+            NotarizationSessionResponse, NotarizationSessionStatusResponse, NotaryGlobals,
+        },
     },
     error::NotaryServerError,
+    policy::SessionServerNamePolicy,
     service::{
         axum_websocket::{header_eq, WebSocketUpgrade},
         tcp::{tcp_notarize, TcpUpgrade},
         websocket::websocket_notarize,
     },
+    store::{unix_timestamp, Session},
 };
 
 /// A wrapper enum to facilitate extracting TCP connection for either WebSocket
@@ -80,28 +87,160 @@ pub async fn upgrade_protocol(
     info!("Received upgrade protocol request");
     let session_id = params.session_id;
     // Check if session_id exists in the store, this also removes session_id from
-    // the store as each session_id can only be used once
-    if notary_globals
-        .store
+    // the store as each session_id can only be used once. The session holds the
+    // limits that were negotiated for it in the /session API
+    let session = match notary_globals.store.take(&session_id).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            let err_msg = format!("Session id {} does not exist", session_id);
+            error!(err_msg);
+            return NotaryServerError::BadProverRequest(err_msg).into_response();
+        }
+        Err(err) => {
+            error!("Failed to retrieve session {session_id} from store: {err}");
+            return err.into_response();
+        }
+    };
+    if session.is_expired(unix_timestamp()) {
+        let err_msg = format!("Session id {} has expired", session_id);
+        error!(err_msg);
+        return NotaryServerError::BadProverRequest(err_msg).into_response();
+    }
+    // The API key which created the session may have been revoked or changed by a
+    // reload of the whitelist in the meantime, while tokens are checked again as
+    // /notarize is otherwise unauthenticated
+    let token = params.token.as_deref();
+    let authorization = match authorize_session(&notary_globals, &session, token) {
+        Ok(authorization) => authorization,
+        Err(err) => {
+            error!("Session id {session_id} is no longer authorized: {err}");
+            return err.into_response();
+        }
+    };
+    // Attestations are signed with the key which is current when the
+    // notarization starts
+    let Some(crypto_provider) = notary_globals
+        .keyring
         .lock()
         .unwrap()
-        .remove(&session_id)
-        .is_none()
-    {
-        let err_msg = format!("Session id {} does not exist", session_id);
+        .crypto_provider(Utc::now())
+    else {
+        let err_msg = "Keyring does not contain a key which is currently valid";
         error!(err_msg);
-        return NotaryServerError::BadProverRequest(err_msg).into_response();
+        return NotaryServerError::Unexpected(eyre!(err_msg)).into_response();
+    };
+    // Join the queue of notarizations before completing the upgrade, so that the
+    // prover can still be told to retry later if the notary is busy. The session
+    // is put back into the store so that it can be used for the retry
+    let Some(ticket) = notary_globals.limiter.enqueue(&session_id) else {
+        let err_msg = format!("Too many notarizations in progress to start session {session_id}");
+        error!(err_msg);
+        if let Err(err) = notary_globals.store.insert(&session_id, session).await {
+            error!("Failed to store session {session_id} for retry: {err}");
+        }
+        return NotaryServerError::Busy(err_msg, notary_globals.limiter.retry_after())
+            .into_response();
     };
+    let attestation_validity = notary_globals.notarization_config.attestation_validity;
+    // The server name declared in /session is not proven, so the servers allowed
+    // by the authorization are checked against the server identity which the
+    // prover must disclose in the attestation request
+    let server_name_policy =
+        SessionServerNamePolicy::new(notary_globals.server_name_policy, authorization)
+            .map(Arc::new);
+    let metrics = notary_globals.metrics;
     // This completes the HTTP Upgrade request and returns a successful response to
-    // the client, meanwhile initiating the websocket or tcp connection
+    // the client, meanwhile initiating the websocket or tcp connection, which waits
+    // in the queue until a running notarization finishes if there is no free slot
     match protocol_upgrade {
-        ProtocolUpgrade::Ws(ws) => {
-            ws.on_upgrade(move |socket| websocket_notarize(socket, notary_globals, session_id))
+        ProtocolUpgrade::Ws(ws) => ws.on_upgrade(move |socket| async move {
+            let _permit = ticket.start().await;
+            let session_metrics = metrics.start_session(ClientType::Websocket, &session);
+            websocket_notarize(
+                socket,
+                crypto_provider,
+                session_id,
+                session,
+                attestation_validity,
+                server_name_policy,
+                session_metrics,
+            )
+            .await
+        }),
+        ProtocolUpgrade::Tcp(tcp) => tcp.on_upgrade(move |stream| async move {
+            let _permit = ticket.start().await;
+            let session_metrics = metrics.start_session(ClientType::Tcp, &session);
+            tcp_notarize(
+                stream,
+                crypto_provider,
+                session_id,
+                session,
+                attestation_validity,
+                server_name_policy,
+                session_metrics,
+            )
+            .await
+        }),
+    }
+}
+
+/// Checks that the session is still authorized when it is used, i.e. that the
+/// API key which was used to create it still allows it, or that a valid token
+/// which allows it is provided in the JWT authorization mode. Returns the
+/// policy of the API key or token, if authorization is turned on
+fn authorize_session(
+    notary_globals: &NotaryGlobals,
+    session: &Session,
+    token: Option<&str>,
+) -> Result<Option<AuthorizationPolicy>, NotaryServerError> {
+    let policy = if let Some(jwt_authorizer) = &notary_globals.jwt_authorizer {
+        let Some(token) = token else {
+            notary_globals.metrics.auth_rejected("missing_token");
+            return Err(NotaryServerError::UnauthorizedProverRequest(
+                "Missing token.".to_string(),
+            ));
+        };
+        match jwt_authorizer.verify(token) {
+            Ok(claims) => claims.policy(),
+            Err(err) => {
+                notary_globals.metrics.auth_rejected("invalid_token");
+                return Err(NotaryServerError::UnauthorizedProverRequest(
+                    err.to_string(),
+                ));
+            }
         }
-        ProtocolUpgrade::Tcp(tcp) => {
-            tcp.on_upgrade(move |stream| tcp_notarize(stream, notary_globals, session_id))
+    } else if let (Some(whitelist), Some(api_key_hash)) = (
+        &notary_globals.authorization_whitelist,
+        &session.api_key_hash,
+    ) {
+        let whitelist = whitelist.lock().unwrap();
+        let Some(record) = whitelist
+            .values()
+            .find(|record| record.api_key_hash() == *api_key_hash)
+        else {
+            notary_globals.metrics.auth_rejected("invalid_api_key");
+            return Err(NotaryServerError::UnauthorizedProverRequest(
+                "Invalid API key.".to_string(),
+            ));
+        };
+        if record.is_expired(Utc::now()) {
+            notary_globals.metrics.auth_rejected("expired_api_key");
+            return Err(NotaryServerError::UnauthorizedProverRequest(
+                "Expired API key.".to_string(),
+            ));
         }
+        record.policy()
+    } else {
+        return Ok(None);
+    };
+
+    if !policy.allows_session(session) {
+        notary_globals.metrics.auth_rejected("policy_changed");
+        return Err(NotaryServerError::UnauthorizedProverRequest(
+            "Session is not allowed by the authorization.".to_string(),
+        ));
     }
+    Ok(Some(policy))
 }
 
 /// Handler to initialize and configure notarization for both TCP and WebSocket
@@ -109,6 +248,8 @@ pub async fn upgrade_protocol(
 #[debug_handler(state = NotaryGlobals)]
 pub async fn initialize(
     State(notary_globals): State<NotaryGlobals>,
+    authorized_record: Option<Extension<AuthorizationWhitelistRecord>>,
+    authorized_claims: Option<Extension<JwtClaims>>,
     payload: Result<Json<NotarizationSessionRequest>, JsonRejection>,
 ) -> impl IntoResponse {
     info!(
@@ -125,6 +266,14 @@ pub async fn initialize(
         }
     };
 
+    // Reject new sessions early if they could not be notarized right now
+    if notary_globals.limiter.is_full() {
+        let err_msg = "Too many notarizations in progress to create a new session";
+        error!(err_msg);
+        return NotaryServerError::Busy(err_msg.to_string(), notary_globals.limiter.retry_after())
+            .into_response();
+    }
+
     // Ensure that the max_sent_data, max_recv_data submitted is not larger than the
     // global max limits configured in notary server
     if payload.max_sent_data.is_some() || payload.max_recv_data.is_some() {
@@ -156,16 +305,102 @@ pub async fn initialize(
         }
     }
 
+    // Use the global max limits for the session if the prover did not request
+    // smaller ones
+    let mut max_sent_data = payload
+        .max_sent_data
+        .unwrap_or(notary_globals.notarization_config.max_sent_data);
+    let mut max_recv_data = payload
+        .max_recv_data
+        .unwrap_or(notary_globals.notarization_config.max_recv_data);
+
+    // Enforce the policy of the API key or token used to authorize this request,
+    // if any
+    let policy = match (&authorized_record, &authorized_claims) {
+        (Some(Extension(record)), _) => Some(record.policy()),
+        (None, Some(Extension(claims))) => Some(claims.policy()),
+        (None, None) => None,
+    };
+    if let Some(policy) = &policy {
+        if let Some(policy_max_sent_data) = policy.max_sent_data {
+            if payload.max_sent_data.unwrap_or_default() > policy_max_sent_data {
+                error!(
+                    "Max sent data requested {:?} exceeds the maximum threshold {:?} of the authorization",
+                    payload.max_sent_data.unwrap_or_default(),
+                    policy_max_sent_data,
+                );
+                return NotaryServerError::BadProverRequest(
+                    "Max sent data requested exceeds the maximum threshold of the authorization"
+                        .to_string(),
+                )
+                .into_response();
+            }
+            max_sent_data = max_sent_data.min(policy_max_sent_data);
+        }
+        if let Some(policy_max_recv_data) = policy.max_recv_data {
+            if payload.max_recv_data.unwrap_or_default() > policy_max_recv_data {
+                error!(
+                    "Max recv data requested {:?} exceeds the maximum threshold {:?} of the authorization",
+                    payload.max_recv_data.unwrap_or_default(),
+                    policy_max_recv_data,
+                );
+                return NotaryServerError::BadProverRequest(
+                    "Max recv data requested exceeds the maximum threshold of the authorization"
+                        .to_string(),
+                )
+                .into_response();
+            }
+            max_recv_data = max_recv_data.min(policy_max_recv_data);
+        }
+        if !policy.allows_server_name(payload.server_name.as_deref()) {
+            let err_msg = format!(
+                "Server name {:?} is not allowed by the authorization",
+                payload.server_name
+            );
+            error!(err_msg);
+            notary_globals
+                .metrics
+                .auth_rejected("server_name_not_allowed");
+            return NotaryServerError::UnauthorizedProverRequest(err_msg).into_response();
+        }
+    }
+    if let Some(Extension(record)) = &authorized_record {
+        // Only sessions which are actually created count towards the limits
+        if let Err(err_msg) = notary_globals
+            .authorization_usage
+            .lock()
+            .unwrap()
+            .record_session(record, unix_timestamp())
+        {
+            error!("{err_msg} API key: {}", record.name);
+            notary_globals
+                .metrics
+                .auth_rejected("session_limit_reached");
+            return NotaryServerError::TooManyProverRequests(err_msg).into_response();
+        }
+    }
+
+    let mut session = Session::new(
+        max_sent_data,
+        max_recv_data,
+        notary_globals.notarization_config.session_ttl,
+    );
+    session.api_key_hash = authorized_record.map(|Extension(record)| record.api_key_hash());
+    session.server_name = payload.server_name.clone();
+
     let prover_session_id = Uuid::new_v4().to_string();
 
-    // Store the configuration data in a temporary store
-    notary_globals
-        .store
-        .lock()
-        .unwrap()
-        .insert(prover_session_id.clone(), ());
+    trace!(?prover_session_id, ?session, "Storing session");
 
-    trace!("Latest store state: {:?}", notary_globals.store);
+    // Store the session so that it can be used by the /notarize API
+    if let Err(err) = notary_globals
+        .store
+        .insert(&prover_session_id, session)
+        .await
+    {
+        error!("Failed to store session {prover_session_id}: {err}");
+        return err.into_response();
+    }
 
     // Return the session id in the response to the client
     (
@@ -177,18 +412,48 @@ pub async fn initialize(
         .into_response()
 }
 
-/// Run the notarization
+/// Handler to report the status of a notarization which has been started using
+/// the /notarize API, e.g. its position in the queue
+pub async fn session_status(
+    State(notary_globals): State<NotaryGlobals>,
+    Path(session_id): Path<String>,
+) -> Response {
+    match notary_globals.limiter.status(&session_id) {
+        Some(status) => (
+            StatusCode::OK,
+            Json(NotarizationSessionStatusResponse { status }),
+        )
+            .into_response(),
+        None => {
+            let err_msg = format!("Session id {} is neither queued nor running", session_id);
+            error!(err_msg);
+            NotaryServerError::BadProverRequest(err_msg).into_response()
+        }
+    }
+}
+
+/// Run the notarization, returning the length of the notarized transcript
 pub async fn notary_service<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
     socket: T,
     crypto_provider: Arc<CryptoProvider>,
     session_id: &str,
     max_sent_data: usize,
     max_recv_data: usize,
-) -> Result<(), NotaryServerError> {
+    attestation_validity: Option<u64>,
+    server_name_policy: Option<Arc<SessionServerNamePolicy>>,
+) -> Result<TranscriptLength, NotaryServerError> {
     debug!(?session_id, "Starting notarization...");
 
-    let att_config = AttestationConfig::builder()
-        .supported_signature_algs(Vec::from_iter(crypto_provider.signer.supported_algs()))
+    let mut att_config_builder = AttestationConfig::builder();
+    att_config_builder
+        .supported_signature_algs(Vec::from_iter(crypto_provider.signer.supported_algs()));
+    if let Some(validity) = attestation_validity {
+        att_config_builder.validity_period(validity);
+    }
+    if let Some(policy) = server_name_policy {
+        att_config_builder.server_name_policy(policy);
+    }
+    let att_config = att_config_builder
         .build()
         .map_err(|err| NotaryServerError::Notarization(Box::new(err)))?;
 
@@ -202,9 +467,16 @@ pub async fn notary_service<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
         .crypto_provider(crypto_provider)
         .build()?;
 
-    Verifier::new(config)
-        .notarize(socket.compat(), &att_config)
+    let verifier = Verifier::new(config)
+        .setup(socket.compat())
+        .await?
+        .run()
         .await?;
 
-    Ok(())
+    // Take the transcript length from the notary's own view of the connection
+    let transcript_length = verifier.connection_info().transcript_length.clone();
+
+    verifier.start_notarize().finalize(&att_config).await?;
+
+    Ok(transcript_length)
 }
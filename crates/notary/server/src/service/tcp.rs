@@ -7,10 +7,14 @@ use axum::{
 use axum_core::body::Body;
 use hyper::upgrade::{OnUpgrade, Upgraded};
 use hyper_util::rt::TokioIo;
-use std::future::Future;
+use std::{future::Future, sync::Arc};
+use tlsn_core::CryptoProvider;
 use tracing::{debug, error, info};
 
-use crate::{domain::notary::NotaryGlobals, service::notary_service, NotaryServerError};
+use crate::{
+    metrics::SessionMetrics, policy::SessionServerNamePolicy, service::notary_service,
+    store::Session, NotaryServerError,
+};
 
 /// Custom extractor used to extract underlying TCP connection for TCP client —
 /// using the same upgrade primitives used by the WebSocket implementation where
@@ -83,24 +87,37 @@ impl TcpUpgrade {
 /// Perform notarization using the extracted tcp connection
 pub async fn tcp_notarize(
     stream: TokioIo<Upgraded>,
-    notary_globals: NotaryGlobals,
+    crypto_provider: Arc<CryptoProvider>,
     session_id: String,
+    session: Session,
+    attestation_validity: Option<u64>,
+    server_name_policy: Option<Arc<SessionServerNamePolicy>>,
+    session_metrics: SessionMetrics,
 ) {
     debug!(?session_id, "Upgraded to tcp connection");
     match notary_service(
         stream,
-        notary_globals.crypto_provider.clone(),
+        crypto_provider,
         &session_id,
-        notary_globals.notarization_config.max_sent_data,
-        notary_globals.notarization_config.max_recv_data,
+        session.max_sent_data,
+        session.max_recv_data,
+        attestation_validity,
+        server_name_policy,
     )
     .await
     {
-        Ok(_) => {
-            info!(?session_id, "Successful notarization using tcp!");
+        Ok(transcript_length) => {
+            info!(
+                ?session_id,
+                sent = transcript_length.sent,
+                received = transcript_length.received,
+                "Successful notarization using tcp!"
+            );
+            session_metrics.completed(&transcript_length);
         }
         Err(err) => {
             error!(?session_id, "Failed notarization using tcp: {err}");
+            session_metrics.failed();
         }
     }
 }
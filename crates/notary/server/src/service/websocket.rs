@@ -1,16 +1,24 @@
+use std::sync::Arc;
+use tlsn_core::CryptoProvider;
 use tracing::{debug, error, info};
 use ws_stream_tungstenite::WsStream;
 
 use crate::{
-    domain::notary::NotaryGlobals,
+    metrics::SessionMetrics,
+    policy::SessionServerNamePolicy,
     service::{axum_websocket::WebSocket, notary_service},
+    store::Session,
 };
 
 /// Perform notarization using the established websocket connection
 pub async fn websocket_notarize(
     socket: WebSocket,
-    notary_globals: NotaryGlobals,
+    crypto_provider: Arc<CryptoProvider>,
     session_id: String,
+    session: Session,
+    attestation_validity: Option<u64>,
+    server_name_policy: Option<Arc<SessionServerNamePolicy>>,
+    session_metrics: SessionMetrics,
 ) {
     debug!(?session_id, "Upgraded to websocket connection");
     // Wrap the websocket in WsStream so that we have AsyncRead and AsyncWrite
@@ -18,18 +26,27 @@ pub async fn websocket_notarize(
     let stream = WsStream::new(socket.into_inner());
     match notary_service(
         stream,
-        notary_globals.crypto_provider.clone(),
+        crypto_provider,
         &session_id,
-        notary_globals.notarization_config.max_sent_data,
-        notary_globals.notarization_config.max_recv_data,
+        session.max_sent_data,
+        session.max_recv_data,
+        attestation_validity,
+        server_name_policy,
     )
     .await
     {
-        Ok(_) => {
-            info!(?session_id, "Successful notarization using websocket!");
+        Ok(transcript_length) => {
+            info!(
+                ?session_id,
+                sent = transcript_length.sent,
+                received = transcript_length.received,
+                "Successful notarization using websocket!"
+            );
+            session_metrics.completed(&transcript_length);
         }
         Err(err) => {
             error!(?session_id, "Failed notarization using websocket: {err}");
+            session_metrics.failed();
         }
     }
 }
@@ -0,0 +1,329 @@
+//! Storage of notarization sessions created by the /session API.
+//!
+//! Sessions are inserted when a prover calls /session and taken out of the
+//! store when the prover connects to /notarize, so each session id can only be
+//! used once.
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{
+    config::{SessionStoreBackend, SessionStoreProperties},
+    error::NotaryServerError,
+};
+
+/// A notarization session created by the /session API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    /// Maximum data that can be sent by the prover in this session
+    pub max_sent_data: usize,
+    /// Maximum data that can be received by the prover in this session
+    pub max_recv_data: usize,
+    /// Unix timestamp in seconds of when the session was created
+    pub created_at: u64,
+    /// Unix timestamp in seconds after which the session can no longer be used
+    pub expires_at: u64,
+    /// Hex encoded SHA-256 hash of the API key which was used to create the
+    /// session, if authorization is turned on. The key itself is not stored as
+    /// sessions may be persisted to disk
+    #[serde(default)]
+    pub api_key_hash: Option<String>,
+    /// Name of the server that the prover declared to notarize a session with
+    #[serde(default)]
+    pub server_name: Option<String>,
+}
+
+impl Session {
+    /// Creates a new session which was created now and expires after `ttl`
+    /// seconds.
+    pub fn new(max_sent_data: usize, max_recv_data: usize, ttl: u64) -> Self {
+        let created_at = unix_timestamp();
+        Self {
+            max_sent_data,
+            max_recv_data,
+            created_at,
+            expires_at: created_at.saturating_add(ttl),
+            api_key_hash: None,
+            server_name: None,
+        }
+    }
+
+    /// Returns whether the session has expired at the given unix timestamp.
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// A store of notarization sessions.
+#[async_trait]
+pub trait SessionStore: Debug + Send + Sync {
+    /// Inserts a session.
+    async fn insert(&self, session_id: &str, session: Session) -> Result<(), NotaryServerError>;
+
+    /// Removes a session from the store, returning it if it exists.
+    ///
+    /// The session is returned even if it has expired, so that the caller can
+    /// tell expired sessions apart from unknown ones.
+    async fn take(&self, session_id: &str) -> Result<Option<Session>, NotaryServerError>;
+
+    /// Removes all sessions which have expired at the given unix timestamp,
+    /// returning the number of removed sessions.
+    async fn remove_expired(&self, now: u64) -> Result<usize, NotaryServerError>;
+}
+
+/// Session store which keeps sessions in memory.
+///
+/// Sessions are lost when the server restarts and cannot be shared with other
+/// notary instances.
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn insert(&self, session_id: &str, session: Session) -> Result<(), NotaryServerError> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), session);
+        Ok(())
+    }
+
+    async fn take(&self, session_id: &str) -> Result<Option<Session>, NotaryServerError> {
+        Ok(self.sessions.lock().unwrap().remove(session_id))
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<usize, NotaryServerError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let len = sessions.len();
+        sessions.retain(|_, session| !session.is_expired(now));
+        Ok(len - sessions.len())
+    }
+}
+
+/// Session store which keeps each session in a JSON file in a directory.
+///
+/// Sessions survive restarts, and the directory can be shared by multiple
+/// notary instances, e.g. behind a load balancer. A session is taken by
+/// deleting its file, so only one instance can ever use it.
+#[derive(Debug)]
+pub struct FileSessionStore {
+    dir: PathBuf,
+}
+
+impl FileSessionStore {
+    /// Creates a new file session store, creating the directory if it does not
+    /// exist.
+    pub async fn new(dir: impl Into<PathBuf>) -> Result<Self, NotaryServerError> {
+        let dir = dir.into();
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|err| eyre!("Failed to create session store directory {dir:?}: {err}"))?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the path of the session file, or `None` if the session id is
+    /// not a valid uuid.
+    fn session_path(&self, session_id: &str) -> Option<PathBuf> {
+        // Session ids come from the prover, so they must be validated before
+        // they are used as a file name
+        let session_id = Uuid::parse_str(session_id).ok()?;
+        Some(self.dir.join(format!("{session_id}.json")))
+    }
+}
+
+#[async_trait]
+impl SessionStore for FileSessionStore {
+    async fn insert(&self, session_id: &str, session: Session) -> Result<(), NotaryServerError> {
+        let path = self
+            .session_path(session_id)
+            .ok_or_else(|| eyre!("Invalid session id: {session_id}"))?;
+        let data = serde_json::to_vec(&session)
+            .map_err(|err| eyre!("Failed to serialize session: {err}"))?;
+
+        // Write to a temporary file first so that other instances never read a
+        // partially written session
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, data)
+            .await
+            .map_err(|err| eyre!("Failed to write session file {tmp_path:?}: {err}"))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .map_err(|err| eyre!("Failed to write session file {path:?}: {err}"))?;
+
+        Ok(())
+    }
+
+    async fn take(&self, session_id: &str) -> Result<Option<Session>, NotaryServerError> {
+        let Some(path) = self.session_path(session_id) else {
+            return Ok(None);
+        };
+
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(eyre!("Failed to read session file {path:?}: {err}").into()),
+        };
+
+        // Only the instance which successfully removes the file gets to use the
+        // session
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                debug!(?session_id, "Session was taken by another instance");
+                return Ok(None);
+            }
+            Err(err) => return Err(eyre!("Failed to remove session file {path:?}: {err}").into()),
+        }
+
+        let session = serde_json::from_slice(&data)
+            .map_err(|err| eyre!("Failed to deserialize session file {path:?}: {err}"))?;
+
+        Ok(Some(session))
+    }
+
+    async fn remove_expired(&self, now: u64) -> Result<usize, NotaryServerError> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .map_err(|err| eyre!("Failed to read session store directory: {err}"))?;
+
+        let mut count = 0;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| eyre!("Failed to read session store directory: {err}"))?
+        {
+            let path = entry.path();
+            // Skip temporary files of sessions which are being inserted
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+
+            // The session may have been taken in the meantime, e.g. by another
+            // instance, in which case it is skipped
+            let Ok(data) = tokio::fs::read(&path).await else {
+                continue;
+            };
+            let Ok(session) = serde_json::from_slice::<Session>(&data) else {
+                error!("Skipping malformed session file {path:?}");
+                continue;
+            };
+
+            if session.is_expired(now) && tokio::fs::remove_file(&path).await.is_ok() {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+/// Creates the session store configured in the server properties.
+pub async fn load_session_store(
+    config: &SessionStoreProperties,
+) -> Result<Arc<dyn SessionStore>, NotaryServerError> {
+    let store: Arc<dyn SessionStore> = match config.backend {
+        SessionStoreBackend::Memory => Arc::new(MemorySessionStore::default()),
+        SessionStoreBackend::File => Arc::new(FileSessionStore::new(&config.path).await?),
+    };
+    debug!(backend = ?config.backend, "Loaded session store");
+    Ok(store)
+}
+
+/// Periodically removes expired sessions from the store.
+pub async fn reap_expired_sessions(store: Arc<dyn SessionStore>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        match store.remove_expired(unix_timestamp()).await {
+            Ok(0) => {}
+            Ok(count) => debug!(count, "Removed expired sessions"),
+            Err(err) => error!("Failed to remove expired sessions: {err}"),
+        }
+    }
+}
+
+/// Returns the current unix timestamp in seconds.
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is after unix epoch")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn test_store(store: &dyn SessionStore) {
+        let session_id = Uuid::new_v4().to_string();
+        let session = Session::new(1024, 4096, 60);
+
+        store.insert(&session_id, session.clone()).await.unwrap();
+        assert_eq!(store.take(&session_id).await.unwrap(), Some(session));
+        // Each session can only be used once
+        assert_eq!(store.take(&session_id).await.unwrap(), None);
+
+        // Expired sessions are still returned so that they can be told apart
+        let expired_session_id = Uuid::new_v4().to_string();
+        let expired_session = Session::new(1024, 4096, 0);
+        store
+            .insert(&expired_session_id, expired_session.clone())
+            .await
+            .unwrap();
+        let taken_session = store.take(&expired_session_id).await.unwrap().unwrap();
+        assert!(taken_session.is_expired(unix_timestamp()));
+
+        // Only expired sessions are removed
+        store.insert(&session_id, session.clone()).await.unwrap();
+        store
+            .insert(&expired_session_id, expired_session)
+            .await
+            .unwrap();
+        assert_eq!(store.remove_expired(unix_timestamp()).await.unwrap(), 1);
+        assert!(store.take(&expired_session_id).await.unwrap().is_none());
+        assert!(store.take(&session_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_memory_session_store() {
+        test_store(&MemorySessionStore::default()).await;
+    }
+
+    #[tokio::test]
+    async fn test_file_session_store() {
+        let dir = std::env::temp_dir().join(format!("notary-sessions-{}", Uuid::new_v4()));
+        let store = FileSessionStore::new(&dir).await.unwrap();
+
+        test_store(&store).await;
+
+        // Sessions are shared between stores using the same directory
+        let session_id = Uuid::new_v4().to_string();
+        store
+            .insert(&session_id, Session::new(1024, 4096, 60))
+            .await
+            .unwrap();
+        let other_store = FileSessionStore::new(&dir).await.unwrap();
+        assert!(other_store.take(&session_id).await.unwrap().is_some());
+        assert!(store.take(&session_id).await.unwrap().is_none());
+
+        // Invalid session ids are never used as a path
+        assert!(store.take("../session").await.unwrap().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -8,6 +8,7 @@ use hyper_util::{
     client::legacy::{connect::HttpConnector, Builder},
     rt::{TokioExecutor, TokioIo},
 };
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 use notary_client::{NotarizationRequest, NotaryClient, NotaryConnection};
 use rstest::rstest;
 use rustls::{Certificate, RootCertStore};
@@ -23,9 +24,10 @@ use tracing::debug;
 use ws_stream_tungstenite::WsStream;
 
 use notary_server::{
-    read_pem_file, run_server, AuthorizationProperties, LoggingProperties, NotarizationProperties,
+    read_pem_file, run_server, AuthorizationMode, AuthorizationProperties, ConcurrencyProperties,
+    JwtClaims, JwtProperties, LoggingProperties, NotarizationProperties,
     NotarizationSessionRequest, NotarizationSessionResponse, NotaryServerProperties,
-    NotarySigningKeyProperties, ServerProperties, TLSProperties,
+    NotarySigningKeyProperties, ServerProperties, SessionStoreProperties, TLSProperties,
 };
 
 const MAX_SENT_DATA: usize = 1 << 13;
@@ -36,6 +38,8 @@ const NOTARY_DNS: &str = "tlsnotaryserver.io";
 const NOTARY_CA_CERT_PATH: &str = "../server/fixture/tls/rootCA.crt";
 const NOTARY_CA_CERT_BYTES: &[u8] = include_bytes!("../../server/fixture/tls/rootCA.crt");
 const API_KEY: &str = "test_api_key_0";
+const JWT_KEY_PATH: &str = "../server/fixture/auth/jwt.key";
+const JWT_PUBLIC_KEY_PATH: &str = "../server/fixture/auth/jwt.pub";
 
 fn get_server_config(port: u16, tls_enabled: bool, auth_enabled: bool) -> NotaryServerProperties {
     NotaryServerProperties {
@@ -48,6 +52,9 @@ fn get_server_config(port: u16, tls_enabled: bool, auth_enabled: bool) -> Notary
         notarization: NotarizationProperties {
             max_sent_data: 1 << 13,
             max_recv_data: 1 << 14,
+            session_ttl: 60,
+            attestation_validity: None,
+            server_name_policy: Default::default(),
         },
         tls: TLSProperties {
             enabled: tls_enabled,
@@ -57,6 +64,7 @@ fn get_server_config(port: u16, tls_enabled: bool, auth_enabled: bool) -> Notary
         notary_key: NotarySigningKeyProperties {
             private_key_pem_path: "../server/fixture/notary/notary.key".to_string(),
             public_key_pem_path: "../server/fixture/notary/notary.pub".to_string(),
+            keyring_csv_path: None,
         },
         logging: LoggingProperties {
             level: "DEBUG".to_string(),
@@ -65,7 +73,10 @@ fn get_server_config(port: u16, tls_enabled: bool, auth_enabled: bool) -> Notary
         authorization: AuthorizationProperties {
             enabled: auth_enabled,
             whitelist_csv_path: "../server/fixture/auth/whitelist.csv".to_string(),
+            ..Default::default()
         },
+        session_store: SessionStoreProperties::default(),
+        concurrency: ConcurrencyProperties::default(),
     }
 }
 
@@ -299,6 +310,7 @@ async fn test_websocket_prover() {
         client_type: notary_server::ClientType::Websocket,
         max_sent_data: Some(MAX_SENT_DATA),
         max_recv_data: Some(MAX_RECV_DATA),
+        server_name: None,
     })
     .unwrap();
 
@@ -452,3 +464,158 @@ async fn test_websocket_prover() {
 
     debug!("Done notarization!");
 }
+
+#[tokio::test]
+async fn test_session_limits() {
+    let notary_config = setup_config_and_server(100, 7051, false, false).await;
+
+    // The Prover requests smaller limits at /session than the global limits of
+    // the Notary.
+    assert!(MAX_RECV_DATA < notary_config.notarization.max_recv_data);
+    let (notary_socket, _) = tcp_prover(notary_config.clone()).await;
+
+    // But then connects with the global limit.
+    let protocol_config = ProtocolConfig::builder()
+        .max_sent_data(MAX_SENT_DATA)
+        .max_recv_data(notary_config.notarization.max_recv_data)
+        .build()
+        .unwrap();
+
+    let prover_config = ProverConfig::builder()
+        .server_name(SERVER_DOMAIN)
+        .protocol_config(protocol_config)
+        .build()
+        .unwrap();
+
+    // The Notary enforces the limits of the session.
+    assert!(Prover::new(prover_config)
+        .setup(notary_socket.compat())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+#[ignore = "expensive"]
+async fn test_jwt_server_names() {
+    let mut notary_config = get_server_config(7052, false, true);
+    notary_config.authorization.mode = AuthorizationMode::Jwt;
+    notary_config.authorization.jwt = JwtProperties {
+        public_key_pem_path: Some(JWT_PUBLIC_KEY_PATH.to_string()),
+        ..Default::default()
+    };
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let config = notary_config.clone();
+    tokio::spawn(async move {
+        run_server(&config).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // The token only allows a server other than the one the Prover connects to.
+    let claims = JwtClaims {
+        exp: u64::MAX / 2,
+        server_names: Some(vec!["tlsnotary.org".to_string()]),
+        ..Default::default()
+    };
+    let key = EncodingKey::from_ec_pem(&std::fs::read(JWT_KEY_PATH).unwrap()).unwrap();
+    let token = encode(&Header::new(Algorithm::ES256), &claims, &key).unwrap();
+
+    let notary_client = NotaryClient::builder()
+        .host(&notary_config.server.host)
+        .port(notary_config.server.port)
+        .enable_tls(false)
+        .token(token)
+        .build()
+        .unwrap();
+
+    // The Prover declares the allowed server when creating the session.
+    let notarization_request = NotarizationRequest::builder()
+        .max_sent_data(MAX_SENT_DATA)
+        .max_recv_data(MAX_RECV_DATA)
+        .server_name("tlsnotary.org")
+        .build()
+        .unwrap();
+
+    let accepted_request = notary_client
+        .request_notarization(notarization_request)
+        .await
+        .unwrap();
+
+    let mut root_store = tls_core::anchors::RootCertStore::empty();
+    root_store
+        .add(&tls_core::key::Certificate(CA_CERT_DER.to_vec()))
+        .unwrap();
+
+    let provider = CryptoProvider {
+        cert: WebPkiVerifier::new(root_store, None),
+        ..Default::default()
+    };
+
+    let protocol_config = ProtocolConfig::builder()
+        .max_sent_data(MAX_SENT_DATA)
+        .max_recv_data(MAX_RECV_DATA)
+        .build()
+        .unwrap();
+
+    // But then connects to the test server.
+    let prover_config = ProverConfig::builder()
+        .server_name(SERVER_DOMAIN)
+        .protocol_config(protocol_config)
+        .crypto_provider(provider)
+        .build()
+        .unwrap();
+
+    let prover = Prover::new(prover_config)
+        .setup(accepted_request.io.compat())
+        .await
+        .unwrap();
+
+    let (client_socket, server_socket) = tokio::io::duplex(1 << 16);
+    let server_task = tokio::spawn(bind_test_server_hyper(server_socket.compat()));
+
+    let (tls_connection, prover_fut) = prover.connect(client_socket.compat()).await.unwrap();
+    let prover_task = tokio::spawn(prover_fut);
+
+    let (mut request_sender, connection) =
+        hyper::client::conn::http1::handshake(TokioIo::new(tls_connection.compat()))
+            .await
+            .unwrap();
+
+    tokio::spawn(connection);
+
+    let request = Request::builder()
+        .uri(format!("https://{}/echo", SERVER_DOMAIN))
+        .method("POST")
+        .header("Host", SERVER_DOMAIN)
+        .header("Connection", "close")
+        .body(Full::<Bytes>::new("echo".into()))
+        .unwrap();
+
+    let response = request_sender.send_request(request).await.unwrap();
+
+    assert!(response.status() == StatusCode::OK);
+
+    server_task.await.unwrap().unwrap();
+
+    let mut prover = prover_task.await.unwrap().unwrap().start_notarize();
+
+    let (sent_len, recv_len) = prover.transcript().len();
+
+    let mut builder = TranscriptCommitConfig::builder(prover.transcript());
+
+    builder.commit_sent(&(0..sent_len)).unwrap();
+    builder.commit_recv(&(0..recv_len)).unwrap();
+
+    let commit_config = builder.build().unwrap();
+
+    prover.transcript_commit(commit_config);
+
+    let mut request = RequestConfig::builder();
+    request.disclose_server_identity(true);
+    let request = request.build().unwrap();
+
+    // The Notary checks the proven server name against the token, and refuses to
+    // sign the attestation.
+    assert!(prover.finalize(&request).await.is_err());
+}
@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
 use mpz_ot::{chou_orlandi, kos};
-use tls_mpc::{MpcTlsCommonConfig, MpcTlsLeaderConfig, TranscriptConfig};
-use tlsn_common::config::ProtocolConfig;
+use tls_client::{kx_group, SupportedCipherSuite, SupportedKxGroup, ALL_CIPHER_SUITES};
+use tls_mpc::{
+    AeadAlgorithm, KeyExchangeGroup, MpcTlsCommonConfig, MpcTlsLeaderConfig, TranscriptConfig,
+};
+use tlsn_common::config::{self, ProtocolConfig};
 use tlsn_core::{connection::ServerName, CryptoProvider};
 
 /// Configuration for the prover
@@ -72,6 +75,8 @@ impl ProverConfig {
                             .unwrap(),
                     )
                     .handshake_commit(true)
+                    .aead_algorithm(self.aead_algorithm())
+                    .key_exchange_groups(self.key_exchange_groups())
                     .build()
                     .unwrap(),
             )
@@ -79,6 +84,48 @@ impl ProverConfig {
             .unwrap()
     }
 
+    /// Returns the cipher suites offered to the server, which are the ones
+    /// using the AEAD agreed with the verifier.
+    pub(crate) fn cipher_suites(&self) -> Vec<SupportedCipherSuite> {
+        let aead_algorithm = self.aead_algorithm();
+
+        ALL_CIPHER_SUITES
+            .iter()
+            .filter(|suite| aead_algorithm.cipher_suites().contains(&suite.suite()))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the key exchange groups offered to the server, which are the
+    /// ones agreed with the verifier.
+    pub(crate) fn kx_groups(&self) -> Vec<&'static SupportedKxGroup> {
+        self.key_exchange_groups()
+            .into_iter()
+            .map(|group| match group {
+                KeyExchangeGroup::X25519 => &kx_group::X25519,
+                KeyExchangeGroup::P256 => &kx_group::SECP256R1,
+            })
+            .collect()
+    }
+
+    fn key_exchange_groups(&self) -> Vec<KeyExchangeGroup> {
+        self.protocol_config
+            .key_exchange_groups()
+            .iter()
+            .map(|group| match group {
+                config::KeyExchangeGroup::X25519 => KeyExchangeGroup::X25519,
+                config::KeyExchangeGroup::P256 => KeyExchangeGroup::P256,
+            })
+            .collect()
+    }
+
+    fn aead_algorithm(&self) -> AeadAlgorithm {
+        match self.protocol_config.aead_algorithm() {
+            config::AeadAlgorithm::Aes128Gcm => AeadAlgorithm::Aes128Gcm,
+            config::AeadAlgorithm::ChaCha20Poly1305 => AeadAlgorithm::ChaCha20Poly1305,
+        }
+    }
+
     pub(crate) fn build_base_ot_sender_config(&self) -> chou_orlandi::SenderConfig {
         chou_orlandi::SenderConfig::builder()
             .receiver_commit()
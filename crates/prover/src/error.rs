@@ -138,3 +138,9 @@ impl From<mpz_garble::ProveError> for ProverError {
         Self::new(ErrorKind::Mpc, e)
     }
 }
+
+impl From<tlsn_common::commit::CommitError> for ProverError {
+    fn from(e: tlsn_common::commit::CommitError) -> Self {
+        Self::new(ErrorKind::Mpc, e)
+    }
+}
@@ -149,7 +149,10 @@ impl Prover<state::Setup> {
             })?;
 
         let config = tls_client::ClientConfig::builder()
-            .with_safe_defaults()
+            .with_cipher_suites(&self.config.cipher_suites())
+            .with_kx_groups(&self.config.kx_groups())
+            .with_safe_default_protocol_versions()
+            .map_err(ProverError::config)?
             .with_root_certificates(self.config.crypto_provider().cert.root_store().clone())
             .with_no_client_auth();
         let client =
@@ -5,6 +5,10 @@
 use super::{state::Notarize, Prover, ProverError};
 use mpz_ot::VerifiableOTReceiver;
 use serio::{stream::IoStreamExt as _, SinkExt as _};
+use tlsn_common::{
+    commit::{prove_plaintext_hash, SUPPORTED_PLAINTEXT_HASH_ALGS},
+    predicate::prove_predicate,
+};
 use tlsn_core::{
     attestation::Attestation,
     request::{Request, RequestConfig},
@@ -55,6 +59,10 @@ impl Prover<Notarize> {
             .server_cert_data(server_cert_data)
             .transcript(transcript);
 
+        if let Some(key) = config.holder_key() {
+            builder.holder_key(key.clone());
+        }
+
         if let Some(config) = transcript_commit_config {
             if config.has_encoding() {
                 builder.encoding_tree(
@@ -67,6 +75,18 @@ impl Prover<Notarize> {
                     .unwrap(),
                 );
             }
+
+            if let Some((_, alg)) = config
+                .iter_hash()
+                .find(|(_, alg)| !SUPPORTED_PLAINTEXT_HASH_ALGS.contains(alg))
+            {
+                return Err(ProverError::config(format!(
+                    "plaintext hash commitments are not supported with hash algorithm: {alg}"
+                )));
+            }
+
+            builder.plaintext_hashes(config.iter_hash().map(|(idx, alg)| (idx.clone(), *alg)));
+            builder.predicates(config.iter_predicates().cloned());
         }
 
         let (request, secrets) = builder.build(provider).map_err(ProverError::attestation)?;
@@ -77,6 +97,31 @@ impl Prover<Notarize> {
 
                 io.send(request.clone()).await?;
 
+                // Prove the plaintext hashes were computed over the transcript.
+                for (i, (hash, secret)) in request
+                    .plaintext_hashes()
+                    .iter()
+                    .zip(secrets.plaintext_hashes())
+                    .enumerate()
+                {
+                    prove_plaintext_hash(
+                        &mut vm,
+                        &format!("plaintext_hash/{i}"),
+                        hash,
+                        secret.blinder(),
+                    )
+                    .await?;
+                }
+
+                debug!("proved plaintext hashes");
+
+                // Prove the predicates hold for the transcript.
+                for (i, statement) in request.predicates().iter().enumerate() {
+                    prove_predicate(&mut vm, &format!("predicate/{i}"), statement).await?;
+                }
+
+                debug!("proved predicates");
+
                 ot_recv.accept_reveal(&mut ctx).await?;
 
                 debug!("received OT secret");
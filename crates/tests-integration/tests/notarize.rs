@@ -1,8 +1,12 @@
 use tls_core::verify::WebPkiVerifier;
 use tlsn_common::config::{ProtocolConfig, ProtocolConfigValidator};
 use tlsn_core::{
-    attestation::AttestationConfig, request::RequestConfig, signing::SignatureAlgId,
-    transcript::TranscriptCommitConfig, CryptoProvider,
+    attestation::{Attestation, AttestationConfig},
+    presentation::{Nonce, VerifyOptions},
+    request::RequestConfig,
+    signing::{Secp256k1Signer, SignatureAlgId, Signer},
+    transcript::TranscriptCommitConfig,
+    CryptoProvider, Secrets,
 };
 use tlsn_prover::{Prover, ProverConfig};
 use tlsn_server_fixture::bind;
@@ -28,24 +32,90 @@ async fn notarize() {
 
     let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
 
-    tokio::join!(prover(socket_0), notary(socket_1));
+    tokio::join!(prover(socket_0, RequestConfig::default()), notary(socket_1));
 }
 
-#[instrument(skip(notary_socket))]
-async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(notary_socket: T) {
-    let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
+/// Tests that a presentation of an attestation with a holder key can be bound
+/// to a nonce and verified by a Verifier.
+#[tokio::test]
+#[ignore]
+async fn notarize_with_binding() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
 
-    let server_task = tokio::spawn(bind(server_socket.compat()));
+    // Key of the Prover which is included in the attestation.
+    let holder = Secp256k1Signer::new(&[7u8; 32]).unwrap();
+
+    let mut config = RequestConfig::builder();
+    config.holder_key(holder.verifying_key());
+    let config = config.build().unwrap();
+
+    let ((attestation, secrets), _) = tokio::join!(prover(socket_0, config), notary(socket_1));
+
+    assert_eq!(attestation.body.holder_key(), Some(&holder.verifying_key()));
+
+    let provider = crypto_provider();
+    let (sent_len, recv_len) = secrets.transcript().len();
+
+    let mut transcript_proof_builder = secrets.transcript_proof_builder();
+    transcript_proof_builder
+        .reveal_sent(&(0..sent_len))
+        .unwrap()
+        .reveal_recv(&(0..recv_len))
+        .unwrap();
+    let transcript_proof = transcript_proof_builder.build().unwrap();
+
+    // Verifier issues a nonce.
+    let nonce = Nonce::from([3u8; 32]);
+
+    let mut builder = attestation.presentation_builder(&provider);
+    builder
+        .identity_proof(secrets.identity_proof())
+        .transcript_proof(transcript_proof)
+        .binding(nonce.clone(), &holder);
+    let presentation = builder.build().unwrap();
+
+    let output = presentation
+        .clone()
+        .verify(&provider, &VerifyOptions::new().nonce(nonce.clone()))
+        .unwrap();
+
+    assert_eq!(output.server_name.unwrap().as_str(), SERVER_DOMAIN);
+    assert_eq!(
+        output.transcript.unwrap().received_unsafe(),
+        secrets.transcript().received()
+    );
 
+    // A presentation bound to another nonce is rejected.
+    assert!(presentation
+        .verify(
+            &provider,
+            &VerifyOptions::new().nonce(Nonce::from([4u8; 32]))
+        )
+        .is_err());
+}
+
+fn crypto_provider() -> CryptoProvider {
     let mut root_store = tls_core::anchors::RootCertStore::empty();
     root_store
         .add(&tls_core::key::Certificate(CA_CERT_DER.to_vec()))
         .unwrap();
 
-    let provider = CryptoProvider {
+    CryptoProvider {
         cert: WebPkiVerifier::new(root_store, None),
         ..Default::default()
-    };
+    }
+}
+
+#[instrument(skip(notary_socket, request_config))]
+async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
+    notary_socket: T,
+    request_config: RequestConfig,
+) -> (Attestation, Secrets) {
+    let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
+
+    let server_task = tokio::spawn(bind(server_socket.compat()));
+
+    let provider = crypto_provider();
 
     let protocol_config = ProtocolConfig::builder()
         .max_sent_data(MAX_SENT_DATA)
@@ -109,22 +179,12 @@ async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(notary_socke
 
     prover.transcript_commit(config);
 
-    let config = RequestConfig::default();
-
-    prover.finalize(&config).await.unwrap();
+    prover.finalize(&request_config).await.unwrap()
 }
 
 #[instrument(skip(socket))]
 async fn notary<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(socket: T) {
-    let mut root_store = tls_core::anchors::RootCertStore::empty();
-    root_store
-        .add(&tls_core::key::Certificate(CA_CERT_DER.to_vec()))
-        .unwrap();
-
-    let mut provider = CryptoProvider {
-        cert: WebPkiVerifier::new(root_store, None),
-        ..Default::default()
-    };
+    let mut provider = crypto_provider();
 
     provider.signer.set_secp256k1(&[1u8; 32]).unwrap();
 
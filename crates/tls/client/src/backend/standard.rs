@@ -1,12 +1,15 @@
 use super::{Backend, BackendError};
-use crate::{DecryptMode, EncryptMode, Error};
+use crate::{
+    kx::{KeyExchange, SupportedKxGroup, SECP256R1, X25519},
+    DecryptMode, EncryptMode, Error,
+};
 use aes_gcm::{
     aead::{generic_array::GenericArray, Aead, NewAead, Payload},
     Aes128Gcm,
 };
 use async_trait::async_trait;
-use p256::{ecdh::EphemeralSecret, EncodedPoint, PublicKey as ECDHPublicKey};
-use rand::{rngs::OsRng, thread_rng, Rng};
+use chacha20poly1305::ChaCha20Poly1305;
+use rand::{thread_rng, Rng};
 
 use digest::Digest;
 use std::{any::Any, collections::VecDeque, convert::TryInto};
@@ -17,13 +20,16 @@ use tls_core::{
     msgs::{
         base::Payload as TLSPayload,
         enums::{CipherSuite, ContentType, NamedGroup, ProtocolVersion},
-        handshake::Random,
+        handshake::{Random, ServerECDHParams},
         message::{OpaqueMessage, PlainMessage},
     },
     prf::prf,
-    suites::{self, SupportedCipherSuite},
+    suites::{self, tls12::decode_ecdh_params, SupportedCipherSuite},
 };
 
+/// Key exchange groups implemented by the backend.
+static IMPLEMENTED_KX_GROUPS: [&SupportedKxGroup; 2] = [&SECP256R1, &X25519];
+
 /// Implementation of TLS backend using RustCrypto primitives
 pub struct RustCryptoBackend {
     client_random: Option<Random>,
@@ -33,13 +39,13 @@ pub struct RustCryptoBackend {
     // extended master secret seed
     ems_seed: Option<Vec<u8>>,
     ecdh_pubkey: Option<Vec<u8>>,
-    ecdh_secret: Option<EphemeralSecret>,
+    ecdh_secret: Option<KeyExchange>,
     // session_keys size can vary depending on the ciphersuite
     session_keys: Option<Vec<u8>>,
     protocol_version: Option<ProtocolVersion>,
     cipher_suite: Option<SupportedCipherSuite>,
     curve: Option<NamedGroup>,
-    implemented_suites: [CipherSuite; 2],
+    implemented_suites: [CipherSuite; 4],
     encrypter: Option<Encrypter>,
     decrypter: Option<Decrypter>,
 
@@ -63,6 +69,8 @@ impl RustCryptoBackend {
             implemented_suites: [
                 CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
                 CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+                CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+                CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
             ],
             encrypter: None,
             decrypter: None,
@@ -93,7 +101,8 @@ impl RustCryptoBackend {
         client_random: &[u8; 32],
         server_random: &[u8; 32],
         pms: &[u8],
-    ) -> ([u8; 48], [u8; 40]) {
+        key_block_len: usize,
+    ) -> ([u8; 48], Vec<u8>) {
         // first expand pms into ms
         let mut ms = [0u8; 48];
         prf(
@@ -105,7 +114,7 @@ impl RustCryptoBackend {
         .expect("key length is valid");
 
         // expand ms into session keys
-        let mut session_keys = [0u8; 40];
+        let mut session_keys = vec![0u8; key_block_len];
         prf(
             &mut session_keys,
             &ms,
@@ -122,51 +131,40 @@ impl RustCryptoBackend {
             "can not set enccrypter, ciphersuite not set".to_string(),
         ))?;
 
-        match cipher_suite.suite() {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => {
-                // extract client_write_key and client_write_iv. They may be at different
-                // offsets depending on the cipher suite.
-                let mut write_key = [0u8; 16];
-                let mut write_iv = [0u8; 4];
-                let session_keys = self
-                    .session_keys
-                    .as_ref()
-                    .ok_or(BackendError::InvalidState(
-                        "can not set encrypter, session_keys are not set".to_string(),
-                    ))?;
-                write_key.copy_from_slice(&session_keys[0..16]);
-                write_iv.copy_from_slice(&session_keys[32..36]);
-                self.encrypter = Some(Encrypter::new(write_key, write_iv, cipher_suite.suite()));
-            }
-            suite => return Err(BackendError::UnsupportedCiphersuite(suite)),
-        }
+        // extract client_write_key and client_write_iv. They are at different
+        // offsets depending on the cipher suite.
+        let (key_len, iv_len) = key_iv_len(cipher_suite.suite())?;
+        let session_keys = self
+            .session_keys
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "can not set encrypter, session_keys are not set".to_string(),
+            ))?;
+        let write_key = session_keys[..key_len].to_vec();
+        let write_iv = session_keys[2 * key_len..2 * key_len + iv_len].to_vec();
+        self.encrypter = Some(Encrypter::new(write_key, write_iv, cipher_suite.suite()));
+
         Ok(())
     }
+
     fn set_decrypter(&mut self) -> Result<(), BackendError> {
         let cipher_suite = self.cipher_suite.ok_or(BackendError::InvalidState(
             "can not set decrypter, ciphersuite not set".to_string(),
         ))?;
 
-        match cipher_suite.suite() {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => {
-                // extract server_write_key and server_write_iv. They may be at different
-                // offsets depending on the cipher suite.
-                let mut write_key = [0u8; 16];
-                let mut write_iv = [0u8; 4];
-                let session_keys = self
-                    .session_keys
-                    .as_ref()
-                    .ok_or(BackendError::InvalidState(
-                        "can not set decrypter, session_keys are not set".to_string(),
-                    ))?;
-                write_key.copy_from_slice(&session_keys[16..32]);
-                write_iv.copy_from_slice(&session_keys[36..40]);
-                self.decrypter = Some(Decrypter::new(write_key, write_iv, cipher_suite.suite()));
-            }
-            suite => return Err(BackendError::UnsupportedCiphersuite(suite)),
-        }
+        // extract server_write_key and server_write_iv. They are at different
+        // offsets depending on the cipher suite.
+        let (key_len, iv_len) = key_iv_len(cipher_suite.suite())?;
+        let session_keys = self
+            .session_keys
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "can not set decrypter, session_keys are not set".to_string(),
+            ))?;
+        let write_key = session_keys[key_len..2 * key_len].to_vec();
+        let write_iv = session_keys[2 * key_len + iv_len..2 * (key_len + iv_len)].to_vec();
+        self.decrypter = Some(Decrypter::new(write_key, write_iv, cipher_suite.suite()));
+
         Ok(())
     }
 }
@@ -227,17 +225,21 @@ impl Backend for RustCryptoBackend {
 
     async fn get_client_key_share(&mut self) -> Result<PublicKey, BackendError> {
         // TODO make sure this and other methods are not called twice/out of order
+        let group = self.curve.ok_or(BackendError::InvalidState(
+            "ECDH key curve not set yet".to_string(),
+        ))?;
+
         // generate our ECDH keypair
-        let sk = EphemeralSecret::random(&mut OsRng);
-        let pk_bytes = EncodedPoint::from(sk.public_key()).to_bytes().to_vec();
+        let kx_group = KeyExchange::choose(group, &IMPLEMENTED_KX_GROUPS)
+            .ok_or(BackendError::UnsupportedCurveGroup(group))?;
+        let sk = KeyExchange::start(kx_group).ok_or(BackendError::InternalError(
+            "failed to generate ECDH key".to_string(),
+        ))?;
+        let pk_bytes = sk.pubkey.as_ref().to_vec();
         self.ecdh_pubkey = Some(pk_bytes.clone());
         self.ecdh_secret = Some(sk);
 
         // return our ECDH pubkey
-        let group = self.curve.ok_or(BackendError::InvalidState(
-            "ECDH key curve not set yet".to_string(),
-        ))?;
-
         Ok(PublicKey {
             group,
             key: pk_bytes,
@@ -251,25 +253,19 @@ impl Backend for RustCryptoBackend {
     }
 
     async fn set_server_key_share(&mut self, key: PublicKey) -> Result<(), BackendError> {
-        // convert raw server ECDH pubkey to an object
-        let server_pk =
-            ECDHPublicKey::from_sec1_bytes(&key.key).map_err(|_| BackendError::InvalidServerKey)?;
+        let sk = self.ecdh_secret.take().ok_or(BackendError::InvalidState(
+            "ECDH key not generated yet".to_string(),
+        ))?;
 
-        let sk = self.ecdh_secret.as_ref().unwrap();
-        // perform ECDH, obtain PMS (which is the X coordinate of the resulting
-        // EC point). The size of X for 256-bit curves is 32 bytes, for 384-bit
-        // curves it is 48 bytes etc.
-        let x_size = match self.curve.ok_or(BackendError::InvalidState(
-            "ECDH key curve not set yet".to_string(),
-        ))? {
-            NamedGroup::secp256r1 => 32,
-            NamedGroup::secp384r1 => 48,
-            group => return Err(BackendError::UnsupportedCurveGroup(group)),
-        };
+        if key.group != sk.group() {
+            return Err(BackendError::InvalidServerKey);
+        }
 
-        let mut pms = vec![0u8; x_size];
-        let secret = *sk.diffie_hellman(&server_pk).raw_secret_bytes();
-        pms.copy_from_slice(&secret);
+        // perform ECDH, obtain PMS (which is the X coordinate of the resulting
+        // EC point for NIST curves, or the X25519 output).
+        let pms = sk
+            .complete(&key.key, |secret| secret.to_vec())
+            .map_err(|_| BackendError::InvalidServerKey)?;
 
         let (client_random, server_random) = match (self.client_random, self.server_random) {
             (Some(cr), Some(sr)) => (cr.0, sr.0),
@@ -280,12 +276,22 @@ impl Backend for RustCryptoBackend {
             }
         };
 
+        let cipher_suite = self.cipher_suite.ok_or(BackendError::InvalidState(
+            "Ciphersuite not set".to_string(),
+        ))?;
+        let (key_len, iv_len) = key_iv_len(cipher_suite.suite())?;
+
         (self.master_secret, self.session_keys) = match self.protocol_version.ok_or(
             BackendError::InvalidState("Protocol version not set".to_string()),
         )? {
             ProtocolVersion::TLSv1_2 => {
-                let (ms, ek) = self.key_expansion_tls12(&client_random, &server_random, &pms);
-                (Some(ms), Some(ek.to_vec()))
+                let (ms, ek) = self.key_expansion_tls12(
+                    &client_random,
+                    &server_random,
+                    &pms,
+                    2 * (key_len + iv_len),
+                );
+                (Some(ms), Some(ek))
             }
             version => return Err(BackendError::UnsupportedProtocolVersion(version)),
         };
@@ -305,8 +311,18 @@ impl Backend for RustCryptoBackend {
 
     async fn set_server_kx_details(
         &mut self,
-        _kx_details: ServerKxDetails,
+        kx_details: ServerKxDetails,
     ) -> Result<(), BackendError> {
+        // use the group chosen by the server for our key share
+        let ecdh_params = decode_ecdh_params::<ServerECDHParams>(kx_details.kx_params())
+            .ok_or(BackendError::InvalidServerKey)?;
+        let group = ecdh_params.curve_params.named_group;
+
+        if KeyExchange::choose(group, &IMPLEMENTED_KX_GROUPS).is_none() {
+            return Err(BackendError::UnsupportedCurveGroup(group));
+        }
+        self.curve = Some(group);
+
         Ok(())
     }
 
@@ -373,6 +389,15 @@ impl Backend for RustCryptoBackend {
                     return Err(BackendError::UnsupportedProtocolVersion(version));
                 }
             },
+            CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256 => match msg.version {
+                ProtocolVersion::TLSv1_2 => {
+                    return enc.encrypt_chacha20poly1305(&msg, seq);
+                }
+                version => {
+                    return Err(BackendError::UnsupportedProtocolVersion(version));
+                }
+            },
             suite => {
                 return Err(BackendError::UnsupportedCiphersuite(suite));
             }
@@ -401,6 +426,15 @@ impl Backend for RustCryptoBackend {
                     return Err(BackendError::UnsupportedProtocolVersion(version));
                 }
             },
+            CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256 => match msg.version {
+                ProtocolVersion::TLSv1_2 => {
+                    return dec.decrypt_chacha20poly1305(&msg, seq);
+                }
+                version => {
+                    return Err(BackendError::UnsupportedProtocolVersion(version));
+                }
+            },
             suite => {
                 return Err(BackendError::UnsupportedCiphersuite(suite));
             }
@@ -421,6 +455,40 @@ impl Backend for RustCryptoBackend {
     }
 }
 
+/// Returns the length of the write key and the fixed write IV of a cipher
+/// suite.
+fn key_iv_len(suite: CipherSuite) -> Result<(usize, usize), BackendError> {
+    match suite {
+        CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
+        | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => Ok((16, 4)),
+        CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+        | CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256 => Ok((32, 12)),
+        suite => Err(BackendError::UnsupportedCiphersuite(suite)),
+    }
+}
+
+/// Builds the TLS 1.2 AEAD additional data.
+fn make_tls12_aad(seq: u64, typ: ContentType, version: ProtocolVersion, len: usize) -> [u8; 13] {
+    let mut aad = [0u8; 13];
+    aad[..8].copy_from_slice(&seq.to_be_bytes());
+    aad[8] = typ.get_u8();
+    aad[9..11].copy_from_slice(&version.get_u16().to_be_bytes());
+    aad[11..13].copy_from_slice(&(len as u16).to_be_bytes());
+    aad
+}
+
+/// Builds the ChaCha20-Poly1305 nonce by XORing the sequence number into the
+/// fixed IV, see RFC 7905.
+fn make_chacha20poly1305_nonce(iv: &[u8], seq: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(iv);
+    nonce[4..]
+        .iter_mut()
+        .zip(seq.to_be_bytes())
+        .for_each(|(n, s)| *n ^= s);
+    nonce
+}
+
 /// Concatenates two slices into a new array.
 ///
 /// # Panics
@@ -435,13 +503,13 @@ fn concat<const O: usize>(left: &[u8], right: &[u8]) -> [u8; O] {
 }
 
 pub struct Encrypter {
-    write_key: [u8; 16],
-    write_iv: [u8; 4],
+    write_key: Vec<u8>,
+    write_iv: Vec<u8>,
     cipher_suite: CipherSuite,
 }
 
 impl Encrypter {
-    pub fn new(write_key: [u8; 16], write_iv: [u8; 4], cipher_suite: CipherSuite) -> Self {
+    pub fn new(write_key: Vec<u8>, write_iv: Vec<u8>, cipher_suite: CipherSuite) -> Self {
         Self {
             write_key,
             write_iv,
@@ -456,11 +524,7 @@ impl Encrypter {
         seq: u64,
         explicit_nonce: &[u8; 8],
     ) -> Result<OpaqueMessage, BackendError> {
-        let mut aad = [0u8; 13];
-        aad[..8].copy_from_slice(&seq.to_be_bytes());
-        aad[8] = m.typ.get_u8();
-        aad[9..11].copy_from_slice(&m.version.get_u16().to_be_bytes());
-        aad[11..13].copy_from_slice(&(m.payload.0.len() as u16).to_be_bytes());
+        let aad = make_tls12_aad(seq, m.typ, m.version, m.payload.0.len());
         let payload = Payload {
             msg: &m.payload.0,
             aad: &aad,
@@ -488,16 +552,43 @@ impl Encrypter {
 
         Ok(om)
     }
+
+    /// Encrypt with ChaCha20Poly1305 using TLS-specific AAD.
+    fn encrypt_chacha20poly1305(
+        &self,
+        m: &PlainMessage,
+        seq: u64,
+    ) -> Result<OpaqueMessage, BackendError> {
+        let aad = make_tls12_aad(seq, m.typ, m.version, m.payload.0.len());
+        let payload = Payload {
+            msg: &m.payload.0,
+            aad: &aad,
+        };
+
+        let nonce = make_chacha20poly1305_nonce(&self.write_iv, seq);
+        let nonce = GenericArray::from_slice(&nonce);
+        let cipher = ChaCha20Poly1305::new_from_slice(&self.write_key).unwrap();
+        // ciphertext will have the MAC appended, there is no explicit nonce
+        let ciphertext = cipher
+            .encrypt(nonce, payload)
+            .map_err(|e| BackendError::EncryptionError(e.to_string()))?;
+
+        Ok(OpaqueMessage {
+            typ: m.typ,
+            version: m.version,
+            payload: TLSPayload::new(ciphertext),
+        })
+    }
 }
 
 pub struct Decrypter {
-    write_key: [u8; 16],
-    write_iv: [u8; 4],
+    write_key: Vec<u8>,
+    write_iv: Vec<u8>,
     cipher_suite: CipherSuite,
 }
 
 impl Decrypter {
-    pub fn new(write_key: [u8; 16], write_iv: [u8; 4], cipher_suite: CipherSuite) -> Self {
+    pub fn new(write_key: Vec<u8>, write_iv: Vec<u8>, cipher_suite: CipherSuite) -> Self {
         Self {
             write_key,
             write_iv,
@@ -514,13 +605,9 @@ impl Decrypter {
                 payload: TLSPayload(m.payload.0.clone()),
             });
         }
-        let mut aad = [0u8; 13];
-        aad[..8].copy_from_slice(&seq.to_be_bytes());
-        aad[8] = m.typ.get_u8();
-        aad[9..11].copy_from_slice(&m.version.get_u16().to_be_bytes());
         // 8-byte explicit nonce and 16-byte MAC are not counted towards
         // plaintext size.
-        aad[11..13].copy_from_slice(&((m.payload.0.len() - 24) as u16).to_be_bytes());
+        let aad = make_tls12_aad(seq, m.typ, m.version, m.payload.0.len() - 24);
         let aes_payload = Payload {
             msg: &m.payload.0[8..],
             aad: &aad,
@@ -541,4 +628,46 @@ impl Decrypter {
             payload: TLSPayload(plaintext),
         })
     }
+
+    fn decrypt_chacha20poly1305(
+        &self,
+        m: &OpaqueMessage,
+        seq: u64,
+    ) -> Result<PlainMessage, BackendError> {
+        // TODO tls-client shouldnt call decrypt with CCS
+        if m.typ == ContentType::ChangeCipherSpec {
+            return Ok(PlainMessage {
+                typ: m.typ,
+                version: m.version,
+                payload: TLSPayload(m.payload.0.clone()),
+            });
+        }
+        // 16-byte MAC is not counted towards plaintext size.
+        let plaintext_len = m
+            .payload
+            .0
+            .len()
+            .checked_sub(16)
+            .ok_or(BackendError::DecryptionError(
+                "ciphertext is too short".to_string(),
+            ))?;
+        let aad = make_tls12_aad(seq, m.typ, m.version, plaintext_len);
+        let payload = Payload {
+            msg: &m.payload.0,
+            aad: &aad,
+        };
+
+        let cipher = ChaCha20Poly1305::new_from_slice(&self.write_key).unwrap();
+        let nonce = make_chacha20poly1305_nonce(&self.write_iv, seq);
+        let nonce = GenericArray::from_slice(&nonce);
+        let plaintext = cipher
+            .decrypt(nonce, payload)
+            .map_err(|e| BackendError::DecryptionError(e.to_string()))?;
+
+        Ok(PlainMessage {
+            typ: m.typ,
+            version: m.version,
+            payload: TLSPayload(plaintext),
+        })
+    }
 }
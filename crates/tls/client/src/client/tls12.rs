@@ -831,6 +831,13 @@ impl State<ClientConnectionData> for ExpectServerDone {
                 }
             };
 
+        // The backend needs to know the group chosen by the server before
+        // generating the client key share.
+        cx.common
+            .backend
+            .set_server_kx_details(st.server_kx)
+            .await?;
+
         let key_share = cx.common.backend.get_client_key_share().await?;
         if key_share.group != ecdh_params.curve_params.named_group {
             return Err(Error::PeerMisbehavedError(
@@ -861,10 +868,6 @@ impl State<ClientConnectionData> for ExpectServerDone {
         let server_key_share =
             PublicKey::new(ecdh_params.curve_params.named_group, &ecdh_params.public.0);
 
-        cx.common
-            .backend
-            .set_server_kx_details(st.server_kx)
-            .await?;
         cx.common
             .backend
             .set_server_key_share(server_key_share)
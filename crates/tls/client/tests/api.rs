@@ -1625,18 +1625,18 @@ static TEST_CIPHERSUITES: &[(&tls_client::SupportedProtocolVersion, KeyType, Cip
     //     KeyType::Rsa,
     //     CipherSuite::TLS13_AES_128_GCM_SHA256,
     // ),
-    // #[cfg(feature = "tls12")]
-    // (
-    //     &tls_client::version::TLS12,
-    //     KeyType::Ecdsa,
-    //     CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
-    // ),
-    // #[cfg(feature = "tls12")]
-    // (
-    //     &tls_client::version::TLS12,
-    //     KeyType::Rsa,
-    //     CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
-    // ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Ecdsa,
+        CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Rsa,
+        CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
     // #[cfg(feature = "tls12")]
     // (
     //     &tls_client::version::TLS12,
@@ -2370,6 +2370,18 @@ async fn test_client_config_keyshare() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_client_config_keyshare_x25519() {
+    let client_config =
+        make_client_config_with_kx_groups(KeyType::Rsa, &[&tls_client::kx_group::X25519]);
+    let server_config =
+        make_server_config_with_kx_groups(KeyType::Rsa, &[&rustls::kx_group::X25519]);
+    let (mut client, mut server) = make_pair_for_configs(client_config, server_config).await;
+    do_handshake_until_error(&mut client, &mut server)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_client_config_keyshare_mismatch() {
     let client_config =
@@ -163,25 +163,37 @@ pub static ALL_CIPHER_SUITES: &[SupportedCipherSuite] = &[
     // TLS13_AES_128_GCM_SHA256,
     // TLS13_CHACHA20_POLY1305_SHA256,
     // TLS1.2 suites
+    //
+    // The AES-256-GCM suites stay disabled until the MPC backend has AES-256
+    // and HMAC-SHA384 circuits.
     // #[cfg(feature = "tls12")]
     // TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
     #[cfg(feature = "tls12")]
     TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
-    // #[cfg(feature = "tls12")]
-    // TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
     // #[cfg(feature = "tls12")]
     // TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
     #[cfg(feature = "tls12")]
     TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
-    // #[cfg(feature = "tls12")]
-    // TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
 ];
 
 /// The cipher suite configuration that an application should use by default.
 ///
 /// This will be [`ALL_CIPHER_SUITES`] sans any supported cipher suites that
 /// shouldn't be enabled by most applications.
-pub static DEFAULT_CIPHER_SUITES: &[SupportedCipherSuite] = ALL_CIPHER_SUITES;
+///
+/// The MPC backend has to be preprocessed for a single AEAD, which is
+/// AES-128-GCM unless configured otherwise, so ChaCha20-Poly1305 suites are
+/// not enabled by default.
+pub static DEFAULT_CIPHER_SUITES: &[SupportedCipherSuite] = &[
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+];
 
 // These both O(N^2)!
 pub fn choose_ciphersuite_preferring_client(
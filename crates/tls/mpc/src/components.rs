@@ -1,23 +1,35 @@
 use aead::{
-    aes_gcm::{AesGcmConfig, AesGcmError, MpcAesGcm, Role as AeadRole},
-    Aead,
+    aes_gcm::{AesGcmConfig, MpcAesGcm},
+    chacha20_poly1305::{ChaCha20Poly1305Config, MpcChaCha20Poly1305},
+    Aead, AeadError, Role as AeadRole,
 };
 use block_cipher::{Aes128, BlockCipherConfig, MpcBlockCipher};
 use hmac_sha256::{MpcPrf, Prf, PrfConfig, Role as PrfRole};
-use key_exchange::{KeyExchange, KeyExchangeConfig, MpcKeyExchange, Role as KeRole};
+use key_exchange::{
+    x25519::F25519, KeyExchange, KeyExchangeConfig, MpcKeyExchange, Role as KeRole,
+};
 use mpz_common::{Context, Preprocess};
 use mpz_fields::{gf2_128::Gf2_128, p256::P256};
 use mpz_garble::{Decode, DecodePrivate, Execute, Load, Memory, Prove, Thread, Verify};
 use mpz_ole::rot::{OLEReceiver, OLESender};
 use mpz_ot::{OTError, RandomOTReceiver, RandomOTSender};
 use mpz_share_conversion::{ShareConversionReceiver, ShareConversionSender};
-use tlsn_stream_cipher::{Aes128Ctr, MpcStreamCipher, StreamCipherConfig};
+use tlsn_stream_cipher::{Aes128Ctr, ChaCha20, MpcStreamCipher, StreamCipherConfig};
 use tlsn_universal_hash::{
     ghash::{Ghash, GhashConfig},
+    poly1305::{Poly1305, Poly1305Config, P1305},
     UniversalHash,
 };
 
-use crate::{MpcTlsCommonConfig, TlsRole};
+use crate::{AeadAlgorithm, MpcTlsCommonConfig, TlsRole};
+
+/// The Poly1305 block length in bytes.
+const POLY1305_BLOCK_LEN: usize = 16;
+/// The maximum length of a TLS record plaintext.
+const MAX_RECORD_LEN: usize = 1 << 14;
+/// The number of records, beyond one per KiB of the transcript, for which
+/// Poly1305 keys are provisioned, eg. for the Finished messages and alerts.
+const EXTRA_RECORDS: usize = 8;
 
 /// Builds the components for MPC-TLS.
 // TODO: Better dependency injection!!
@@ -42,8 +54,8 @@ pub fn build_components<Ctx, T, OTS, OTR>(
 ) -> (
     Box<dyn KeyExchange + Send>,
     Box<dyn Prf + Send>,
-    Box<dyn Aead<Error = AesGcmError> + Send>,
-    Box<dyn Aead<Error = AesGcmError> + Send>,
+    Box<dyn Aead<Error = AeadError> + Send>,
+    Box<dyn Aead<Error = AeadError> + Send>,
 )
 where
     Ctx: Context + 'static,
@@ -61,6 +73,8 @@ where
     OTS: Preprocess<Ctx, Error = OTError>
         + RandomOTSender<Ctx, [P256; 2]>
         + RandomOTSender<Ctx, [Gf2_128; 2]>
+        + RandomOTSender<Ctx, [P1305; 2]>
+        + RandomOTSender<Ctx, [F25519; 2]>
         + Clone
         + Send
         + Sync
@@ -68,30 +82,44 @@ where
     OTR: Preprocess<Ctx, Error = OTError>
         + RandomOTReceiver<Ctx, bool, P256>
         + RandomOTReceiver<Ctx, bool, Gf2_128>
+        + RandomOTReceiver<Ctx, bool, P1305>
+        + RandomOTReceiver<Ctx, bool, F25519>
         + Clone
         + Send
         + Sync
         + 'static,
 {
+    let ke_groups = config
+        .key_exchange_groups()
+        .iter()
+        .map(|group| group.to_ke_group())
+        .collect::<Vec<_>>();
+
     let ke: Box<dyn KeyExchange + Send> = match role {
         TlsRole::Leader => Box::new(MpcKeyExchange::new(
             KeyExchangeConfig::builder()
                 .role(KeRole::Leader)
+                .groups(ke_groups)
                 .build()
                 .unwrap(),
             ctx_ke,
             ShareConversionSender::new(OLESender::new(ot_send.clone())),
             ShareConversionReceiver::new(OLEReceiver::new(ot_recv.clone())),
+            ShareConversionSender::new(OLESender::new(ot_send.clone())),
+            ShareConversionReceiver::new(OLEReceiver::new(ot_recv.clone())),
             thread_ke,
         )),
         TlsRole::Follower => Box::new(MpcKeyExchange::new(
             KeyExchangeConfig::builder()
                 .role(KeRole::Follower)
+                .groups(ke_groups)
                 .build()
                 .unwrap(),
             ctx_ke,
             ShareConversionReceiver::new(OLEReceiver::new(ot_recv.clone())),
             ShareConversionSender::new(OLESender::new(ot_send.clone())),
+            ShareConversionReceiver::new(OLEReceiver::new(ot_recv.clone())),
+            ShareConversionSender::new(OLESender::new(ot_send.clone())),
             thread_ke,
         )),
     };
@@ -108,101 +136,219 @@ where
         thread_prf_1,
     ));
 
-    // Encrypter
+    let aead_role = match role {
+        TlsRole::Leader => AeadRole::Leader,
+        TlsRole::Follower => AeadRole::Follower,
+    };
+
+    let (mut encrypter, mut decrypter): (
+        Box<dyn Aead<Error = AeadError> + Send>,
+        Box<dyn Aead<Error = AeadError> + Send>,
+    ) = match config.aead_algorithm() {
+        AeadAlgorithm::Aes128Gcm => (
+            build_aes_gcm(
+                "encrypter",
+                "tx",
+                role,
+                aead_role,
+                ctx_encrypter,
+                ctx_ghash_encrypter,
+                thread_encrypter_block_cipher,
+                thread_encrypter_stream_cipher,
+                ot_send.clone(),
+                ot_recv.clone(),
+            ),
+            build_aes_gcm(
+                "decrypter",
+                "rx",
+                role,
+                aead_role,
+                ctx_decrypter,
+                ctx_ghash_decrypter,
+                thread_decrypter_block_cipher,
+                thread_decrypter_stream_cipher,
+                ot_send,
+                ot_recv,
+            ),
+        ),
+        AeadAlgorithm::ChaCha20Poly1305 => (
+            build_chacha20_poly1305(
+                "encrypter",
+                "tx",
+                config.tx_config().max_online_size(),
+                role,
+                aead_role,
+                ctx_ghash_encrypter,
+                thread_encrypter_block_cipher,
+                thread_encrypter_stream_cipher,
+                ot_send.clone(),
+                ot_recv.clone(),
+            ),
+            build_chacha20_poly1305(
+                "decrypter",
+                "rx",
+                config.rx_config().max_online_size() + config.rx_config().max_offline_size(),
+                role,
+                aead_role,
+                ctx_ghash_decrypter,
+                thread_decrypter_block_cipher,
+                thread_decrypter_stream_cipher,
+                ot_send,
+                ot_recv,
+            ),
+        ),
+    };
+
+    encrypter.set_transcript_id(config.tx_config().opaque_id());
+    decrypter.set_transcript_id(config.rx_config().opaque_id());
+
+    (ke, prf, encrypter, decrypter)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_aes_gcm<Ctx, T, OTS, OTR>(
+    id: &str,
+    transcript_id: &str,
+    role: TlsRole,
+    aead_role: AeadRole,
+    ctx: Ctx,
+    ctx_ghash: Ctx,
+    thread_block_cipher: T,
+    thread_stream_cipher: T,
+    ot_send: OTS,
+    ot_recv: OTR,
+) -> Box<dyn Aead<Error = AeadError> + Send>
+where
+    Ctx: Context + 'static,
+    T: Thread
+        + Memory
+        + Execute
+        + Load
+        + Decode
+        + DecodePrivate
+        + Prove
+        + Verify
+        + Send
+        + Sync
+        + 'static,
+    OTS: Preprocess<Ctx, Error = OTError> + RandomOTSender<Ctx, [Gf2_128; 2]> + Send + 'static,
+    OTR: Preprocess<Ctx, Error = OTError> + RandomOTReceiver<Ctx, bool, Gf2_128> + Send + 'static,
+{
     let block_cipher = Box::new(MpcBlockCipher::<Aes128, _>::new(
         BlockCipherConfig::builder()
-            .id("encrypter/block_cipher")
+            .id(format!("{id}/block_cipher"))
             .build()
             .unwrap(),
-        thread_encrypter_block_cipher,
+        thread_block_cipher,
     ));
 
     let stream_cipher = Box::new(MpcStreamCipher::<Aes128Ctr, _>::new(
         StreamCipherConfig::builder()
-            .id("encrypter/stream_cipher")
-            .transcript_id("tx")
+            .id(format!("{id}/stream_cipher"))
+            .transcript_id(transcript_id)
             .build()
             .unwrap(),
-        thread_encrypter_stream_cipher,
+        thread_stream_cipher,
     ));
 
     let ghash: Box<dyn UniversalHash + Send> = match role {
         TlsRole::Leader => Box::new(Ghash::new(
             GhashConfig::builder().build().unwrap(),
-            ShareConversionSender::new(OLESender::new(ot_send.clone())),
-            ctx_ghash_encrypter,
+            ShareConversionSender::new(OLESender::new(ot_send)),
+            ctx_ghash,
         )),
         TlsRole::Follower => Box::new(Ghash::new(
             GhashConfig::builder().build().unwrap(),
-            ShareConversionReceiver::new(OLEReceiver::new(ot_recv.clone())),
-            ctx_ghash_encrypter,
+            ShareConversionReceiver::new(OLEReceiver::new(ot_recv)),
+            ctx_ghash,
         )),
     };
 
-    let mut encrypter = Box::new(MpcAesGcm::new(
+    Box::new(MpcAesGcm::new(
         AesGcmConfig::builder()
-            .id("encrypter/aes_gcm")
-            .role(match role {
-                TlsRole::Leader => AeadRole::Leader,
-                TlsRole::Follower => AeadRole::Follower,
-            })
+            .id(format!("{id}/aes_gcm"))
+            .role(aead_role)
             .build()
             .unwrap(),
-        ctx_encrypter,
+        ctx,
         block_cipher,
         stream_cipher,
         ghash,
-    ));
-
-    encrypter.set_transcript_id(config.tx_config().opaque_id());
-
-    // Decrypter
-    let block_cipher = Box::new(MpcBlockCipher::<Aes128, _>::new(
-        BlockCipherConfig::builder()
-            .id("decrypter/block_cipher")
-            .build()
-            .unwrap(),
-        thread_decrypter_block_cipher,
-    ));
+    ))
+}
 
-    let stream_cipher = Box::new(MpcStreamCipher::<Aes128Ctr, _>::new(
+#[allow(clippy::too_many_arguments)]
+fn build_chacha20_poly1305<Ctx, T, OTS, OTR>(
+    id: &str,
+    transcript_id: &str,
+    max_transcript_size: usize,
+    role: TlsRole,
+    aead_role: AeadRole,
+    ctx_poly1305: Ctx,
+    thread_tag: T,
+    thread_stream_cipher: T,
+    ot_send: OTS,
+    ot_recv: OTR,
+) -> Box<dyn Aead<Error = AeadError> + Send>
+where
+    Ctx: Context + 'static,
+    T: Thread
+        + Memory
+        + Execute
+        + Load
+        + Decode
+        + DecodePrivate
+        + Prove
+        + Verify
+        + Send
+        + Sync
+        + 'static,
+    OTS: Preprocess<Ctx, Error = OTError> + RandomOTSender<Ctx, [P1305; 2]> + Send + 'static,
+    OTR: Preprocess<Ctx, Error = OTError> + RandomOTReceiver<Ctx, bool, P1305> + Send + 'static,
+{
+    // The first keystream block of every record is used for the Poly1305 key.
+    let stream_cipher = Box::new(MpcStreamCipher::<ChaCha20, _>::new(
         StreamCipherConfig::builder()
-            .id("decrypter/stream_cipher")
-            .transcript_id("rx")
+            .id(format!("{id}/stream_cipher"))
+            .start_ctr(1)
+            .transcript_id(transcript_id)
             .build()
             .unwrap(),
-        thread_decrypter_stream_cipher,
+        thread_stream_cipher,
     ));
 
-    let ghash: Box<dyn UniversalHash + Send> = match role {
-        TlsRole::Leader => Box::new(Ghash::new(
-            GhashConfig::builder().build().unwrap(),
+    // Poly1305 keys are single-use, so share conversions are provisioned per
+    // record. Each record also authenticates a block of additional data and
+    // a block of lengths.
+    let record_count = max_transcript_size.div_ceil(1024) + EXTRA_RECORDS;
+    let poly1305_config = Poly1305Config::builder()
+        .key_count(record_count)
+        .block_count(max_transcript_size.div_ceil(POLY1305_BLOCK_LEN) + 2 * record_count)
+        .max_block_count(MAX_RECORD_LEN / POLY1305_BLOCK_LEN + 2)
+        .build()
+        .unwrap();
+
+    let poly1305: Box<dyn UniversalHash + Send> = match role {
+        TlsRole::Leader => Box::new(Poly1305::new(
+            poly1305_config,
             ShareConversionSender::new(OLESender::new(ot_send)),
-            ctx_ghash_decrypter,
+            ctx_poly1305,
         )),
-        TlsRole::Follower => Box::new(Ghash::new(
-            GhashConfig::builder().build().unwrap(),
+        TlsRole::Follower => Box::new(Poly1305::new(
+            poly1305_config,
             ShareConversionReceiver::new(OLEReceiver::new(ot_recv)),
-            ctx_ghash_decrypter,
+            ctx_poly1305,
         )),
     };
 
-    let mut decrypter = Box::new(MpcAesGcm::new(
-        AesGcmConfig::builder()
-            .id("decrypter/aes_gcm")
-            .role(match role {
-                TlsRole::Leader => AeadRole::Leader,
-                TlsRole::Follower => AeadRole::Follower,
-            })
+    Box::new(MpcChaCha20Poly1305::new(
+        ChaCha20Poly1305Config::builder()
+            .id(format!("{id}/chacha20_poly1305"))
+            .role(aead_role)
             .build()
             .unwrap(),
-        ctx_decrypter,
-        block_cipher,
+        thread_tag,
         stream_cipher,
-        ghash,
-    ));
-
-    decrypter.set_transcript_id(config.rx_config().opaque_id());
-
-    (ke, prf, encrypter, decrypter)
+        poly1305,
+    ))
 }
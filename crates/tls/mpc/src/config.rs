@@ -1,4 +1,5 @@
 use derive_builder::Builder;
+use tls_core::msgs::enums::{CipherSuite, NamedGroup};
 
 static DEFAULT_OPAQUE_TX_TRANSCRIPT_ID: &str = "opaque_tx";
 static DEFAULT_OPAQUE_RX_TRANSCRIPT_ID: &str = "opaque_rx";
@@ -79,6 +80,90 @@ impl TranscriptConfig {
     }
 }
 
+/// The AEAD used to protect TLS records.
+///
+/// The MPC components are preprocessed before the connection is opened, so
+/// both parties have to agree on the AEAD in advance, and the leader only
+/// accepts cipher suites which use it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AeadAlgorithm {
+    /// AES-128-GCM.
+    #[default]
+    Aes128Gcm,
+    /// ChaCha20-Poly1305.
+    ChaCha20Poly1305,
+}
+
+impl AeadAlgorithm {
+    /// Returns the TLS 1.2 cipher suites which use this AEAD.
+    pub fn cipher_suites(&self) -> &'static [CipherSuite] {
+        match self {
+            Self::Aes128Gcm => &[
+                CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+                CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+            ],
+            Self::ChaCha20Poly1305 => &[
+                CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+                CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+            ],
+        }
+    }
+
+    /// Returns the length of the explicit nonce sent with each record.
+    pub(crate) fn explicit_nonce_len(&self) -> usize {
+        match self {
+            Self::Aes128Gcm => 8,
+            // RFC 7905 derives the nonce from the sequence number.
+            Self::ChaCha20Poly1305 => 0,
+        }
+    }
+}
+
+/// A group for the key exchange with the server.
+///
+/// The key exchange is preprocessed for all enabled groups, as the server
+/// picks one of them only during the handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyExchangeGroup {
+    /// X25519.
+    X25519,
+    /// NIST P-256, ie. secp256r1.
+    P256,
+}
+
+impl KeyExchangeGroup {
+    /// Returns the TLS name of this group.
+    pub fn named_group(&self) -> NamedGroup {
+        match self {
+            Self::X25519 => NamedGroup::X25519,
+            Self::P256 => NamedGroup::secp256r1,
+        }
+    }
+
+    /// Returns the group with the given TLS name, if it is supported.
+    pub(crate) fn from_named_group(group: NamedGroup) -> Option<Self> {
+        match group {
+            NamedGroup::X25519 => Some(Self::X25519),
+            NamedGroup::secp256r1 => Some(Self::P256),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_ke_group(group: key_exchange::Group) -> Self {
+        match group {
+            key_exchange::Group::X25519 => Self::X25519,
+            key_exchange::Group::P256 => Self::P256,
+        }
+    }
+
+    pub(crate) fn to_ke_group(self) -> key_exchange::Group {
+        match self {
+            Self::X25519 => key_exchange::Group::X25519,
+            Self::P256 => key_exchange::Group::P256,
+        }
+    }
+}
+
 /// Configuration options which are common to both the leader and the follower
 #[derive(Debug, Clone, Builder)]
 pub struct MpcTlsCommonConfig {
@@ -94,6 +179,15 @@ pub struct MpcTlsCommonConfig {
     /// Whether the leader commits to the handshake data.
     #[builder(default = "true")]
     handshake_commit: bool,
+    /// The AEAD used to protect TLS records.
+    #[builder(default)]
+    aead_algorithm: AeadAlgorithm,
+    /// The groups offered for the key exchange, in order of preference.
+    ///
+    /// The key exchange is preprocessed for each group, so only P-256 is
+    /// offered by default.
+    #[builder(default = "vec![KeyExchangeGroup::P256]")]
+    key_exchange_groups: Vec<KeyExchangeGroup>,
 }
 
 impl MpcTlsCommonConfig {
@@ -121,6 +215,16 @@ impl MpcTlsCommonConfig {
     pub fn handshake_commit(&self) -> bool {
         self.handshake_commit
     }
+
+    /// Returns the AEAD used to protect TLS records.
+    pub fn aead_algorithm(&self) -> AeadAlgorithm {
+        self.aead_algorithm
+    }
+
+    /// Returns the groups offered for the key exchange.
+    pub fn key_exchange_groups(&self) -> &[KeyExchangeGroup] {
+        &self.key_exchange_groups
+    }
 }
 
 /// Configuration for the leader
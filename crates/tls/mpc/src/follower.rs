@@ -8,9 +8,7 @@ use futures::{
 use key_exchange as ke;
 use ludi::{Address, FuturesAddress};
 
-use p256::elliptic_curve::sec1::ToEncodedPoint;
-
-use aead::{aes_gcm::AesGcmError, Aead};
+use aead::{Aead, AeadError};
 use hmac_sha256::Prf;
 use ke::KeyExchange;
 use tls_core::{
@@ -19,7 +17,7 @@ use tls_core::{
         alert::AlertMessagePayload,
         base::Payload,
         codec::Codec,
-        enums::{AlertDescription, ContentType, HandshakeType, NamedGroup, ProtocolVersion},
+        enums::{AlertDescription, ContentType, HandshakeType, ProtocolVersion},
         handshake::{HandshakeMessagePayload, HandshakePayload},
         message::{OpaqueMessage, PlainMessage},
     },
@@ -30,7 +28,7 @@ use crate::{
     error::Kind,
     msg::{CloseConnection, Commit, MpcTlsFollowerMsg, MpcTlsMessage},
     record_layer::{Decrypter, Encrypter},
-    Direction, MpcTlsChannel, MpcTlsError, MpcTlsFollowerConfig,
+    Direction, KeyExchangeGroup, MpcTlsChannel, MpcTlsError, MpcTlsFollowerConfig,
 };
 
 /// Controller for MPC-TLS follower.
@@ -94,16 +92,18 @@ impl MpcTlsFollower {
         channel: MpcTlsChannel,
         ke: Box<dyn KeyExchange + Send>,
         prf: Box<dyn Prf + Send>,
-        encrypter: Box<dyn Aead<Error = AesGcmError> + Send>,
-        decrypter: Box<dyn Aead<Error = AesGcmError> + Send>,
+        encrypter: Box<dyn Aead<Error = AeadError> + Send>,
+        decrypter: Box<dyn Aead<Error = AeadError> + Send>,
     ) -> Self {
         let encrypter = Encrypter::new(
             encrypter,
+            config.common().aead_algorithm(),
             config.common().tx_config().id().to_string(),
             config.common().tx_config().opaque_id().to_string(),
         );
         let decrypter = Decrypter::new(
             decrypter,
+            config.common().aead_algorithm(),
             config.common().rx_config().id().to_string(),
             config.common().rx_config().opaque_id().to_string(),
         );
@@ -278,8 +278,8 @@ impl MpcTlsFollower {
 
         self.state = State::Ke(Ke {
             server_key: PublicKey::new(
-                NamedGroup::secp256r1,
-                server_key.to_encoded_point(false).as_bytes(),
+                KeyExchangeGroup::from_ke_group(server_key.group()).named_group(),
+                &server_key.to_bytes(),
             ),
         });
 
@@ -5,7 +5,7 @@ use futures::SinkExt;
 
 use key_exchange as ke;
 
-use aead::{aes_gcm::AesGcmError, Aead};
+use aead::{Aead, AeadError};
 use hmac_sha256::Prf;
 use ke::KeyExchange;
 
@@ -20,11 +20,11 @@ use tls_core::{
     msgs::{
         alert::AlertMessagePayload,
         codec::Codec,
-        enums::{AlertDescription, CipherSuite, ContentType, NamedGroup, ProtocolVersion},
-        handshake::Random,
+        enums::{AlertDescription, CipherSuite, ContentType, ProtocolVersion},
+        handshake::{Random, ServerECDHParams},
         message::{OpaqueMessage, PlainMessage},
     },
-    suites::SupportedCipherSuite,
+    suites::{tls12::decode_ecdh_params, SupportedCipherSuite},
 };
 use tracing::{debug, instrument, trace, Instrument};
 
@@ -37,7 +37,7 @@ use crate::{
     },
     msg::{CloseConnection, Commit, MpcTlsLeaderMsg, MpcTlsMessage},
     record_layer::{Decrypter, Encrypter},
-    Direction, MpcTlsChannel, MpcTlsError, MpcTlsLeaderConfig,
+    Direction, KeyExchangeGroup, MpcTlsChannel, MpcTlsError, MpcTlsLeaderConfig,
 };
 
 /// Controller for MPC-TLS leader.
@@ -88,16 +88,18 @@ impl MpcTlsLeader {
         channel: MpcTlsChannel,
         ke: Box<dyn KeyExchange + Send>,
         prf: Box<dyn Prf + Send>,
-        encrypter: Box<dyn Aead<Error = AesGcmError> + Send>,
-        decrypter: Box<dyn Aead<Error = AesGcmError> + Send>,
+        encrypter: Box<dyn Aead<Error = AeadError> + Send>,
+        decrypter: Box<dyn Aead<Error = AeadError> + Send>,
     ) -> Self {
         let encrypter = Encrypter::new(
             encrypter,
+            config.common().aead_algorithm(),
             config.common().tx_config().id().to_string(),
             config.common().tx_config().opaque_id().to_string(),
         );
         let decrypter = Decrypter::new(
             decrypter,
+            config.common().aead_algorithm(),
             config.common().rx_config().id().to_string(),
             config.common().rx_config().opaque_id().to_string(),
         );
@@ -424,6 +426,18 @@ impl Backend for MpcTlsLeader {
 
         trace!("setting cipher suite: {:?}", suite);
 
+        // The AEAD was preprocessed for the configured algorithm, so it can not
+        // be changed once the server has picked a suite.
+        if !self
+            .config
+            .common()
+            .aead_algorithm()
+            .cipher_suites()
+            .contains(&suite.suite())
+        {
+            return Err(BackendError::UnsupportedCiphersuite(suite.suite()));
+        }
+
         *cipher_suite = Some(suite.suite());
 
         Ok(())
@@ -452,8 +466,8 @@ impl Backend for MpcTlsLeader {
         let pk = self.ke.client_key().await.map_err(MpcTlsError::from)?;
 
         Ok(PublicKey::new(
-            NamedGroup::secp256r1,
-            &p256::EncodedPoint::from(pk).to_bytes(),
+            KeyExchangeGroup::from_ke_group(pk.group()).named_group(),
+            &pk.to_bytes(),
         ))
     }
 
@@ -471,25 +485,21 @@ impl Backend for MpcTlsLeader {
             server_public_key, ..
         } = self.state.try_as_ke_mut().map_err(MpcTlsError::from)?;
 
-        if key.group != NamedGroup::secp256r1 {
-            Err(MpcTlsError::new(
+        // The key exchange was started with the key from the server's key
+        // exchange parameters, which this has to match.
+        let server_key = KeyExchangeGroup::from_named_group(key.group)
+            .and_then(|group| ke::PublicKey::from_bytes(group.to_ke_group(), &key.key).ok());
+        if server_key.is_none() || server_key != self.ke.server_key() {
+            return Err(MpcTlsError::new(
                 Kind::KeyExchange,
-                format!("unsupported key group: {:?}", key.group),
+                "server key does not match the key exchange parameters",
             )
-            .into())
-        } else {
-            let server_key = p256::PublicKey::from_sec1_bytes(&key.key)
-                .map_err(|_| MpcTlsError::other("server key is not valid sec1p256"))?;
-
-            *server_public_key = Some(key);
+            .into());
+        }
 
-            self.ke
-                .set_server_key(server_key)
-                .await
-                .map_err(MpcTlsError::from)?;
+        *server_public_key = Some(key);
 
-            Ok(())
-        }
+        Ok(())
     }
 
     async fn set_server_cert_details(
@@ -506,6 +516,7 @@ impl Backend for MpcTlsLeader {
         Ok(())
     }
 
+    #[instrument(level = "debug", skip_all, err)]
     async fn set_server_kx_details(
         &mut self,
         kx_details: ServerKxDetails,
@@ -514,8 +525,31 @@ impl Backend for MpcTlsLeader {
             server_kx_details, ..
         } = self.state.try_as_ke_mut().map_err(MpcTlsError::from)?;
 
+        // The server's key determines the group of the client key share, so the
+        // key exchange is started as soon as the server has picked it.
+        let ecdh_params = decode_ecdh_params::<ServerECDHParams>(kx_details.kx_params())
+            .ok_or_else(|| MpcTlsError::other("server key exchange parameters are invalid"))?;
+        let named_group = ecdh_params.curve_params.named_group;
+
+        let group = KeyExchangeGroup::from_named_group(named_group)
+            .filter(|group| self.config.common().key_exchange_groups().contains(group))
+            .ok_or_else(|| {
+                MpcTlsError::new(
+                    Kind::KeyExchange,
+                    format!("unsupported key group: {:?}", named_group),
+                )
+            })?;
+
+        let server_key = ke::PublicKey::from_bytes(group.to_ke_group(), &ecdh_params.public.0)
+            .map_err(|_| MpcTlsError::other("server key is not valid"))?;
+
         *server_kx_details = Some(kx_details);
 
+        self.ke
+            .set_server_key(server_key)
+            .await
+            .map_err(MpcTlsError::from)?;
+
         Ok(())
     }
 
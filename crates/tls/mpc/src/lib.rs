@@ -19,7 +19,7 @@ pub(crate) mod record_layer;
 
 pub use components::build_components;
 pub use config::{
-    MpcTlsCommonConfig, MpcTlsCommonConfigBuilder, MpcTlsCommonConfigBuilderError,
+    AeadAlgorithm, KeyExchangeGroup, MpcTlsCommonConfig, MpcTlsCommonConfigBuilder, MpcTlsCommonConfigBuilderError,
     MpcTlsFollowerConfig, MpcTlsFollowerConfigBuilder, MpcTlsFollowerConfigBuilderError,
     MpcTlsLeaderConfig, MpcTlsLeaderConfigBuilder, MpcTlsLeaderConfigBuilderError,
     TranscriptConfig, TranscriptConfigBuilder, TranscriptConfigBuilderError,
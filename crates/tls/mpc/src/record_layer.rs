@@ -1,4 +1,4 @@
-use aead::aes_gcm::AesGcmError;
+use aead::AeadError;
 use mpz_garble::value::ValueRef;
 
 use tls_core::{
@@ -10,10 +10,11 @@ use tls_core::{
     },
 };
 
-use crate::{error::Kind, MpcTlsError};
+use crate::{error::Kind, AeadAlgorithm, MpcTlsError};
 
 pub(crate) struct Encrypter {
-    aead: Box<dyn aead::Aead<Error = AesGcmError>>,
+    aead: Box<dyn aead::Aead<Error = AeadError>>,
+    algorithm: AeadAlgorithm,
     seq: u64,
     sent_bytes: usize,
     transcript_id: String,
@@ -22,12 +23,14 @@ pub(crate) struct Encrypter {
 
 impl Encrypter {
     pub(crate) fn new(
-        aead: Box<dyn aead::Aead<Error = AesGcmError>>,
+        aead: Box<dyn aead::Aead<Error = AeadError>>,
+        algorithm: AeadAlgorithm,
         transcript_id: String,
         opaque_transcript_id: String,
     ) -> Self {
         Self {
             aead,
+            algorithm,
             seq: 0,
             sent_bytes: 0,
             transcript_id,
@@ -101,6 +104,7 @@ impl Encrypter {
         self.record_message(typ, len);
 
         let mut payload = explicit_nonce;
+        payload.truncate(self.algorithm.explicit_nonce_len());
         payload.extend(ciphertext);
 
         Ok(OpaqueMessage {
@@ -158,6 +162,7 @@ impl Encrypter {
         self.record_message(typ, len);
 
         let mut payload = explicit_nonce;
+        payload.truncate(self.algorithm.explicit_nonce_len());
         payload.extend(ciphertext);
 
         Ok(OpaqueMessage {
@@ -186,7 +191,8 @@ impl Encrypter {
 }
 
 pub(crate) struct Decrypter {
-    aead: Box<dyn aead::Aead<Error = AesGcmError>>,
+    aead: Box<dyn aead::Aead<Error = AeadError>>,
+    algorithm: AeadAlgorithm,
     seq: u64,
     recv_bytes: usize,
     transcript_id: String,
@@ -195,12 +201,14 @@ pub(crate) struct Decrypter {
 
 impl Decrypter {
     pub(crate) fn new(
-        aead: Box<dyn aead::Aead<Error = AesGcmError>>,
+        aead: Box<dyn aead::Aead<Error = AeadError>>,
+        algorithm: AeadAlgorithm,
         transcript_id: String,
         opaque_transcript_id: String,
     ) -> Self {
         Self {
             aead,
+            algorithm,
             seq: 0,
             recv_bytes: 0,
             transcript_id,
@@ -258,9 +266,8 @@ impl Decrypter {
             mut payload,
         } = msg;
 
-        let explicit_nonce: Vec<u8> = payload.0.drain(..8).collect();
-        let len = payload.0.len() - 16;
         let seq = self.seq;
+        let (explicit_nonce, len) = self.split_payload(seq, &mut payload.0)?;
 
         self.prepare_decrypt(typ);
 
@@ -287,9 +294,8 @@ impl Decrypter {
             mut payload,
         } = msg;
 
-        let explicit_nonce: Vec<u8> = payload.0.drain(..8).collect();
-        let len = payload.0.len() - 16;
         let seq = self.seq;
+        let (explicit_nonce, len) = self.split_payload(seq, &mut payload.0)?;
 
         self.prepare_decrypt(typ);
 
@@ -314,9 +320,8 @@ impl Decrypter {
             mut payload,
         } = msg;
 
-        let explicit_nonce: Vec<u8> = payload.0.drain(..8).collect();
-        let len = payload.0.len() - 16;
         let seq = self.seq;
+        let (explicit_nonce, len) = self.split_payload(seq, &mut payload.0)?;
 
         self.prepare_decrypt(typ);
 
@@ -365,9 +370,8 @@ impl Decrypter {
             mut payload,
         } = msg;
 
-        let explicit_nonce: Vec<u8> = payload.0.drain(..8).collect();
-        let len = payload.0.len() - 16;
         let seq = self.seq;
+        let (explicit_nonce, len) = self.split_payload(seq, &mut payload.0)?;
 
         self.prepare_decrypt(typ);
 
@@ -399,9 +403,8 @@ impl Decrypter {
             mut payload,
         } = msg;
 
-        let explicit_nonce: Vec<u8> = payload.0.drain(..8).collect();
-        let len = payload.0.len() - 16;
         let seq = self.seq;
+        let (explicit_nonce, len) = self.split_payload(seq, &mut payload.0)?;
 
         self.prepare_decrypt(typ);
 
@@ -418,6 +421,33 @@ impl Decrypter {
         Ok(())
     }
 
+    /// Removes the explicit nonce from the payload, returning the nonce and the
+    /// length of the plaintext.
+    ///
+    /// Suites without an explicit nonce, such as ChaCha20-Poly1305, use the
+    /// sequence number instead.
+    fn split_payload(
+        &self,
+        seq: u64,
+        payload: &mut Vec<u8>,
+    ) -> Result<(Vec<u8>, usize), MpcTlsError> {
+        let explicit_nonce_len = self.algorithm.explicit_nonce_len();
+        let Some(len) = payload.len().checked_sub(explicit_nonce_len + 16) else {
+            return Err(MpcTlsError::new(
+                Kind::Decrypt,
+                "payload is too short to contain the explicit nonce and tag",
+            ));
+        };
+
+        let explicit_nonce = if explicit_nonce_len > 0 {
+            payload.drain(..explicit_nonce_len).collect()
+        } else {
+            seq.to_be_bytes().to_vec()
+        };
+
+        Ok((explicit_nonce, len))
+    }
+
     fn prepare_decrypt(&mut self, typ: ContentType) {
         // Set the transcript id depending on the type of message
         match typ {
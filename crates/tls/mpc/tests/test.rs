@@ -12,11 +12,11 @@ use mpz_ot::{
     CommittedOTSender, VerifiableOTReceiver,
 };
 use serio::StreamExt;
-use tls_client::Certificate;
+use tls_client::{kx_group, Certificate, ALL_CIPHER_SUITES};
 use tls_client_async::bind_client;
 use tls_mpc::{
-    build_components, MpcTlsCommonConfig, MpcTlsFollower, MpcTlsFollowerConfig, MpcTlsLeader,
-    MpcTlsLeaderConfig, TlsRole,
+    build_components, AeadAlgorithm, KeyExchangeGroup, MpcTlsCommonConfig, MpcTlsFollower,
+    MpcTlsFollowerConfig, MpcTlsLeader, MpcTlsLeaderConfig, TlsRole,
 };
 use tls_server_fixture::{bind_test_server_hyper, CA_CERT_DER, SERVER_DOMAIN};
 use tokio_util::compat::TokioAsyncReadCompatExt;
@@ -112,6 +112,24 @@ async fn leader(config: MpcTlsCommonConfig, mux: TestFramedMux) {
         ot_receiver.clone(),
     );
 
+    // Only offer the suites which use the preprocessed AEAD.
+    let aead_algorithm = config.aead_algorithm();
+    let cipher_suites = ALL_CIPHER_SUITES
+        .iter()
+        .filter(|suite| aead_algorithm.cipher_suites().contains(&suite.suite()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    // Only offer the groups for which the key exchange was preprocessed.
+    let key_exchange_groups = config.key_exchange_groups().to_vec();
+    let kx_groups = key_exchange_groups
+        .iter()
+        .map(|group| match group {
+            KeyExchangeGroup::X25519 => &kx_group::X25519,
+            KeyExchangeGroup::P256 => &kx_group::SECP256R1,
+        })
+        .collect::<Vec<_>>();
+
     let mut leader = MpcTlsLeader::new(
         MpcTlsLeaderConfig::builder()
             .common(config)
@@ -130,12 +148,15 @@ async fn leader(config: MpcTlsCommonConfig, mux: TestFramedMux) {
     leader.setup().await.unwrap();
 
     let (leader_ctrl, leader_fut) = leader.run();
-    tokio::spawn(async { leader_fut.await.unwrap() });
+    let leader_task = tokio::spawn(leader_fut);
 
     let mut root_store = tls_client::RootCertStore::empty();
     root_store.add(&Certificate(CA_CERT_DER.to_vec())).unwrap();
     let config = tls_client::ClientConfig::builder()
-        .with_safe_defaults()
+        .with_cipher_suites(&cipher_suites)
+        .with_kx_groups(&kx_groups)
+        .with_safe_default_protocol_versions()
+        .unwrap()
         .with_root_certificates(root_store)
         .with_no_client_auth();
 
@@ -200,6 +221,12 @@ async fn leader(config: MpcTlsCommonConfig, mux: TestFramedMux) {
     leader_ctrl.close_connection().await.unwrap();
     conn.close().await.unwrap();
 
+    let data = leader_task.await.unwrap().unwrap();
+    assert!(aead_algorithm.cipher_suites().contains(&data.cipher_suite));
+    assert!(key_exchange_groups
+        .iter()
+        .any(|group| group.named_group() == data.server_public_key.group));
+
     let mut ctx = exec.new_thread().await.unwrap();
 
     ot_receiver.accept_reveal(&mut ctx).await.unwrap();
@@ -332,3 +359,36 @@ async fn test() {
         follower(common_config.clone(), follower_mux)
     );
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_chacha20_poly1305() {
+    let (leader_mux, follower_mux) = test_framed_mux(8);
+
+    let common_config = MpcTlsCommonConfig::builder()
+        .aead_algorithm(AeadAlgorithm::ChaCha20Poly1305)
+        .build()
+        .unwrap();
+
+    tokio::join!(
+        leader(common_config.clone(), leader_mux),
+        follower(common_config.clone(), follower_mux)
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_x25519() {
+    let (leader_mux, follower_mux) = test_framed_mux(8);
+
+    // Only P-256 is offered by the default config.
+    let common_config = MpcTlsCommonConfig::builder()
+        .key_exchange_groups(vec![KeyExchangeGroup::X25519])
+        .build()
+        .unwrap();
+
+    tokio::join!(
+        leader(common_config.clone(), leader_mux),
+        follower(common_config.clone(), follower_mux)
+    );
+}
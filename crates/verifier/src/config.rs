@@ -3,8 +3,10 @@ use std::{
     fmt::{Debug, Formatter, Result},
     sync::Arc,
 };
-use tls_mpc::{MpcTlsCommonConfig, MpcTlsFollowerConfig, TranscriptConfig};
-use tlsn_common::config::{ProtocolConfig, ProtocolConfigValidator};
+use tls_mpc::{
+    AeadAlgorithm, KeyExchangeGroup, MpcTlsCommonConfig, MpcTlsFollowerConfig, TranscriptConfig,
+};
+use tlsn_common::config::{self, ProtocolConfig, ProtocolConfigValidator};
 use tlsn_core::CryptoProvider;
 
 /// Configuration for the [`Verifier`](crate::tls::Verifier).
@@ -88,6 +90,20 @@ impl VerifierConfig {
                             .unwrap(),
                     )
                     .handshake_commit(true)
+                    .aead_algorithm(match protocol_config.aead_algorithm() {
+                        config::AeadAlgorithm::Aes128Gcm => AeadAlgorithm::Aes128Gcm,
+                        config::AeadAlgorithm::ChaCha20Poly1305 => AeadAlgorithm::ChaCha20Poly1305,
+                    })
+                    .key_exchange_groups(
+                        protocol_config
+                            .key_exchange_groups()
+                            .iter()
+                            .map(|group| match group {
+                                config::KeyExchangeGroup::X25519 => KeyExchangeGroup::X25519,
+                                config::KeyExchangeGroup::P256 => KeyExchangeGroup::P256,
+                            })
+                            .collect(),
+                    )
                     .build()
                     .unwrap(),
             )
@@ -26,12 +26,25 @@ impl VerifierError {
         Self::new(ErrorKind::Attestation, source)
     }
 
+    pub(crate) fn server_name<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync + 'static>>,
+    {
+        Self::new(ErrorKind::ServerName, source)
+    }
+
     pub(crate) fn verify<E>(source: E) -> Self
     where
         E: Into<Box<dyn Error + Send + Sync + 'static>>,
     {
         Self::new(ErrorKind::Verify, source)
     }
+
+    /// Returns whether the Notary refused to sign the attestation because the
+    /// server name is not permitted by its policy.
+    pub fn is_server_name_rejected(&self) -> bool {
+        matches!(self.kind, ErrorKind::ServerName)
+    }
 }
 
 #[derive(Debug)]
@@ -40,6 +53,7 @@ enum ErrorKind {
     Config,
     Mpc,
     Attestation,
+    ServerName,
     Verify,
 }
 
@@ -52,6 +66,7 @@ impl fmt::Display for VerifierError {
             ErrorKind::Config => f.write_str("config error")?,
             ErrorKind::Mpc => f.write_str("mpc error")?,
             ErrorKind::Attestation => f.write_str("attestation error")?,
+            ErrorKind::ServerName => f.write_str("server name error")?,
             ErrorKind::Verify => f.write_str("verification error")?,
         }
 
@@ -140,3 +155,9 @@ impl From<mpz_garble::VerifyError> for VerifierError {
         Self::new(ErrorKind::Mpc, e)
     }
 }
+
+impl From<tlsn_common::commit::CommitError> for VerifierError {
+    fn from(e: tlsn_common::commit::CommitError) -> Self {
+        Self::new(ErrorKind::Mpc, e)
+    }
+}
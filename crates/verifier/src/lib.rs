@@ -231,6 +231,11 @@ impl Verifier<state::Setup> {
 }
 
 impl Verifier<state::Closed> {
+    /// Returns the information about the TLS connection.
+    pub fn connection_info(&self) -> &ConnectionInfo {
+        &self.state.connection_info
+    }
+
     /// Starts notarization of the TLS session.
     ///
     /// If the verifier is a Notary, this function will transition the verifier
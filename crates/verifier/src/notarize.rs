@@ -6,6 +6,7 @@ use super::{state::Notarize, Verifier, VerifierError};
 use mpz_ot::CommittedOTSender;
 use serio::{stream::IoStreamExt, SinkExt as _};
 
+use tlsn_common::{commit::verify_plaintext_hash, predicate::verify_predicate};
 use tlsn_core::{
     attestation::{Attestation, AttestationConfig},
     request::Request,
@@ -38,6 +39,20 @@ impl Verifier<Notarize> {
                 // finalization.
                 let request: Request = io.expect_next().await?;
 
+                // Verify the plaintext hashes were computed over the transcript.
+                for (i, hash) in request.plaintext_hashes().iter().enumerate() {
+                    verify_plaintext_hash(&mut vm, &format!("plaintext_hash/{i}"), hash).await?;
+                }
+
+                debug!("verified plaintext hashes");
+
+                // Verify the predicates hold for the transcript.
+                for (i, statement) in request.predicates().iter().enumerate() {
+                    verify_predicate(&mut vm, &format!("predicate/{i}"), statement).await?;
+                }
+
+                debug!("verified predicates");
+
                 // Finalize all MPC before attesting.
                 ot_send.reveal(&mut ctx).await?;
 
@@ -56,9 +71,16 @@ impl Verifier<Notarize> {
                     .server_ephemeral_key(server_ephemeral_key)
                     .encoding_seed(encoder_seed.to_vec());
 
+                // Fails if the server name is not permitted by the policy of the config.
                 let attestation = builder
                     .build(self.config.crypto_provider())
-                    .map_err(VerifierError::attestation)?;
+                    .map_err(|err| {
+                        if err.is_server_name_rejected() {
+                            VerifierError::server_name(err)
+                        } else {
+                            VerifierError::attestation(err)
+                        }
+                    })?;
 
                 io.send(attestation.clone()).await?;
 
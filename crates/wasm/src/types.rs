@@ -4,7 +4,7 @@ use http_body_util::Full;
 use hyper::body::Bytes;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use tlsn_core::CryptoProvider;
+use tlsn_core::{presentation::VerifyOptions, CryptoProvider};
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
@@ -254,7 +254,7 @@ impl Presentation {
 
         self.0
             .clone()
-            .verify(&provider)
+            .verify(&provider, &VerifyOptions::default())
             .map(PresentationOutput::from)
             .map_err(JsError::from)
     }